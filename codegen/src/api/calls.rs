@@ -32,12 +32,16 @@ use quote::{
     format_ident,
     quote,
 };
-use scale_info::form::PortableForm;
+use scale_info::{
+    form::PortableForm,
+    TypeDef,
+};
 
 pub fn generate_calls(
     type_gen: &TypeGenerator,
     pallet: &PalletMetadata<PortableForm>,
     call: &PalletCallMetadata<PortableForm>,
+    outer_call_type_id: Option<u32>,
     types_mod_ident: &syn::Ident,
 ) -> TokenStream2 {
     let struct_defs = super::generate_structs_from_variants(
@@ -46,38 +50,97 @@ pub fn generate_calls(
         |name| name.to_upper_camel_case().into(),
         "Call",
     );
+    let variant_names = match type_gen.resolve_type(call.ty.id()).type_def() {
+        TypeDef::Variant(variant) => {
+            variant
+                .variants()
+                .iter()
+                .map(|var| format_ident!("{}", var.name()))
+                .collect::<Vec<_>>()
+        }
+        _ => abort_call_site!("Call type {} should be a variant/enum type", call.ty.id()),
+    };
+    let pallet_call_path = type_gen.resolve_type_path(call.ty.id(), &[]);
+    let outer_call_path =
+        outer_call_type_id.map(|id| type_gen.resolve_type_path(id, &[]));
+    let outer_call_variant = format_ident!("{}", pallet.name);
+
     let (call_structs, call_fns): (Vec<_>, Vec<_>) = struct_defs
         .iter()
-        .map(|struct_def| {
-            let (call_fn_args, call_args): (Vec<_>, Vec<_>) =
-                match struct_def.fields {
-                    CompositeDefFields::Named(ref named_fields) => {
-                        named_fields
-                            .iter()
-                            .map(|(name, field)| {
-                                let fn_arg_type = &field.type_path;
-                                let call_arg = if field.is_boxed() {
-                                    quote! { #name: ::std::boxed::Box::new(#name) }
-                                } else {
-                                    quote! { #name }
-                                };
-                                (quote!( #name: #fn_arg_type ), call_arg)
-                            })
-                            .unzip()
-                    }
-                    CompositeDefFields::NoFields => Default::default(),
-                    CompositeDefFields::Unnamed(_) =>
-                        abort_call_site!(
-                            "Call variant for type {} must have all named fields",
-                            call.ty.id()
-                        )
-                };
+        .zip(variant_names)
+        .map(|(struct_def, variant_name)| {
+            let call_struct_name = &struct_def.name;
+            let (call_fn_args, construct_call) = match struct_def.fields {
+                CompositeDefFields::Named(ref named_fields) => {
+                    let (call_fn_args, call_args): (Vec<_>, Vec<_>) = named_fields
+                        .iter()
+                        .map(|(name, field)| {
+                            let fn_arg_type = &field.type_path;
+                            let call_arg = if field.is_boxed() {
+                                quote! { #name: ::std::boxed::Box::new(#name) }
+                            } else {
+                                quote! { #name }
+                            };
+                            (quote!( #name: #fn_arg_type ), call_arg)
+                        })
+                        .unzip();
+                    (call_fn_args, quote!( #call_struct_name { #( #call_args, )* } ))
+                }
+                CompositeDefFields::Unnamed(ref unnamed_fields) => {
+                    let (call_fn_args, call_args): (Vec<_>, Vec<_>) = unnamed_fields
+                        .iter()
+                        .enumerate()
+                        .map(|(i, field)| {
+                            let name = format_ident!("arg_{}", i);
+                            let fn_arg_type = &field.type_path;
+                            let call_arg = if field.is_boxed() {
+                                quote! { ::std::boxed::Box::new(#name) }
+                            } else {
+                                quote! { #name }
+                            };
+                            (quote!( #name: #fn_arg_type ), call_arg)
+                        })
+                        .unzip();
+                    (call_fn_args, quote!( #call_struct_name( #( #call_args, )* ) ))
+                }
+                CompositeDefFields::NoFields => {
+                    (Vec::new(), quote!( #call_struct_name {} ))
+                }
+            };
 
             let pallet_name = &pallet.name;
-            let call_struct_name = &struct_def.name;
             let function_name = struct_def.name.to_string().to_snake_case();
             let fn_name = format_ident!("{}", function_name);
 
+            // Allows the call to be converted into the runtime's outer `Call` type, for
+            // instance to be dispatched as part of a `utility.batch`.
+            let into_outer_call = outer_call_path.as_ref().map(|outer_call_path| {
+                let pallet_call = match struct_def.fields {
+                    CompositeDefFields::Named(ref named_fields) => {
+                        let field_names =
+                            named_fields.iter().map(|(name, _)| name).collect::<Vec<_>>();
+                        quote! {
+                            #pallet_call_path::#variant_name { #( #field_names: call.#field_names, )* }
+                        }
+                    }
+                    CompositeDefFields::Unnamed(ref unnamed_fields) => {
+                        let field_indices = (0..unnamed_fields.len()).map(syn::Index::from);
+                        quote! {
+                            #pallet_call_path::#variant_name( #( call.#field_indices, )* )
+                        }
+                    }
+                    CompositeDefFields::NoFields => quote!( #pallet_call_path::#variant_name ),
+                };
+                quote! {
+                    impl ::core::convert::From<#call_struct_name> for #outer_call_path {
+                        #[allow(unused_variables)]
+                        fn from(call: #call_struct_name) -> Self {
+                            Self::#outer_call_variant(#pallet_call)
+                        }
+                    }
+                }
+            });
+
             let call_struct = quote! {
                 #struct_def
 
@@ -85,13 +148,15 @@ pub fn generate_calls(
                     const PALLET: &'static str = #pallet_name;
                     const FUNCTION: &'static str = #function_name;
                 }
+
+                #into_outer_call
             };
             let client_fn = quote! {
                 pub fn #fn_name(
                     &self,
                     #( #call_fn_args, )*
                 ) -> ::subxt::SubmittableExtrinsic<'a, T, X, A, #call_struct_name, DispatchError, root_mod::Event> {
-                    let call = #call_struct_name { #( #call_args, )* };
+                    let call = #construct_call;
                     ::subxt::SubmittableExtrinsic::new(self.client, call)
                 }
             };
@@ -128,3 +193,109 @@ pub fn generate_calls(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frame_metadata::{
+        ExtrinsicMetadata,
+        RuntimeMetadataLastVersion,
+    };
+    use scale_info::{
+        meta_type,
+        TypeInfo,
+    };
+
+    #[allow(dead_code, non_camel_case_types)]
+    #[derive(TypeInfo)]
+    enum Call {
+        named { a: u32, b: bool },
+        unnamed(u32, bool),
+        no_fields,
+    }
+
+    #[allow(dead_code)]
+    #[derive(TypeInfo)]
+    enum OuterCall {
+        Test(Call),
+    }
+
+    #[test]
+    fn calls_convert_into_the_outer_call() {
+        let pallets = vec![frame_metadata::PalletMetadata {
+            name: "Test",
+            storage: None,
+            calls: Some(frame_metadata::PalletCallMetadata {
+                ty: meta_type::<Call>(),
+            }),
+            event: None,
+            constants: vec![],
+            error: None,
+            index: 0,
+        }];
+        let extrinsic = ExtrinsicMetadata {
+            ty: meta_type::<()>(),
+            version: 4,
+            signed_extensions: vec![],
+        };
+        let metadata =
+            RuntimeMetadataLastVersion::new(pallets, extrinsic, meta_type::<OuterCall>());
+        let type_gen = TypeGenerator::new(
+            &metadata.types,
+            "runtime_types",
+            Default::default(),
+            Default::default(),
+        );
+        let pallet = &metadata.pallets[0];
+        let generated = generate_calls(
+            &type_gen,
+            pallet,
+            pallet.calls.as_ref().unwrap(),
+            Some(metadata.ty.id()),
+            &format_ident!("runtime_types"),
+        )
+        .to_string();
+
+        let call_path = quote!(runtime_types::subxt_codegen::api::calls::tests::Call);
+        let outer_call_path =
+            quote!(runtime_types::subxt_codegen::api::calls::tests::OuterCall);
+        let expected = [
+            quote! {
+                impl ::core::convert::From<Named> for #outer_call_path {
+                    #[allow(unused_variables)]
+                    fn from(call: Named) -> Self {
+                        Self::Test(#call_path::named { a: call.a, b: call.b, })
+                    }
+                }
+            },
+            quote! {
+                impl ::core::convert::From<Unnamed> for #outer_call_path {
+                    #[allow(unused_variables)]
+                    fn from(call: Unnamed) -> Self {
+                        Self::Test(#call_path::unnamed(call.0, call.1,))
+                    }
+                }
+            },
+            quote! {
+                impl ::core::convert::From<NoFields> for #outer_call_path {
+                    #[allow(unused_variables)]
+                    fn from(call: NoFields) -> Self {
+                        Self::Test(#call_path::no_fields)
+                    }
+                }
+            },
+            quote! {
+                let call = Unnamed(arg_0, arg_1,);
+            },
+        ];
+        for expected in expected {
+            let expected = expected.to_string();
+            assert!(
+                generated.contains(&expected),
+                "{} not found in {}",
+                expected,
+                generated
+            );
+        }
+    }
+}
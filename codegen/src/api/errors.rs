@@ -19,7 +19,10 @@ use proc_macro2::{
     Span as Span2,
     TokenStream as TokenStream2,
 };
-use quote::quote;
+use quote::{
+    format_ident,
+    quote,
+};
 
 /// Tokens which allow us to provide static error information in the generated output.
 pub struct ErrorDetails {
@@ -29,6 +32,9 @@ pub struct ErrorDetails {
     // A function which will live in an impl block for our `DispatchError`,
     // to statically return details for known error types:
     pub dispatch_error_impl_fn: TokenStream2,
+    // A function which will live in the same impl block, returning a runtime
+    // independent `DispatchErrorKind` for any variant of our `DispatchError`:
+    pub dispatch_error_kind_fn: TokenStream2,
 }
 
 impl ErrorDetails {
@@ -37,6 +43,7 @@ impl ErrorDetails {
         ErrorDetails {
             type_def: quote!(),
             dispatch_error_impl_fn: quote!(compile_error!(#err_lit_str)),
+            dispatch_error_kind_fn: quote!(),
         }
     }
 }
@@ -46,6 +53,15 @@ impl ErrorDetails {
 /// nicer error strings with documentation. To do this, we emit the type we'll return instances of,
 /// and a function that returns such an instance for all of the error codes seen in the metadata.
 pub fn generate_error_details(metadata: &RuntimeMetadataV14) -> ErrorDetails {
+    let dispatch_error_kind_fn = match dispatch_error_kind_fn(metadata) {
+        Ok(kind_fn) => kind_fn,
+        Err(e) => {
+            let err_string =
+                format!("Failed to generate error details from metadata: {}", e);
+            return ErrorDetails::emit_compile_error(&err_string)
+        }
+    };
+
     let errors = match pallet_errors(metadata) {
         Ok(errors) => errors,
         Err(e) => {
@@ -91,9 +107,76 @@ pub fn generate_error_details(metadata: &RuntimeMetadataV14) -> ErrorDetails {
                 }
             }
         },
+        dispatch_error_kind_fn,
     }
 }
 
+/// Generate a `kind()` function which maps each variant of the runtime's `DispatchError`
+/// onto a `::subxt::DispatchErrorKind`. Variants are matched by name, so that this keeps
+/// working as variants are added to (or removed from) the `DispatchError` over time.
+fn dispatch_error_kind_fn(
+    metadata: &RuntimeMetadataV14,
+) -> Result<TokenStream2, InvalidMetadataError> {
+    let (type_id, dispatch_error) = metadata
+        .types
+        .types()
+        .iter()
+        .enumerate()
+        .find(|(_, ty)| ty.ty().path().segments() == ["sp_runtime", "DispatchError"])
+        .ok_or(InvalidMetadataError::MissingDispatchError)?;
+    let variants = match dispatch_error.ty().type_def() {
+        scale_info::TypeDef::Variant(var) => var.variants(),
+        _ => return Err(InvalidMetadataError::TypeDefNotVariant(type_id as u32)),
+    };
+
+    let match_arms = variants.iter().map(|var| {
+        let name = var.name();
+        let variant = format_ident!("{}", name);
+        let is_named = var.fields().iter().any(|f| f.name().is_some());
+        match (name.as_str(), var.fields().len()) {
+            (
+                "Other" | "CannotLookup" | "BadOrigin" | "ConsumerRemaining" | "NoProviders"
+                | "TooManyConsumers",
+                0,
+            ) => {
+                quote! { Self::#variant => ::subxt::DispatchErrorKind::#variant }
+            }
+            ("Module", _) if is_named => {
+                quote! {
+                    Self::Module { index, error, .. } => ::subxt::DispatchErrorKind::Module {
+                        index: *index,
+                        error: *error,
+                    }
+                }
+            }
+            ("Token" | "Arithmetic" | "Transactional", 1) if !is_named => {
+                quote! {
+                    Self::#variant(nested) => ::subxt::DispatchErrorKind::from_nested(#name, nested)
+                }
+            }
+            (_, 0) => {
+                quote! { Self::#variant => ::subxt::DispatchErrorKind::Unknown(#name.to_string()) }
+            }
+            _ if is_named => {
+                quote! { Self::#variant { .. } => ::subxt::DispatchErrorKind::Unknown(#name.to_string()) }
+            }
+            _ => {
+                quote! { Self::#variant(..) => ::subxt::DispatchErrorKind::Unknown(#name.to_string()) }
+            }
+        }
+    });
+
+    Ok(quote! {
+        /// Describe this error in a way that doesn't depend on the types generated for this
+        /// runtime, so that non-pallet errors (like token and arithmetic errors) can be matched on.
+        pub fn kind(&self) -> ::subxt::DispatchErrorKind {
+            match self {
+                #( #match_arms, )*
+            }
+        }
+    })
+}
+
 fn pallet_errors(
     metadata: &RuntimeMetadataV14,
 ) -> Result<Vec<ErrorMetadata>, InvalidMetadataError> {
@@ -146,6 +229,7 @@ struct ErrorMetadata {
 enum InvalidMetadataError {
     MissingType(u32),
     TypeDefNotVariant(u32),
+    MissingDispatchError,
 }
 
 impl std::fmt::Display for InvalidMetadataError {
@@ -157,6 +241,9 @@ impl std::fmt::Display for InvalidMetadataError {
             InvalidMetadataError::TypeDefNotVariant(n) => {
                 write!(f, "Type {} was not a variant/enum type", n)
             }
+            InvalidMetadataError::MissingDispatchError => {
+                write!(f, "sp_runtime::DispatchError missing from type registry")
+            }
         }
     }
 }
@@ -178,9 +178,17 @@ impl RuntimeGenerator {
             })
             .collect::<Vec<_>>();
 
+        let outer_call_type_id = outer_call_type_id(&self.metadata);
+
         let modules = pallets_with_mod_names.iter().map(|(pallet, mod_name)| {
             let calls = if let Some(ref calls) = pallet.calls {
-                calls::generate_calls(&type_gen, pallet, calls, types_mod_ident)
+                calls::generate_calls(
+                    &type_gen,
+                    pallet,
+                    calls,
+                    outer_call_type_id,
+                    types_mod_ident,
+                )
             } else {
                 quote!()
             };
@@ -197,6 +205,15 @@ impl RuntimeGenerator {
                 quote!()
             };
 
+            let error = if let Some(ref error) = pallet.error {
+                let error_type = type_gen.resolve_type_path(error.ty.id(), &[]);
+                quote! {
+                    pub type Error = #error_type;
+                }
+            } else {
+                quote!()
+            };
+
             let constants_mod = if !pallet.constants.is_empty() {
                 constants::generate_constants(
                     &type_gen,
@@ -213,6 +230,7 @@ impl RuntimeGenerator {
                     use super::#types_mod_ident;
                     #calls
                     #event
+                    #error
                     #storage_mod
                     #constants_mod
                 }
@@ -239,6 +257,26 @@ impl RuntimeGenerator {
             }
         };
 
+        let outer_error_variants = self.metadata.pallets.iter().filter_map(|p| {
+            let variant_name = format_ident!("{}", p.name);
+            let mod_name = format_ident!("{}", p.name.to_string().to_snake_case());
+            let index = proc_macro2::Literal::u8_unsuffixed(p.index);
+
+            p.error.as_ref().map(|_| {
+                quote! {
+                    #[codec(index = #index)]
+                    #variant_name(#mod_name::Error),
+                }
+            })
+        });
+
+        let outer_error = quote! {
+            #derives
+            pub enum ModuleError {
+                #( #outer_error_variants )*
+            }
+        };
+
         let mod_ident = item_mod_ir.ident;
         let pallets_with_constants =
             pallets_with_mod_names
@@ -262,6 +300,7 @@ impl RuntimeGenerator {
         let error_details = errors::generate_error_details(&self.metadata);
         let error_type = error_details.type_def;
         let error_fn = error_details.dispatch_error_impl_fn;
+        let error_kind_fn = error_details.dispatch_error_kind_fn;
 
         let default_account_data_ident = format_ident!("DefaultAccountData");
         let default_account_data_impl = generate_default_account_data_impl(
@@ -280,6 +319,7 @@ impl RuntimeGenerator {
                 use super::#mod_ident as root_mod;
 
                 #outer_event
+                #outer_error
                 #( #modules )*
                 #types_mod
 
@@ -290,6 +330,18 @@ impl RuntimeGenerator {
                 #error_type
                 impl DispatchError {
                     #error_fn
+
+                    #error_kind_fn
+
+                    /// Decode a `DispatchError::Module` into the generated error type of the
+                    /// pallet that it came from, so that it can be matched on.
+                    pub fn module_error(&self) -> Option<ModuleError> {
+                        if let Self::Module { index, error } = self {
+                            ::subxt::codec::Decode::decode(&mut &[*index, *error][..]).ok()
+                        } else {
+                            None
+                        }
+                    }
                 }
 
                 #default_account_data_impl
@@ -349,6 +401,14 @@ impl RuntimeGenerator {
                     pub async fn subscribe_finalized(&self) -> Result<::subxt::events::EventSubscription<'a, T, Event>, ::subxt::BasicError> {
                         ::subxt::events::subscribe_finalized::<T, Event>(self.client).await
                     }
+
+                    pub async fn subscribe_best_chain(&self) -> Result<::subxt::events::BestChainEvents<'a, T, Event>, ::subxt::BasicError> {
+                        ::subxt::events::subscribe_best_chain::<T, Event>(self.client).await
+                    }
+
+                    pub fn between(&self, from_block: u64, to_block: u64) -> ::subxt::events::EventsBetween<'a, T, Event> {
+                        ::subxt::events::between::<T, Event>(self.client, from_block, to_block)
+                    }
                 }
 
                 pub struct ConstantsApi;
@@ -464,6 +524,18 @@ fn generate_default_account_data_impl(
     })
 }
 
+/// Returns the type id of the runtime's outer `Call` enum, which is a type parameter of its
+/// extrinsic type.
+fn outer_call_type_id(metadata: &RuntimeMetadataV14) -> Option<u32> {
+    let extrinsic_ty = metadata.types.resolve(metadata.extrinsic.ty.id())?;
+    extrinsic_ty
+        .type_params()
+        .iter()
+        .find(|param| param.name() == "Call")
+        .and_then(|param| param.ty())
+        .map(|ty| ty.id())
+}
+
 pub fn generate_structs_from_variants<'a, F>(
     type_gen: &'a TypeGenerator,
     type_id: u32,
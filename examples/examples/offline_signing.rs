@@ -0,0 +1,91 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! This example constructs and signs a balance transfer without making any RPC calls at all,
+//! for instance so that transactions can be prepared on an air-gapped machine. Everything
+//! that would normally be obtained from a node (metadata, runtime version, genesis hash,
+//! account nonce and a recent block for the transaction era) must be provided up front.
+//!
+//! The resulting extrinsic bytes can be submitted from elsewhere, for instance by using
+//! `client.rpc().submit_extrinsic(..)` on a connected machine.
+
+use codec::{
+    Decode,
+    Encode,
+};
+use sp_keyring::AccountKeyring;
+use subxt::{
+    extrinsic::create_signed,
+    rpc::RuntimeVersion,
+    sp_core::H256,
+    sp_runtime::generic::Era,
+    Call,
+    DefaultConfig,
+    DefaultExtra,
+    DefaultExtraParams,
+    Metadata,
+    PairSigner,
+};
+
+#[subxt::subxt(runtime_metadata_path = "examples/polkadot_metadata.scale")]
+pub mod polkadot {}
+
+type Transfer = polkadot::balances::calls::Transfer;
+
+#[async_std::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    env_logger::init();
+
+    // Details about the target chain, which we'd usually obtain from a node:
+    let metadata = Metadata::decode(&mut &include_bytes!("polkadot_metadata.scale")[..])?;
+    let runtime_version = RuntimeVersion {
+        spec_version: 9130,
+        transaction_version: 8,
+        other: Default::default(),
+    };
+    let genesis_hash = H256::from_slice(&hex::decode(
+        "91b171bb158e2d3848fa23a9f1c25182fb8e20313b2c1eb49219da7a70ce90c3",
+    )?);
+
+    // Details about the account and a recent block, which we must know in advance:
+    let nonce = 0;
+    let (checkpoint_number, checkpoint_hash) = (0, genesis_hash);
+
+    // Encode the call data using the metadata:
+    let transfer = Transfer {
+        dest: AccountKeyring::Bob.to_account_id().into(),
+        value: 10_000,
+    };
+    let call = metadata.pallet(Transfer::PALLET)?.encode_call(&transfer)?;
+
+    // Sign the transaction, making it valid for 64 blocks from the checkpoint block:
+    let signer = PairSigner::new(AccountKeyring::Alice.pair());
+    let params = DefaultExtraParams::new()
+        .era(Era::mortal(64, checkpoint_number), checkpoint_hash);
+    let extrinsic = create_signed::<DefaultConfig, DefaultExtra<DefaultConfig>>(
+        &runtime_version,
+        genesis_hash,
+        nonce,
+        call,
+        &signer,
+        params,
+    )
+    .await?;
+
+    println!("Signed extrinsic: 0x{}", hex::encode(extrinsic.encode()));
+
+    Ok(())
+}
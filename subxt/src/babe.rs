@@ -0,0 +1,179 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Inspect the BABE epochs of a chain, for instance to predict when a validator will
+//! produce blocks.
+//!
+//! The types here are copied from `sp-consensus-babe` and `sc-consensus-babe-rpc` to avoid
+//! a dependency on those crates. Therefore they must be kept compatible with those types
+//! from the target substrate version.
+
+use crate::{
+    error::BasicError,
+    rpc::Rpc,
+    Config,
+};
+use codec::{
+    Decode,
+    Encode,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use sp_core::sr25519;
+
+/// The slots of the current epoch in which one of the node's keys may author a block, as
+/// returned from the RPC call `babe_epochAuthorship`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpochAuthorship {
+    /// The slots in which the key wins the VRF lottery, and so is a primary author.
+    pub primary: Vec<u64>,
+    /// The slots in which the key is assigned as a secondary author.
+    pub secondary: Vec<u64>,
+    /// The slots in which the key is assigned as a secondary author, and authors blocks
+    /// with a VRF output.
+    pub secondary_vrf: Vec<u64>,
+}
+
+/// A BABE epoch, as returned from the `BabeApi_current_epoch` and `BabeApi_next_epoch`
+/// runtime APIs.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct Epoch {
+    /// The index of the epoch.
+    pub epoch_index: u64,
+    /// The first slot of the epoch.
+    pub start_slot: u64,
+    /// The number of slots in the epoch.
+    pub duration: u64,
+    /// The authorities of the epoch, along with their weights.
+    pub authorities: Vec<(sr25519::Public, u64)>,
+    /// The randomness used to assign slots in the epoch.
+    pub randomness: [u8; 32],
+    /// The configuration of the epoch.
+    pub config: EpochConfiguration,
+}
+
+impl Epoch {
+    /// The slot after the last slot of the epoch, which is the first slot of the next one.
+    pub fn end_slot(&self) -> u64 {
+        self.start_slot.saturating_add(self.duration)
+    }
+}
+
+/// The configuration of a BABE epoch.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct EpochConfiguration {
+    /// The probability of a slot having a primary author, as a fraction `(numerator,
+    /// denominator)`.
+    pub c: (u64, u64),
+    /// Which kinds of slot may be authored.
+    pub allowed_slots: AllowedSlots,
+}
+
+/// Which kinds of slot may be authored in a BABE epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum AllowedSlots {
+    /// Only primary slots.
+    PrimarySlots,
+    /// Primary and secondary slots.
+    PrimaryAndSecondaryPlainSlots,
+    /// Primary and secondary slots, with secondary authors providing a VRF output.
+    PrimaryAndSecondaryVRFSlots,
+}
+
+/// Fetch the current epoch at the given block (or the latest block if `None` is given),
+/// using the `BabeApi_current_epoch` runtime API.
+pub async fn current_epoch<T: Config>(
+    rpc: &Rpc<T>,
+    at: Option<T::Hash>,
+) -> Result<Epoch, BasicError> {
+    let bytes = rpc.call("BabeApi_current_epoch", &[], at).await?;
+    Ok(Epoch::decode(&mut &bytes[..])?)
+}
+
+/// Fetch the next epoch at the given block (or the latest block if `None` is given), using
+/// the `BabeApi_next_epoch` runtime API.
+pub async fn next_epoch<T: Config>(
+    rpc: &Rpc<T>,
+    at: Option<T::Hash>,
+) -> Result<Epoch, BasicError> {
+    let bytes = rpc.call("BabeApi_next_epoch", &[], at).await?;
+    Ok(Epoch::decode(&mut &bytes[..])?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn epoch(start_slot: u64, duration: u64) -> Epoch {
+        Epoch {
+            epoch_index: 3,
+            start_slot,
+            duration,
+            authorities: vec![(sr25519::Public::from_raw([1; 32]), 1)],
+            randomness: [2; 32],
+            config: EpochConfiguration {
+                c: (1, 4),
+                allowed_slots: AllowedSlots::PrimaryAndSecondaryVRFSlots,
+            },
+        }
+    }
+
+    #[test]
+    fn epochs_end_where_the_next_one_starts() {
+        let current = epoch(1_000, 600);
+        assert_eq!(current.end_slot(), 1_600);
+        let next = Epoch {
+            epoch_index: current.epoch_index + 1,
+            start_slot: current.end_slot(),
+            ..current.clone()
+        };
+        assert_eq!(next.end_slot(), 2_200);
+
+        // The end slot saturates rather than overflowing.
+        assert_eq!(epoch(u64::MAX - 10, 600).end_slot(), u64::MAX);
+    }
+
+    #[test]
+    fn epochs_are_decoded_in_the_runtime_api_layout() {
+        let mut bytes = Vec::new();
+        3u64.encode_to(&mut bytes);
+        1_000u64.encode_to(&mut bytes);
+        600u64.encode_to(&mut bytes);
+        vec![([1u8; 32], 1u64)].encode_to(&mut bytes);
+        [2u8; 32].encode_to(&mut bytes);
+        (1u64, 4u64).encode_to(&mut bytes);
+        2u8.encode_to(&mut bytes);
+
+        assert_eq!(Epoch::decode(&mut &bytes[..]).unwrap(), epoch(1_000, 600));
+    }
+
+    #[test]
+    fn epoch_authorship_is_deserialized() {
+        let json = r#"{"primary":[1,5],"secondary":[2],"secondaryVrf":[3,4]}"#;
+        let authorship: EpochAuthorship = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            authorship,
+            EpochAuthorship {
+                primary: vec![1, 5],
+                secondary: vec![2],
+                secondary_vrf: vec![3, 4],
+            }
+        );
+    }
+}
@@ -0,0 +1,585 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+    error::BasicError,
+    events::{
+        self,
+        Events,
+        EventsMetadata,
+        RawEventDetails,
+    },
+    extrinsic::ExtrinsicDetails,
+    Client,
+    Config,
+};
+use codec::{
+    Decode,
+    Encode,
+};
+use derivative::Derivative;
+use futures::{
+    Stream,
+    StreamExt,
+};
+use jsonrpsee::core::client::Subscription;
+use sp_runtime::{
+    traits::{
+        Header,
+        UniqueSaturatedInto,
+    },
+    ConsensusEngineId,
+    DigestItem,
+};
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    task::{
+        Context,
+        Poll,
+    },
+};
+
+/// Client for fetching blocks, along with their decoded extrinsics and the events those
+/// extrinsics emitted.
+pub struct BlocksClient<'a, T: Config> {
+    client: &'a Client<T>,
+}
+
+impl<'a, T: Config> Clone for BlocksClient<'a, T> {
+    fn clone(&self) -> Self {
+        Self {
+            client: self.client,
+        }
+    }
+}
+
+impl<'a, T: Config> BlocksClient<'a, T> {
+    /// Create a new [`BlocksClient`]
+    pub fn new(client: &'a Client<T>) -> Self {
+        Self { client }
+    }
+
+    /// Fetch the block with the given hash, or `None` if the node doesn't know of it.
+    /// The extrinsics of the block are decoded into their signer, nonce, tip and call,
+    /// and `Evs` is the outermost event enum that the events of the block are decoded
+    /// into (see [`Client::events_at()`]). Blocks from another runtime than the one the
+    /// client was built for (for instance, from before a runtime upgrade) are decoded
+    /// with the metadata of that runtime, which is fetched when first needed.
+    ///
+    /// ```no_run
+    /// # #[async_std::main]
+    /// # async fn main() -> Result<(), subxt::BasicError> {
+    /// use subxt::{ClientBuilder, DefaultConfig};
+    ///
+    /// let client = ClientBuilder::new().build::<DefaultConfig>().await?;
+    /// let block_hash = client.rpc().finalized_head().await?;
+    /// let block = client
+    ///     .blocks()
+    ///     .at::<()>(block_hash)
+    ///     .await?
+    ///     .expect("the finalized block is known");
+    /// for extrinsic in block.extrinsics_with_events()? {
+    ///     println!(
+    ///         "{}::{} emitted {} events",
+    ///         extrinsic.details.pallet,
+    ///         extrinsic.details.call,
+    ///         extrinsic.events.len()
+    ///     );
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn at<Evs: Decode>(
+        &self,
+        block_hash: T::Hash,
+    ) -> Result<Option<BlockDetails<'a, T, Evs>>, BasicError>
+    where
+        T::Signature: Decode,
+    {
+        let block = match self.client.rpc().block(Some(block_hash)).await? {
+            Some(block) => block.block,
+            None => return Ok(None),
+        };
+        let metadata = match self.client.other_metadata_at(block_hash).await? {
+            Some(metadata) => EventsMetadata::Other(metadata),
+            None => EventsMetadata::Client(self.client.metadata()),
+        };
+        let extrinsics = block
+            .extrinsics
+            .iter()
+            .map(|extrinsic| ExtrinsicDetails::decode(&metadata, &extrinsic.encode()))
+            .collect::<Result<_, _>>()?;
+        let events = events::at_with_metadata(self.client, block_hash, metadata).await?;
+
+        Ok(Some(BlockDetails {
+            header: block.header,
+            extrinsics,
+            events,
+        }))
+    }
+
+    /// Subscribe to the headers of new best blocks. Blocks may be skipped over, and may
+    /// later be replaced by another fork.
+    pub async fn subscribe_best_headers(
+        &self,
+    ) -> Result<HeaderSubscription<T>, BasicError> {
+        let subscription = self.client.rpc().subscribe_blocks().await?;
+        Ok(HeaderSubscription { subscription })
+    }
+
+    /// Subscribe to the headers of newly finalized blocks. If several blocks are finalized
+    /// at once, only the header of the last one is handed back.
+    pub async fn subscribe_finalized_headers(
+        &self,
+    ) -> Result<HeaderSubscription<T>, BasicError> {
+        let subscription = self.client.rpc().subscribe_finalized_blocks().await?;
+        Ok(HeaderSubscription { subscription })
+    }
+
+    /// Subscribe to the headers of every newly finalized block, in order. Unlike
+    /// [`BlocksClient::subscribe_finalized_headers()`], when several blocks are finalized
+    /// at once the headers of the blocks skipped over are fetched, so that each finalized
+    /// block is handed back exactly once. See [`AllFinalizedHeaders`].
+    pub async fn subscribe_all_finalized_headers(
+        &self,
+    ) -> Result<AllFinalizedHeaders<'a, T>, BasicError> {
+        let state = AllFinalizedHeadersState {
+            client: self.client,
+            subscription: self.subscribe_finalized_headers().await?,
+            last_block_number: None,
+            pending: VecDeque::new(),
+        };
+        let headers = futures::stream::unfold(state, |mut state| {
+            async move {
+                let header = state.next_header().await?;
+                Some((header, state))
+            }
+        });
+        Ok(AllFinalizedHeaders {
+            headers: Box::pin(headers),
+        })
+    }
+}
+
+/// A subscription to the headers of every finalized block that implements [`Stream`],
+/// created by [`BlocksClient::subscribe_all_finalized_headers()`].
+///
+/// Blocks finalized before the first notification aren't handed back. If fetching the
+/// headers of skipped blocks fails, the error is handed back and they're fetched again
+/// when the next block is finalized, so no block is missed.
+#[derive(Derivative)]
+#[derivative(Debug(bound = ""))]
+pub struct AllFinalizedHeaders<'a, T: Config> {
+    #[derivative(Debug = "ignore")]
+    headers: Pin<Box<dyn Stream<Item = Result<T::Header, BasicError>> + 'a>>,
+}
+
+impl<'a, T: Config> Unpin for AllFinalizedHeaders<'a, T> {}
+
+impl<'a, T: Config> Stream for AllFinalizedHeaders<'a, T> {
+    type Item = Result<T::Header, BasicError>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        self.headers.poll_next_unpin(cx)
+    }
+}
+
+struct AllFinalizedHeadersState<'a, T: Config> {
+    client: &'a Client<T>,
+    subscription: HeaderSubscription<T>,
+    // The number of the last block handed back, if any.
+    last_block_number: Option<u64>,
+    // Headers waiting to be handed back, in order.
+    pending: VecDeque<T::Header>,
+}
+
+impl<'a, T: Config> AllFinalizedHeadersState<'a, T> {
+    async fn next_header(&mut self) -> Option<Result<T::Header, BasicError>> {
+        loop {
+            if let Some(header) = self.pending.pop_front() {
+                return Some(Ok(header))
+            }
+
+            let header = match self.subscription.next().await? {
+                Ok(header) => header,
+                Err(e) => return Some(Err(e)),
+            };
+            let number = header.block_number();
+            let first_missed = match self.last_block_number {
+                // We've handed this block back already.
+                Some(last) if number <= last => continue,
+                Some(last) => last + 1,
+                None => number,
+            };
+
+            // Walk back through the parents of the block to the last one handed back.
+            // Finalized blocks can't be reverted, so these are the blocks that were
+            // finalized along with it.
+            let mut missed = Vec::new();
+            let mut parent_hash = header.parent();
+            for _ in first_missed..number {
+                match self.client.rpc().header(Some(parent_hash)).await {
+                    Ok(Some(parent)) => {
+                        parent_hash = parent.parent();
+                        missed.push(parent);
+                    }
+                    Ok(None) => {
+                        return Some(Err(format!(
+                            "Finalized block {:?} not found",
+                            parent_hash
+                        )
+                        .into()))
+                    }
+                    Err(e) => return Some(Err(e)),
+                }
+            }
+
+            self.last_block_number = Some(number);
+            self.pending.extend(missed.into_iter().rev());
+            self.pending.push_back(header);
+        }
+    }
+}
+
+/// A subscription to block headers that implements [`Stream`], created by
+/// [`BlocksClient::subscribe_best_headers()`] or
+/// [`BlocksClient::subscribe_finalized_headers()`]. See [`HeaderExt`] for helpers to
+/// inspect the headers handed back.
+#[derive(Derivative)]
+#[derivative(Debug(bound = ""))]
+pub struct HeaderSubscription<T: Config> {
+    subscription: Subscription<T::Header>,
+}
+
+impl<T: Config> Unpin for HeaderSubscription<T> {}
+
+impl<T: Config> Stream for HeaderSubscription<T> {
+    type Item = Result<T::Header, BasicError>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        self.subscription
+            .poll_next_unpin(cx)
+            .map(|header| header.map(|header| header.map_err(Into::into)))
+    }
+}
+
+/// Helpers for inspecting block headers, implemented for every [`Header`] (and so for
+/// every [`Config::Header`]).
+pub trait HeaderExt: Header {
+    /// The number of the block, as a `u64` whatever the block number type of the chain.
+    fn block_number(&self) -> u64 {
+        (*self.number()).unique_saturated_into()
+    }
+
+    /// The hash of the parent of the block.
+    fn parent(&self) -> Self::Hash {
+        *self.parent_hash()
+    }
+
+    /// The digest items of the block, such as the pre-runtime digest of the block author
+    /// and the seal.
+    fn digest_logs(&self) -> &[DigestItem] {
+        self.digest().logs()
+    }
+
+    /// The data of the pre-runtime digest item of the consensus engine given (for
+    /// instance `*b"BABE"` or `*b"aura"`), which identifies the slot and author of the
+    /// block.
+    fn pre_runtime_digest(&self, engine_id: ConsensusEngineId) -> Option<&[u8]> {
+        self.digest_logs().iter().find_map(|item| {
+            match item.as_pre_runtime() {
+                Some((id, data)) if id == engine_id => Some(data),
+                _ => None,
+            }
+        })
+    }
+}
+
+impl<H: Header> HeaderExt for H {}
+
+/// A block, along with its decoded extrinsics and events, as fetched by
+/// [`BlocksClient::at()`].
+#[derive(Derivative)]
+#[derivative(Debug(
+    bound = "T::Address: std::fmt::Debug, T::Signature: std::fmt::Debug"
+))]
+pub struct BlockDetails<'a, T: Config, Evs: Decode> {
+    header: T::Header,
+    extrinsics: Vec<ExtrinsicDetails<T>>,
+    events: Events<'a, T, Evs>,
+}
+
+impl<'a, T: Config, Evs: Decode> BlockDetails<'a, T, Evs> {
+    /// The hash of the block.
+    pub fn hash(&self) -> T::Hash {
+        self.events.block_hash()
+    }
+
+    /// The number of the block.
+    pub fn number(&self) -> T::BlockNumber {
+        *self.header.number()
+    }
+
+    /// The header of the block.
+    pub fn header(&self) -> &T::Header {
+        &self.header
+    }
+
+    /// The extrinsics of the block, in order. Use [`ExtrinsicDetails::as_call()`] to
+    /// decode the calls into the generated call types.
+    pub fn extrinsics(&self) -> &[ExtrinsicDetails<T>] {
+        &self.extrinsics
+    }
+
+    /// The events emitted in the block, including those emitted during its
+    /// initialization and finalization rather than by an extrinsic.
+    pub fn events(&self) -> &Events<'a, T, Evs> {
+        &self.events
+    }
+
+    /// The extrinsics of the block, in order, each along with its index in the block and
+    /// the events it emitted.
+    pub fn extrinsics_with_events(
+        &self,
+    ) -> Result<Vec<BlockExtrinsic<'_, T>>, BasicError> {
+        let mut extrinsics: Vec<_> = self
+            .extrinsics
+            .iter()
+            .enumerate()
+            .map(|(index, details)| {
+                BlockExtrinsic {
+                    index: index as u32,
+                    details,
+                    events: Vec::new(),
+                }
+            })
+            .collect();
+        for event in self.events.iter_raw() {
+            let event = event?;
+            let extrinsic = event
+                .extrinsic_index()
+                .and_then(|index| extrinsics.get_mut(index as usize));
+            if let Some(extrinsic) = extrinsic {
+                extrinsic.events.push(event);
+            }
+        }
+        Ok(extrinsics)
+    }
+}
+
+/// An extrinsic of a block along with the events it emitted, as handed back by
+/// [`BlockDetails::extrinsics_with_events()`].
+#[derive(Derivative)]
+#[derivative(Debug(
+    bound = "T::Address: std::fmt::Debug, T::Signature: std::fmt::Debug"
+))]
+pub struct BlockExtrinsic<'b, T: Config> {
+    /// The index of the extrinsic in the block.
+    pub index: u32,
+    /// The decoded extrinsic.
+    pub details: &'b ExtrinsicDetails<T>,
+    /// The events emitted while applying the extrinsic, in order.
+    pub events: Vec<RawEventDetails<T::Hash>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rpc::MockRpcClient,
+        ClientBuilder,
+        DefaultConfig,
+    };
+    use frame_metadata::{
+        v14::{
+            ExtrinsicMetadata,
+            PalletCallMetadata,
+            PalletMetadata,
+            RuntimeMetadataLastVersion,
+        },
+        RuntimeMetadataPrefixed,
+    };
+    use scale_info::{
+        meta_type,
+        TypeInfo,
+    };
+    use serde_json::json;
+    use sp_core::H256;
+    use sp_runtime::{
+        generic,
+        traits::BlakeTwo256,
+        Digest,
+        OpaqueExtrinsic,
+    };
+
+    type T = DefaultConfig;
+
+    #[allow(dead_code, non_camel_case_types)]
+    #[derive(TypeInfo)]
+    enum TestCall {
+        remark { remark: Vec<u8> },
+    }
+
+    // The metadata of a runtime with a single pallet, with the name given, at index 5.
+    fn metadata(pallet: &'static str) -> RuntimeMetadataPrefixed {
+        let pallets = vec![PalletMetadata {
+            name: pallet,
+            storage: None,
+            calls: Some(PalletCallMetadata {
+                ty: meta_type::<TestCall>(),
+            }),
+            event: None,
+            constants: vec![],
+            error: None,
+            index: 5,
+        }];
+        let extrinsic = ExtrinsicMetadata {
+            ty: meta_type::<()>(),
+            version: 4,
+            signed_extensions: vec![],
+        };
+        RuntimeMetadataLastVersion::new(pallets, extrinsic, meta_type::<()>()).into()
+    }
+
+    // A chain of headers, from the genesis block to the block with the number given.
+    fn chain(last: u32) -> Vec<<T as Config>::Header> {
+        let mut headers: Vec<<T as Config>::Header> = Vec::new();
+        for number in 0..=last {
+            let parent_hash = headers.last().map(|parent| parent.hash());
+            headers.push(Header::new(
+                number,
+                Default::default(),
+                Default::default(),
+                parent_hash.unwrap_or_default(),
+                Default::default(),
+            ));
+        }
+        headers
+    }
+
+    #[async_std::test]
+    async fn every_finalized_header_is_handed_back_once_in_order() {
+        let chain = chain(8);
+        // Finality jumps from block 1 to 4 and from 5 to 8, and block 4 is notified twice.
+        let notified = [1, 4, 4, 5, 8].map(|n| chain[n].clone());
+        // The skipped blocks are fetched walking back from the block finalized.
+        let mock = MockRpcClient::new()
+            .with_chain(metadata("System"))
+            .add_subscription("chain_subscribeFinalizedHeads", notified)
+            .add_response("chain_getHeader", &chain[3])
+            .add_response("chain_getHeader", &chain[2])
+            .add_response("chain_getHeader", &chain[7])
+            .add_response("chain_getHeader", &chain[6]);
+        let client = ClientBuilder::new()
+            .set_client(mock.clone())
+            .build::<T>()
+            .await
+            .unwrap();
+
+        let headers: Vec<_> = client
+            .blocks()
+            .subscribe_all_finalized_headers()
+            .await
+            .unwrap()
+            .map(|header| header.unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(headers, chain[1..].to_vec());
+        let fetched: Vec<_> = [3, 2, 7, 6]
+            .iter()
+            .map(|&n| serde_json::json!([chain[n].hash()]))
+            .collect();
+        assert_eq!(mock.requests_to("chain_getHeader"), fetched);
+    }
+
+    #[async_std::test]
+    async fn blocks_are_decoded_with_the_metadata_of_their_runtime() {
+        // An unsigned extrinsic calling the pallet at index 5.
+        let extrinsic = (4u8, 5u8, 0u8, vec![1u8, 2]).encode();
+        let block = generic::SignedBlock {
+            block: generic::Block {
+                header: chain(1).remove(1),
+                extrinsics: vec![
+                    OpaqueExtrinsic::from_bytes(&extrinsic.encode()).unwrap()
+                ],
+            },
+            justifications: None,
+        };
+        // The client is built for a runtime whose pallet 5 is called "Old", but the block
+        // is from a later runtime, which renamed it "New".
+        let mock = MockRpcClient::new()
+            .with_chain(metadata("Old"))
+            .add_response(
+                "state_getRuntimeVersion",
+                json!({ "specVersion": 2, "transactionVersion": 1 }),
+            )
+            .add_response(
+                "state_getMetadata",
+                sp_core::Bytes(metadata("New").encode()),
+            )
+            .add_response("chain_getBlock", block)
+            .add_response("state_getStorage", serde_json::Value::Null);
+        let client = ClientBuilder::new()
+            .set_client(mock)
+            .build::<T>()
+            .await
+            .unwrap();
+
+        let block = client
+            .blocks()
+            .at::<()>(H256::repeat_byte(1))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let extrinsic = &block.extrinsics()[0];
+        assert_eq!(
+            (extrinsic.pallet.as_str(), extrinsic.call.as_str()),
+            ("New", "remark")
+        );
+    }
+
+    #[test]
+    fn headers_are_inspected() {
+        let header = generic::Header::<u32, BlakeTwo256>::new(
+            5,
+            H256::repeat_byte(1),
+            H256::repeat_byte(2),
+            H256::repeat_byte(3),
+            Digest {
+                logs: vec![
+                    DigestItem::PreRuntime(*b"aura", vec![1, 2]),
+                    DigestItem::PreRuntime(*b"BABE", vec![3, 4]),
+                    DigestItem::Seal(*b"BABE", vec![5]),
+                ],
+            },
+        );
+
+        assert_eq!(header.block_number(), 5);
+        assert_eq!(header.parent(), H256::repeat_byte(3));
+        assert_eq!(header.digest_logs().len(), 3);
+        assert_eq!(header.pre_runtime_digest(*b"BABE"), Some(&[3, 4][..]));
+        assert_eq!(header.pre_runtime_digest(*b"FRNK"), None);
+    }
+}
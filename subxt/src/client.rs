@@ -14,41 +14,140 @@
 // You should have received a copy of the GNU General Public License
 // along with subxt.  If not, see <http://www.gnu.org/licenses/>.
 
-use futures::future;
-use sp_runtime::traits::Hash;
+use futures::{
+    future,
+    Future,
+    StreamExt,
+};
 pub use sp_runtime::traits::SignedExtension;
+use sp_runtime::{
+    generic::Era,
+    traits::{
+        Hash,
+        Header,
+        One,
+        UniqueSaturatedInto,
+    },
+};
 
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+use crate::rpc::Certificate;
+#[cfg(feature = "proxy")]
+use crate::rpc::Proxy;
+#[cfg(feature = "metrics")]
+use crate::rpc::{
+    MeteredClient,
+    RpcMetrics,
+};
 use crate::{
-    error::BasicError,
+    blocks::BlocksClient,
+    error::{
+        BasicError,
+        TransactionError,
+    },
+    events::{
+        self,
+        EventDecoders,
+        EventDetails,
+        EventSummary,
+        Events,
+        EventsBetween,
+        SubscriptionOverflow,
+        TopicSubscription,
+    },
     extrinsic::{
         self,
+        ExtrinsicDetails,
+        MetadataHashInfo,
         SignedExtra,
+        SignedPayload,
         Signer,
         UncheckedExtrinsic,
     },
+    indexer::EventIndexer,
     rpc::{
+        CertificateStore,
+        ConnectionEvent,
+        DryRunResult,
+        ExponentialBackoff,
+        FeeDetails,
+        FollowedChain,
+        RateLimitedClient,
+        ReconnectingClient,
+        RetryPolicy,
+        RetryingClient,
         Rpc,
-        RpcClient,
+        RpcConnection,
+        RuntimeDispatchInfo,
         RuntimeVersion,
         SystemProperties,
+        WsClientConfig,
     },
+    runtime_api::RuntimeApiClient,
     storage::StorageClient,
-    transaction::TransactionProgress,
+    transaction::{
+        substrate_statuses,
+        transaction_watch_statuses,
+        TransactionProgress,
+        TransactionStatuses,
+    },
     AccountData,
     Call,
     Config,
+    Encoded,
+    Event,
     Metadata,
 };
-use codec::Decode;
+use codec::{
+    Decode,
+    Encode,
+};
 use derivative::Derivative;
-use std::sync::Arc;
+use futures_timer::Delay;
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time::Duration,
+};
 
 /// ClientBuilder for constructing a Client.
 #[derive(Default)]
 pub struct ClientBuilder {
     url: Option<String>,
-    client: Option<RpcClient>,
+    fallback_urls: Vec<String>,
+    client: Option<RpcConnection>,
+    reconnect: Option<ExponentialBackoff>,
+    primary_check_interval: Option<Duration>,
+    on_connection_event: Option<Arc<dyn Fn(&ConnectionEvent) + Send + Sync>>,
     page_size: Option<u32>,
+    stale_nonce_retries: u32,
+    default_tip: u128,
+    default_mortality: Option<u64>,
+    transaction_watch: bool,
+    chain_head_backend: bool,
+    subscription_buffer_size: Option<usize>,
+    subscription_overflow: SubscriptionOverflow,
+    connection_timeout: Option<Duration>,
+    request_timeout: Option<Duration>,
+    subscription_timeout: Option<Duration>,
+    headers: Vec<(String, String)>,
+    certificate_store: Option<CertificateStore>,
+    #[cfg(any(feature = "native-tls", feature = "rustls"))]
+    root_certificates: Vec<Certificate>,
+    #[cfg(any(feature = "native-tls", feature = "rustls"))]
+    accept_invalid_certificates: bool,
+    ping_interval: Option<Duration>,
+    max_inactivity: Option<Duration>,
+    #[cfg(feature = "proxy")]
+    proxy: Option<Proxy>,
+    rate_limit: Option<(u32, u32)>,
+    retry_policy: Option<RetryPolicy>,
+    #[cfg(feature = "metrics")]
+    metrics: Option<RpcMetrics>,
+    event_decoders: EventDecoders,
 }
 
 impl ClientBuilder {
@@ -56,54 +155,458 @@ impl ClientBuilder {
     pub fn new() -> Self {
         Self {
             url: None,
+            fallback_urls: Vec::new(),
             client: None,
+            reconnect: None,
+            primary_check_interval: None,
+            on_connection_event: None,
             page_size: None,
+            stale_nonce_retries: 0,
+            default_tip: 0,
+            default_mortality: None,
+            transaction_watch: false,
+            chain_head_backend: false,
+            subscription_buffer_size: None,
+            subscription_overflow: SubscriptionOverflow::Error,
+            connection_timeout: None,
+            request_timeout: None,
+            subscription_timeout: None,
+            headers: Vec::new(),
+            certificate_store: None,
+            #[cfg(any(feature = "native-tls", feature = "rustls"))]
+            root_certificates: Vec::new(),
+            #[cfg(any(feature = "native-tls", feature = "rustls"))]
+            accept_invalid_certificates: false,
+            ping_interval: None,
+            max_inactivity: None,
+            #[cfg(feature = "proxy")]
+            proxy: None,
+            rate_limit: None,
+            retry_policy: None,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+            event_decoders: EventDecoders::default(),
         }
     }
 
-    /// Sets the jsonrpsee client.
-    pub fn set_client<C: Into<RpcClient>>(mut self, client: C) -> Self {
+    /// Sets the jsonrpsee client (or a [`ReconnectingClient`]).
+    pub fn set_client<C: Into<RpcConnection>>(mut self, client: C) -> Self {
         self.client = Some(client.into());
         self
     }
 
     /// Set the substrate rpc address.
+    ///
+    /// To talk to a co-located node over its IPC endpoint (a Unix domain socket) instead,
+    /// build a client with `rpc::ipc_client()` (with the `ipc` feature) and hand
+    /// it to [`Self::set_client()`].
     pub fn set_url<P: Into<String>>(mut self, url: P) -> Self {
         self.url = Some(url.into());
         self
     }
 
+    /// Reconnect to the node if the connection is lost, waiting according to the backoff
+    /// given between attempts. See [`ReconnectingClient`]. By default, the client doesn't
+    /// reconnect. This is ignored if a custom client is provided with [`Self::set_client()`].
+    pub fn set_reconnect(mut self, backoff: ExponentialBackoff) -> Self {
+        self.reconnect = Some(backoff);
+        self
+    }
+
+    /// Set several substrate rpc addresses to connect to. The first is the primary one,
+    /// and the rest are failed over to in turn if the one we're connected to can't be
+    /// reached. While connected to any other than the primary, we periodically check if
+    /// the primary is healthy again, and move back to it if so (see
+    /// [`Self::set_primary_check_interval()`]). This implies [`Self::set_reconnect()`],
+    /// using the default backoff if none was set.
+    pub fn set_urls<I, P>(mut self, urls: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<String>,
+    {
+        let mut urls = urls.into_iter().map(Into::into);
+        self.url = urls.next();
+        self.fallback_urls = urls.collect();
+        self
+    }
+
+    /// Set how often to check whether the primary address given to [`Self::set_urls()`]
+    /// is healthy again while connected to another. Defaults to 30 seconds.
+    pub fn set_primary_check_interval(mut self, interval: Duration) -> Self {
+        self.primary_check_interval = Some(interval);
+        self
+    }
+
+    /// Call the handler given whenever the state of the connection to the node changes,
+    /// for instance to flip a health check or pause work while it's down. This only has
+    /// an effect along with [`Self::set_reconnect()`] (or [`Self::set_urls()`]); see
+    /// [`ReconnectingClient::on_connection_event()`].
+    pub fn set_connection_event_handler(
+        mut self,
+        handler: impl Fn(&ConnectionEvent) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_connection_event = Some(Arc::new(handler));
+        self
+    }
+
     /// Set the page size.
     pub fn set_page_size(mut self, size: u32) -> Self {
         self.page_size = Some(size);
         self
     }
 
+    /// Set the number of times that submitting a signed extrinsic is retried, with a
+    /// freshly fetched account nonce, if the node rejects it because its nonce is stale
+    /// (for instance because another transaction from the same account was submitted in
+    /// the meantime), or because another transaction with the same nonce is already
+    /// waiting in the pool. Defaults to 0, ie no retries.
+    pub fn set_stale_nonce_retries(mut self, retries: u32) -> Self {
+        self.stale_nonce_retries = retries;
+        self
+    }
+
+    /// Set the tip given by submissions which don't provide their own extrinsic parameters.
+    /// Defaults to 0.
+    pub fn set_default_tip(mut self, tip: u128) -> Self {
+        self.default_tip = tip;
+        self
+    }
+
+    /// Make submissions which don't provide their own extrinsic parameters mortal, valid
+    /// for the given number of blocks (rounded up to a power of two) from the latest
+    /// finalized block at the time of signing. By default, such submissions are immortal.
+    pub fn set_default_mortality(mut self, period: u64) -> Self {
+        self.default_mortality = Some(period);
+        self
+    }
+
+    /// Watch the progress of submissions using `transactionWatch_unstable_submitAndWatch` from
+    /// the new JSON-RPC spec, rather than the legacy `author_submitAndWatchExtrinsic`. The
+    /// events it reports are mapped onto the same [`crate::TransactionStatus`]es (see
+    /// [`TransactionProgress::from_transaction_watch()`]). Defaults to false.
+    pub fn set_transaction_watch(mut self, enabled: bool) -> Self {
+        self.transaction_watch = enabled;
+        self
+    }
+
+    /// Set whether the client follows the chain using the `chainHead` methods of the new
+    /// JSON-RPC spec, rather than the legacy `state_` and `chain_` methods. The genesis hash,
+    /// metadata and runtime version are then fetched with them, and so are storage entries
+    /// and events at the blocks being followed (falling back to the legacy methods for
+    /// other blocks, and for iterating over storage), while submissions are watched with
+    /// `transactionWatch_unstable_submitAndWatch` (falling back to
+    /// `author_submitAndWatchExtrinsic` if the node doesn't support it). Defaults to false.
+    pub fn set_chain_head_backend(mut self, enabled: bool) -> Self {
+        self.chain_head_backend = enabled;
+        self
+    }
+
+    /// Set the maximum number of notifications buffered for each subscription, waiting to be
+    /// handled. If a subscription falls further behind than this, it's closed by the client,
+    /// and then event subscriptions act according to [`Self::set_subscription_overflow()`].
+    /// Defaults to 4096. This is ignored if a custom client is provided with
+    /// [`Self::set_client()`].
+    pub fn set_subscription_buffer_size(mut self, size: usize) -> Self {
+        self.subscription_buffer_size = Some(size);
+        self
+    }
+
+    /// Set what event subscriptions do when the underlying block subscription is closed
+    /// because its buffer is full. Defaults to [`SubscriptionOverflow::Error`].
+    pub fn set_subscription_overflow(mut self, overflow: SubscriptionOverflow) -> Self {
+        self.subscription_overflow = overflow;
+        self
+    }
+
+    /// Set how long to wait for the connection to the node to be established. Defaults to
+    /// 10 seconds. This is ignored if a custom client is provided with [`Self::set_client()`].
+    pub fn set_connection_timeout(mut self, timeout: Duration) -> Self {
+        self.connection_timeout = Some(timeout);
+        self
+    }
+
+    /// Set how long to wait for the response to each request before giving up on it.
+    /// Defaults to 60 seconds. This is ignored if a custom client is provided with
+    /// [`Self::set_client()`].
+    pub fn set_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    /// Set how long the block subscriptions underlying event subscriptions (and the
+    /// [`crate::indexer::EventIndexer`]) can go without seeing a new block before they're
+    /// considered to have failed, in which case they resubscribe or hand back an error as
+    /// they would if the subscription had failed for any other reason. By default, they
+    /// wait for new blocks forever.
+    pub fn set_subscription_timeout(mut self, timeout: Duration) -> Self {
+        self.subscription_timeout = Some(timeout);
+        self
+    }
+
+    /// Add a header to send with the WebSocket handshake when connecting to the node, for
+    /// instance an API key required by a hosted RPC provider. This can be called several
+    /// times to add several headers. This is ignored if a custom client is provided with
+    /// [`Self::set_client()`].
+    pub fn set_header(
+        mut self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Authenticate with the node using the bearer token given, which is sent in an
+    /// `Authorization` header. See [`Self::set_header()`].
+    pub fn set_bearer_token(self, token: impl AsRef<str>) -> Self {
+        let value = format!("Bearer {}", token.as_ref());
+        self.set_header("Authorization", value)
+    }
+
+    /// Set where to find the root certificates trusted when connecting to the node over
+    /// TLS. See [`WsClientConfig::certificate_store`]. This is ignored if a custom client is
+    /// provided with [`Self::set_client()`].
+    pub fn set_certificate_store(mut self, store: CertificateStore) -> Self {
+        self.certificate_store = Some(store);
+        self
+    }
+
+    /// Trust the root certificate given when connecting to the node over TLS, in addition
+    /// to those in the certificate store, for instance that of a private certificate
+    /// authority. See [`WsClientConfig::root_certificates`]. This is ignored if a custom
+    /// client is provided with [`Self::set_client()`]. Available with the `native-tls` or
+    /// `rustls` feature.
+    #[cfg(any(feature = "native-tls", feature = "rustls"))]
+    pub fn add_root_certificate(mut self, certificate: Certificate) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    /// Accept any certificate the node presents when connecting over TLS, without
+    /// verifying it. This lets anyone intercept the connection, so should only be used
+    /// for development nodes with self-signed certificates. See
+    /// [`WsClientConfig::accept_invalid_certificates`]. This is ignored if a custom client
+    /// is provided with [`Self::set_client()`]. Available with the `native-tls` or `rustls`
+    /// feature.
+    #[cfg(any(feature = "native-tls", feature = "rustls"))]
+    pub fn set_accept_invalid_certificates(mut self, accept: bool) -> Self {
+        self.accept_invalid_certificates = accept;
+        self
+    }
+
+    /// Send a request to the node to keep the connection alive whenever nothing has been
+    /// received from it for the interval given, so that load balancers and proxies don't
+    /// drop idle connections (which otherwise leaves subscriptions silently waiting
+    /// forever). See [`WsClientConfig::ping_interval`]. By default, nothing is sent. This
+    /// is ignored if a custom client is provided with [`Self::set_client()`].
+    pub fn set_ping_interval(mut self, interval: Duration) -> Self {
+        self.ping_interval = Some(interval);
+        self
+    }
+
+    /// Consider the connection dead, and close it, if nothing is received from the node
+    /// for the duration given. Combined with [`Self::set_reconnect()`], this reconnects
+    /// to the node rather than waiting forever on a connection that was dropped without
+    /// being closed. See [`WsClientConfig::max_inactivity`]. By default, the connection
+    /// is never considered dead. This is ignored if a custom client is provided with
+    /// [`Self::set_client()`].
+    pub fn set_max_inactivity(mut self, max_inactivity: Duration) -> Self {
+        self.max_inactivity = Some(max_inactivity);
+        self
+    }
+
+    /// Connect to the node through the SOCKS5 or HTTP proxy given, rather than directly.
+    /// This applies to every address given to [`Self::set_urls()`], and is ignored if a
+    /// custom client is provided with [`Self::set_client()`]. Available with the `proxy`
+    /// feature.
+    #[cfg(feature = "proxy")]
+    pub fn set_proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Limit the calls made to the node to `requests_per_second` on average, allowing
+    /// bursts of up to `burst` calls at once, so as not to be banned by public endpoints.
+    /// Calls over the limit wait until they're allowed through. This applies to a custom
+    /// client provided with [`Self::set_client()`] too. See [`RateLimitedClient`].
+    pub fn set_rate_limit(mut self, requests_per_second: u32, burst: u32) -> Self {
+        self.rate_limit = Some((requests_per_second, burst));
+        self
+    }
+
+    /// Retry calls which fail for transient reasons (such as the connection being lost, or
+    /// the node being overloaded) according to the policy given. Calls which could have
+    /// side effects if made twice, such as submitting an extrinsic, are never retried. By
+    /// default, calls aren't retried. This applies to a custom client provided with
+    /// [`Self::set_client()`] too. See [`RetryingClient`].
+    pub fn set_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Record Prometheus metrics for the calls made to the node (see [`RpcMetrics`]). Each
+    /// attempt at a call is recorded, including any retries. This applies to a custom
+    /// client provided with [`Self::set_client()`] too. Available with the `metrics`
+    /// feature.
+    #[cfg(feature = "metrics")]
+    pub fn set_metrics(mut self, metrics: RpcMetrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Register a decoder for an event which isn't described by the metadata (for instance,
+    /// one from a pallet that was added to a live chain after the metadata was obtained),
+    /// so that dynamically decoding the events of a block doesn't fail when it's seen.
+    /// `decoder` is handed the bytes following the pallet and variant index of such an
+    /// event, and needs to consume exactly the bytes of the event's fields from them.
+    /// If the metadata does describe the event, the decoder is ignored.
+    ///
+    /// Events decoded this way have no metadata to describe their fields, so they can't
+    /// be turned into JSON, and are never found by [`crate::events::Events::filter_account()`].
+    pub fn register_event_decoder<F>(
+        mut self,
+        (pallet_index, pallet): (u8, impl Into<String>),
+        (variant_index, variant): (u8, impl Into<String>),
+        decoder: F,
+    ) -> Self
+    where
+        F: Fn(&mut &[u8]) -> Result<(), codec::Error> + Send + Sync + 'static,
+    {
+        self.event_decoders.register(
+            (pallet_index, pallet.into()),
+            (variant_index, variant.into()),
+            Arc::new(decoder),
+        );
+        self
+    }
+
+    /// Register the statically known event `E` as the event found at the given pallet and
+    /// variant index, if the metadata doesn't describe an event there. See
+    /// [`Self::register_event_decoder()`].
+    pub fn register_event<E: Event + 'static>(
+        self,
+        pallet_index: u8,
+        variant_index: u8,
+    ) -> Self {
+        self.register_event_decoder(
+            (pallet_index, E::PALLET),
+            (variant_index, E::EVENT),
+            |input| E::decode(input).map(|_| ()),
+        )
+    }
+
     /// Creates a new Client.
     pub async fn build<T: Config>(self) -> Result<Client<T>, BasicError> {
         let client = if let Some(client) = self.client {
             client
         } else {
             let url = self.url.as_deref().unwrap_or("ws://127.0.0.1:9944");
-            crate::rpc::ws_client(url).await?
+            let defaults = WsClientConfig::default();
+            let config = WsClientConfig {
+                buffer_size: self
+                    .subscription_buffer_size
+                    .unwrap_or(defaults.buffer_size),
+                connection_timeout: self
+                    .connection_timeout
+                    .unwrap_or(defaults.connection_timeout),
+                request_timeout: self.request_timeout.unwrap_or(defaults.request_timeout),
+                headers: self.headers,
+                certificate_store: self
+                    .certificate_store
+                    .unwrap_or(defaults.certificate_store),
+                #[cfg(any(feature = "native-tls", feature = "rustls"))]
+                root_certificates: self.root_certificates,
+                #[cfg(any(feature = "native-tls", feature = "rustls"))]
+                accept_invalid_certificates: self.accept_invalid_certificates,
+                ping_interval: self.ping_interval,
+                max_inactivity: self.max_inactivity,
+                #[cfg(feature = "proxy")]
+                proxy: self.proxy,
+            };
+            let reconnect = match (self.reconnect, self.fallback_urls.is_empty()) {
+                (None, true) => None,
+                (backoff, _) => Some(backoff.unwrap_or_default()),
+            };
+            match reconnect {
+                Some(backoff) => {
+                    let mut urls = vec![url.to_string()];
+                    urls.extend(self.fallback_urls.iter().cloned());
+                    let mut client =
+                        ReconnectingClient::with_endpoints(urls, config, backoff).await?;
+                    if let Some(interval) = self.primary_check_interval {
+                        client = client.primary_check_interval(interval);
+                    }
+                    if let Some(handler) = self.on_connection_event {
+                        client.set_connection_event_handler(handler);
+                    }
+                    client.into()
+                }
+                None => {
+                    crate::rpc::ws_client_with_config(url, &config)
+                        .await?
+                        .into()
+                }
+            }
+        };
+        #[cfg(feature = "metrics")]
+        let client = match self.metrics {
+            Some(metrics) => MeteredClient::new(client, metrics).into(),
+            None => client,
+        };
+        let client = match self.rate_limit {
+            Some((requests_per_second, burst)) => {
+                RateLimitedClient::new(client, requests_per_second, burst).into()
+            }
+            None => client,
+        };
+        // Retries go through the rate limit, if there is one.
+        let client = match self.retry_policy {
+            Some(policy) => RetryingClient::new(client, policy).into(),
+            None => client,
         };
         let rpc = Rpc::new(client);
-        let (metadata, genesis_hash, runtime_version, properties) = future::join4(
-            rpc.metadata(),
-            rpc.genesis_hash(),
-            rpc.runtime_version(None),
-            rpc.system_properties(),
-        )
-        .await;
-        let metadata = metadata?;
+        let (metadata, genesis_hash, runtime_version, chain_head) = if self
+            .chain_head_backend
+        {
+            let mut backend = rpc.chain_head(true).await?;
+            let (finalized, runtime_version) = backend.initialized().await?;
+            let runtime_version = runtime_version.ok_or_else(|| {
+                BasicError::Other(
+                    "chainHead_unstable_follow didn't report the runtime".into(),
+                )
+            })?;
+            let (metadata, genesis_hash) =
+                future::join(backend.metadata(finalized), backend.genesis_hash()).await;
+            let chain = FollowedChain::new(backend, finalized);
+            let chain = Arc::new(futures::lock::Mutex::new(chain));
+            (metadata?, genesis_hash?, runtime_version, Some(chain))
+        } else {
+            let (metadata, genesis_hash, runtime_version) = future::join3(
+                rpc.metadata(),
+                rpc.genesis_hash(),
+                rpc.runtime_version(None),
+            )
+            .await;
+            (metadata?, genesis_hash?, runtime_version?, None)
+        };
+        let properties = rpc.system_properties().await;
 
         Ok(Client {
             rpc,
-            genesis_hash: genesis_hash?,
+            genesis_hash,
             metadata: Arc::new(metadata),
             properties: properties.unwrap_or_else(|_| Default::default()),
-            runtime_version: runtime_version?,
+            runtime_version,
             iter_page_size: self.page_size.unwrap_or(10),
+            stale_nonce_retries: self.stale_nonce_retries,
+            default_tip: self.default_tip,
+            default_mortality: self.default_mortality,
+            subscription_overflow: self.subscription_overflow,
+            subscription_timeout: self.subscription_timeout,
+            transaction_watch: self.transaction_watch,
+            event_decoders: self.event_decoders,
+            other_metadata: Default::default(),
+            chain_head,
         })
     }
 }
@@ -118,6 +621,19 @@ pub struct Client<T: Config> {
     properties: SystemProperties,
     runtime_version: RuntimeVersion,
     iter_page_size: u32,
+    stale_nonce_retries: u32,
+    default_tip: u128,
+    default_mortality: Option<u64>,
+    subscription_overflow: SubscriptionOverflow,
+    subscription_timeout: Option<Duration>,
+    transaction_watch: bool,
+    event_decoders: EventDecoders,
+    // The metadata of runtimes other than the one described by `metadata` (for instance,
+    // runtimes which the chain has been upgraded to since), keyed by spec version.
+    other_metadata: Arc<Mutex<HashMap<u32, Arc<Metadata>>>>,
+    // The chain being followed with the `chainHead` methods, if the client was built with
+    // `ClientBuilder::set_chain_head_backend()`. It's shared by every clone of the client.
+    chain_head: Option<Arc<futures::lock::Mutex<FollowedChain<T>>>>,
 }
 
 impl<T: Config> std::fmt::Debug for Client<T> {
@@ -126,10 +642,13 @@ impl<T: Config> std::fmt::Debug for Client<T> {
             .field("rpc", &"<Rpc>")
             .field("genesis_hash", &self.genesis_hash)
             .field("metadata", &"<Metadata>")
-            .field("events_decoder", &"<EventsDecoder>")
+            .field("event_decoders", &self.event_decoders)
             .field("properties", &self.properties)
             .field("runtime_version", &self.runtime_version)
             .field("iter_page_size", &self.iter_page_size)
+            .field("stale_nonce_retries", &self.stale_nonce_retries)
+            .field("default_tip", &self.default_tip)
+            .field("default_mortality", &self.default_mortality)
             .finish()
     }
 }
@@ -157,14 +676,261 @@ impl<T: Config> Client<T> {
         &self.properties
     }
 
+    /// Computes the hash of the runtime's metadata which the
+    /// [`crate::extrinsic::CheckMetadataHash`] extension commits to, from the metadata,
+    /// runtime version and properties of the chain. Provide it to submissions with
+    /// [`crate::extrinsic::DefaultExtraParams::metadata_hash`].
+    ///
+    /// This fails if the chain's properties don't give the symbol and number of decimals
+    /// of its token.
+    pub fn metadata_hash(&self) -> Result<[u8; 32], BasicError> {
+        let info = MetadataHashInfo::from_chain(
+            &self.metadata,
+            &self.runtime_version,
+            &self.properties,
+        )?;
+        Ok(extrinsic::metadata_hash(
+            self.metadata.runtime_metadata(),
+            &info,
+        )?)
+    }
+
     /// Returns the rpc client.
     pub fn rpc(&self) -> &Rpc<T> {
         &self.rpc
     }
 
+    /// Returns what event subscriptions do when their buffer overflows.
+    pub fn subscription_overflow(&self) -> SubscriptionOverflow {
+        self.subscription_overflow
+    }
+
+    /// Returns how long event subscriptions can go without seeing a new block before
+    /// they're considered to have failed, if at all.
+    pub fn subscription_timeout(&self) -> Option<Duration> {
+        self.subscription_timeout
+    }
+
+    /// Returns the metadata of the runtime at the block with the given hash, if that's a
+    /// different runtime from the one that [`Client::metadata()`] describes (for instance
+    /// because the runtime has been upgraded since the client was built). The metadata of
+    /// other runtimes is fetched once, and shared by clones of this client.
+    pub(crate) async fn other_metadata_at(
+        &self,
+        block_hash: T::Hash,
+    ) -> Result<Option<Arc<Metadata>>, BasicError> {
+        let spec_version = self
+            .rpc
+            .runtime_version(Some(block_hash))
+            .await?
+            .spec_version;
+        if spec_version == self.runtime_version.spec_version {
+            return Ok(None)
+        }
+        let cached = self
+            .other_metadata
+            .lock()
+            .expect("lock is never poisoned; qed")
+            .get(&spec_version)
+            .cloned();
+        if let Some(metadata) = cached {
+            return Ok(Some(metadata))
+        }
+        log::debug!("Fetching the metadata of runtime version {}", spec_version);
+        let metadata = Arc::new(self.rpc.metadata_at(Some(block_hash)).await?);
+        self.other_metadata
+            .lock()
+            .expect("lock is never poisoned; qed")
+            .insert(spec_version, metadata.clone());
+        Ok(Some(metadata))
+    }
+
+    /// Returns the decoders registered for events which the metadata doesn't describe.
+    pub(crate) fn event_decoders(&self) -> &EventDecoders {
+        &self.event_decoders
+    }
+
     /// Create a client for accessing runtime storage
     pub fn storage(&self) -> StorageClient<T> {
         StorageClient::new(&self.rpc, &self.metadata, self.iter_page_size)
+            .with_chain_head(self.chain_head.as_ref())
+    }
+
+    /// Create a client for calling runtime API functions
+    pub fn runtime_api(&self) -> RuntimeApiClient<T> {
+        RuntimeApiClient::new(&self.rpc)
+    }
+
+    /// Create a client for fetching blocks along with their extrinsics and events
+    pub fn blocks(&self) -> BlocksClient<T> {
+        BlocksClient::new(self)
+    }
+
+    /// Fetch the events emitted in the block with the given hash. `Evs` is the outermost
+    /// event enum that contains all of the possible events across all pallets, and is
+    /// what [`Events::iter()`] will statically decode each event into, along with the
+    /// [`crate::Phase`] in which it was emitted.
+    ///
+    /// If using the generated API, `api.events().at(block_hash)` does the same thing,
+    /// with `Evs` set to the generated `Event` type.
+    pub async fn events_at<Evs: Decode>(
+        &self,
+        block_hash: T::Hash,
+    ) -> Result<Events<'_, T, Evs>, BasicError> {
+        events::at(self, block_hash).await
+    }
+
+    /// Count the events of each kind in the block with the given hash, without decoding
+    /// them. See [`Events::summary()`].
+    pub async fn event_summary_at(
+        &self,
+        block_hash: T::Hash,
+    ) -> Result<EventSummary, BasicError> {
+        events::at::<T, ()>(self, block_hash).await?.summary()
+    }
+
+    /// Subscribe to the events deposited with the given topic, by watching the
+    /// `System::EventTopics` storage entry for it in each new best block. Whenever
+    /// events with the topic are deposited, the hash of the block is handed back along
+    /// with those events. See [`TopicSubscription`].
+    pub async fn subscribe_event_topic(
+        &self,
+        topic: T::Hash,
+    ) -> Result<TopicSubscription<'_, T>, BasicError> {
+        events::subscribe_topic(self, topic).await
+    }
+
+    /// Fetch the events from every block between `from_block` and `to_block` (inclusive).
+    /// The events of several blocks are fetched and decoded concurrently, but they're
+    /// handed back in block order. See [`EventsBetween`].
+    ///
+    /// If using the generated API, `api.events().between(from_block, to_block)` does the
+    /// same thing, with `Evs` set to the generated `Event` type.
+    pub fn events_between<Evs: Decode + 'static>(
+        &self,
+        from_block: u64,
+        to_block: u64,
+    ) -> EventsBetween<'_, T, Evs> {
+        events::between(self, from_block, to_block)
+    }
+
+    /// Subscribe to events from new blocks, and wait for the first event of type `Ev` which
+    /// satisfies the predicate provided. The event is handed back along with the hash of
+    /// the block that it was found in. If no such event is seen within the `timeout`
+    /// given, a [`TransactionError::Timeout`] error is returned.
+    ///
+    /// **Note:** These blocks haven't necessarily been finalized yet.
+    pub async fn wait_for_event<Ev: Event>(
+        &self,
+        predicate: impl Fn(&Ev) -> bool,
+        timeout: Duration,
+    ) -> Result<(T::Hash, EventDetails<Ev, T::Hash>), BasicError> {
+        let find_event = async {
+            let mut event_sub = events::subscribe::<T, ()>(self).await?;
+            while let Some(events) = event_sub.next().await {
+                let events = events?;
+                for ev in events.find_events::<Ev>() {
+                    let ev = ev?;
+                    if predicate(&ev.event) {
+                        return Ok((events.block_hash(), ev))
+                    }
+                }
+            }
+            Err(BasicError::Other("Event subscription ended".into()))
+        };
+        futures::pin_mut!(find_event);
+
+        match future::select(find_event, Delay::new(timeout)).await {
+            future::Either::Left((res, _)) => res,
+            future::Either::Right(_) => {
+                Err(TransactionError::Timeout { last_status: None }.into())
+            }
+        }
+    }
+
+    /// Fetch the extrinsics waiting in the node's transaction pool, decoded into their
+    /// signer, nonce, tip and call. Use [`ExtrinsicDetails::as_call()`] to decode the calls
+    /// into the generated call types.
+    pub async fn pending_extrinsics(&self) -> Result<Vec<ExtrinsicDetails<T>>, BasicError>
+    where
+        T::Signature: Decode,
+    {
+        self.rpc
+            .pending_extrinsics()
+            .await?
+            .iter()
+            .map(|extrinsic| ExtrinsicDetails::decode(&self.metadata, extrinsic))
+            .collect()
+    }
+
+    /// Create an [`EventIndexer`], which walks through finalized blocks (and optionally,
+    /// historical ones) and hands the events in them to the async handlers registered
+    /// for them. See the [`crate::indexer`] module for an example.
+    pub fn event_indexer(&self) -> EventIndexer<'_, T> {
+        EventIndexer::new(self)
+    }
+
+    /// Returns the extrinsic parameters used for submissions which don't provide their own,
+    /// built from the default tip and mortality configured on the [`ClientBuilder`]. For
+    /// mortal transactions, this fetches the latest finalized block to use as the
+    /// checkpoint of the era.
+    pub async fn default_params<X: SignedExtra<T>>(
+        &self,
+    ) -> Result<X::Parameters, BasicError> {
+        let (era, checkpoint) = match self.default_mortality {
+            Some(period) => {
+                let hash = self.rpc.finalized_head().await?;
+                let header = self.rpc.header(Some(hash)).await?.ok_or_else(|| {
+                    BasicError::Other(format!(
+                        "Header of finalized block {:?} not found",
+                        hash
+                    ))
+                })?;
+                let number = (*header.number()).unique_saturated_into();
+                (Era::mortal(period, number), Some(hash))
+            }
+            None => (Era::Immortal, None),
+        };
+        Ok(X::default_parameters(self.default_tip, era, checkpoint))
+    }
+
+    /// Submits an already signed and SCALE encoded extrinsic (for instance one signed by a
+    /// hardware wallet or another service) to the chain.
+    ///
+    /// Returns a [`TransactionProgress`], which can be used to track the status of the transaction
+    /// and obtain details about it, once it has made it into a block.
+    pub async fn submit_and_watch_raw<E: Decode, Evs: Decode>(
+        &self,
+        extrinsic: Vec<u8>,
+    ) -> Result<TransactionProgress<'_, T, E, Evs>, BasicError> {
+        let ext_hash = extrinsic::extrinsic_hash::<T>(&extrinsic);
+        let statuses = self.watch_extrinsic(Encoded(extrinsic)).await?;
+
+        Ok(TransactionProgress::from_statuses(statuses, self, ext_hash))
+    }
+
+    // Submit the extrinsic and watch its progress, with `transactionWatch_unstable_submitAndWatch`
+    // if the client was configured to use it, or `author_submitAndWatchExtrinsic` otherwise.
+    // With the chainHead backend, the former is tried first, and the latter used if the node
+    // wouldn't start the subscription (in which case nothing has been submitted).
+    pub(crate) async fn watch_extrinsic<X: Encode>(
+        &self,
+        extrinsic: X,
+    ) -> Result<TransactionStatuses<T::Hash>, BasicError> {
+        if self.transaction_watch {
+            let sub = self.rpc.transaction_watch(extrinsic).await?;
+            return Ok(transaction_watch_statuses(sub))
+        }
+        if self.chain_head.is_some() {
+            match self.rpc.transaction_watch(&extrinsic).await {
+                Ok(sub) => return Ok(transaction_watch_statuses(sub)),
+                Err(e) => {
+                    log::debug!("Falling back to author_submitAndWatchExtrinsic: {}", e);
+                }
+            }
+        }
+        let sub = self.rpc.watch_extrinsic(extrinsic).await?;
+        Ok(substrate_statuses(sub))
     }
 
     /// Convert the client to a runtime api wrapper for custom runtime access.
@@ -201,7 +967,8 @@ where
         }
     }
 
-    /// Creates and signs an extrinsic and submits it to the chain.
+    /// Creates and signs an extrinsic and submits it to the chain, using the default
+    /// parameters configured on the [`ClientBuilder`].
     ///
     /// Returns a [`TransactionProgress`], which can be used to track the status of the transaction
     /// and obtain details about it, once it has made it into a block.
@@ -215,19 +982,129 @@ where
         <A as AccountData>::AccountId: From<<T as Config>::AccountId>,
         <A as AccountData>::Index: Into<<T as Config>::Index>,
     {
-        // Sign the call data to create our extrinsic.
-        let extrinsic = self.create_signed(signer, Default::default()).await?;
+        let params = self.client.default_params::<X>().await?;
+        self.sign_and_submit_then_watch_with_params(signer, params)
+            .await
+    }
+
+    /// Creates and signs an extrinsic using the provided parameters (for instance, to
+    /// set a tip), and submits it to the chain.
+    ///
+    /// Returns a [`TransactionProgress`], which can be used to track the status of the transaction
+    /// and obtain details about it, once it has made it into a block.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(pallet = C::PALLET, call = C::FUNCTION))
+    )]
+    pub async fn sign_and_submit_then_watch_with_params(
+        self,
+        signer: &(dyn Signer<T, X> + Send + Sync),
+        params: X::Parameters,
+    ) -> Result<TransactionProgress<'client, T, E, Evs>, BasicError>
+    where
+        <<X as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned:
+            Send + Sync + 'static,
+        <A as AccountData>::AccountId: From<<T as Config>::AccountId>,
+        <A as AccountData>::Index: Into<<T as Config>::Index>,
+    {
+        // Sign the call data to create our extrinsic, and submit and watch for
+        // transaction progress.
+        let (extrinsic, statuses) = self
+            .sign_and_submit_with_retries(signer, params, |extrinsic| {
+                self.client.watch_extrinsic(extrinsic)
+            })
+            .await?;
 
         // Get a hash of the extrinsic (we'll need this later).
         let ext_hash = T::Hashing::hash_of(&extrinsic);
 
-        // Submit and watch for transaction progress.
-        let sub = self.client.rpc().watch_extrinsic(extrinsic).await?;
+        Ok(TransactionProgress::from_statuses(
+            statuses,
+            self.client,
+            ext_hash,
+        ))
+    }
+
+    /// Creates and signs an extrinsic using the given nonce, which should be the nonce of
+    /// a transaction from the same account that is still pending in the transaction pool,
+    /// and submits it to the chain in order to replace that transaction.
+    ///
+    /// The transaction pool only accepts the replacement if it has a higher priority than
+    /// the pending transaction, for instance because it pays a higher tip (see
+    /// [`crate::DefaultExtraParams::tip`]). If it's accepted, the pending transaction will
+    /// report a [`TransactionStatus::Usurped`](crate::TransactionStatus::Usurped) status.
+    ///
+    /// Returns a [`TransactionProgress`], which can be used to track the status of the
+    /// replacement transaction and obtain details about it, once it has made it into a block.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(pallet = C::PALLET, call = C::FUNCTION))
+    )]
+    pub async fn replace_then_watch(
+        self,
+        signer: &(dyn Signer<T, X> + Send + Sync),
+        nonce: T::Index,
+        params: X::Parameters,
+    ) -> Result<TransactionProgress<'client, T, E, Evs>, BasicError>
+    where
+        <<X as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned:
+            Send + Sync + 'static,
+    {
+        let extrinsic = self.create_signed_with_nonce(signer, nonce, params).await?;
+        let ext_hash = T::Hashing::hash_of(&extrinsic);
+        let statuses = self.client.watch_extrinsic(extrinsic).await?;
+
+        Ok(TransactionProgress::from_statuses(
+            statuses,
+            self.client,
+            ext_hash,
+        ))
+    }
 
-        Ok(TransactionProgress::new(sub, self.client, ext_hash))
+    /// Creates an unsigned extrinsic and submits it to the chain.
+    ///
+    /// Only calls which the runtime explicitly allows to be submitted unsigned will be
+    /// accepted; see [`extrinsic::create_unsigned`].
+    ///
+    /// Returns a [`TransactionProgress`], which can be used to track the status of the transaction
+    /// and obtain details about it, once it has made it into a block.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(pallet = C::PALLET, call = C::FUNCTION))
+    )]
+    pub async fn submit_unsigned_then_watch(
+        self,
+    ) -> Result<TransactionProgress<'client, T, E, Evs>, BasicError> {
+        let extrinsic = self.create_unsigned()?;
+        let ext_hash = T::Hashing::hash_of(&extrinsic);
+        let statuses = self.client.watch_extrinsic(extrinsic).await?;
+
+        Ok(TransactionProgress::from_statuses(
+            statuses,
+            self.client,
+            ext_hash,
+        ))
+    }
+
+    /// Creates an unsigned extrinsic and submits it to the chain for block inclusion.
+    ///
+    /// Returns `Ok` with the extrinsic hash if it is valid extrinsic.
+    ///
+    /// # Note
+    ///
+    /// Success does not mean the extrinsic has been included in the block, just that it is valid
+    /// and has been included in the transaction pool.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(pallet = C::PALLET, call = C::FUNCTION))
+    )]
+    pub async fn submit_unsigned(self) -> Result<T::Hash, BasicError> {
+        let extrinsic = self.create_unsigned()?;
+        self.client.rpc().submit_extrinsic(extrinsic).await
     }
 
-    /// Creates and signs an extrinsic and submits to the chain for block inclusion.
+    /// Creates and signs an extrinsic and submits to the chain for block inclusion, using the
+    /// default parameters configured on the [`ClientBuilder`].
     ///
     /// Returns `Ok` with the extrinsic hash if it is valid extrinsic.
     ///
@@ -245,8 +1122,149 @@ where
         <A as AccountData>::AccountId: From<<T as Config>::AccountId>,
         <A as AccountData>::Index: Into<<T as Config>::Index>,
     {
-        let extrinsic = self.create_signed(signer, Default::default()).await?;
-        self.client.rpc().submit_extrinsic(extrinsic).await
+        let params = self.client.default_params::<X>().await?;
+        self.sign_and_submit_with_params(signer, params).await
+    }
+
+    /// Creates and signs an extrinsic using the provided parameters (for instance, to
+    /// set a tip), and submits it to the chain for block inclusion.
+    ///
+    /// Returns `Ok` with the extrinsic hash if it is valid extrinsic.
+    ///
+    /// # Note
+    ///
+    /// Success does not mean the extrinsic has been included in the block, just that it is valid
+    /// and has been included in the transaction pool.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(pallet = C::PALLET, call = C::FUNCTION))
+    )]
+    pub async fn sign_and_submit_with_params(
+        self,
+        signer: &(dyn Signer<T, X> + Send + Sync),
+        params: X::Parameters,
+    ) -> Result<T::Hash, BasicError>
+    where
+        <<X as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned:
+            Send + Sync + 'static,
+        <A as AccountData>::AccountId: From<<T as Config>::AccountId>,
+        <A as AccountData>::Index: Into<<T as Config>::Index>,
+    {
+        let (_, hash) = self
+            .sign_and_submit_with_retries(signer, params, |extrinsic| {
+                self.client.rpc().submit_extrinsic(extrinsic)
+            })
+            .await?;
+        Ok(hash)
+    }
+
+    /// Signs the extrinsic and submits it using the given function. If the node rejects it
+    /// because its nonce is stale, or is already used by a transaction in the pool, it's
+    /// signed again using a freshly fetched nonce and resubmitted, up to the number of
+    /// times configured with [`ClientBuilder::set_stale_nonce_retries`].
+    async fn sign_and_submit_with_retries<R, F, Fut>(
+        &self,
+        signer: &(dyn Signer<T, X> + Send + Sync),
+        params: X::Parameters,
+        submit: F,
+    ) -> Result<(UncheckedExtrinsic<T, X>, R), BasicError>
+    where
+        <<X as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned:
+            Send + Sync + 'static,
+        <A as AccountData>::AccountId: From<<T as Config>::AccountId>,
+        <A as AccountData>::Index: Into<<T as Config>::Index>,
+        F: Fn(Encoded) -> Fut,
+        Fut: Future<Output = Result<R, BasicError>>,
+    {
+        let mut retries = self.client.stale_nonce_retries;
+        let mut nonce = match signer.nonce() {
+            Some(nonce) => nonce,
+            None => self.fetch_nonce(signer.account_id()).await?,
+        };
+        loop {
+            let extrinsic = self
+                .create_signed_with_nonce(signer, nonce, params.clone())
+                .await?;
+            match submit(Encoded(extrinsic.encode())).await {
+                Err(err) if retries > 0 && is_stale_nonce_error(&err) => {
+                    log::debug!("Stale nonce, retrying with a new one: {}", err);
+                    retries -= 1;
+                    let fetched = self.fetch_nonce(signer.account_id()).await?;
+                    // The nonce stored on chain doesn't count the transactions waiting in
+                    // the pool, so it may well be the one that clashed.
+                    nonce = if is_priority_too_low_error(&err) {
+                        fetched.max(nonce.saturating_add(One::one()))
+                    } else {
+                        fetched
+                    };
+                }
+                res => return res.map(|r| (extrinsic, r)),
+            }
+        }
+    }
+
+    /// Creates and signs an extrinsic, and asks the node for an estimate of its weight and
+    /// the fee that would be paid to include it in a block. The extrinsic is not submitted.
+    pub async fn estimate_fee(
+        &self,
+        signer: &(dyn Signer<T, X> + Send + Sync),
+    ) -> Result<RuntimeDispatchInfo, BasicError>
+    where
+        <<X as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned:
+            Send + Sync + 'static,
+        <A as AccountData>::AccountId: From<<T as Config>::AccountId>,
+        <A as AccountData>::Index: Into<<T as Config>::Index>,
+    {
+        let extrinsic = self
+            .create_signed(signer, self.client.default_params::<X>().await?)
+            .await?;
+        self.client
+            .rpc()
+            .payment_query_info(&extrinsic.encode(), None)
+            .await
+    }
+
+    /// Creates and signs an extrinsic, and asks the node for a breakdown of the inclusion
+    /// fee that would be paid for it. The extrinsic is not submitted.
+    pub async fn estimate_fee_details(
+        &self,
+        signer: &(dyn Signer<T, X> + Send + Sync),
+    ) -> Result<FeeDetails, BasicError>
+    where
+        <<X as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned:
+            Send + Sync + 'static,
+        <A as AccountData>::AccountId: From<<T as Config>::AccountId>,
+        <A as AccountData>::Index: Into<<T as Config>::Index>,
+    {
+        let extrinsic = self
+            .create_signed(signer, self.client.default_params::<X>().await?)
+            .await?;
+        self.client
+            .rpc()
+            .payment_query_fee_details(&extrinsic.encode(), None)
+            .await
+    }
+
+    /// Creates and signs an extrinsic, and dry runs it against the state of the block
+    /// with the given hash (or the best block if `None`). This tells us whether the
+    /// extrinsic would be valid, and whether dispatching it would succeed or fail (and
+    /// if so, with what error), without submitting it or paying any fees.
+    pub async fn dry_run(
+        &self,
+        signer: &(dyn Signer<T, X> + Send + Sync),
+        at: Option<T::Hash>,
+    ) -> Result<DryRunResult<E>, BasicError>
+    where
+        <<X as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned:
+            Send + Sync + 'static,
+        <A as AccountData>::AccountId: From<<T as Config>::AccountId>,
+        <A as AccountData>::Index: Into<<T as Config>::Index>,
+    {
+        let extrinsic = self
+            .create_signed(signer, self.client.default_params::<X>().await?)
+            .await?;
+        let result_bytes = self.client.rpc().dry_run(&extrinsic.encode(), at).await?;
+        Ok(DryRunResult::decode(&result_bytes)?)
     }
 
     /// Creates a signed extrinsic.
@@ -261,28 +1279,31 @@ where
         <A as AccountData>::AccountId: From<<T as Config>::AccountId>,
         <A as AccountData>::Index: Into<<T as Config>::Index>,
     {
-        let account_nonce = if let Some(nonce) = signer.nonce() {
-            nonce
-        } else {
-            let account_storage_entry =
-                A::storage_entry(signer.account_id().clone().into());
-            let account_data = self
-                .client
-                .storage()
-                .fetch_or_default(&account_storage_entry, None)
-                .await?;
-            A::nonce(&account_data).into()
+        let account_nonce = match signer.nonce() {
+            Some(nonce) => nonce,
+            None => self.fetch_nonce(signer.account_id()).await?,
         };
-        let call = self
-            .client
-            .metadata()
-            .pallet(C::PALLET)
-            .and_then(|pallet| pallet.encode_call(&self.call))?;
+        self.create_signed_with_nonce(signer, account_nonce, additional_params)
+            .await
+    }
 
+    /// Creates a signed extrinsic using the given nonce, rather than the signer's or
+    /// the account's current one.
+    pub async fn create_signed_with_nonce(
+        &self,
+        signer: &(dyn Signer<T, X> + Send + Sync),
+        nonce: T::Index,
+        additional_params: X::Parameters,
+    ) -> Result<UncheckedExtrinsic<T, X>, BasicError>
+    where
+        <<X as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned:
+            Send + Sync + 'static,
+    {
+        let call = self.encode_call()?;
         let signed = extrinsic::create_signed(
             &self.client.runtime_version,
             self.client.genesis_hash,
-            account_nonce,
+            nonce,
             call,
             signer,
             additional_params,
@@ -290,4 +1311,357 @@ where
         .await?;
         Ok(signed)
     }
+
+    /// Creates an unsigned extrinsic.
+    pub fn create_unsigned(&self) -> Result<UncheckedExtrinsic<T, X>, BasicError> {
+        let call = self.encode_call()?;
+        Ok(extrinsic::create_unsigned::<T, X>(call))
+    }
+
+    /// Creates the payload which must be signed by the given account in order to
+    /// submit this extrinsic, using the account's current nonce.
+    ///
+    /// This is useful when signing with something that cannot implement [`Signer`]
+    /// (for instance an HSM that only accepts raw payloads). The bytes to sign are given
+    /// by [`extrinsic::SignedPayloadExt::signing_bytes()`], and the signed extrinsic can
+    /// then be constructed with [`extrinsic::attach_signature`].
+    pub async fn create_signed_payload(
+        &self,
+        account_id: &T::AccountId,
+        additional_params: X::Parameters,
+    ) -> Result<SignedPayload<T, X>, BasicError>
+    where
+        <A as AccountData>::AccountId: From<<T as Config>::AccountId>,
+        <A as AccountData>::Index: Into<<T as Config>::Index>,
+    {
+        let account_nonce = self.fetch_nonce(account_id).await?;
+        let call = self.encode_call()?;
+        extrinsic::create_signed_payload::<T, X>(
+            &self.client.runtime_version,
+            self.client.genesis_hash,
+            account_nonce,
+            call,
+            additional_params,
+        )
+    }
+
+    /// Returns the SCALE encoded call data (the pallet and call indices followed by the call
+    /// arguments), without any signature or signed extensions. This is what is hashed to
+    /// obtain the preimage hash of a governance proposal, or the `call_hash` approved by
+    /// multisig signatories.
+    pub fn encode_call_data(&self) -> Result<Vec<u8>, BasicError> {
+        Ok(self.encode_call()?.0)
+    }
+
+    fn encode_call(&self) -> Result<Encoded, BasicError> {
+        let call = self
+            .client
+            .metadata()
+            .pallet(C::PALLET)
+            .and_then(|pallet| pallet.encode_call(&self.call))?;
+        Ok(call)
+    }
+
+    async fn fetch_nonce(&self, account_id: &T::AccountId) -> Result<T::Index, BasicError>
+    where
+        <A as AccountData>::AccountId: From<<T as Config>::AccountId>,
+        <A as AccountData>::Index: Into<<T as Config>::Index>,
+    {
+        let account_storage_entry = A::storage_entry(account_id.clone().into());
+        let account_data = self
+            .client
+            .storage()
+            .fetch_or_default(&account_storage_entry, None)
+            .await?;
+        Ok(A::nonce(&account_data).into())
+    }
+}
+
+/// Returns true if the node rejected a transaction because its nonce has already been used
+/// ("Transaction is outdated"), or because another transaction with the same nonce is
+/// already in the pool (see [`is_priority_too_low_error`]).
+fn is_stale_nonce_error(err: &BasicError) -> bool {
+    match err {
+        BasicError::Rpc(err) => {
+            let message = err.to_string();
+            message.contains("Transaction is outdated")
+                || message.contains("Priority is too low")
+        }
+        _ => false,
+    }
+}
+
+/// Returns true if the node rejected a transaction because another one with the same nonce
+/// is already in the pool, and the replacement doesn't pay a higher priority.
+fn is_priority_too_low_error(err: &BasicError) -> bool {
+    match err {
+        BasicError::Rpc(err) => err.to_string().contains("Priority is too low"),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        extrinsic::{
+            ChargeTransactionPayment,
+            CheckGenesis,
+            CheckMortality,
+            CheckNonce,
+            CheckSpecVersion,
+            CheckTxVersion,
+            CheckWeight,
+            ExtrinsicDetails,
+            PairSigner,
+        },
+        rpc::MockRpcClient,
+        storage::{
+            StorageEntry,
+            StorageEntryKey,
+        },
+        DefaultConfig,
+        DefaultExtra,
+    };
+    use frame_metadata::{
+        v14::{
+            ExtrinsicMetadata,
+            PalletCallMetadata,
+            PalletMetadata,
+            RuntimeMetadataLastVersion,
+            SignedExtensionMetadata,
+        },
+        RuntimeMetadataPrefixed,
+    };
+    use scale_info::{
+        meta_type,
+        TypeInfo,
+    };
+    use sp_core::{
+        sr25519,
+        storage::StorageData,
+        Bytes,
+        Pair,
+        H256,
+    };
+    use std::convert::TryFrom;
+
+    type T = DefaultConfig;
+
+    #[allow(dead_code, non_camel_case_types)]
+    #[derive(TypeInfo)]
+    enum TestCall {
+        remark { remark: Vec<u8> },
+    }
+
+    #[derive(Encode)]
+    struct Remark {
+        remark: Vec<u8>,
+    }
+
+    impl Call for Remark {
+        const PALLET: &'static str = "System";
+        const FUNCTION: &'static str = "remark";
+    }
+
+    // The nonce of an account, stored on its own for simplicity.
+    struct AccountNonce;
+
+    impl StorageEntry for AccountNonce {
+        const PALLET: &'static str = "System";
+        const STORAGE: &'static str = "AccountNonce";
+        type Value = u32;
+        fn key(&self) -> StorageEntryKey {
+            StorageEntryKey::Plain
+        }
+    }
+
+    struct TestAccountData;
+
+    impl AccountData for TestAccountData {
+        type StorageEntry = AccountNonce;
+        type AccountId = <T as Config>::AccountId;
+        type Index = u32;
+
+        fn storage_entry(_account_id: Self::AccountId) -> Self::StorageEntry {
+            AccountNonce
+        }
+
+        fn nonce(result: &u32) -> u32 {
+            *result
+        }
+    }
+
+    fn extension<E: TypeInfo + 'static>(
+        identifier: &'static str,
+    ) -> SignedExtensionMetadata {
+        SignedExtensionMetadata {
+            identifier,
+            ty: meta_type::<E>(),
+            additional_signed: meta_type::<()>(),
+        }
+    }
+
+    fn runtime_metadata() -> RuntimeMetadataPrefixed {
+        let pallets = vec![PalletMetadata {
+            name: "System",
+            storage: None,
+            calls: Some(PalletCallMetadata {
+                ty: meta_type::<TestCall>(),
+            }),
+            event: None,
+            constants: vec![],
+            error: None,
+            index: 0,
+        }];
+        let extrinsic = ExtrinsicMetadata {
+            ty: meta_type::<()>(),
+            version: 4,
+            signed_extensions: vec![
+                extension::<CheckSpecVersion<T>>("CheckSpecVersion"),
+                extension::<CheckTxVersion<T>>("CheckTxVersion"),
+                extension::<CheckGenesis<T>>("CheckGenesis"),
+                extension::<CheckMortality<T>>("CheckMortality"),
+                extension::<CheckNonce<T>>("CheckNonce"),
+                extension::<CheckWeight<T>>("CheckWeight"),
+                extension::<ChargeTransactionPayment<T>>("ChargeTransactionPayment"),
+            ],
+        };
+        RuntimeMetadataLastVersion::new(pallets, extrinsic, meta_type::<()>()).into()
+    }
+
+    // The nonces of the extrinsics submitted to the mock, in turn.
+    fn submitted_nonces(mock: &MockRpcClient) -> Vec<Option<u32>> {
+        let metadata = Metadata::try_from(runtime_metadata()).unwrap();
+        mock.requests_to("author_submitExtrinsic")
+            .into_iter()
+            .map(|params| {
+                let extrinsic: Bytes = serde_json::from_value(params[0].clone()).unwrap();
+                let details =
+                    ExtrinsicDetails::<T>::decode(&metadata, &extrinsic).unwrap();
+                details.signature.unwrap().nonce
+            })
+            .collect()
+    }
+
+    #[async_std::test]
+    async fn stale_nonce_is_retried_with_the_fetched_nonce() {
+        let mock = MockRpcClient::new()
+            .with_chain(runtime_metadata())
+            .add_response("state_getStorage", StorageData(1u32.encode()))
+            .add_response("state_getStorage", StorageData(4u32.encode()))
+            .add_error(
+                "author_submitExtrinsic",
+                1010,
+                "Invalid Transaction: Transaction is outdated",
+            )
+            .add_response("author_submitExtrinsic", H256::repeat_byte(2));
+        let client: Client<T> = ClientBuilder::new()
+            .set_client(mock.clone())
+            .set_stale_nonce_retries(1)
+            .build()
+            .await
+            .unwrap();
+        let signer =
+            PairSigner::<T, DefaultExtra<T>, _>::new(sr25519::Pair::from_seed(&[1; 32]));
+
+        let hash =
+            SubmittableExtrinsic::<T, DefaultExtra<T>, TestAccountData, _, (), ()>::new(
+                &client,
+                Remark {
+                    remark: b"hello".to_vec(),
+                },
+            )
+            .sign_and_submit(&signer)
+            .await
+            .unwrap();
+        assert_eq!(hash, H256::repeat_byte(2));
+        assert_eq!(submitted_nonces(&mock), vec![Some(1), Some(4)]);
+    }
+
+    #[async_std::test]
+    async fn priority_too_low_is_retried_with_the_next_nonce() {
+        // The nonce stored on chain doesn't count the transaction already in the pool.
+        let mock = MockRpcClient::new()
+            .with_chain(runtime_metadata())
+            .add_response("state_getStorage", StorageData(1u32.encode()))
+            .add_error(
+                "author_submitExtrinsic",
+                1014,
+                "Priority is too low: (140 vs 140)",
+            )
+            .add_response("author_submitExtrinsic", H256::repeat_byte(2));
+        let client: Client<T> = ClientBuilder::new()
+            .set_client(mock.clone())
+            .set_stale_nonce_retries(1)
+            .build()
+            .await
+            .unwrap();
+        let signer =
+            PairSigner::<T, DefaultExtra<T>, _>::new(sr25519::Pair::from_seed(&[1; 32]));
+
+        let hash =
+            SubmittableExtrinsic::<T, DefaultExtra<T>, TestAccountData, _, (), ()>::new(
+                &client,
+                Remark {
+                    remark: b"hello".to_vec(),
+                },
+            )
+            .sign_and_submit(&signer)
+            .await
+            .unwrap();
+        assert_eq!(hash, H256::repeat_byte(2));
+        assert_eq!(submitted_nonces(&mock), vec![Some(1), Some(2)]);
+    }
+
+    #[async_std::test]
+    async fn submissions_use_the_default_tip_and_mortality() {
+        let finalized = <T as Config>::Header::new(
+            1000,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        );
+        let mock = MockRpcClient::new()
+            .with_chain(runtime_metadata())
+            .add_response("chain_getFinalizedHead", finalized.hash())
+            .add_response("chain_getHeader", finalized.clone())
+            .add_response("state_getStorage", StorageData(1u32.encode()))
+            .add_response("author_submitExtrinsic", H256::repeat_byte(2));
+        let client: Client<T> = ClientBuilder::new()
+            .set_client(mock.clone())
+            .set_default_tip(5)
+            .set_default_mortality(64)
+            .build()
+            .await
+            .unwrap();
+        let signer =
+            PairSigner::<T, DefaultExtra<T>, _>::new(sr25519::Pair::from_seed(&[1; 32]));
+
+        SubmittableExtrinsic::<T, DefaultExtra<T>, TestAccountData, _, (), ()>::new(
+            &client,
+            Remark {
+                remark: b"hello".to_vec(),
+            },
+        )
+        .sign_and_submit(&signer)
+        .await
+        .unwrap();
+
+        let params = mock.requests_to("author_submitExtrinsic");
+        let extrinsic: Bytes = serde_json::from_value(params[0][0].clone()).unwrap();
+        let metadata = Metadata::try_from(runtime_metadata()).unwrap();
+        let signature = ExtrinsicDetails::<T>::decode(&metadata, &extrinsic)
+            .unwrap()
+            .signature
+            .unwrap();
+        assert_eq!(signature.tip, Some(5));
+        assert_eq!(signature.era, Some(Era::mortal(64, 1000)));
+        // The era is constructed from the latest finalized block.
+        assert_eq!(
+            mock.requests_to("chain_getHeader"),
+            vec![serde_json::json!([finalized.hash()])]
+        );
+    }
 }
@@ -67,6 +67,10 @@ pub trait Config: 'static {
     type AccountId: Parameter + Member;
 
     /// The address type. This instead of `<frame_system::Trait::Lookup as StaticLookup>::Source`.
+    ///
+    /// This is usually `MultiAddress<Self::AccountId, AccountIndex>`, but chains using
+    /// Ethereum style accounts use the account id directly, e.g.
+    /// [`AccountId20`](crate::ethereum::AccountId20).
     type Address: Codec + Clone + PartialEq;
 
     /// The block header.
@@ -86,6 +90,11 @@ pub trait Parameter: Codec + EncodeLike + Clone + Eq + Debug {}
 impl<T> Parameter for T where T: Codec + EncodeLike + Clone + Eq + Debug {}
 
 /// Default set of commonly used types by Substrate runtimes.
+///
+/// This also works for runtimes using `MultiAddress<AccountId32, ()>` (such as Polkadot),
+/// since the `MultiAddress::Id` variant used for signing is encoded the same regardless of
+/// the account index type. See [`EthereumConfig`](crate::ethereum::EthereumConfig) for
+/// chains with Ethereum style accounts.
 // Note: We only use this at the type level, so it should be impossible to
 // create an instance of it.
 pub enum DefaultConfig {}
@@ -21,9 +21,16 @@ use crate::{
         MetadataError,
     },
 };
+use codec::{
+    Decode,
+    Encode,
+};
 use core::fmt::Debug;
 use jsonrpsee::core::error::Error as RequestError;
-use sp_core::crypto::SecretStringError;
+use sp_core::{
+    crypto::SecretStringError,
+    H256,
+};
 use sp_runtime::transaction_validity::TransactionValidityError;
 
 /// An error that may contain some runtime error `E`
@@ -57,7 +64,7 @@ pub enum GenericError<E> {
     Invalid(TransactionValidityError),
     /// Invalid metadata error
     #[error("Invalid Metadata: {0}")]
-    InvalidMetadata(#[from] InvalidMetadataError),
+    InvalidMetadata(InvalidMetadataError),
     /// Invalid metadata error
     #[error("Metadata: {0}")]
     Metadata(#[from] MetadataError),
@@ -121,6 +128,12 @@ impl<E> From<SecretStringError> for GenericError<E> {
     }
 }
 
+impl<E> From<InvalidMetadataError> for GenericError<E> {
+    fn from(error: InvalidMetadataError) -> Self {
+        GenericError::InvalidMetadata(error)
+    }
+}
+
 impl<E> From<TransactionValidityError> for GenericError<E> {
     fn from(error: TransactionValidityError) -> Self {
         GenericError::Invalid(error)
@@ -155,6 +168,125 @@ impl<E> RuntimeError<E> {
     }
 }
 
+/// A runtime independent description of a `DispatchError`. The generated
+/// `DispatchError` type exposes a `kind()` method which returns this, so that
+/// errors other than pallet errors can be matched on without having to know
+/// the exact shape of the types generated for a given runtime.
+#[derive(Clone, Debug, Eq, thiserror::Error, PartialEq)]
+pub enum DispatchErrorKind {
+    /// Some error occurred.
+    #[error("Some unknown error occurred")]
+    Other,
+    /// Failed to lookup some data.
+    #[error("Failed to lookup some data")]
+    CannotLookup,
+    /// A bad origin.
+    #[error("Bad origin")]
+    BadOrigin,
+    /// A custom error in a pallet. Use the generated `details()` or `module_error()`
+    /// methods on the `DispatchError` to find out more about it.
+    #[error("Error {error} in pallet {index}")]
+    Module {
+        /// The index of the pallet in the runtime.
+        index: u8,
+        /// The index of the error within the pallet.
+        error: u8,
+    },
+    /// At least one consumer is remaining so the account cannot be destroyed.
+    #[error("At least one consumer is remaining so the account cannot be destroyed")]
+    ConsumerRemaining,
+    /// There are no providers so the account cannot be created.
+    #[error("There are no providers so the account cannot be created")]
+    NoProviders,
+    /// There are too many consumers so the account cannot be created.
+    #[error("There are too many consumers so the account cannot be created")]
+    TooManyConsumers,
+    /// An error to do with tokens.
+    #[error("Token error: {0}")]
+    Token(TokenError),
+    /// An arithmetic error.
+    #[error("Arithmetic error: {0}")]
+    Arithmetic(ArithmeticError),
+    /// An error to do with transactional storage layers.
+    #[error("Transactional error: {0}")]
+    Transactional(TransactionalError),
+    /// A variant of `DispatchError` (named here) which this version of subxt
+    /// doesn't know how to interpret.
+    #[error("Unknown dispatch error: {0}")]
+    Unknown(String),
+}
+
+impl DispatchErrorKind {
+    /// Build a [`DispatchErrorKind`] from a `DispatchError` variant which wraps some
+    /// nested error type, by re-interpreting the SCALE encoded bytes of the nested error.
+    /// This is used by the generated code, and falls back to [`DispatchErrorKind::Unknown`]
+    /// if the nested error cannot be interpreted.
+    #[doc(hidden)]
+    pub fn from_nested<E: Encode>(variant: &str, nested: &E) -> Self {
+        let bytes = nested.encode();
+        let kind = match variant {
+            "Token" => TokenError::decode(&mut &*bytes).map(Self::Token),
+            "Arithmetic" => ArithmeticError::decode(&mut &*bytes).map(Self::Arithmetic),
+            "Transactional" => {
+                TransactionalError::decode(&mut &*bytes).map(Self::Transactional)
+            }
+            _ => return Self::Unknown(variant.to_string()),
+        };
+        kind.unwrap_or_else(|_| Self::Unknown(variant.to_string()))
+    }
+}
+
+/// Description of what went wrong when trying to complete an operation on a token.
+#[derive(Clone, Copy, Debug, Decode, Encode, Eq, thiserror::Error, PartialEq)]
+pub enum TokenError {
+    /// Funds are unavailable.
+    #[error("Funds are unavailable")]
+    NoFunds,
+    /// Account that must exist would die.
+    #[error("Account that must exist would die")]
+    WouldDie,
+    /// Account cannot exist with the funds that would be given.
+    #[error("Account cannot exist with the funds that would be given")]
+    BelowMinimum,
+    /// Account cannot be created.
+    #[error("Account cannot be created")]
+    CannotCreate,
+    /// The asset in question is unknown.
+    #[error("The asset in question is unknown")]
+    UnknownAsset,
+    /// Funds exist but are frozen.
+    #[error("Funds exist but are frozen")]
+    Frozen,
+    /// Operation is not supported by the asset.
+    #[error("Operation is not supported by the asset")]
+    Unsupported,
+}
+
+/// Arithmetic errors.
+#[derive(Clone, Copy, Debug, Decode, Encode, Eq, thiserror::Error, PartialEq)]
+pub enum ArithmeticError {
+    /// Underflow.
+    #[error("Underflow")]
+    Underflow,
+    /// Overflow.
+    #[error("Overflow")]
+    Overflow,
+    /// Division by zero.
+    #[error("Division by zero")]
+    DivisionByZero,
+}
+
+/// Errors related to transactional storage layers.
+#[derive(Clone, Copy, Debug, Decode, Encode, Eq, thiserror::Error, PartialEq)]
+pub enum TransactionalError {
+    /// Too many transactional layers have been spawned.
+    #[error("Too many transactional layers have been spawned")]
+    LimitReached,
+    /// A transactional layer was expected, but does not exist.
+    #[error("A transactional layer was expected, but does not exist")]
+    NoLayer,
+}
+
 /// Transaction error.
 #[derive(Clone, Debug, Eq, thiserror::Error, PartialEq)]
 pub enum TransactionError {
@@ -166,4 +298,54 @@ pub enum TransactionError {
     /// This is probably because the block was retracted before being finalized.
     #[error("The block containing the transaction can no longer be found (perhaps it was on a non-finalized fork?)")]
     BlockHashNotFound,
+    /// The transaction was replaced in the pool by another transaction from the same
+    /// account with the same nonce. This contains the hash of the replacement transaction.
+    #[error("The transaction was replaced by another transaction ({:?})", .0)]
+    Usurped(H256),
+    /// The transaction was dropped from the pool, for instance because the pool is full.
+    /// This contains the reason the node gave, if any.
+    #[error("The transaction was dropped from the transaction pool (reason: {})", .0.as_deref().unwrap_or("none given"))]
+    Dropped(Option<String>),
+    /// The transaction is no longer valid in the current state. This contains the reason the
+    /// node gave, if any.
+    #[error("The transaction is no longer valid (reason: {})", .0.as_deref().unwrap_or("none given"))]
+    Invalid(Option<String>),
+    /// No final status was received for the transaction within the timeout given to
+    /// [`crate::TransactionProgress::with_timeout`], or no matching event was seen within the
+    /// timeout given to [`crate::Client::wait_for_event`]. This contains a description of the
+    /// last status received, if any.
+    #[error("Timed out waiting for the transaction or event (last status: {})", .last_status.as_deref().unwrap_or("none"))]
+    Timeout {
+        /// The last status received before timing out.
+        last_status: Option<String>,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dispatch_error_kind_from_nested() {
+        assert_eq!(
+            DispatchErrorKind::from_nested("Arithmetic", &1u8),
+            DispatchErrorKind::Arithmetic(ArithmeticError::Overflow)
+        );
+        assert_eq!(
+            DispatchErrorKind::from_nested("Token", &2u8),
+            DispatchErrorKind::Token(TokenError::BelowMinimum)
+        );
+        assert_eq!(
+            DispatchErrorKind::from_nested("Transactional", &1u8),
+            DispatchErrorKind::Transactional(TransactionalError::NoLayer)
+        );
+        assert_eq!(
+            DispatchErrorKind::from_nested("Arithmetic", &200u8),
+            DispatchErrorKind::Unknown("Arithmetic".to_string())
+        );
+        assert_eq!(
+            DispatchErrorKind::from_nested("Exhausted", &0u8),
+            DispatchErrorKind::Unknown("Exhausted".to_string())
+        );
+    }
 }
@@ -0,0 +1,235 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Account and signature types for Ethereum compatible (e.g. Frontier based) chains such
+//! as Moonbeam, which identify accounts by a 20 byte address derived from a secp256k1
+//! public key and verify ECDSA signatures over the `keccak256` hash of the payload.
+//!
+//! To talk to such a chain, use a [`Config`](crate::Config) with
+//! [`AccountId20`] as both the `AccountId` and `Address`, and [`EthereumSignature`] as the
+//! `Signature`, for instance [`EthereumConfig`]. Since the generated runtime types contain
+//! their own copy of the account id, substitute it with this one in the `subxt` macro:
+//!
+//! ```ignore
+//! #[subxt::subxt(runtime_metadata_path = "moonbeam_metadata.scale")]
+//! pub mod moonbeam {
+//!     #[subxt(substitute_type = "account::AccountId20")]
+//!     use ::subxt::ethereum::AccountId20;
+//! }
+//! ```
+
+use codec::{
+    Decode,
+    Encode,
+};
+use sp_core::{
+    ecdsa,
+    hashing::keccak_256,
+};
+use sp_runtime::traits::{
+    IdentifyAccount,
+    Lazy,
+    Verify,
+};
+
+/// An Ethereum style 20 byte account id: the last 20 bytes of the `keccak256` hash of the
+/// uncompressed secp256k1 public key.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    Encode,
+    Decode,
+    scale_info::TypeInfo,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub struct AccountId20(pub [u8; 20]);
+
+impl AccountId20 {
+    /// Returns the EIP-55 mixed case checksum encoding of the address, prefixed with `0x`.
+    pub fn checksum(&self) -> String {
+        let hex_address = hex::encode(self.0);
+        let hash = keccak_256(hex_address.as_bytes());
+        let checksummed = hex_address
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                let nibble = (hash[i / 2] >> if i % 2 == 0 { 4 } else { 0 }) & 0x0f;
+                if nibble >= 8 {
+                    c.to_ascii_uppercase()
+                } else {
+                    c
+                }
+            })
+            .collect::<String>();
+        format!("0x{}", checksummed)
+    }
+}
+
+impl std::fmt::Display for AccountId20 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.checksum())
+    }
+}
+
+impl std::str::FromStr for AccountId20 {
+    type Err = hex::FromHexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bytes = [0u8; 20];
+        hex::decode_to_slice(s.trim_start_matches("0x"), &mut bytes)?;
+        Ok(Self(bytes))
+    }
+}
+
+impl From<[u8; 20]> for AccountId20 {
+    fn from(bytes: [u8; 20]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl AsRef<[u8]> for AccountId20 {
+    fn as_ref(&self) -> &[u8] {
+        &self.0[..]
+    }
+}
+
+impl From<ecdsa::Public> for AccountId20 {
+    fn from(public: ecdsa::Public) -> Self {
+        let public = libsecp256k1::PublicKey::parse_compressed(&public.0)
+            .expect("an ecdsa::Public always holds a valid compressed public key; qed");
+        // Skip the leading `0x04` tag of the uncompressed serialization.
+        let hash = keccak_256(&public.serialize()[1..]);
+        let mut bytes = [0u8; 20];
+        bytes.copy_from_slice(&hash[12..]);
+        Self(bytes)
+    }
+}
+
+/// The signer of an [`EthereumSignature`], identifying the [`AccountId20`] it belongs to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Encode, Decode)]
+pub struct EthereumSigner([u8; 20]);
+
+impl IdentifyAccount for EthereumSigner {
+    type AccountId = AccountId20;
+
+    fn into_account(self) -> AccountId20 {
+        AccountId20(self.0)
+    }
+}
+
+impl From<ecdsa::Public> for EthereumSigner {
+    fn from(public: ecdsa::Public) -> Self {
+        Self(AccountId20::from(public).0)
+    }
+}
+
+/// A secp256k1 ECDSA signature over the `keccak256` hash of the signed payload.
+#[derive(Clone, Debug, Eq, PartialEq, Encode, Decode)]
+pub struct EthereumSignature(pub ecdsa::Signature);
+
+impl EthereumSignature {
+    /// Signs the `keccak256` hash of the message with the given pair.
+    ///
+    /// Note that this differs from [`sp_core::Pair::sign`] for ECDSA pairs, which signs the
+    /// `blake2_256` hash of the message instead.
+    pub fn sign(pair: &ecdsa::Pair, message: &[u8]) -> Self {
+        Self(pair.sign_prehashed(&keccak_256(message)))
+    }
+}
+
+impl From<ecdsa::Signature> for EthereumSignature {
+    fn from(signature: ecdsa::Signature) -> Self {
+        Self(signature)
+    }
+}
+
+impl Verify for EthereumSignature {
+    type Signer = EthereumSigner;
+
+    fn verify<L: Lazy<[u8]>>(&self, mut msg: L, signer: &AccountId20) -> bool {
+        self.0
+            .recover_prehashed(&keccak_256(msg.get()))
+            .map(|public| AccountId20::from(public) == *signer)
+            .unwrap_or(false)
+    }
+}
+
+/// A set of types used by Ethereum compatible runtimes such as Moonbeam.
+// Note: We only use this at the type level, so it should be impossible to
+// create an instance of it.
+pub enum EthereumConfig {}
+
+impl crate::Config for EthereumConfig {
+    type Index = u32;
+    type BlockNumber = u32;
+    type Hash = sp_core::H256;
+    type Hashing = sp_runtime::traits::BlakeTwo256;
+    type AccountId = AccountId20;
+    type Address = AccountId20;
+    type Header =
+        sp_runtime::generic::Header<Self::BlockNumber, sp_runtime::traits::BlakeTwo256>;
+    type Signature = EthereumSignature;
+    type Extrinsic = sp_runtime::OpaqueExtrinsic;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_core::Pair;
+
+    // The well known "Alith" development account of Moonbeam.
+    const ALITH_SEED: &str =
+        "5fb92d6e98884f76de468fa3f6278f8807c48bebc13595d45af5bdc4da702133";
+    const ALITH_ADDRESS: &str = "0xf24FF3a9CF04c71Dbc94D0b566f7A27B94566cac";
+
+    fn alith() -> ecdsa::Pair {
+        let mut seed = [0u8; 32];
+        hex::decode_to_slice(ALITH_SEED, &mut seed).unwrap();
+        ecdsa::Pair::from_seed(&seed)
+    }
+
+    #[test]
+    fn account_id_is_derived_from_public_key() {
+        let account = AccountId20::from(alith().public());
+        assert_eq!(account.to_string(), ALITH_ADDRESS);
+        assert_eq!(ALITH_ADDRESS.parse::<AccountId20>().unwrap(), account);
+        assert_eq!(
+            EthereumSigner::from(alith().public()).into_account(),
+            account
+        );
+    }
+
+    #[test]
+    fn signature_verifies_against_keccak_hash() {
+        let pair = alith();
+        let account = AccountId20::from(pair.public());
+        let signature = EthereumSignature::sign(&pair, b"payload");
+
+        assert!(signature.verify(&b"payload"[..], &account));
+        assert!(!signature.verify(&b"other payload"[..], &account));
+        // A plain ECDSA signature is made over the blake2 hash, so it doesn't verify.
+        let blake2_signature = EthereumSignature::from(pair.sign(b"payload"));
+        assert!(!blake2_signature.verify(&b"payload"[..], &account));
+    }
+}
@@ -19,6 +19,10 @@
 use crate::{
     error::BasicError,
     metadata::MetadataError,
+    rpc::{
+        NumberOrHex,
+        RpcError,
+    },
     Client,
     Config,
     Event,
@@ -33,30 +37,63 @@ use codec::{
     Codec,
     Compact,
     Decode,
+    Encode,
     Error as CodecError,
     Input,
 };
 use derivative::Derivative;
 use futures::{
+    future::{
+        self,
+        Either,
+    },
     Future,
     FutureExt,
     Stream,
     StreamExt,
 };
+use futures_timer::Delay;
 use jsonrpsee::core::client::Subscription;
 use scale_info::{
+    form::PortableForm,
+    Field,
     PortableRegistry,
     TypeDef,
     TypeDefPrimitive,
 };
+use serde::Serialize;
+use serde_json::Value;
 use sp_core::{
+    blake2_128,
     storage::StorageKey,
     twox_128,
     Bytes,
 };
+use sp_runtime::traits::{
+    Header,
+    UniqueSaturatedInto,
+};
 use std::{
+    collections::{
+        BTreeMap,
+        HashMap,
+        VecDeque,
+    },
     marker::Unpin,
+    ops::{
+        Deref,
+        Range,
+    },
+    pin::Pin,
+    sync::{
+        atomic::{
+            AtomicU64,
+            Ordering,
+        },
+        Arc,
+    },
     task::Poll,
+    time::Duration,
 };
 
 /// Obtain events at some block hash. The generic parameter is what we
@@ -64,6 +101,10 @@ use std::{
 /// and is expected to be the outermost event enum that contains all of
 /// the possible events across all pallets.
 ///
+/// The events are decoded using the metadata of the runtime at the block, which is
+/// fetched (once per runtime version) if the runtime has been upgraded since the client
+/// was built.
+///
 /// **Note:** This function is hidden from the documentation
 /// and is exposed only to be called via the codegen. Thus, prefer to use
 /// `api.events().at(block_hash)` over calling this directly.
@@ -72,9 +113,22 @@ pub async fn at<T: Config, Evs: Decode>(
     client: &'_ Client<T>,
     block_hash: T::Hash,
 ) -> Result<Events<'_, T, Evs>, BasicError> {
+    let metadata = match client.other_metadata_at(block_hash).await? {
+        Some(metadata) => EventsMetadata::Other(metadata),
+        None => EventsMetadata::Client(client.metadata()),
+    };
+    at_with_metadata(client, block_hash, metadata).await
+}
+
+// Obtain events at some block hash, decoding them with the metadata provided.
+pub(crate) async fn at_with_metadata<'a, T: Config, Evs: Decode>(
+    client: &'a Client<T>,
+    block_hash: T::Hash,
+    metadata: EventsMetadata<'a>,
+) -> Result<Events<'a, T, Evs>, BasicError> {
     let mut event_bytes = client
-        .rpc()
-        .storage(&system_events_key(), Some(block_hash))
+        .storage()
+        .fetch_raw(system_events_key(), Some(block_hash))
         .await?
         .map(|s| s.0)
         .unwrap_or_else(Vec::new);
@@ -92,10 +146,13 @@ pub async fn at<T: Config, Evs: Decode>(
     event_bytes.drain(0..event_bytes_len - remaining_len);
 
     Ok(Events {
-        metadata: client.metadata(),
+        metadata,
+        decoders: client.event_decoders().clone(),
         block_hash,
         event_bytes,
         num_events,
+        pallet_filter: None,
+        account_filter: None,
         _event_type: std::marker::PhantomData,
     })
 }
@@ -112,8 +169,9 @@ pub async fn at<T: Config, Evs: Decode>(
 pub async fn subscribe<T: Config, Evs: Decode + 'static>(
     client: &'_ Client<T>,
 ) -> Result<EventSubscription<'_, T, Evs>, BasicError> {
-    let block_subscription = client.rpc().subscribe_blocks().await?;
-    Ok(EventSubscription::new(client, block_subscription))
+    let kind = BlockSubscriptionKind::Best;
+    let block_subscription = subscribe_headers(client, kind).await?;
+    Ok(EventSubscription::new(client, block_subscription, kind))
 }
 
 /// Subscribe to events from finalized blocks.
@@ -125,23 +183,578 @@ pub async fn subscribe<T: Config, Evs: Decode + 'static>(
 pub async fn subscribe_finalized<T: Config, Evs: Decode + 'static>(
     client: &'_ Client<T>,
 ) -> Result<EventSubscription<'_, T, Evs>, BasicError> {
-    let block_subscription = client.rpc().subscribe_finalized_blocks().await?;
-    Ok(EventSubscription::new(client, block_subscription))
+    let kind = BlockSubscriptionKind::Finalized;
+    let block_subscription = subscribe_headers(client, kind).await?;
+    Ok(EventSubscription::new(client, block_subscription, kind))
 }
 
-/// A subscription to events that implements [`Stream`], and returns [`Events`] objects for each block.
+/// Fetch the events from every block in the given (inclusive) range of block numbers.
+///
+/// **Note:** This function is hidden from the documentation
+/// and is exposed only to be called via the codegen. Thus, prefer to use
+/// `api.events().between(from_block, to_block)` over calling this directly.
+#[doc(hidden)]
+pub fn between<T: Config, Evs: Decode + 'static>(
+    client: &'_ Client<T>,
+    from_block: u64,
+    to_block: u64,
+) -> EventsBetween<'_, T, Evs> {
+    EventsBetween {
+        client,
+        blocks: from_block..to_block.saturating_add(1),
+        max_concurrent_requests: DEFAULT_MAX_CONCURRENT_REQUESTS,
+        events: None,
+    }
+}
+
+// How many blocks [`EventsBetween`] fetches the events of at once, by default.
+const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 16;
+
+/// A stream of the [`Events`] in each block of some range, in block order. Returned from
+/// [`crate::Client::events_between()`].
+///
+/// The events of several blocks are fetched concurrently (see
+/// [`EventsBetween::max_concurrent_requests()`]), but they are always handed back in order.
+/// If fetching the events of some block fails, an error is handed back in its place.
 #[derive(Derivative)]
 #[derivative(Debug(bound = ""))]
-pub struct EventSubscription<'a, T: Config, Evs: Decode + 'static> {
-    finished: bool,
+pub struct EventsBetween<'a, T: Config, Evs: Decode + 'static> {
+    client: &'a Client<T>,
+    blocks: Range<u64>,
+    max_concurrent_requests: usize,
+    // Created when the stream is first polled.
+    #[derivative(Debug = "ignore")]
+    events:
+        Option<Pin<Box<dyn Stream<Item = Result<Events<'a, T, Evs>, BasicError>> + 'a>>>,
+}
+
+impl<'a, T: Config, Evs: Decode> EventsBetween<'a, T, Evs> {
+    /// The maximum number of blocks to fetch the events of at once. Defaults to 16.
+    ///
+    /// **Note:** This has no effect once the stream has been polled.
+    pub fn max_concurrent_requests(mut self, max: usize) -> Self {
+        self.max_concurrent_requests = max.max(1);
+        self
+    }
+}
+
+impl<'a, T: Config, Evs: Decode> Unpin for EventsBetween<'a, T, Evs> {}
+
+impl<'a, T: Config, Evs: Decode> Stream for EventsBetween<'a, T, Evs> {
+    type Item = Result<Events<'a, T, Evs>, BasicError>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        if self.events.is_none() {
+            let client = self.client;
+            let blocks = std::mem::replace(&mut self.blocks, 0..0);
+            let events = futures::stream::iter(blocks)
+                .map(move |n| {
+                    async move {
+                        let block_number = NumberOrHex::Number(n).into();
+                        let block_hash = client
+                            .rpc()
+                            .block_hash(Some(block_number))
+                            .await?
+                            .ok_or_else(|| {
+                                BasicError::Other(format!("Block {} not found", n))
+                            })?;
+                        at(client, block_hash).await
+                    }
+                })
+                .buffered(self.max_concurrent_requests);
+            self.events = Some(Box::pin(events));
+        }
+
+        self.events
+            .as_mut()
+            .expect("events stream should have been set above")
+            .poll_next_unpin(cx)
+    }
+}
+
+/// Subscribe to events from the best chain, being told when blocks that events were
+/// handed back for are no longer part of it.
+///
+/// **Note:** This function is hidden from the documentation
+/// and is exposed only to be called via the codegen. Thus, prefer to use
+/// `api.events().subscribe_best_chain()` over calling this directly.
+#[doc(hidden)]
+pub async fn subscribe_best_chain<T: Config, Evs: Decode + 'static>(
+    client: &'_ Client<T>,
+) -> Result<BestChainEvents<'_, T, Evs>, BasicError> {
+    let block_header_subscription =
+        subscribe_headers(client, BlockSubscriptionKind::Best).await?;
+    let state = BestChain {
+        client,
+        block_header_subscription,
+        tracker: ChainTracker::default(),
+        pending: VecDeque::new(),
+    };
+    let events = futures::stream::unfold(state, |mut state| {
+        async move {
+            let update = match state.next_update().await? {
+                Ok(ChainUpdate::Imported(block_hash)) => {
+                    at(state.client, block_hash)
+                        .await
+                        .map(BestChainEvent::Imported)
+                }
+                Ok(ChainUpdate::Retracted(block_hash)) => {
+                    Ok(BestChainEvent::Retracted { block_hash })
+                }
+                Err(e) => Err(e),
+            };
+            Some((update, state))
+        }
+    });
+    Ok(BestChainEvents {
+        events: Box::pin(events),
+    })
+}
+
+/// An update to the best chain, handed back from [`BestChainEvents`].
+#[derive(Derivative)]
+#[derivative(Debug(bound = ""))]
+pub enum BestChainEvent<'a, T: Config, Evs: Decode> {
+    /// A block is now part of the best chain, and these are its events.
+    Imported(Events<'a, T, Evs>),
+    /// A block whose events were handed back previously is no longer part of the best
+    /// chain, because a fork containing it was abandoned. Any state derived from the
+    /// events of this block should be rolled back.
+    Retracted {
+        /// The hash of the block that was retracted.
+        block_hash: T::Hash,
+    },
+}
+
+/// A stream of the events in blocks as they become part of the best chain (which are not
+/// necessarily finalized yet). When the best chain switches to another fork, the blocks
+/// which are no longer part of it are handed back as [`BestChainEvent::Retracted`] (newest
+/// first), followed by the events of every block on the new fork (oldest first).
+///
+/// Reorganisations are only noticed if they're no more than 256 blocks deep.
+#[derive(Derivative)]
+#[derivative(Debug(bound = ""))]
+pub struct BestChainEvents<'a, T: Config, Evs: Decode + 'static> {
+    #[derivative(Debug = "ignore")]
+    events:
+        Pin<Box<dyn Stream<Item = Result<BestChainEvent<'a, T, Evs>, BasicError>> + 'a>>,
+}
+
+impl<'a, T: Config, Evs: Decode> Unpin for BestChainEvents<'a, T, Evs> {}
+
+impl<'a, T: Config, Evs: Decode> Stream for BestChainEvents<'a, T, Evs> {
+    type Item = Result<BestChainEvent<'a, T, Evs>, BasicError>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        self.events.poll_next_unpin(cx)
+    }
+}
+
+// Follows the best block subscription, working out which blocks are imported and retracted.
+struct BestChain<'a, T: Config> {
     client: &'a Client<T>,
     block_header_subscription: Subscription<T::Header>,
+    tracker: ChainTracker<T::Hash>,
+    pending: VecDeque<ChainUpdate<T::Hash>>,
+}
+
+impl<'a, T: Config> BestChain<'a, T> {
+    async fn next_update(&mut self) -> Option<Result<ChainUpdate<T::Hash>, BasicError>> {
+        loop {
+            if let Some(update) = self.pending.pop_front() {
+                return Some(Ok(update))
+            }
+
+            let header = match self.block_header_subscription.next().await? {
+                Ok(header) => header,
+                Err(e) => return Some(Err(e.into())),
+            };
+            if let Err(e) = self.follow_branch(header).await {
+                return Some(Err(e))
+            }
+        }
+    }
+
+    // Follow the branch that the new best block is on back to the blocks we've already
+    // seen, fetching the headers of any blocks on it that we haven't. Usually the new best
+    // block is a child of the last one, so nothing needs fetching.
+    async fn follow_branch(&mut self, mut header: T::Header) -> Result<(), BasicError> {
+        loop {
+            let number: u64 = (*header.number()).unique_saturated_into();
+            let parent_hash = *header.parent_hash();
+            if let Some(updates) =
+                self.tracker
+                    .add_with_parent(number, header.hash(), parent_hash)
+            {
+                self.pending.extend(updates);
+                return Ok(())
+            }
+            header = self
+                .client
+                .rpc()
+                .header(Some(parent_hash))
+                .await?
+                .ok_or_else(|| {
+                    BasicError::Other(format!("Block header {:?} not found", parent_hash))
+                })?;
+        }
+    }
+}
+
+// The deepest reorganisation of the best chain that we can notice.
+const MAX_REORG_DEPTH: usize = 256;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ChainUpdate<Hash> {
+    Imported(Hash),
+    Retracted(Hash),
+}
+
+// Keeps track of the most recent blocks on the best chain, so that we can work out which
+// blocks are retracted when the best chain switches to another fork.
+#[derive(Debug)]
+struct ChainTracker<Hash> {
+    // The number and hash of the blocks on the best chain, oldest first.
+    chain: VecDeque<(u64, Hash)>,
+    // Blocks on a new branch, newest first, which we're following back to the chain.
+    branch: Vec<(u64, Hash)>,
+}
+
+impl<Hash> Default for ChainTracker<Hash> {
+    fn default() -> Self {
+        ChainTracker {
+            chain: VecDeque::new(),
+            branch: Vec::new(),
+        }
+    }
+}
+
+impl<Hash: Copy + PartialEq> ChainTracker<Hash> {
+    // Add a block to the branch being followed, starting with the new best block and then
+    // adding each parent in turn. Once the branch joins the chain (or we give up looking),
+    // the updates to the chain are returned. Until then, `None` is returned, and the
+    // parent of the block just added should be added next.
+    fn add(&mut self, number: u64, hash: Hash) -> Option<Vec<ChainUpdate<Hash>>> {
+        if let Some(pos) = self.chain.iter().rposition(|(_, h)| *h == hash) {
+            return Some(self.switch_branch(Some(pos)))
+        }
+
+        // If we're below every block on the chain, the branch joins it at some point before
+        // the blocks we know about, so assume that they're all retracted.
+        let below_chain = self.chain.front().map_or(false, |(n, _)| number < *n);
+        if below_chain && !self.branch.is_empty() {
+            return Some(self.switch_branch(None))
+        }
+
+        self.branch.push((number, hash));
+        if self.chain.is_empty() || number == 0 || self.branch.len() > MAX_REORG_DEPTH {
+            return Some(self.switch_branch(None))
+        }
+        None
+    }
+
+    // Much like `add`, but given the hash of the block's parent too, so that if the parent
+    // is on the chain (as it is when the block just extends the chain), the branch joins
+    // the chain there without its parent needing to be added.
+    fn add_with_parent(
+        &mut self,
+        number: u64,
+        hash: Hash,
+        parent_hash: Hash,
+    ) -> Option<Vec<ChainUpdate<Hash>>> {
+        let on_chain = |h: &Hash| self.chain.iter().rposition(|(_, c)| c == h);
+        if on_chain(&hash).is_none() {
+            if let Some(pos) = on_chain(&parent_hash) {
+                self.branch.push((number, hash));
+                return Some(self.switch_branch(Some(pos)))
+            }
+        }
+        self.add(number, hash)
+    }
+
+    // Retract the blocks on the chain after the given position (or all of them), and then
+    // import the blocks on the branch.
+    fn switch_branch(&mut self, join_pos: Option<usize>) -> Vec<ChainUpdate<Hash>> {
+        let keep = join_pos.map_or(0, |pos| pos + 1);
+        let mut updates: Vec<_> = self
+            .chain
+            .drain(keep..)
+            .rev()
+            .map(|(_, hash)| ChainUpdate::Retracted(hash))
+            .collect();
+        for (number, hash) in self.branch.drain(..).rev() {
+            updates.push(ChainUpdate::Imported(hash));
+            self.chain.push_back((number, hash));
+        }
+        while self.chain.len() > MAX_REORG_DEPTH {
+            self.chain.pop_front();
+        }
+        updates
+    }
+}
+
+/// Subscribe to the events deposited with the given topic.
+///
+/// **Note:** This function is hidden from the documentation
+/// and is exposed only to be called via the client. Thus, prefer to use
+/// `client.subscribe_event_topic(topic)` over calling this directly.
+#[doc(hidden)]
+pub async fn subscribe_topic<T: Config>(
+    client: &'_ Client<T>,
+    topic: T::Hash,
+) -> Result<TopicSubscription<'_, T>, BasicError> {
+    let key = event_topics_key(topic.as_ref());
+    let storage_subscription = client
+        .rpc()
+        .subscribe_storage(Some(vec![key.clone()]))
+        .await?;
+    let events = futures::stream::unfold(storage_subscription, move |mut sub| {
+        let key = key.clone();
+        async move {
+            loop {
+                let change_set = match sub.next().await? {
+                    Ok(change_set) => change_set,
+                    Err(e) => return Some((Err(e.into()), sub)),
+                };
+                let block_hash = change_set.block;
+                let data = change_set
+                    .changes
+                    .into_iter()
+                    .find(|(k, _)| *k == key)
+                    .and_then(|(_, data)| data);
+                // The topics are cleared at the start of every block, so there's nothing
+                // to hand back if they were only removed.
+                let data = match data {
+                    Some(data) => data,
+                    None => continue,
+                };
+                let res = topic_events::<T>(client, block_hash, &data.0).await;
+                match res {
+                    Ok(events) if events.is_empty() => continue,
+                    Ok(events) => return Some((Ok((block_hash, events)), sub)),
+                    Err(e) => return Some((Err(e), sub)),
+                }
+            }
+        }
+    });
+    Ok(TopicSubscription {
+        events: Box::pin(events),
+    })
+}
+
+// Given the SCALE encoded `System::EventTopics` entry for some topic, which contains
+// the indexes of the events deposited with it, fetch those events.
+async fn topic_events<T: Config>(
+    client: &Client<T>,
+    block_hash: T::Hash,
+    encoded_entries: &[u8],
+) -> Result<Vec<RawEventDetails<T::Hash>>, BasicError> {
+    let entries = Vec::<(T::BlockNumber, u32)>::decode(&mut &*encoded_entries)?;
+    if entries.is_empty() {
+        return Ok(Vec::new())
+    }
+    let events = at::<T, ()>(client, block_hash).await?;
+    events
+        .iter_raw()
+        .filter(|ev| {
+            match ev {
+                Ok(ev) => entries.iter().any(|(_, index)| *index == ev.index),
+                Err(_) => true,
+            }
+        })
+        .collect()
+}
+
+/// A subscription to the events deposited with some topic, returned from
+/// [`crate::Client::subscribe_event_topic()`]. This hands back the hash of each
+/// new best block that contains events with the topic, along with those events.
+#[derive(Derivative)]
+#[derivative(Debug(bound = ""))]
+pub struct TopicSubscription<'a, T: Config> {
     #[derivative(Debug = "ignore")]
-    at: Option<
-        std::pin::Pin<
-            Box<dyn Future<Output = Result<Events<'a, T, Evs>, BasicError>> + 'a>,
+    events: Pin<
+        Box<
+            dyn Stream<
+                    Item = Result<(T::Hash, Vec<RawEventDetails<T::Hash>>), BasicError>,
+                > + 'a,
         >,
     >,
+}
+
+impl<'a, T: Config> Unpin for TopicSubscription<'a, T> {}
+
+impl<'a, T: Config> Stream for TopicSubscription<'a, T> {
+    type Item = Result<(T::Hash, Vec<RawEventDetails<T::Hash>>), BasicError>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        self.events.poll_next_unpin(cx)
+    }
+}
+
+// Which blocks are we subscribing to the events of?
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum BlockSubscriptionKind {
+    Best,
+    Finalized,
+}
+
+pub(crate) async fn subscribe_headers<T: Config>(
+    client: &Client<T>,
+    kind: BlockSubscriptionKind,
+) -> Result<Subscription<T::Header>, BasicError> {
+    match kind {
+        BlockSubscriptionKind::Best => client.rpc().subscribe_blocks().await,
+        BlockSubscriptionKind::Finalized => {
+            client.rpc().subscribe_finalized_blocks().await
+        }
+    }
+}
+
+/// What an [`EventSubscription`] does when the underlying block subscription is closed
+/// because a slow consumer let the buffer of notifications waiting to be handled fill up
+/// (see [`crate::ClientBuilder::set_subscription_buffer_size()`]). The client never
+/// buffers more than that many notifications, so memory usage is bounded either way.
+///
+/// The client closes the subscription without saying why, and it's closed in the same way
+/// when the connection to the node closes, so overflowing is detected by resubscribing and
+/// checking whether any blocks were missed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubscriptionOverflow {
+    /// Resubscribe, and if any blocks were missed, hand back an error and end the
+    /// subscription. If resubscribing fails (for instance because the connection was
+    /// closed), the subscription just ends. This is the default.
+    Error,
+    /// Resubscribe, skipping any blocks that were missed, so that the subscription
+    /// carries on from the latest block.
+    DropOldest,
+    /// Resubscribe, and fetch the events for any blocks that were missed as they are
+    /// asked for. Nothing is lost; a slow consumer just falls further behind the head
+    /// of the chain, rather than blocks being buffered up for it.
+    Backfill,
+}
+
+impl Default for SubscriptionOverflow {
+    fn default() -> Self {
+        SubscriptionOverflow::Error
+    }
+}
+
+/// A handle to the health metrics of a block based subscription, such as an
+/// [`EventSubscription`] or an [`crate::indexer::EventIndexer`]. It's cheap to clone,
+/// and can be handed to some other task which keeps an eye on the subscription (for
+/// instance, to alert if it falls too far behind the head of the chain).
+#[derive(Clone, Debug, Default)]
+pub struct SubscriptionMetrics {
+    inner: Arc<SubscriptionMetricsInner>,
+}
+
+// Block numbers are stored plus one, so that 0 means that no block has been seen yet.
+#[derive(Debug, Default)]
+struct SubscriptionMetricsInner {
+    head: AtomicU64,
+    last: AtomicU64,
+    buffered: AtomicU64,
+    resubscribes: AtomicU64,
+    dropped: AtomicU64,
+}
+
+impl SubscriptionMetrics {
+    /// The number of the latest block seen on the underlying block subscription.
+    pub fn head_block_number(&self) -> Option<u64> {
+        self.inner.head.load(Ordering::Relaxed).checked_sub(1)
+    }
+
+    /// The number of the last block that was handed back from the subscription.
+    pub fn last_block_number(&self) -> Option<u64> {
+        self.inner.last.load(Ordering::Relaxed).checked_sub(1)
+    }
+
+    /// How many blocks the subscription is behind the latest block that it has seen.
+    pub fn lag(&self) -> u64 {
+        match (self.head_block_number(), self.last_block_number()) {
+            (Some(head), Some(last)) => head.saturating_sub(last),
+            (Some(head), None) => head.saturating_add(1),
+            _ => 0,
+        }
+    }
+
+    /// The number of blocks waiting to be handed back, including any missed blocks
+    /// that still need to be backfilled.
+    ///
+    /// **Note:** Notifications buffered by the RPC client (see
+    /// [`crate::ClientBuilder::set_subscription_buffer_size()`]) aren't included.
+    pub fn buffered(&self) -> u64 {
+        self.inner.buffered.load(Ordering::Relaxed)
+    }
+
+    /// The number of times the underlying block subscription has been re-established.
+    pub fn resubscribes(&self) -> u64 {
+        self.inner.resubscribes.load(Ordering::Relaxed)
+    }
+
+    /// The number of blocks that were skipped over, and so will never be handed back.
+    /// This happens when resubscribing with [`SubscriptionOverflow::DropOldest`], or
+    /// when several blocks are finalized at once and the subscription isn't resilient
+    /// (see [`EventSubscription::resilient()`]).
+    pub fn dropped_blocks(&self) -> u64 {
+        self.inner.dropped.load(Ordering::Relaxed)
+    }
+
+    fn record_head(&self, block_number: u64) {
+        self.inner
+            .head
+            .fetch_max(block_number.saturating_add(1), Ordering::Relaxed);
+    }
+
+    fn record_handed_back(&self, block_number: u64, buffered: u64) {
+        self.inner
+            .last
+            .store(block_number.saturating_add(1), Ordering::Relaxed);
+        self.inner.buffered.store(buffered, Ordering::Relaxed);
+    }
+
+    fn record_resubscribe(&self) {
+        self.inner.resubscribes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_dropped(&self, blocks: u64) {
+        self.inner.dropped.fetch_add(blocks, Ordering::Relaxed);
+    }
+}
+
+// A future which resolves to the events in some block.
+type EventsFuture<'a, T, Evs> =
+    Pin<Box<dyn Future<Output = Result<Events<'a, T, Evs>, BasicError>> + 'a>>;
+
+/// A subscription to events that implements [`Stream`], and returns [`Events`] objects for each block.
+///
+/// If the runtime is upgraded, the metadata of the new runtime is fetched and used to
+/// decode the events of every block after it (see [`Client::events_at()`]).
+#[derive(Derivative)]
+#[derivative(Debug(bound = ""))]
+pub struct EventSubscription<'a, T: Config, Evs: Decode + 'static> {
+    finished: bool,
+    client: &'a Client<T>,
+    // How to obtain block hashes. This can be configured until the subscription is first
+    // polled, at which point it's turned into the `block_hashes` stream.
+    #[derivative(Debug = "ignore")]
+    block_hashes_state: Option<BlockHashes<'a, T>>,
+    #[derivative(Debug = "ignore")]
+    block_hashes: Option<BlockHashStream<'a, T>>,
+    #[derivative(Debug = "ignore")]
+    at: Option<EventsFuture<'a, T, Evs>>,
+    pallet_filter: Option<Vec<String>>,
+    account_filter: Option<T::AccountId>,
+    metrics: SubscriptionMetrics,
     _event_type: std::marker::PhantomData<Evs>,
 }
 
@@ -149,15 +762,99 @@ impl<'a, T: Config, Evs: Decode> EventSubscription<'a, T, Evs> {
     fn new(
         client: &'a Client<T>,
         block_header_subscription: Subscription<T::Header>,
+        kind: BlockSubscriptionKind,
     ) -> Self {
+        let overflow = client.subscription_overflow();
+        let block_hashes_state =
+            BlockHashes::new(client, kind, block_header_subscription, overflow);
+        let metrics = block_hashes_state.metrics();
         EventSubscription {
             finished: false,
             client,
-            block_header_subscription,
+            block_hashes_state: Some(block_hashes_state),
+            block_hashes: None,
             at: None,
+            pallet_filter: None,
+            account_filter: None,
+            metrics,
             _event_type: std::marker::PhantomData,
         }
     }
+
+    /// A handle to the health metrics of this subscription, which can be handed to some
+    /// other task to keep an eye on how far behind the head of the chain it is, how many
+    /// times it had to resubscribe, and so on.
+    pub fn metrics(&self) -> SubscriptionMetrics {
+        self.metrics.clone()
+    }
+
+    /// Only hand back events from the given pallet. This can be called multiple times
+    /// to receive events from several pallets. See [`Events::filter_pallets()`].
+    pub fn filter_pallet(self, pallet: impl Into<String>) -> Self {
+        self.filter_pallets([pallet])
+    }
+
+    /// Only hand back events from the given pallets. See [`Events::filter_pallets()`].
+    pub fn filter_pallets<I, S>(mut self, pallets: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.pallet_filter
+            .get_or_insert_with(Vec::new)
+            .extend(pallets.into_iter().map(Into::into));
+        self
+    }
+
+    /// Only hand back events which reference the given account, so that everything
+    /// touching some account can be watched for. See [`Events::filter_account()`].
+    pub fn filter_account(mut self, account: T::AccountId) -> Self {
+        self.account_filter = Some(account);
+        self
+    }
+
+    /// Make this subscription resilient to the underlying block subscription failing. If it
+    /// errors or ends, we'll resubscribe (up to `max_resubscribes` times), and if any
+    /// blocks are skipped over (for instance while resubscribing, or because several
+    /// blocks were finalized at once), their events are fetched too, so that events are
+    /// handed back for every block in order. This overrides the [`SubscriptionOverflow`]
+    /// behaviour configured on the client with [`SubscriptionOverflow::Backfill`].
+    ///
+    /// Resubscribing uses the same RPC client, so it's only useful if the client is able
+    /// to reconnect after the connection to the node drops.
+    ///
+    /// **Note:** This has no effect once the subscription has been polled for events.
+    pub fn resilient(mut self, max_resubscribes: usize) -> Self {
+        if let Some(state) = &mut self.block_hashes_state {
+            state.overflow = SubscriptionOverflow::Backfill;
+            state.resubscribes_left = Some(max_resubscribes);
+        }
+        self
+    }
+
+    /// Flatten this subscription into a stream of individual events, each statically
+    /// decoded into `Evs` (the generated outer `Event` enum when obtained via
+    /// `api.events().subscribe()`), and paired with the hash of the block it came from.
+    ///
+    /// As with [`Events::iter()`], if an event cannot be decoded, an error is returned
+    /// and the rest of the events in that block are skipped.
+    pub fn decoded(self) -> DecodedEvents<'a, T, Evs> {
+        DecodedEvents {
+            subscription: self,
+            buffered: VecDeque::new(),
+        }
+    }
+
+    /// Flatten this subscription into a stream of the events matching the [`EventFilter`]
+    /// provided, each paired with the hash of the block it came from. Every other event
+    /// is skipped over. See [`Events::filter_events()`].
+    pub fn filter_events<F: EventFilter>(self) -> FilterEvents<'a, T, Evs, F> {
+        FilterEvents {
+            subscription: self,
+            buffered: VecDeque::new(),
+            _filter: std::marker::PhantomData,
+        }
+    }
 }
 
 impl<'a, T: Config, Evs: Decode> Unpin for EventSubscription<'a, T, Evs> {}
@@ -192,22 +889,30 @@ impl<'a, T: Config, Evs: Decode> Stream for EventSubscription<'a, T, Evs> {
         }
 
         // If there isn't an `at` function yet that's busy resolving a block hash into
-        // some event details, then poll the block header subscription to get one.
+        // some event details, then poll the block hash stream to get one.
         if self.at.is_none() {
-            match futures::ready!(self.block_header_subscription.poll_next_unpin(cx)) {
+            if let Some(state) = self.block_hashes_state.take() {
+                self.block_hashes = Some(state.into_stream());
+            }
+            let block_hashes = self
+                .block_hashes
+                .as_mut()
+                .expect("block hash stream should have been set above");
+            match futures::ready!(block_hashes.poll_next_unpin(cx)) {
                 None => {
                     self.finished = true;
                     return Poll::Ready(None)
                 }
                 Some(Err(e)) => {
-                    self.finished = true;
-                    return Poll::Ready(Some(Err(e.into())))
+                    // The block hash stream will end if it can't carry on after this.
+                    return Poll::Ready(Some(Err(e)))
                 }
-                Some(Ok(block_header)) => {
-                    use sp_runtime::traits::Header;
+                Some(Ok((_, block_hash))) => {
                     // Note [jsdw]: We may be able to get rid of the per-item allocation
                     // with https://github.com/oblique/reusable-box-future.
-                    self.at = Some(Box::pin(at(self.client, block_header.hash())));
+                    let at_fn: EventsFuture<'a, T, Evs> =
+                        Box::pin(at(self.client, block_hash));
+                    self.at = Some(at_fn);
                     // Continue, so that we poll this function future we've just created.
                 }
             }
@@ -221,39 +926,551 @@ impl<'a, T: Config, Evs: Decode> Stream for EventSubscription<'a, T, Evs> {
             .expect("'at' function should have been set above'");
         let events = futures::ready!(at_fn.poll_unpin(cx));
         self.at = None;
+        let events = match &self.pallet_filter {
+            Some(pallets) => events.map(|events| events.filter_pallets(pallets.clone())),
+            None => events,
+        };
+        let events = match &self.account_filter {
+            Some(account) => events.map(|events| events.filter_account(account)),
+            None => events,
+        };
         Poll::Ready(Some(events))
     }
 }
 
-/// A collection of events obtained from a block, bundled with the necessary
-/// information needed to decode and iterate over them.
-#[derive(Derivative)]
-#[derivative(Debug(bound = ""))]
-pub struct Events<'a, T: Config, Evs: Decode> {
-    metadata: &'a Metadata,
-    block_hash: T::Hash,
-    // Note; raw event bytes are prefixed with a Compact<u32> containing
-    // the number of events to be decoded. We should have stripped that off
-    // before storing the bytes here.
-    event_bytes: Vec<u8>,
-    num_events: u32,
-    _event_type: std::marker::PhantomData<Evs>,
+// The number of missed blocks to fetch the hashes of at once when backfilling.
+const BACKFILL_BATCH_SIZE: u64 = 256;
+
+// How long to wait before resubscribing to blocks.
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(1);
+
+// The numbers and hashes of blocks, as produced by [`BlockHashes::into_stream()`].
+pub(crate) type BlockHashStream<'a, T> =
+    Pin<Box<dyn Stream<Item = Result<(u64, <T as Config>::Hash), BasicError>> + 'a>>;
+
+// Produces the numbers and hashes of the blocks that an [`EventSubscription`] should fetch
+// events for, resubscribing and backfilling missed blocks according to the
+// [`SubscriptionOverflow`] behaviour.
+pub(crate) struct BlockHashes<'a, T: Config> {
+    client: &'a Client<T>,
+    kind: BlockSubscriptionKind,
+    block_header_subscription: Option<Subscription<T::Header>>,
+    overflow: SubscriptionOverflow,
+    // `None` if we can resubscribe any number of times.
+    resubscribes_left: Option<usize>,
+    // The number of the block we expect to see next, if we've seen any yet.
+    next_block_number: Option<u64>,
+    // Blocks that were skipped over, whose hashes we've fetched, to hand back first.
+    backfilled: VecDeque<(u64, T::Hash)>,
+    // Numbers of the blocks that were skipped over and still need to be handed back.
+    // These come before anything in `pending`.
+    missed: Range<u64>,
+    // Blocks that we need to hand back next.
+    pending: VecDeque<(u64, T::Hash)>,
+    // Whether we resubscribed to find out if the last subscription overflowed, and so
+    // need to check the next block for a gap.
+    check_for_overflow: bool,
+    metrics: SubscriptionMetrics,
 }
 
-impl<'a, T: Config, Evs: Decode> Events<'a, T, Evs> {
-    /// The number of events.
-    pub fn len(&self) -> u32 {
-        self.num_events
+impl<'a, T: Config> BlockHashes<'a, T> {
+    pub(crate) fn new(
+        client: &'a Client<T>,
+        kind: BlockSubscriptionKind,
+        block_header_subscription: Subscription<T::Header>,
+        overflow: SubscriptionOverflow,
+    ) -> Self {
+        BlockHashes {
+            client,
+            kind,
+            block_header_subscription: Some(block_header_subscription),
+            overflow,
+            resubscribes_left: match overflow {
+                SubscriptionOverflow::Error => Some(0),
+                _ => None,
+            },
+            next_block_number: None,
+            backfilled: VecDeque::new(),
+            missed: 0..0,
+            pending: VecDeque::new(),
+            check_for_overflow: false,
+            metrics: SubscriptionMetrics::default(),
+        }
     }
 
-    /// Are there no events in this block?
-    // Note: mainly here to satisfy clippy.
-    pub fn is_empty(&self) -> bool {
-        self.num_events == 0
+    // The metrics which are updated as blocks are seen and handed back.
+    pub(crate) fn metrics(&self) -> SubscriptionMetrics {
+        self.metrics.clone()
     }
 
-    /// Return the block hash that these events are from.
-    pub fn block_hash(&self) -> T::Hash {
+    // Update the metrics given rather than the ones created with this.
+    pub(crate) fn report_to(&mut self, metrics: SubscriptionMetrics) {
+        self.metrics = metrics;
+    }
+
+    // Hand back every block from the given block number onwards, backfilling any that
+    // are older than the first block seen on the subscription. This only makes sense with
+    // [`SubscriptionOverflow::Backfill`], since otherwise nothing is backfilled.
+    pub(crate) fn start_from(&mut self, block_number: u64) {
+        self.next_block_number = Some(block_number);
+    }
+
+    // The number of times we'll resubscribe if the block subscription fails or ends;
+    // `None` to always resubscribe.
+    pub(crate) fn max_resubscribes(&mut self, max_resubscribes: Option<usize>) {
+        self.resubscribes_left = max_resubscribes;
+    }
+
+    pub(crate) fn into_stream(self) -> BlockHashStream<'a, T> {
+        Box::pin(futures::stream::unfold(self, |mut state| {
+            async move {
+                let hash = state.next_hash().await?;
+                if let Ok((n, _)) = &hash {
+                    let buffered = state.missed.end - state.missed.start;
+                    let buffered = buffered
+                        + state.backfilled.len() as u64
+                        + state.pending.len() as u64;
+                    state.metrics.record_handed_back(*n, buffered);
+                }
+                Some((hash, state))
+            }
+        }))
+    }
+
+    async fn next_hash(&mut self) -> Option<Result<(u64, T::Hash), BasicError>> {
+        loop {
+            if let Some(block) = self.backfilled.pop_front() {
+                return Some(Ok(block))
+            }
+
+            // Backfill any blocks that we skipped over, fetching the hashes of a batch of
+            // them at once, and leaving them in place if we fail so that we'll try again
+            // next time.
+            if !self.missed.is_empty() {
+                let batch_end = self
+                    .missed
+                    .end
+                    .min(self.missed.start.saturating_add(BACKFILL_BATCH_SIZE));
+                let batch = self.missed.start..batch_end;
+                let block_numbers = batch
+                    .clone()
+                    .map(|n| NumberOrHex::Number(n).into())
+                    .collect();
+                match self.client.rpc().block_hashes(block_numbers).await {
+                    Ok(hashes) => {
+                        self.missed.start = batch_end;
+                        for (n, hash) in batch.zip(hashes) {
+                            match hash {
+                                Some(hash) => self.backfilled.push_back((n, hash)),
+                                None => log::warn!("No hash found for block {}", n),
+                            }
+                        }
+                    }
+                    Err(e) => return Some(Err(e)),
+                }
+                continue
+            }
+
+            // Hand back any blocks we know about next.
+            if let Some(block) = self.pending.pop_front() {
+                return Some(Ok(block))
+            }
+
+            // Resubscribe if we need to (and can).
+            if self.block_header_subscription.is_none() {
+                match &mut self.resubscribes_left {
+                    Some(0) => return None,
+                    Some(n) => *n -= 1,
+                    None => {}
+                }
+                Delay::new(RESUBSCRIBE_DELAY).await;
+                match subscribe_headers(self.client, self.kind).await {
+                    Ok(sub) => self.block_header_subscription = Some(sub),
+                    Err(e) => return Some(Err(e)),
+                }
+                self.metrics.record_resubscribe();
+            }
+
+            let sub = self
+                .block_header_subscription
+                .as_mut()
+                .expect("subscription set above; qed");
+            let next = match self.client.subscription_timeout() {
+                Some(timeout) => {
+                    match future::select(Box::pin(sub.next()), Delay::new(timeout)).await
+                    {
+                        Either::Left((next, _)) => next,
+                        Either::Right(_) => {
+                            Some(Err(RpcError::Custom(format!(
+                                "No new blocks seen for {:?}",
+                                timeout
+                            ))))
+                        }
+                    }
+                }
+                None => sub.next().await,
+            };
+            match next {
+                Some(Ok(header)) => {
+                    let number: u64 = (*header.number()).unique_saturated_into();
+                    self.metrics.record_head(number);
+                    let check_for_overflow = std::mem::take(&mut self.check_for_overflow);
+                    if let Some(next) = self.next_block_number {
+                        if self.overflow == SubscriptionOverflow::Backfill {
+                            self.missed = next..number;
+                        } else if check_for_overflow && number > next {
+                            self.metrics.record_dropped(number - next);
+                            self.block_header_subscription = None;
+                            self.resubscribes_left = Some(0);
+                            return Some(Err(BasicError::Other(format!(
+                                "Block subscription closed, and blocks {} to {} were \
+                                 missed; it fell behind and its buffer overflowed",
+                                next,
+                                number - 1
+                            ))))
+                        } else {
+                            // Carry on from the latest block if we don't care about
+                            // missed ones, but keep count of them.
+                            self.metrics.record_dropped(number.saturating_sub(next));
+                        }
+                    }
+                    self.pending.push_back((number, header.hash()));
+                    let after = number.saturating_add(1);
+                    self.next_block_number =
+                        Some(self.next_block_number.map_or(after, |n| n.max(after)));
+                }
+                Some(Err(e)) => {
+                    self.block_header_subscription = None;
+                    if self.resubscribes_left == Some(0) {
+                        return Some(Err(e.into()))
+                    }
+                    log::warn!("Block subscription failed; resubscribing: {:?}", e);
+                }
+                None if self.overflow == SubscriptionOverflow::Error => {
+                    // Resubscribe straight away to find out whether we missed anything.
+                    // If we can't, the connection was most likely closed, and there's
+                    // nothing more to hand back.
+                    match subscribe_headers(self.client, self.kind).await {
+                        Ok(sub) => {
+                            log::debug!("Block subscription closed; resubscribed");
+                            self.block_header_subscription = Some(sub);
+                            self.check_for_overflow = true;
+                            self.metrics.record_resubscribe();
+                        }
+                        Err(e) => {
+                            log::debug!("Block subscription closed: {:?}", e);
+                            self.block_header_subscription = None;
+                            self.resubscribes_left = Some(0);
+                            return None
+                        }
+                    }
+                }
+                None => {
+                    self.block_header_subscription = None;
+                    if self.resubscribes_left == Some(0) {
+                        return Some(Err(BasicError::Other(
+                            "Block subscription closed, and can't be resubscribed to again"
+                                .into(),
+                        )))
+                    }
+                    log::debug!("Block subscription closed; resubscribing");
+                }
+            }
+        }
+    }
+}
+
+/// A stream of statically decoded events, returned from [`EventSubscription::decoded()`].
+#[derive(Derivative)]
+#[derivative(Debug(bound = ""))]
+pub struct DecodedEvents<'a, T: Config, Evs: Decode + 'static> {
+    subscription: EventSubscription<'a, T, Evs>,
+    #[derivative(Debug = "ignore")]
+    buffered: VecDeque<Result<(T::Hash, EventDetails<Evs, T::Hash>), BasicError>>,
+}
+
+impl<'a, T: Config, Evs: Decode> Unpin for DecodedEvents<'a, T, Evs> {}
+
+impl<'a, T: Config, Evs: Decode> Stream for DecodedEvents<'a, T, Evs> {
+    type Item = Result<(T::Hash, EventDetails<Evs, T::Hash>), BasicError>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        loop {
+            // Hand back any events we've already decoded first.
+            if let Some(event) = self.buffered.pop_front() {
+                return Poll::Ready(Some(event))
+            }
+
+            // Else, wait for the events in the next block and buffer them up.
+            match futures::ready!(self.subscription.poll_next_unpin(cx)) {
+                None => return Poll::Ready(None),
+                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                Some(Ok(events)) => {
+                    let block_hash = events.block_hash();
+                    let decoded = events.iter().map(|ev| ev.map(|ev| (block_hash, ev)));
+                    self.buffered.extend(decoded);
+                }
+            }
+        }
+    }
+}
+
+/// A stream of the events matching some [`EventFilter`], returned from
+/// [`EventSubscription::filter_events()`].
+#[derive(Derivative)]
+#[derivative(Debug(bound = ""))]
+pub struct FilterEvents<'a, T: Config, Evs: Decode + 'static, F: EventFilter> {
+    subscription: EventSubscription<'a, T, Evs>,
+    #[derivative(Debug = "ignore")]
+    buffered: VecDeque<Result<(T::Hash, EventDetails<F::Output, T::Hash>), BasicError>>,
+    _filter: std::marker::PhantomData<F>,
+}
+
+impl<'a, T: Config, Evs: Decode, F: EventFilter> Unpin for FilterEvents<'a, T, Evs, F> {}
+
+impl<'a, T: Config, Evs: Decode, F: EventFilter> Stream for FilterEvents<'a, T, Evs, F> {
+    type Item = Result<(T::Hash, EventDetails<F::Output, T::Hash>), BasicError>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        loop {
+            // Hand back any matching events we've already found first.
+            if let Some(event) = self.buffered.pop_front() {
+                return Poll::Ready(Some(event))
+            }
+
+            // Else, wait for the events in the next block and buffer up any matches.
+            match futures::ready!(self.subscription.poll_next_unpin(cx)) {
+                None => return Poll::Ready(None),
+                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                Some(Ok(events)) => {
+                    let block_hash = events.block_hash();
+                    let found = events
+                        .filter_events::<F>()
+                        .map(|ev| ev.map(|ev| (block_hash, ev)));
+                    self.buffered.extend(found);
+                }
+            }
+        }
+    }
+}
+
+/// A set of events to look for, used in [`Events::filter_events()`] and
+/// [`EventSubscription::filter_events()`].
+///
+/// This is implemented for tuples of [`Event`]s, whose output is a tuple with the position
+/// of the matching event set to `Some`. For instance, filtering on `(Transfer, Deposit)`
+/// will hand back `(Some(transfer), None)` or `(None, Some(deposit))` for each match.
+///
+/// It can also be implemented to dispatch matching events into an enum of your own:
+///
+/// ```rust
+/// use subxt::{
+///     codec::{
+///         Decode,
+///         Error,
+///     },
+///     events::{
+///         EventFilter,
+///         RawEventDetails,
+///     },
+///     Event,
+/// };
+///
+/// #[derive(Decode)]
+/// pub struct Transfer(u128);
+/// impl Event for Transfer {
+///     const PALLET: &'static str = "Balances";
+///     const EVENT: &'static str = "Transfer";
+/// }
+///
+/// #[derive(Decode)]
+/// pub struct Deposit(u128);
+/// impl Event for Deposit {
+///     const PALLET: &'static str = "Balances";
+///     const EVENT: &'static str = "Deposit";
+/// }
+///
+/// pub enum BalanceEvent {
+///     Transfer(Transfer),
+///     Deposit(Deposit),
+/// }
+///
+/// impl EventFilter for BalanceEvent {
+///     type Output = Self;
+///
+///     fn filter<H>(event: &RawEventDetails<H>) -> Result<Option<Self>, Error> {
+///         if let Some(ev) = event.as_event::<Transfer>()? {
+///             return Ok(Some(BalanceEvent::Transfer(ev)))
+///         }
+///         Ok(event.as_event::<Deposit>()?.map(BalanceEvent::Deposit))
+///     }
+/// }
+/// ```
+pub trait EventFilter {
+    /// The type handed back for each matching event.
+    type Output;
+
+    /// Attempt to decode the given event into our output, returning `None` if it's not
+    /// an event that we're looking for.
+    fn filter<H>(event: &RawEventDetails<H>) -> Result<Option<Self::Output>, CodecError>;
+}
+
+macro_rules! impl_event_filter {
+    ($($ev:ident : $idx:tt),+) => {
+        impl<$($ev: Event),+> EventFilter for ($($ev,)+) {
+            type Output = ($(Option<$ev>,)+);
+
+            fn filter<H>(event: &RawEventDetails<H>) -> Result<Option<Self::Output>, CodecError> {
+                let mut output: Self::Output = Default::default();
+                $(
+                    if let Some(ev) = event.as_event::<$ev>()? {
+                        output.$idx = Some(ev);
+                        return Ok(Some(output))
+                    }
+                )+
+                Ok(None)
+            }
+        }
+    };
+}
+
+impl_event_filter!(A: 0);
+impl_event_filter!(A: 0, B: 1);
+impl_event_filter!(A: 0, B: 1, C: 2);
+impl_event_filter!(A: 0, B: 1, C: 2, D: 3);
+impl_event_filter!(A: 0, B: 1, C: 2, D: 3, E: 4);
+impl_event_filter!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
+impl_event_filter!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6);
+impl_event_filter!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7);
+
+// Decoders for events which aren't described by the metadata, registered with
+// [`crate::ClientBuilder::register_event_decoder()`] and keyed by pallet and variant index.
+#[derive(Clone, Default)]
+pub(crate) struct EventDecoders {
+    decoders: Arc<HashMap<(u8, u8), CustomEventDecoder>>,
+}
+
+#[derive(Clone)]
+struct CustomEventDecoder {
+    pallet: String,
+    variant: String,
+    consume: Arc<dyn Fn(&mut &[u8]) -> Result<(), CodecError> + Send + Sync>,
+}
+
+impl EventDecoders {
+    pub(crate) fn register(
+        &mut self,
+        (pallet_index, pallet): (u8, String),
+        (variant_index, variant): (u8, String),
+        consume: Arc<dyn Fn(&mut &[u8]) -> Result<(), CodecError> + Send + Sync>,
+    ) {
+        let decoder = CustomEventDecoder {
+            pallet,
+            variant,
+            consume,
+        };
+        Arc::make_mut(&mut self.decoders).insert((pallet_index, variant_index), decoder);
+    }
+
+    fn get(&self, pallet_index: u8, variant_index: u8) -> Option<&CustomEventDecoder> {
+        self.decoders.get(&(pallet_index, variant_index))
+    }
+}
+
+impl std::fmt::Debug for EventDecoders {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.decoders.keys()).finish()
+    }
+}
+
+// The metadata used to decode some [`Events`]. This is normally the client's metadata,
+// but blocks from other runtimes (for instance, after a runtime upgrade) need decoding
+// with the metadata of that runtime.
+#[derive(Clone, Debug)]
+pub(crate) enum EventsMetadata<'a> {
+    Client(&'a Metadata),
+    Other(Arc<Metadata>),
+}
+
+impl<'a> Deref for EventsMetadata<'a> {
+    type Target = Metadata;
+    fn deref(&self) -> &Metadata {
+        match self {
+            EventsMetadata::Client(metadata) => metadata,
+            EventsMetadata::Other(metadata) => metadata,
+        }
+    }
+}
+
+/// A collection of events obtained from a block, bundled with the necessary
+/// information needed to decode and iterate over them.
+#[derive(Derivative)]
+#[derivative(Debug(bound = ""))]
+pub struct Events<'a, T: Config, Evs: Decode> {
+    metadata: EventsMetadata<'a>,
+    // Used to decode any events that the metadata doesn't know about.
+    decoders: EventDecoders,
+    block_hash: T::Hash,
+    // Note; raw event bytes are prefixed with a Compact<u32> containing
+    // the number of events to be decoded. We should have stripped that off
+    // before storing the bytes here.
+    event_bytes: Vec<u8>,
+    num_events: u32,
+    // If set, events from any pallet not in this list are skipped over.
+    pallet_filter: Option<Vec<String>>,
+    // If set, events which don't reference the account with these SCALE encoded bytes
+    // are skipped over.
+    account_filter: Option<Vec<u8>>,
+    _event_type: std::marker::PhantomData<Evs>,
+}
+
+impl<'a, T: Config, Evs: Decode> Events<'a, T, Evs> {
+    /// Only hand back events from the given pallets when iterating. Events from any other
+    /// pallet are skipped over using the metadata, and are never statically decoded. This
+    /// can be called multiple times to add more pallets.
+    ///
+    /// **Note:** [`Events::len()`] and the event indexes are unaffected by this filter.
+    pub fn filter_pallets<I, S>(mut self, pallets: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.pallet_filter
+            .get_or_insert_with(Vec::new)
+            .extend(pallets.into_iter().map(Into::into));
+        self
+    }
+
+    /// Only hand back events which reference the given account in one of their fields
+    /// (at any depth) when iterating. Every other event is skipped over using the metadata,
+    /// and is never statically decoded. See [`RawEventDetails::references_account()`] for
+    /// details of how accounts are found.
+    ///
+    /// **Note:** [`Events::len()`] and the event indexes are unaffected by this filter.
+    pub fn filter_account(mut self, account: &T::AccountId) -> Self {
+        self.account_filter = Some(account.encode());
+        self
+    }
+
+    /// The number of events.
+    pub fn len(&self) -> u32 {
+        self.num_events
+    }
+
+    /// Are there no events in this block?
+    // Note: mainly here to satisfy clippy.
+    pub fn is_empty(&self) -> bool {
+        self.num_events == 0
+    }
+
+    /// Return the block hash that these events are from.
+    pub fn block_hash(&self) -> T::Hash {
         self.block_hash
     }
 
@@ -267,26 +1484,31 @@ impl<'a, T: Config, Evs: Decode> Events<'a, T, Evs> {
     /// which uses runtime metadata to skip over unknown events.
     pub fn iter(
         &self,
-    ) -> impl Iterator<Item = Result<EventDetails<Evs>, BasicError>> + '_ {
+    ) -> impl Iterator<Item = Result<EventDetails<Evs, T::Hash>, BasicError>> + '_ {
         let event_bytes = &self.event_bytes;
 
         let mut pos = 0;
         let mut index = 0;
         std::iter::from_fn(move || {
-            let cursor = &mut &event_bytes[pos..];
-            let start_len = cursor.len();
+            loop {
+                let cursor = &mut &event_bytes[pos..];
+                let start_len = cursor.len();
+
+                if start_len == 0 || self.num_events == index {
+                    return None
+                }
 
-            if start_len == 0 || self.num_events == index {
-                None
-            } else {
                 let mut decode_one_event = || -> Result<_, BasicError> {
                     let phase = Phase::decode(cursor)?;
+                    if self.skip_filtered_event(cursor)? {
+                        return Ok(None)
+                    }
                     let ev = Evs::decode(cursor)?;
-                    let _topics = Vec::<T::Hash>::decode(cursor)?;
-                    Ok((phase, ev))
+                    let topics = Vec::<T::Hash>::decode(cursor)?;
+                    Ok(Some((phase, ev, topics)))
                 };
                 match decode_one_event() {
-                    Ok((phase, event)) => {
+                    Ok(Some((phase, event, topics))) => {
                         // Skip over decoded bytes in next iteration:
                         pos += start_len - cursor.len();
                         // Gather the event details before incrementing the index for the next iter.
@@ -294,22 +1516,94 @@ impl<'a, T: Config, Evs: Decode> Events<'a, T, Evs> {
                             phase,
                             index,
                             event,
+                            topics,
                         }));
                         index += 1;
-                        res
+                        return res
+                    }
+                    Ok(None) => {
+                        // The event was filtered out; move on to the next one.
+                        pos += start_len - cursor.len();
+                        index += 1;
                     }
                     Err(e) => {
                         // By setting the position to the "end" of the event bytes,
                         // the cursor len will become 0 and the iterator will return `None`
                         // from now on:
                         pos = event_bytes.len();
-                        Some(Err(e))
+                        return Some(Err(e))
                     }
                 }
             }
         })
     }
 
+    // If a pallet or account filter is set and the event at the front of the input (which
+    // is expected to have had its phase decoded already) isn't let through by it, use the
+    // metadata to consume the event, including its topics, and return true.
+    fn skip_filtered_event(&self, input: &mut &[u8]) -> Result<bool, BasicError> {
+        if self.pallet_filter.is_none() && self.account_filter.is_none() {
+            return Ok(false)
+        }
+
+        let mut peek = *input;
+        let pallet_index = peek.read_byte()?;
+        let variant_index = peek.read_byte()?;
+        let event_metadata = match self.metadata.event(pallet_index, variant_index) {
+            Ok(event_metadata) => event_metadata,
+            Err(e) => {
+                // We can't see inside events with custom decoders, so they never
+                // reference the account being filtered on.
+                let decoder = self.decoders.get(pallet_index, variant_index).ok_or(e)?;
+                if self.includes_pallet(&decoder.pallet) && self.account_filter.is_none()
+                {
+                    return Ok(false)
+                }
+                (decoder.consume)(&mut peek)?;
+                let _topics = Vec::<T::Hash>::decode(&mut peek)?;
+                *input = peek;
+                return Ok(true)
+            }
+        };
+        let types = &self.metadata.runtime_metadata().types;
+        let fields = event_metadata.variant().fields();
+        let mut included = self.includes_pallet(event_metadata.pallet());
+        if let (true, Some(account)) = (included, &self.account_filter) {
+            let mut fields_input = peek;
+            included =
+                fields_reference_account(fields, types, &mut fields_input, account)?;
+        }
+        if included {
+            return Ok(false)
+        }
+
+        for arg in fields {
+            decode_and_consume_type(arg.ty().id(), types, &mut peek)?;
+        }
+        let _topics = Vec::<T::Hash>::decode(&mut peek)?;
+        *input = peek;
+        Ok(true)
+    }
+
+    // Is this event decoded using a custom decoder rather than the metadata?
+    fn is_custom_event(&self, ev: &RawEventDetails<T::Hash>) -> bool {
+        self.metadata
+            .event(ev.pallet_index, ev.variant_index)
+            .is_err()
+            && self
+                .decoders
+                .get(ev.pallet_index, ev.variant_index)
+                .is_some()
+    }
+
+    // Is the given pallet allowed through the pallet filter, if any?
+    fn includes_pallet(&self, pallet: &str) -> bool {
+        self.pallet_filter
+            .as_ref()
+            .map(|pallets| pallets.iter().any(|p| p == pallet))
+            .unwrap_or(true)
+    }
+
     /// Iterate over all of the events, using metadata to dynamically
     /// decode them as we go, and returning the raw bytes and other associated
     /// details. If an error occurs, all subsequent iterations return `None`.
@@ -319,7 +1613,34 @@ impl<'a, T: Config, Evs: Decode> Events<'a, T, Evs> {
     /// obtained at runtime, which does.
     pub fn iter_raw(
         &self,
-    ) -> impl Iterator<Item = Result<RawEventDetails, BasicError>> + '_ {
+    ) -> impl Iterator<Item = Result<RawEventDetails<T::Hash>, BasicError>> + '_ {
+        self.iter_raw_unfiltered().filter_map(move |ev| {
+            let ev = match ev {
+                Ok(ev) => ev,
+                Err(e) => return Some(Err(e)),
+            };
+            if !self.includes_pallet(&ev.pallet) {
+                return None
+            }
+            match &self.account_filter {
+                // As above, we can't see inside events with custom decoders.
+                Some(_) if self.is_custom_event(&ev) => None,
+                Some(account) => {
+                    match ev.references_account_bytes(&self.metadata, account) {
+                        Ok(true) => Some(Ok(ev)),
+                        Ok(false) => None,
+                        Err(e) => Some(Err(e)),
+                    }
+                }
+                None => Some(Ok(ev)),
+            }
+        })
+    }
+
+    // Iterate over all of the events as in [`Events::iter_raw()`], ignoring any filters.
+    fn iter_raw_unfiltered(
+        &self,
+    ) -> impl Iterator<Item = Result<RawEventDetails<T::Hash>, BasicError>> + '_ {
         let event_bytes = &self.event_bytes;
 
         let mut pos = 0;
@@ -331,7 +1652,12 @@ impl<'a, T: Config, Evs: Decode> Events<'a, T, Evs> {
             if start_len == 0 || self.num_events == index {
                 None
             } else {
-                match decode_raw_event_details::<T>(self.metadata, index, cursor) {
+                match decode_raw_event_details::<T>(
+                    &self.metadata,
+                    &self.decoders,
+                    index,
+                    cursor,
+                ) {
                     Ok(raw_event) => {
                         // Skip over decoded bytes in next iteration:
                         pos += start_len - cursor.len();
@@ -352,6 +1678,44 @@ impl<'a, T: Config, Evs: Decode> Events<'a, T, Evs> {
         })
     }
 
+    /// The metadata used to decode these events.
+    pub(crate) fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Display every event in a human readable form, one per line, alongside its index
+    /// and the [`Phase`] in which it was produced. See [`RawEventDetails::display()`].
+    pub fn display(&self) -> DisplayEvents<'_, 'a, T, Evs> {
+        DisplayEvents { events: self }
+    }
+
+    /// Use the metadata to decode every event into a JSON array, as per
+    /// [`RawEventDetails::to_json()`].
+    pub fn to_json(&self) -> Result<Value, BasicError> {
+        self.iter_raw()
+            .map(|ev| ev.and_then(|ev| ev.to_json(&self.metadata)))
+            .collect::<Result<_, _>>()
+            .map(Value::Array)
+    }
+
+    /// Iterate over all of the events, using metadata to dynamically decode them as in
+    /// [`Events::iter_raw()`], and handing back the result of statically decoding each one
+    /// into `Evs` alongside the raw event details. Unlike [`Events::iter()`], an event
+    /// which fails to statically decode doesn't stop the iteration, and its raw bytes
+    /// remain available.
+    pub fn iter_with_raw(
+        &self,
+    ) -> impl Iterator<
+        Item = Result<(RawEventDetails<T::Hash>, Result<Evs, CodecError>), BasicError>,
+    > + '_ {
+        self.iter_raw().map(|ev| {
+            ev.map(|ev| {
+                let event = ev.as_root_event::<Evs>();
+                (ev, event)
+            })
+        })
+    }
+
     /// Iterate through the events using metadata to dynamically decode and skip
     /// them, and return only those which should decode to the provided `Ev` type.
     /// If an error occurs, all subsequent iterations return `None`.
@@ -365,6 +1729,50 @@ impl<'a, T: Config, Evs: Decode> Events<'a, T, Evs> {
         })
     }
 
+    /// Iterate through the events using metadata to dynamically decode and skip
+    /// them, and return every event which decodes to the provided `Ev` type, along
+    /// with its index and the [`Phase`] in which it was produced.
+    /// If an error occurs, all subsequent iterations return `None`.
+    ///
+    /// **Note:** This method internally uses [`Events::iter_raw()`], so it is safe to
+    /// use even if you do not statically know about all of the possible events.
+    pub fn find_events<Ev: Event>(
+        &self,
+    ) -> impl Iterator<Item = Result<EventDetails<Ev, T::Hash>, BasicError>> + '_ {
+        self.iter_raw().filter_map(|ev| {
+            ev.and_then(|ev| ev.as_event_details::<Ev>().map_err(Into::into))
+                .transpose()
+        })
+    }
+
+    /// Iterate through the events using metadata to dynamically decode and skip
+    /// them, and return every event which matches the provided [`EventFilter`], along
+    /// with its index and the [`Phase`] in which it was produced. This allows looking
+    /// for several types of event at once, e.g. `filter_events::<(Transfer, Deposit)>()`.
+    /// If an error occurs, all subsequent iterations return `None`.
+    ///
+    /// **Note:** This method internally uses [`Events::iter_raw()`], so it is safe to
+    /// use even if you do not statically know about all of the possible events.
+    pub fn filter_events<F: EventFilter>(
+        &self,
+    ) -> impl Iterator<Item = Result<EventDetails<F::Output, T::Hash>, BasicError>> + '_
+    {
+        self.iter_raw().filter_map(|ev| {
+            ev.and_then(|ev| {
+                let event = F::filter(&ev)?;
+                Ok(event.map(|event| {
+                    EventDetails {
+                        phase: ev.phase,
+                        index: ev.index,
+                        event,
+                        topics: ev.topics,
+                    }
+                }))
+            })
+            .transpose()
+        })
+    }
+
     /// Iterate through the events using metadata to dynamically decode and skip
     /// them, and return the first event found which decodes to the provided `Ev` type.
     ///
@@ -374,6 +1782,32 @@ impl<'a, T: Config, Evs: Decode> Events<'a, T, Evs> {
         self.find::<Ev>().next().transpose()
     }
 
+    /// Iterate through the events using metadata to dynamically decode and skip
+    /// them, and return the last event found which decodes to the provided `Ev` type.
+    ///
+    /// **Note:** This method internally uses [`Events::iter_raw()`], so it is safe to
+    /// use even if you do not statically know about all of the possible events.
+    pub fn find_last_event<Ev: Event>(&self) -> Result<Option<Ev>, BasicError> {
+        self.find::<Ev>().last().transpose()
+    }
+
+    /// Like [`Events::find_first_event()`], but also hands back the position of the event
+    /// found; that is, its index and the [`Phase`] in which it was produced (from which the
+    /// index of the extrinsic that produced it can be obtained).
+    pub fn find_first_event_details<Ev: Event>(
+        &self,
+    ) -> Result<Option<EventDetails<Ev, T::Hash>>, BasicError> {
+        self.find_events::<Ev>().next().transpose()
+    }
+
+    /// Like [`Events::find_last_event()`], but also hands back the position of the event
+    /// found. See [`Events::find_first_event_details()`].
+    pub fn find_last_event_details<Ev: Event>(
+        &self,
+    ) -> Result<Option<EventDetails<Ev, T::Hash>>, BasicError> {
+        self.find_events::<Ev>().last().transpose()
+    }
+
     /// Find an event that decodes to the type provided. Returns true if it was found.
     ///
     /// **Note:** This method internally uses [`Events::iter_raw()`], so it is safe to
@@ -381,23 +1815,96 @@ impl<'a, T: Config, Evs: Decode> Events<'a, T, Evs> {
     pub fn has<Ev: crate::Event>(&self) -> Result<bool, BasicError> {
         Ok(self.find::<Ev>().next().transpose()?.is_some())
     }
+
+    /// Count the events of each kind, using the metadata to find where each event begins
+    /// and ends without decoding any of them. Any pallet or account filter is ignored, so
+    /// that every event in the block is counted.
+    pub fn summary(&self) -> Result<EventSummary, BasicError> {
+        let mut summary = EventSummary::default();
+        for ev in self.iter_raw_unfiltered() {
+            let ev = ev?;
+            *summary
+                .counts
+                .entry(ev.pallet)
+                .or_default()
+                .entry(ev.variant)
+                .or_default() += 1;
+        }
+        Ok(summary)
+    }
+}
+
+/// The number of events of each kind in a block, as returned from [`Events::summary()`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct EventSummary {
+    // Pallet name to event name to count.
+    counts: BTreeMap<String, BTreeMap<String, u32>>,
+}
+
+impl EventSummary {
+    /// The total number of events.
+    pub fn total(&self) -> u32 {
+        self.counts
+            .keys()
+            .map(|pallet| self.pallet_count(pallet))
+            .sum()
+    }
+
+    /// The number of events from the given pallet.
+    pub fn pallet_count(&self, pallet: &str) -> u32 {
+        self.counts
+            .get(pallet)
+            .map_or(0, |events| events.values().sum())
+    }
+
+    /// The number of events of the given kind.
+    pub fn count(&self, pallet: &str, event: &str) -> u32 {
+        self.counts
+            .get(pallet)
+            .and_then(|events| events.get(event))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Iterate over the number of events of each kind, as `(pallet, event, count)`,
+    /// ordered by pallet and then event name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str, u32)> + '_ {
+        self.counts.iter().flat_map(|(pallet, events)| {
+            events
+                .iter()
+                .map(move |(event, count)| (pallet.as_str(), event.as_str(), *count))
+        })
+    }
 }
 
 /// A decoded event and associated details.
-#[derive(Debug, Clone, PartialEq)]
-pub struct EventDetails<Evs> {
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EventDetails<Evs, Hash = sp_core::H256> {
     /// During which [`Phase`] was the event produced?
     pub phase: Phase,
     /// What index is this event in the stored events for this block.
     pub index: u32,
     /// The event itself.
     pub event: Evs,
+    /// The topics that the event was deposited with.
+    pub topics: Vec<Hash>,
+}
+
+impl<Evs, Hash> EventDetails<Evs, Hash> {
+    /// The index of the extrinsic which produced this event, if it was produced
+    /// while applying an extrinsic.
+    pub fn extrinsic_index(&self) -> Option<u32> {
+        match self.phase {
+            Phase::ApplyExtrinsic(idx) => Some(idx),
+            _ => None,
+        }
+    }
 }
 
 /// The raw bytes for an event with associated details about
 /// where and when it was emitted.
-#[derive(Debug, Clone, PartialEq)]
-pub struct RawEventDetails {
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RawEventDetails<Hash = sp_core::H256> {
     /// When was the event produced?
     pub phase: Phase,
     /// What index is this event in the stored events for this block.
@@ -412,25 +1919,261 @@ pub struct RawEventDetails {
     pub variant_index: u8,
     /// The raw Event data
     pub data: Bytes,
+    /// The topics that the event was deposited with.
+    pub topics: Vec<Hash>,
 }
 
-impl RawEventDetails {
-    /// Attempt to decode this [`RawEventDetails`] into a specific event.
-    pub fn as_event<E: Event>(&self) -> Result<Option<E>, CodecError> {
+impl<Hash> RawEventDetails<Hash> {
+    /// The index of the extrinsic which produced this event, if it was produced
+    /// while applying an extrinsic.
+    pub fn extrinsic_index(&self) -> Option<u32> {
+        match self.phase {
+            Phase::ApplyExtrinsic(idx) => Some(idx),
+            _ => None,
+        }
+    }
+
+    /// The SCALE encoded bytes of the event as it appears in the outermost event enum;
+    /// that is, the pallet index, followed by the variant index and then the event data.
+    pub fn event_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.data.len() + 2);
+        bytes.push(self.pallet_index);
+        bytes.push(self.variant_index);
+        bytes.extend_from_slice(&self.data);
+        bytes
+    }
+
+    /// Attempt to statically decode this [`RawEventDetails`] into the outermost event
+    /// enum `Evs`, which contains all of the possible events across all pallets.
+    pub fn as_root_event<Evs: Decode>(&self) -> Result<Evs, CodecError> {
+        Evs::decode(&mut &*self.event_bytes())
+    }
+
+    /// Use the metadata provided to decode this event into a JSON value containing
+    /// its details, including an object or array of its decoded fields. This
+    /// doesn't require the event to be statically known, and so is useful for
+    /// archiving events or pushing them into external stores as they are.
+    ///
+    /// Byte arrays and sequences are represented as hex strings, as are 256 bit
+    /// integers (in their little endian SCALE encoded form), and 128 bit integers
+    /// are represented as strings if they don't fit into 64 bits.
+    pub fn to_json(&self, metadata: &Metadata) -> Result<Value, BasicError>
+    where
+        Hash: Serialize,
+    {
+        let event_metadata = metadata.event(self.pallet_index, self.variant_index)?;
+        let types = &metadata.runtime_metadata().types;
+        let fields = decode_fields_as_json(
+            event_metadata.variant().fields(),
+            types,
+            &mut &*self.data,
+        )?;
+
+        Ok(serde_json::json!({
+            "index": self.index,
+            "phase": self.phase,
+            "pallet": self.pallet,
+            "variant": self.variant,
+            "fields": fields,
+            "topics": self.topics,
+        }))
+    }
+
+    /// Use the metadata provided to display this event in a human readable form, as
+    /// `Pallet.Variant` followed by its decoded fields, for instance
+    /// `Balances.Transfer { from: 0x.., to: 0x.., amount: 10000 }`. This is intended
+    /// for logging; events that can't be decoded using the metadata are displayed
+    /// with their raw data as a hex string.
+    pub fn display<'b>(&'b self, metadata: &'b Metadata) -> DisplayEvent<'b, Hash> {
+        DisplayEvent {
+            event: self,
+            metadata,
+        }
+    }
+
+    /// Use the metadata provided to find out whether any of the fields of this event
+    /// (at any depth) are the given account. Fields are considered to be accounts if
+    /// the name of their type begins with `AccountId` (for instance, `AccountId32` or
+    /// `AccountId20`), which is the case for the `AccountId` of most chains.
+    pub fn references_account<AccountId: Encode>(
+        &self,
+        metadata: &Metadata,
+        account: &AccountId,
+    ) -> Result<bool, BasicError> {
+        self.references_account_bytes(metadata, &account.encode())
+    }
+
+    fn references_account_bytes(
+        &self,
+        metadata: &Metadata,
+        account: &[u8],
+    ) -> Result<bool, BasicError> {
+        let event_metadata = metadata.event(self.pallet_index, self.variant_index)?;
+        let types = &metadata.runtime_metadata().types;
+        fields_reference_account(
+            event_metadata.variant().fields(),
+            types,
+            &mut &*self.data,
+            account,
+        )
+    }
+
+    /// Attempt to decode this [`RawEventDetails`] into a specific event.
+    pub fn as_event<E: Event>(&self) -> Result<Option<E>, CodecError> {
         if self.pallet == E::PALLET && self.variant == E::EVENT {
             Ok(Some(E::decode(&mut &self.data[..])?))
         } else {
             Ok(None)
         }
     }
+
+    /// Attempt to decode this [`RawEventDetails`] into a specific event, keeping
+    /// the details of when it was produced.
+    pub fn as_event_details<E: Event>(
+        &self,
+    ) -> Result<Option<EventDetails<E, Hash>>, CodecError>
+    where
+        Hash: Clone,
+    {
+        Ok(self.as_event::<E>()?.map(|event| {
+            EventDetails {
+                phase: self.phase.clone(),
+                index: self.index,
+                event,
+                topics: self.topics.clone(),
+            }
+        }))
+    }
+}
+
+/// Displays the [`Events`] in a block in a human readable form. See [`Events::display()`].
+pub struct DisplayEvents<'b, 'a, T: Config, Evs: Decode> {
+    events: &'b Events<'a, T, Evs>,
+}
+
+impl<'b, 'a, T: Config, Evs: Decode> std::fmt::Display for DisplayEvents<'b, 'a, T, Evs> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "events in block {:?}:", self.events.block_hash)?;
+        fmt_raw_events(self.events.iter_raw(), &self.events.metadata, f)
+    }
+}
+
+// Write out each of the events given on its own line, stopping at the first error.
+pub(crate) fn fmt_raw_events<Hash>(
+    events: impl Iterator<Item = Result<RawEventDetails<Hash>, BasicError>>,
+    metadata: &Metadata,
+    f: &mut std::fmt::Formatter<'_>,
+) -> std::fmt::Result {
+    for ev in events {
+        match ev {
+            Ok(ev) => {
+                write!(
+                    f,
+                    "\n  #{} {:?}: {}",
+                    ev.index,
+                    ev.phase,
+                    ev.display(metadata)
+                )?
+            }
+            Err(e) => return write!(f, "\n  <failed to decode event: {}>", e),
+        }
+    }
+    Ok(())
+}
+
+/// Displays a [`RawEventDetails`] in a human readable form. See [`RawEventDetails::display()`].
+pub struct DisplayEvent<'a, Hash> {
+    event: &'a RawEventDetails<Hash>,
+    metadata: &'a Metadata,
+}
+
+impl<'a, Hash> std::fmt::Display for DisplayEvent<'a, Hash> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let event = self.event;
+        write!(f, "{}.{}", event.pallet, event.variant)?;
+        let event_metadata =
+            match self.metadata.event(event.pallet_index, event.variant_index) {
+                Ok(event_metadata) => event_metadata,
+                Err(_) => return write!(f, "(0x{})", hex::encode(&event.data)),
+            };
+        let types = &self.metadata.runtime_metadata().types;
+        let fields = event_metadata.variant().fields();
+        let input = &mut &*event.data;
+        let values = fields
+            .iter()
+            .map(|field| decode_type_as_json(field.ty().id(), types, input))
+            .collect::<Result<Vec<_>, _>>();
+        let values = match values {
+            Ok(values) => values,
+            Err(_) => return write!(f, "(0x{})", hex::encode(&event.data)),
+        };
+        fmt_fields(fields, &values, f)
+    }
+}
+
+// Write out the decoded fields of an event or extrinsic as `{ name: value, .. }` if
+// they are named, or `(value, ..)` if not. Nothing is written if there are no fields.
+fn fmt_fields(
+    fields: &[Field<PortableForm>],
+    values: &[Value],
+    f: &mut std::fmt::Formatter<'_>,
+) -> std::fmt::Result {
+    if values.is_empty() {
+        return Ok(())
+    }
+    let named = fields.iter().all(|field| field.name().is_some());
+    f.write_str(if named { " { " } else { "(" })?;
+    for (i, (field, value)) in fields.iter().zip(values).enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        if let (true, Some(name)) = (named, field.name()) {
+            write!(f, "{}: ", name)?;
+        }
+        fmt_value(value, f)?;
+    }
+    f.write_str(if named { " }" } else { ")" })
+}
+
+// Write out a value decoded via `decode_type_as_json` without the JSON noise; strings
+// aren't quoted and objects are written in the same way as Rust structs.
+fn fmt_value(value: &Value, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match value {
+        Value::Null => f.write_str("()"),
+        Value::Bool(b) => write!(f, "{}", b),
+        Value::Number(n) => write!(f, "{}", n),
+        Value::String(s) => f.write_str(s),
+        Value::Array(values) => {
+            f.write_str("[")?;
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                fmt_value(value, f)?;
+            }
+            f.write_str("]")
+        }
+        Value::Object(map) => {
+            f.write_str("{ ")?;
+            for (i, (name, value)) in map.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{}: ", name)?;
+                fmt_value(value, f)?;
+            }
+            f.write_str(" }")
+        }
+    }
 }
 
 // Attempt to dynamically decode a single event from our events input.
 fn decode_raw_event_details<T: Config>(
     metadata: &Metadata,
+    decoders: &EventDecoders,
     index: u32,
     input: &mut &[u8],
-) -> Result<RawEventDetails, BasicError> {
+) -> Result<RawEventDetails<T::Hash>, BasicError> {
     // Decode basic event details:
     let phase = Phase::decode(input)?;
     let pallet_index = input.read_byte()?;
@@ -443,29 +2186,58 @@ fn decode_raw_event_details<T: Config>(
     );
     log::debug!("remaining input: {}", hex::encode(&input));
 
-    // Get metadata for the event:
-    let event_metadata = metadata.event(pallet_index, variant_index)?;
-    log::debug!(
-        "Decoding Event '{}::{}'",
-        event_metadata.pallet(),
-        event_metadata.event()
-    );
+    // Get metadata for the event, falling back to any custom decoder registered for it:
+    let (pallet, variant, event_bytes) = match metadata.event(pallet_index, variant_index)
+    {
+        Ok(event_metadata) => {
+            log::debug!(
+                "Decoding Event '{}::{}'",
+                event_metadata.pallet(),
+                event_metadata.event()
+            );
+
+            // Use metadata to figure out which bytes belong to this event:
+            let mut event_bytes = Vec::new();
+            for arg in event_metadata.variant().fields() {
+                let type_id = arg.ty().id();
+                let all_bytes = *input;
+                // consume some bytes, moving the cursor forward:
+                decode_and_consume_type(
+                    type_id,
+                    &metadata.runtime_metadata().types,
+                    input,
+                )?;
+                // count how many bytes were consumed based on remaining length:
+                let consumed_len = all_bytes.len() - input.len();
+                // move those consumed bytes to the output vec unaltered:
+                event_bytes.extend(&all_bytes[0..consumed_len]);
+            }
+            (
+                event_metadata.pallet().to_string(),
+                event_metadata.event().to_string(),
+                event_bytes,
+            )
+        }
+        Err(e) => {
+            let decoder = decoders.get(pallet_index, variant_index).ok_or(e)?;
+            log::debug!(
+                "Decoding Event '{}::{}' with a custom decoder",
+                decoder.pallet,
+                decoder.variant
+            );
+
+            let all_bytes = *input;
+            (decoder.consume)(input)?;
+            let consumed_len = all_bytes.len() - input.len();
+            (
+                decoder.pallet.clone(),
+                decoder.variant.clone(),
+                all_bytes[0..consumed_len].to_vec(),
+            )
+        }
+    };
 
-    // Use metadata to figure out which bytes belong to this event:
-    let mut event_bytes = Vec::new();
-    for arg in event_metadata.variant().fields() {
-        let type_id = arg.ty().id();
-        let all_bytes = *input;
-        // consume some bytes, moving the cursor forward:
-        decode_and_consume_type(type_id, &metadata.runtime_metadata().types, input)?;
-        // count how many bytes were consumed based on remaining length:
-        let consumed_len = all_bytes.len() - input.len();
-        // move those consumed bytes to the output vec unaltered:
-        event_bytes.extend(&all_bytes[0..consumed_len]);
-    }
-
-    // topics come after the event data in EventRecord. They aren't used for
-    // anything at the moment, so just decode and throw them away.
+    // topics come after the event data in EventRecord.
     let topics = Vec::<T::Hash>::decode(input)?;
     log::debug!("topics: {:?}", topics);
 
@@ -473,10 +2245,11 @@ fn decode_raw_event_details<T: Config>(
         phase,
         index,
         pallet_index,
-        pallet: event_metadata.pallet().to_string(),
+        pallet,
         variant_index,
-        variant: event_metadata.event().to_string(),
+        variant,
         data: event_bytes.into(),
+        topics,
     })
 }
 
@@ -487,9 +2260,19 @@ fn system_events_key() -> StorageKey {
     StorageKey(storage_key)
 }
 
+// The storage key needed to access the indexes of the events with some topic.
+// `System::EventTopics` is a map using the `Blake2_128Concat` hasher.
+fn event_topics_key(topic: &[u8]) -> StorageKey {
+    let mut storage_key = twox_128(b"System").to_vec();
+    storage_key.extend(twox_128(b"EventTopics").to_vec());
+    storage_key.extend(blake2_128(topic).to_vec());
+    storage_key.extend(topic);
+    StorageKey(storage_key)
+}
+
 // Given a type Id and a type registry, attempt to consume the bytes
 // corresponding to that type from our input.
-fn decode_and_consume_type(
+pub(crate) fn decode_and_consume_type(
     type_id: u32,
     types: &PortableRegistry,
     input: &mut &[u8],
@@ -558,74 +2341,27 @@ fn decode_and_consume_type(
                 TypeDefPrimitive::U32 => consume_type::<u32>(input),
                 TypeDefPrimitive::U64 => consume_type::<u64>(input),
                 TypeDefPrimitive::U128 => consume_type::<u128>(input),
-                TypeDefPrimitive::U256 => {
-                    Err(
-                        EventsDecodingError::UnsupportedPrimitive(TypeDefPrimitive::U256)
-                            .into(),
-                    )
-                }
+                // 256 bit integers are encoded as 32 little endian bytes.
+                TypeDefPrimitive::U256 => consume_type::<[u8; 32]>(input),
                 TypeDefPrimitive::I8 => consume_type::<i8>(input),
                 TypeDefPrimitive::I16 => consume_type::<i16>(input),
                 TypeDefPrimitive::I32 => consume_type::<i32>(input),
                 TypeDefPrimitive::I64 => consume_type::<i64>(input),
                 TypeDefPrimitive::I128 => consume_type::<i128>(input),
-                TypeDefPrimitive::I256 => {
-                    Err(
-                        EventsDecodingError::UnsupportedPrimitive(TypeDefPrimitive::I256)
-                            .into(),
-                    )
-                }
+                TypeDefPrimitive::I256 => consume_type::<[u8; 32]>(input),
             }
         }
         TypeDef::Compact(compact) => {
-            let inner = types
-                .resolve(compact.type_param().id())
-                .ok_or(MetadataError::TypeNotFound(type_id))?;
-            let mut decode_compact_primitive = |primitive: &TypeDefPrimitive| {
-                match primitive {
-                    TypeDefPrimitive::U8 => consume_type::<Compact<u8>>(input),
-                    TypeDefPrimitive::U16 => consume_type::<Compact<u16>>(input),
-                    TypeDefPrimitive::U32 => consume_type::<Compact<u32>>(input),
-                    TypeDefPrimitive::U64 => consume_type::<Compact<u64>>(input),
-                    TypeDefPrimitive::U128 => consume_type::<Compact<u128>>(input),
-                    prim => {
-                        Err(EventsDecodingError::InvalidCompactPrimitive(prim.clone())
-                            .into())
-                    }
-                }
-            };
-            match inner.type_def() {
-                TypeDef::Primitive(primitive) => decode_compact_primitive(primitive),
-                TypeDef::Composite(composite) => {
-                    match composite.fields() {
-                        [field] => {
-                            let field_ty =
-                                types.resolve(field.ty().id()).ok_or_else(|| {
-                                    MetadataError::TypeNotFound(field.ty().id())
-                                })?;
-                            if let TypeDef::Primitive(primitive) = field_ty.type_def() {
-                                decode_compact_primitive(primitive)
-                            } else {
-                                Err(EventsDecodingError::InvalidCompactType(
-                                    "Composite type must have a single primitive field"
-                                        .into(),
-                                )
-                                .into())
-                            }
-                        }
-                        _ => {
-                            Err(EventsDecodingError::InvalidCompactType(
-                                "Composite type must have a single field".into(),
-                            )
-                            .into())
-                        }
-                    }
-                }
-                _ => {
-                    Err(EventsDecodingError::InvalidCompactType(
-                        "Compact type must be a primitive or a composite type".into(),
-                    )
-                    .into())
+            match compact_primitive(compact.type_param().id(), types)? {
+                // Types without any fields, like `()`, encode to nothing.
+                None => Ok(()),
+                Some(TypeDefPrimitive::U8) => consume_type::<Compact<u8>>(input),
+                Some(TypeDefPrimitive::U16) => consume_type::<Compact<u16>>(input),
+                Some(TypeDefPrimitive::U32) => consume_type::<Compact<u32>>(input),
+                Some(TypeDefPrimitive::U64) => consume_type::<Compact<u64>>(input),
+                Some(TypeDefPrimitive::U128) => consume_type::<Compact<u128>>(input),
+                Some(prim) => {
+                    Err(EventsDecodingError::InvalidCompactPrimitive(prim).into())
                 }
             }
         }
@@ -664,6 +2400,314 @@ fn decode_and_consume_type(
     }
 }
 
+// Consume the given fields from the input, returning true as soon as one of them
+// is (or contains) the account with the SCALE encoded bytes provided.
+fn fields_reference_account(
+    fields: &[Field<PortableForm>],
+    types: &PortableRegistry,
+    input: &mut &[u8],
+    account: &[u8],
+) -> Result<bool, BasicError> {
+    for field in fields {
+        if type_references_account(field.ty().id(), types, input, account)? {
+            return Ok(true)
+        }
+    }
+    Ok(false)
+}
+
+// Consume a value of the given type from the input, returning true as soon as we find
+// the account with the SCALE encoded bytes provided within it. Any type whose name
+// begins with `AccountId` is considered to be an account.
+fn type_references_account(
+    type_id: u32,
+    types: &PortableRegistry,
+    input: &mut &[u8],
+    account: &[u8],
+) -> Result<bool, BasicError> {
+    let ty = types
+        .resolve(type_id)
+        .ok_or(MetadataError::TypeNotFound(type_id))?;
+
+    let is_account = ty
+        .path()
+        .ident()
+        .map_or(false, |name| name.starts_with("AccountId"));
+    if is_account {
+        let start = *input;
+        decode_and_consume_type(type_id, types, input)?;
+        return Ok(&start[..start.len() - input.len()] == account)
+    }
+
+    match ty.type_def() {
+        TypeDef::Composite(composite) => {
+            fields_reference_account(composite.fields(), types, input, account)
+        }
+        TypeDef::Variant(variant) => {
+            let variant_index = u8::decode(input)?;
+            let variant = variant
+                .variants()
+                .iter()
+                .find(|v| v.index() == variant_index)
+                .ok_or_else(|| {
+                    BasicError::Other(format!("Variant {} not found", variant_index))
+                })?;
+            fields_reference_account(variant.fields(), types, input, account)
+        }
+        TypeDef::Sequence(seq) => {
+            let len = <Compact<u32>>::decode(input)?;
+            for _ in 0..len.0 {
+                if type_references_account(seq.type_param().id(), types, input, account)?
+                {
+                    return Ok(true)
+                }
+            }
+            Ok(false)
+        }
+        TypeDef::Array(arr) => {
+            for _ in 0..arr.len() {
+                if type_references_account(arr.type_param().id(), types, input, account)?
+                {
+                    return Ok(true)
+                }
+            }
+            Ok(false)
+        }
+        TypeDef::Tuple(tuple) => {
+            for field in tuple.fields() {
+                if type_references_account(field.id(), types, input, account)? {
+                    return Ok(true)
+                }
+            }
+            Ok(false)
+        }
+        TypeDef::Primitive(_) | TypeDef::Compact(_) | TypeDef::BitSequence(_) => {
+            decode_and_consume_type(type_id, types, input)?;
+            Ok(false)
+        }
+    }
+}
+
+// Find the primitive type that a compact encoded type wraps, looking through any single
+// field composites or tuples along the way (e.g. `Compact<Perbill>`, where `Perbill(u32)`).
+// Returns `None` if there are no fields at all, as is the case for `Compact<()>`.
+fn compact_primitive(
+    type_id: u32,
+    types: &PortableRegistry,
+) -> Result<Option<TypeDefPrimitive>, BasicError> {
+    let ty = types
+        .resolve(type_id)
+        .ok_or(MetadataError::TypeNotFound(type_id))?;
+
+    match ty.type_def() {
+        TypeDef::Primitive(primitive) => Ok(Some(primitive.clone())),
+        TypeDef::Composite(composite) => {
+            match composite.fields() {
+                [] => Ok(None),
+                [field] => compact_primitive(field.ty().id(), types),
+                _ => {
+                    Err(EventsDecodingError::InvalidCompactType(
+                        "Composite type must have a single field".into(),
+                    )
+                    .into())
+                }
+            }
+        }
+        TypeDef::Tuple(tuple) => {
+            match tuple.fields() {
+                [] => Ok(None),
+                [field] => compact_primitive(field.id(), types),
+                _ => {
+                    Err(EventsDecodingError::InvalidCompactType(
+                        "Tuple type must have a single field".into(),
+                    )
+                    .into())
+                }
+            }
+        }
+        _ => {
+            Err(EventsDecodingError::InvalidCompactType(
+                "Compact type must be a primitive, composite or tuple type".into(),
+            )
+            .into())
+        }
+    }
+}
+
+// Given a type Id and a type registry, decode the bytes corresponding to that type
+// from our input into a JSON value.
+pub(crate) fn decode_type_as_json(
+    type_id: u32,
+    types: &PortableRegistry,
+    input: &mut &[u8],
+) -> Result<Value, BasicError> {
+    let ty = types
+        .resolve(type_id)
+        .ok_or(MetadataError::TypeNotFound(type_id))?;
+
+    fn decode_as<T: Decode + Into<Value>>(
+        input: &mut &[u8],
+    ) -> Result<Value, BasicError> {
+        Ok(T::decode(input)?.into())
+    }
+    fn u128_as_json(n: u128) -> Value {
+        u64::try_from(n)
+            .map(Into::into)
+            .unwrap_or_else(|_| n.to_string().into())
+    }
+    fn i128_as_json(n: i128) -> Value {
+        i64::try_from(n)
+            .map(Into::into)
+            .unwrap_or_else(|_| n.to_string().into())
+    }
+    fn hex_as_json(bytes: &[u8]) -> Value {
+        format!("0x{}", hex::encode(bytes)).into()
+    }
+    let is_u8 = |id: u32| {
+        matches!(
+            types.resolve(id).map(|ty| ty.type_def()),
+            Some(TypeDef::Primitive(TypeDefPrimitive::U8))
+        )
+    };
+
+    match ty.type_def() {
+        TypeDef::Composite(composite) => {
+            decode_fields_as_json(composite.fields(), types, input)
+        }
+        TypeDef::Variant(variant) => {
+            let variant_index = u8::decode(input)?;
+            let variant = variant
+                .variants()
+                .iter()
+                .find(|v| v.index() == variant_index)
+                .ok_or_else(|| {
+                    BasicError::Other(format!("Variant {} not found", variant_index))
+                })?;
+            if variant.fields().is_empty() {
+                return Ok(variant.name().as_str().into())
+            }
+            let fields = decode_fields_as_json(variant.fields(), types, input)?;
+            let mut map = serde_json::Map::new();
+            map.insert(variant.name().to_string(), fields);
+            Ok(Value::Object(map))
+        }
+        TypeDef::Sequence(seq) => {
+            if is_u8(seq.type_param().id()) {
+                return Ok(hex_as_json(&Vec::<u8>::decode(input)?))
+            }
+            let len = <Compact<u32>>::decode(input)?;
+            (0..len.0)
+                .map(|_| decode_type_as_json(seq.type_param().id(), types, input))
+                .collect::<Result<_, _>>()
+                .map(Value::Array)
+        }
+        TypeDef::Array(arr) => {
+            if is_u8(arr.type_param().id()) {
+                let mut bytes = vec![0; arr.len() as usize];
+                input.read(&mut bytes)?;
+                return Ok(hex_as_json(&bytes))
+            }
+            (0..arr.len())
+                .map(|_| decode_type_as_json(arr.type_param().id(), types, input))
+                .collect::<Result<_, _>>()
+                .map(Value::Array)
+        }
+        TypeDef::Tuple(tuple) => {
+            if tuple.fields().is_empty() {
+                return Ok(Value::Null)
+            }
+            tuple
+                .fields()
+                .iter()
+                .map(|field| decode_type_as_json(field.id(), types, input))
+                .collect::<Result<_, _>>()
+                .map(Value::Array)
+        }
+        TypeDef::Primitive(primitive) => {
+            match primitive {
+                TypeDefPrimitive::Bool => decode_as::<bool>(input),
+                TypeDefPrimitive::Char => {
+                    Err(
+                        EventsDecodingError::UnsupportedPrimitive(TypeDefPrimitive::Char)
+                            .into(),
+                    )
+                }
+                TypeDefPrimitive::Str => decode_as::<String>(input),
+                TypeDefPrimitive::U8 => decode_as::<u8>(input),
+                TypeDefPrimitive::U16 => decode_as::<u16>(input),
+                TypeDefPrimitive::U32 => decode_as::<u32>(input),
+                TypeDefPrimitive::U64 => decode_as::<u64>(input),
+                TypeDefPrimitive::U128 => Ok(u128_as_json(u128::decode(input)?)),
+                TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => {
+                    Ok(hex_as_json(&<[u8; 32]>::decode(input)?))
+                }
+                TypeDefPrimitive::I8 => decode_as::<i8>(input),
+                TypeDefPrimitive::I16 => decode_as::<i16>(input),
+                TypeDefPrimitive::I32 => decode_as::<i32>(input),
+                TypeDefPrimitive::I64 => decode_as::<i64>(input),
+                TypeDefPrimitive::I128 => Ok(i128_as_json(i128::decode(input)?)),
+            }
+        }
+        TypeDef::Compact(compact) => {
+            match compact_primitive(compact.type_param().id(), types)? {
+                None => Ok(Value::Null),
+                Some(TypeDefPrimitive::U8) => Ok(Compact::<u8>::decode(input)?.0.into()),
+                Some(TypeDefPrimitive::U16) => {
+                    Ok(Compact::<u16>::decode(input)?.0.into())
+                }
+                Some(TypeDefPrimitive::U32) => {
+                    Ok(Compact::<u32>::decode(input)?.0.into())
+                }
+                Some(TypeDefPrimitive::U64) => {
+                    Ok(Compact::<u64>::decode(input)?.0.into())
+                }
+                Some(TypeDefPrimitive::U128) => {
+                    Ok(u128_as_json(Compact::<u128>::decode(input)?.0))
+                }
+                Some(prim) => {
+                    Err(EventsDecodingError::InvalidCompactPrimitive(prim).into())
+                }
+            }
+        }
+        TypeDef::BitSequence(_) => {
+            // Hand back the SCALE encoded bits as they are.
+            let all_bytes = *input;
+            decode_and_consume_type(type_id, types, input)?;
+            let consumed_len = all_bytes.len() - input.len();
+            Ok(hex_as_json(&all_bytes[..consumed_len]))
+        }
+    }
+}
+
+// Decode the given fields into a JSON value. Named fields become an object, a single
+// unnamed field is represented by its value alone, and several unnamed fields become
+// an array. No fields at all becomes `null`.
+fn decode_fields_as_json(
+    fields: &[Field<PortableForm>],
+    types: &PortableRegistry,
+    input: &mut &[u8],
+) -> Result<Value, BasicError> {
+    match fields {
+        [] => Ok(Value::Null),
+        fields if fields.iter().all(|f| f.name().is_some()) => {
+            let mut map = serde_json::Map::new();
+            for field in fields {
+                let name = field.name().expect("checked above; qed").to_string();
+                map.insert(name, decode_type_as_json(field.ty().id(), types, input)?);
+            }
+            Ok(Value::Object(map))
+        }
+        [field] => decode_type_as_json(field.ty().id(), types, input),
+        fields => {
+            fields
+                .iter()
+                .map(|field| decode_type_as_json(field.ty().id(), types, input))
+                .collect::<Result<_, _>>()
+                .map(Value::Array)
+        }
+    }
+}
+
 /// The possible errors that we can run into attempting to decode events.
 #[derive(Debug, thiserror::Error)]
 pub enum EventsDecodingError {
@@ -753,8 +2797,20 @@ mod tests {
     /// Build fake metadata consisting of a single pallet that knows
     /// about the event type provided.
     fn metadata<E: TypeInfo + 'static>() -> Metadata {
+        pallet_metadata::<E>("Test")
+    }
+
+    /// Much like [`metadata`], but with a pallet of the name given.
+    fn pallet_metadata<E: TypeInfo + 'static>(pallet: &'static str) -> Metadata {
+        Metadata::try_from(runtime_metadata::<E>(pallet)).unwrap()
+    }
+
+    /// The runtime metadata that [`pallet_metadata`] is built from, as a node hands it back.
+    fn runtime_metadata<E: TypeInfo + 'static>(
+        pallet: &'static str,
+    ) -> RuntimeMetadataPrefixed {
         let pallets = vec![PalletMetadata {
-            name: "Test",
+            name: pallet,
             storage: None,
             calls: None,
             event: Some(PalletEventMetadata {
@@ -772,9 +2828,7 @@ mod tests {
         };
 
         let v14 = RuntimeMetadataLastVersion::new(pallets, extrinsic, meta_type::<()>());
-        let runtime_metadata: RuntimeMetadataPrefixed = v14.into();
-
-        Metadata::try_from(runtime_metadata).unwrap()
+        v14.into()
     }
 
     /// Build an `Events` object for test purposes, based on the details provided,
@@ -801,8 +2855,11 @@ mod tests {
         Events {
             block_hash: <DefaultConfig as Config>::Hash::default(),
             event_bytes,
-            metadata,
+            metadata: EventsMetadata::Client(metadata),
+            decoders: EventDecoders::default(),
             num_events,
+            pallet_filter: None,
+            account_filter: None,
             _event_type: std::marker::PhantomData,
         }
     }
@@ -844,7 +2901,8 @@ mod tests {
             vec![EventDetails {
                 index: 0,
                 phase: Phase::Finalization,
-                event: AllEvents::E(Event::A(1))
+                event: AllEvents::E(Event::A(1)),
+                topics: vec![],
             }]
         );
     }
@@ -879,17 +2937,20 @@ mod tests {
                 EventDetails {
                     index: 0,
                     phase: Phase::Initialization,
-                    event: AllEvents::E(Event::A(1))
+                    event: AllEvents::E(Event::A(1)),
+                    topics: vec![],
                 },
                 EventDetails {
                     index: 1,
                     phase: Phase::ApplyExtrinsic(123),
-                    event: AllEvents::E(Event::B(true))
+                    event: AllEvents::E(Event::B(true)),
+                    topics: vec![],
                 },
                 EventDetails {
                     index: 2,
                     phase: Phase::Finalization,
-                    event: AllEvents::E(Event::A(234))
+                    event: AllEvents::E(Event::A(234)),
+                    topics: vec![],
                 },
             ]
         );
@@ -929,7 +2990,8 @@ mod tests {
             EventDetails {
                 index: 0,
                 phase: Phase::Initialization,
-                event: AllEvents::E(Event::A(1))
+                event: AllEvents::E(Event::A(1)),
+                topics: vec![],
             }
         );
         assert_eq!(
@@ -937,7 +2999,8 @@ mod tests {
             EventDetails {
                 index: 1,
                 phase: Phase::ApplyExtrinsic(123),
-                event: AllEvents::E(Event::B(true))
+                event: AllEvents::E(Event::B(true)),
+                topics: vec![],
             }
         );
 
@@ -958,172 +3021,985 @@ mod tests {
         // Create fake metadata that knows about our single event, above:
         let metadata = metadata::<Event>();
 
-        // Encode our events in the format we expect back from a node, and
-        // construst an Events object to iterate them:
-        let event = Event::A(1);
+        // Encode our events in the format we expect back from a node, and
+        // construst an Events object to iterate them:
+        let event = Event::A(1);
+        let events = events::<Event>(
+            &metadata,
+            vec![event_record(Phase::ApplyExtrinsic(123), event)],
+        );
+
+        let event_details: Vec<RawEventDetails> =
+            events.iter_raw().collect::<Result<_, _>>().unwrap();
+        let expected_event_data = {
+            let mut bytes = event.encode();
+            // Strip variant tag off event bytes:
+            bytes.drain(0..1);
+            bytes
+        };
+
+        assert_eq!(
+            event_details,
+            vec![RawEventDetails {
+                index: 0,
+                phase: Phase::ApplyExtrinsic(123),
+                pallet: "Test".to_string(),
+                pallet_index: 0,
+                variant: "A".to_string(),
+                variant_index: 0,
+                data: expected_event_data.into(),
+                topics: vec![],
+            }]
+        );
+    }
+
+    #[test]
+    fn dynamically_decode_multiple_events() {
+        #[derive(Clone, Copy, Debug, PartialEq, Decode, Encode, TypeInfo)]
+        enum Event {
+            A(u8),
+            B(bool),
+        }
+
+        // Create fake metadata that knows about our single event, above:
+        let metadata = metadata::<Event>();
+
+        // Encode our events in the format we expect back from a node, and
+        // construst an Events object to iterate them:
+        let event1 = Event::A(1);
+        let event2 = Event::B(true);
+        let event3 = Event::A(234);
+
+        let events = events::<Event>(
+            &metadata,
+            vec![
+                event_record(Phase::Initialization, event1),
+                event_record(Phase::ApplyExtrinsic(123), event2),
+                event_record(Phase::Finalization, event3),
+            ],
+        );
+
+        let event_details: Vec<RawEventDetails> =
+            events.iter_raw().collect::<Result<_, _>>().unwrap();
+        let event_bytes = |ev: Event| {
+            let mut bytes = ev.encode();
+            // Strip variant tag off event bytes:
+            bytes.drain(0..1);
+            bytes.into()
+        };
+
+        assert_eq!(
+            event_details,
+            vec![
+                RawEventDetails {
+                    index: 0,
+                    phase: Phase::Initialization,
+                    pallet: "Test".to_string(),
+                    pallet_index: 0,
+                    variant: "A".to_string(),
+                    variant_index: 0,
+                    data: event_bytes(event1),
+                    topics: vec![],
+                },
+                RawEventDetails {
+                    index: 1,
+                    phase: Phase::ApplyExtrinsic(123),
+                    pallet: "Test".to_string(),
+                    pallet_index: 0,
+                    variant: "B".to_string(),
+                    variant_index: 1,
+                    data: event_bytes(event2),
+                    topics: vec![],
+                },
+                RawEventDetails {
+                    index: 2,
+                    phase: Phase::Finalization,
+                    pallet: "Test".to_string(),
+                    pallet_index: 0,
+                    variant: "A".to_string(),
+                    variant_index: 0,
+                    data: event_bytes(event3),
+                    topics: vec![],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn dynamically_decode_multiple_events_until_error() {
+        #[derive(Clone, Debug, PartialEq, Decode, Encode, TypeInfo)]
+        enum Event {
+            A(u8),
+            B(bool),
+        }
+
+        // Create fake metadata that knows about our single event, above:
+        let metadata = metadata::<Event>();
+
+        // Encode 2 events:
+        let mut event_bytes = vec![];
+        event_record(Phase::Initialization, Event::A(1)).encode_to(&mut event_bytes);
+        event_record(Phase::ApplyExtrinsic(123), Event::B(true))
+            .encode_to(&mut event_bytes);
+
+        // Push a few naff bytes to the end (a broken third event):
+        event_bytes.extend_from_slice(&[3, 127, 45, 0, 2]);
+
+        // Encode our events in the format we expect back from a node, and
+        // construst an Events object to iterate them:
+        let events = events_raw::<Event>(
+            &metadata,
+            event_bytes,
+            3, // 2 "good" events, and then it'll hit the naff bytes.
+        );
+
+        let event_bytes = |ev: Event| {
+            let mut bytes = ev.encode();
+            // Strip variant tag off event bytes:
+            bytes.drain(0..1);
+            bytes.into()
+        };
+
+        let mut events_iter = events.iter_raw();
+        assert_eq!(
+            events_iter.next().unwrap().unwrap(),
+            RawEventDetails {
+                index: 0,
+                phase: Phase::Initialization,
+                pallet: "Test".to_string(),
+                pallet_index: 0,
+                variant: "A".to_string(),
+                variant_index: 0,
+                data: event_bytes(Event::A(1)),
+                topics: vec![],
+            }
+        );
+        assert_eq!(
+            events_iter.next().unwrap().unwrap(),
+            RawEventDetails {
+                index: 1,
+                phase: Phase::ApplyExtrinsic(123),
+                pallet: "Test".to_string(),
+                pallet_index: 0,
+                variant: "B".to_string(),
+                variant_index: 1,
+                data: event_bytes(Event::B(true)),
+                topics: vec![],
+            }
+        );
+
+        // We'll hit an error trying to decode the third event:
+        assert!(events_iter.next().unwrap().is_err());
+        // ... and then "None" from then on.
+        assert!(events_iter.next().is_none());
+        assert!(events_iter.next().is_none());
+    }
+
+    #[test]
+    fn find_events_with_details() {
+        #[derive(Clone, Copy, Debug, PartialEq, Decode, Encode, TypeInfo)]
+        enum Event {
+            A(u8),
+            B(bool),
+        }
+
+        #[derive(Clone, Copy, Debug, PartialEq, Decode)]
+        struct A(u8);
+
+        impl crate::Event for A {
+            const PALLET: &'static str = "Test";
+            const EVENT: &'static str = "A";
+        }
+
+        // Create fake metadata that knows about our events, above:
+        let metadata = metadata::<Event>();
+
+        let events = events::<Event>(
+            &metadata,
+            vec![
+                event_record(Phase::Initialization, Event::A(1)),
+                event_record(Phase::ApplyExtrinsic(123), Event::B(true)),
+                event_record(Phase::ApplyExtrinsic(123), Event::A(234)),
+            ],
+        );
+
+        let found: Vec<EventDetails<A>> =
+            events.find_events::<A>().collect::<Result<_, _>>().unwrap();
+
+        assert_eq!(
+            found,
+            vec![
+                EventDetails {
+                    phase: Phase::Initialization,
+                    index: 0,
+                    event: A(1),
+                    topics: vec![],
+                },
+                EventDetails {
+                    phase: Phase::ApplyExtrinsic(123),
+                    index: 2,
+                    event: A(234),
+                    topics: vec![],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn find_first_and_last_events() {
+        #[derive(Clone, Copy, Debug, PartialEq, Decode, Encode, TypeInfo)]
+        enum Event {
+            A(u8),
+            B(bool),
+        }
+
+        #[derive(Clone, Copy, Debug, PartialEq, Decode)]
+        struct A(u8);
+
+        impl crate::Event for A {
+            const PALLET: &'static str = "Test";
+            const EVENT: &'static str = "A";
+        }
+
+        #[derive(Clone, Copy, Debug, PartialEq, Decode)]
+        struct C(u8);
+
+        impl crate::Event for C {
+            const PALLET: &'static str = "Test";
+            const EVENT: &'static str = "C";
+        }
+
+        // Create fake metadata that knows about our events, above:
+        let metadata = metadata::<Event>();
+
+        let events = events::<Event>(
+            &metadata,
+            vec![
+                event_record(Phase::ApplyExtrinsic(1), Event::A(1)),
+                event_record(Phase::ApplyExtrinsic(1), Event::B(true)),
+                event_record(Phase::ApplyExtrinsic(2), Event::A(2)),
+                event_record(Phase::Finalization, Event::B(false)),
+            ],
+        );
+
+        assert_eq!(events.find_first_event::<A>().unwrap(), Some(A(1)));
+        assert_eq!(events.find_last_event::<A>().unwrap(), Some(A(2)));
+        assert_eq!(events.find_last_event::<C>().unwrap(), None);
+
+        let first = events.find_first_event_details::<A>().unwrap().unwrap();
+        assert_eq!((first.index, first.extrinsic_index()), (0, Some(1)));
+        let last = events.find_last_event_details::<A>().unwrap().unwrap();
+        assert_eq!((last.index, last.extrinsic_index()), (2, Some(2)));
+        assert_eq!(last.event, A(2));
+        assert!(events.find_first_event_details::<C>().unwrap().is_none());
+    }
+
+    #[test]
+    fn display_events() {
+        #[derive(Clone, Debug, PartialEq, Decode, Encode, TypeInfo)]
+        enum Event {
+            A {
+                who: u32,
+                amount: u128,
+                data: Vec<u8>,
+            },
+            B(bool, Option<u8>),
+            C,
+        }
+
+        // Create fake metadata that knows about our events, above:
+        let metadata = metadata::<Event>();
+
+        let events = events::<Event>(
+            &metadata,
+            vec![
+                event_record(
+                    Phase::ApplyExtrinsic(1),
+                    Event::A {
+                        who: 1,
+                        amount: 2,
+                        data: vec![1, 2, 3],
+                    },
+                ),
+                event_record(Phase::ApplyExtrinsic(1), Event::B(true, Some(3))),
+                event_record(Phase::Finalization, Event::C),
+            ],
+        );
+
+        let displayed: Vec<String> = events
+            .iter_raw()
+            .map(|ev| ev.unwrap().display(&metadata).to_string())
+            .collect();
+        assert_eq!(
+            displayed,
+            vec![
+                "Test.A { who: 1, amount: 2, data: 0x010203 }",
+                "Test.B(true, { Some: 3 })",
+                "Test.C",
+            ]
+        );
+
+        assert_eq!(
+            events.display().to_string(),
+            format!(
+                "events in block {:?}:\n  #0 ApplyExtrinsic(1): {}\n  #1 ApplyExtrinsic(1): {}\n  #2 Finalization: {}",
+                events.block_hash(),
+                displayed[0],
+                displayed[1],
+                displayed[2],
+            )
+        );
+    }
+
+    #[test]
+    fn subscription_metrics() {
+        let metrics = SubscriptionMetrics::default();
+        assert_eq!(metrics.head_block_number(), None);
+        assert_eq!(metrics.lag(), 0);
+
+        metrics.record_head(10);
+        assert_eq!(metrics.lag(), 11);
+
+        metrics.record_handed_back(4, 6);
+        metrics.record_head(8);
+        assert_eq!(metrics.head_block_number(), Some(10));
+        assert_eq!(metrics.last_block_number(), Some(4));
+        assert_eq!(metrics.lag(), 6);
+        assert_eq!(metrics.buffered(), 6);
+
+        metrics.record_resubscribe();
+        metrics.record_dropped(3);
+        metrics.record_dropped(0);
+        let handle = metrics.clone();
+        assert_eq!(handle.resubscribes(), 1);
+        assert_eq!(handle.dropped_blocks(), 3);
+    }
+
+    #[test]
+    fn decode_event_topics() {
+        #[derive(Clone, Debug, PartialEq, Decode, Encode, TypeInfo)]
+        enum Event {
+            A(u8),
+        }
+
+        // Create fake metadata that knows about our single event, above:
+        let metadata = metadata::<Event>();
+
+        let topics = vec![[1u8; 32].into(), [2u8; 32].into()];
+        let events = events::<Event>(
+            &metadata,
+            vec![EventRecord {
+                phase: Phase::ApplyExtrinsic(3),
+                event: AllEvents::E(Event::A(1)),
+                topics: topics.clone(),
+            }],
+        );
+
+        let event = events.iter().next().unwrap().unwrap();
+        assert_eq!(event.topics, topics);
+        assert_eq!(event.extrinsic_index(), Some(3));
+
+        let raw_event = events.iter_raw().next().unwrap().unwrap();
+        assert_eq!(raw_event.topics, topics);
+        assert_eq!(raw_event.extrinsic_index(), Some(3));
+    }
+
+    #[test]
+    fn raw_bytes_alongside_decoded_events() {
+        #[derive(Clone, Debug, PartialEq, Decode, Encode, TypeInfo)]
+        enum Event {
+            A(u8),
+            B(bool),
+        }
+
+        // Create fake metadata that knows about our events, above:
+        let metadata = metadata::<Event>();
+
+        let events = events::<Event>(
+            &metadata,
+            vec![
+                event_record(Phase::Initialization, Event::A(1)),
+                event_record(Phase::ApplyExtrinsic(123), Event::B(true)),
+            ],
+        );
+
+        let found: Vec<_> = events.iter_with_raw().collect::<Result<_, _>>().unwrap();
+        assert_eq!(found.len(), 2);
+
+        let (raw, event) = &found[1];
+        assert_eq!(raw.event_bytes(), AllEvents::E(Event::B(true)).encode());
+        assert_eq!(raw.pallet_index, 0);
+        assert_eq!(raw.variant_index, 1);
+        assert_eq!(event.as_ref().unwrap(), &AllEvents::E(Event::B(true)));
+    }
+
+    #[test]
+    fn events_to_json() {
+        #[derive(Clone, Debug, PartialEq, Decode, Encode, TypeInfo)]
+        enum Inner {
+            X,
+            Y(u16, bool),
+        }
+
+        #[derive(Clone, Debug, PartialEq, Decode, Encode, TypeInfo)]
+        enum Event {
+            A {
+                who: [u8; 4],
+                #[codec(compact)]
+                amount: u128,
+                inner: Inner,
+            },
+            B(Vec<u32>, Inner),
+            C,
+        }
+
+        // Create fake metadata that knows about our events, above:
+        let metadata = metadata::<Event>();
+
         let events = events::<Event>(
             &metadata,
-            vec![event_record(Phase::ApplyExtrinsic(123), event)],
+            vec![
+                event_record(
+                    Phase::ApplyExtrinsic(1),
+                    Event::A {
+                        who: [1, 2, 3, 4],
+                        amount: u128::MAX,
+                        inner: Inner::X,
+                    },
+                ),
+                event_record(
+                    Phase::Finalization,
+                    Event::B(vec![5, 6], Inner::Y(7, true)),
+                ),
+                event_record(Phase::Finalization, Event::C),
+            ],
         );
 
-        let event_details: Vec<RawEventDetails> =
-            events.iter_raw().collect::<Result<_, _>>().unwrap();
-        let expected_event_data = {
-            let mut bytes = event.encode();
-            // Strip variant tag off event bytes:
-            bytes.drain(0..1);
-            bytes
-        };
-
         assert_eq!(
-            event_details,
-            vec![RawEventDetails {
-                index: 0,
-                phase: Phase::ApplyExtrinsic(123),
-                pallet: "Test".to_string(),
-                pallet_index: 0,
-                variant: "A".to_string(),
-                variant_index: 0,
-                data: expected_event_data.into()
-            }]
+            events.to_json().unwrap(),
+            serde_json::json!([
+                {
+                    "index": 0,
+                    "phase": { "ApplyExtrinsic": 1 },
+                    "pallet": "Test",
+                    "variant": "A",
+                    "fields": {
+                        "who": "0x01020304",
+                        "amount": u128::MAX.to_string(),
+                        "inner": "X",
+                    },
+                    "topics": [],
+                },
+                {
+                    "index": 1,
+                    "phase": "Finalization",
+                    "pallet": "Test",
+                    "variant": "B",
+                    "fields": [[5, 6], { "Y": [7, true] }],
+                    "topics": [],
+                },
+                {
+                    "index": 2,
+                    "phase": "Finalization",
+                    "pallet": "Test",
+                    "variant": "C",
+                    "fields": null,
+                    "topics": [],
+                },
+            ])
         );
     }
 
     #[test]
-    fn dynamically_decode_multiple_events() {
+    fn filter_multiple_event_types() {
         #[derive(Clone, Copy, Debug, PartialEq, Decode, Encode, TypeInfo)]
         enum Event {
             A(u8),
             B(bool),
+            C(u16),
         }
 
-        // Create fake metadata that knows about our single event, above:
-        let metadata = metadata::<Event>();
+        #[derive(Clone, Copy, Debug, PartialEq, Decode)]
+        struct A(u8);
+        impl crate::Event for A {
+            const PALLET: &'static str = "Test";
+            const EVENT: &'static str = "A";
+        }
 
-        // Encode our events in the format we expect back from a node, and
-        // construst an Events object to iterate them:
-        let event1 = Event::A(1);
-        let event2 = Event::B(true);
-        let event3 = Event::A(234);
+        #[derive(Clone, Copy, Debug, PartialEq, Decode)]
+        struct C(u16);
+        impl crate::Event for C {
+            const PALLET: &'static str = "Test";
+            const EVENT: &'static str = "C";
+        }
+
+        // Create fake metadata that knows about our events, above:
+        let metadata = metadata::<Event>();
 
         let events = events::<Event>(
             &metadata,
             vec![
-                event_record(Phase::Initialization, event1),
-                event_record(Phase::ApplyExtrinsic(123), event2),
-                event_record(Phase::Finalization, event3),
+                event_record(Phase::Initialization, Event::A(1)),
+                event_record(Phase::ApplyExtrinsic(123), Event::B(true)),
+                event_record(Phase::ApplyExtrinsic(123), Event::C(234)),
             ],
         );
 
-        let event_details: Vec<RawEventDetails> =
-            events.iter_raw().collect::<Result<_, _>>().unwrap();
-        let event_bytes = |ev: Event| {
-            let mut bytes = ev.encode();
-            // Strip variant tag off event bytes:
-            bytes.drain(0..1);
-            bytes.into()
-        };
+        let found: Vec<EventDetails<(Option<A>, Option<C>)>> = events
+            .filter_events::<(A, C)>()
+            .collect::<Result<_, _>>()
+            .unwrap();
 
         assert_eq!(
-            event_details,
+            found,
             vec![
-                RawEventDetails {
-                    index: 0,
+                EventDetails {
                     phase: Phase::Initialization,
-                    pallet: "Test".to_string(),
-                    pallet_index: 0,
-                    variant: "A".to_string(),
-                    variant_index: 0,
-                    data: event_bytes(event1)
+                    index: 0,
+                    event: (Some(A(1)), None),
+                    topics: vec![],
                 },
-                RawEventDetails {
-                    index: 1,
+                EventDetails {
                     phase: Phase::ApplyExtrinsic(123),
-                    pallet: "Test".to_string(),
-                    pallet_index: 0,
-                    variant: "B".to_string(),
-                    variant_index: 1,
-                    data: event_bytes(event2)
-                },
-                RawEventDetails {
                     index: 2,
-                    phase: Phase::Finalization,
-                    pallet: "Test".to_string(),
-                    pallet_index: 0,
-                    variant: "A".to_string(),
-                    variant_index: 0,
-                    data: event_bytes(event3)
+                    event: (None, Some(C(234))),
+                    topics: vec![],
                 },
             ]
         );
     }
 
     #[test]
-    fn dynamically_decode_multiple_events_until_error() {
+    fn filter_events_by_pallet() {
         #[derive(Clone, Debug, PartialEq, Decode, Encode, TypeInfo)]
         enum Event {
             A(u8),
             B(bool),
         }
 
-        // Create fake metadata that knows about our single event, above:
+        // Create fake metadata that knows about our events, above:
         let metadata = metadata::<Event>();
+        let records = || {
+            vec![
+                event_record(Phase::Initialization, Event::A(1)),
+                event_record(Phase::ApplyExtrinsic(123), Event::B(true)),
+            ]
+        };
 
-        // Encode 2 events:
-        let mut event_bytes = vec![];
-        event_record(Phase::Initialization, Event::A(1)).encode_to(&mut event_bytes);
-        event_record(Phase::ApplyExtrinsic(123), Event::B(true))
-            .encode_to(&mut event_bytes);
+        // Events from the "Test" pallet are let through:
+        let events =
+            events::<Event>(&metadata, records()).filter_pallets(["Other", "Test"]);
+        assert_eq!(events.iter().count(), 2);
+        assert_eq!(events.iter_raw().count(), 2);
+
+        // Events from the "Test" pallet are skipped, but the count is unchanged:
+        let events = events::<Event>(&metadata, records()).filter_pallets(["Other"]);
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().next().is_none());
+        assert!(events.iter_raw().next().is_none());
+    }
 
-        // Push a few naff bytes to the end (a broken third event):
-        event_bytes.extend_from_slice(&[3, 127, 45, 0, 2]);
+    #[test]
+    fn filter_events_by_account() {
+        use sp_core::crypto::AccountId32;
 
-        // Encode our events in the format we expect back from a node, and
-        // construst an Events object to iterate them:
-        let events = events_raw::<Event>(
-            &metadata,
-            event_bytes,
-            3, // 2 "good" events, and then it'll hit the naff bytes.
-        );
+        #[derive(Clone, Debug, PartialEq, Decode, Encode, TypeInfo)]
+        enum Event {
+            A(AccountId32, u8),
+            B(u8, Vec<(bool, AccountId32)>),
+            C(Option<AccountId32>),
+        }
 
-        let event_bytes = |ev: Event| {
-            let mut bytes = ev.encode();
-            // Strip variant tag off event bytes:
-            bytes.drain(0..1);
-            bytes.into()
+        let alice = AccountId32::new([1; 32]);
+        let bob = AccountId32::new([2; 32]);
+
+        // Create fake metadata that knows about our events, above:
+        let metadata = metadata::<Event>();
+        let records = || {
+            vec![
+                event_record(Phase::Initialization, Event::A(alice.clone(), 1)),
+                event_record(
+                    Phase::ApplyExtrinsic(0),
+                    Event::B(2, vec![(true, bob.clone()), (false, alice.clone())]),
+                ),
+                event_record(Phase::ApplyExtrinsic(1), Event::C(Some(bob.clone()))),
+                event_record(Phase::Finalization, Event::C(None)),
+            ]
         };
 
-        let mut events_iter = events.iter_raw();
+        // Alice is referenced directly in the first event, and nested in the second:
+        let events = events::<Event>(&metadata, records()).filter_account(&alice);
+        let indexes = |events: &Events<'_, DefaultConfig, AllEvents<Event>>| {
+            let from_iter: Vec<u32> = events.iter().map(|ev| ev.unwrap().index).collect();
+            let from_iter_raw: Vec<u32> =
+                events.iter_raw().map(|ev| ev.unwrap().index).collect();
+            assert_eq!(from_iter, from_iter_raw);
+            from_iter
+        };
+        assert_eq!(indexes(&events), vec![0, 1]);
+
+        // Bob is in the second and third:
+        let events = events::<Event>(&metadata, records()).filter_account(&bob);
+        assert_eq!(indexes(&events), vec![1, 2]);
+
+        // Nobody else is referenced:
+        let charlie = AccountId32::new([3; 32]);
+        let events = events::<Event>(&metadata, records()).filter_account(&charlie);
+        assert_eq!(events.len(), 4);
+        assert!(indexes(&events).is_empty());
+
+        // The check can be made against individual events too:
+        let raw = events::<Event>(&metadata, records())
+            .iter_raw()
+            .nth(2)
+            .unwrap()
+            .unwrap();
+        assert!(raw.references_account(&metadata, &bob).unwrap());
+        assert!(!raw.references_account(&metadata, &alice).unwrap());
+    }
+
+    #[async_std::test]
+    async fn events_are_decoded_with_the_metadata_of_their_block() {
+        use crate::rpc::MockRpcClient;
+        use serde_json::json;
+        use sp_core::{
+            storage::StorageData,
+            Bytes,
+            H256,
+        };
+
+        #[derive(Clone, Debug, PartialEq, Decode, Encode, TypeInfo)]
+        enum OldEvent {
+            A(u8),
+        }
+
+        #[derive(Clone, Debug, PartialEq, Decode, Encode, TypeInfo)]
+        enum NewEvent {
+            A(u8),
+            B(u16),
+        }
+
+        let new_metadata = runtime_metadata::<NewEvent>("Test");
+        let event_bytes =
+            vec![event_record(Phase::Finalization, NewEvent::B(300))].encode();
+        let mock = MockRpcClient::new()
+            .with_chain(runtime_metadata::<OldEvent>("Test"))
+            // The runtime is upgraded once the client has been built.
+            .add_response(
+                "state_getRuntimeVersion",
+                json!({ "specVersion": 2, "transactionVersion": 1 }),
+            )
+            .add_response("state_getMetadata", Bytes(new_metadata.encode()))
+            .add_response("state_getStorage", StorageData(event_bytes));
+        let client = crate::ClientBuilder::new()
+            .set_client(mock.clone())
+            .build::<DefaultConfig>()
+            .await
+            .unwrap();
+
+        let block_hash = H256::repeat_byte(3);
+        for _ in 0..2 {
+            let events = client
+                .events_at::<AllEvents<NewEvent>>(block_hash)
+                .await
+                .unwrap();
+            let decoded: Vec<_> = events.iter().map(|ev| ev.unwrap().event).collect();
+            assert_eq!(decoded, vec![AllEvents::E(NewEvent::B(300))]);
+        }
+
+        // The metadata of the new runtime was fetched at the block, and only once.
         assert_eq!(
-            events_iter.next().unwrap().unwrap(),
-            RawEventDetails {
-                index: 0,
-                phase: Phase::Initialization,
-                pallet: "Test".to_string(),
-                pallet_index: 0,
-                variant: "A".to_string(),
-                variant_index: 0,
-                data: event_bytes(Event::A(1))
+            mock.requests_to("state_getMetadata"),
+            vec![json!([null]), json!([block_hash])]
+        );
+    }
+
+    /// Finalized block headers with the numbers given.
+    fn headers(
+        numbers: std::ops::RangeInclusive<u32>,
+    ) -> Vec<<DefaultConfig as Config>::Header> {
+        numbers
+            .map(|n| {
+                Header::new(
+                    n,
+                    Default::default(),
+                    Default::default(),
+                    Default::default(),
+                    Default::default(),
+                )
+            })
+            .collect()
+    }
+
+    /// Collect the numbers of (up to `limit`) finalized blocks handed back with the
+    /// overflow behaviour given, stopping at the first error.
+    async fn finalized_block_numbers(
+        mock: crate::rpc::MockRpcClient,
+        overflow: SubscriptionOverflow,
+        limit: usize,
+    ) -> (Vec<u64>, Option<BasicError>, SubscriptionMetrics) {
+        #[derive(TypeInfo)]
+        enum Event {
+            #[allow(dead_code)]
+            A(u8),
+        }
+
+        let client = crate::ClientBuilder::new()
+            .set_client(mock.with_chain(runtime_metadata::<Event>("Test")))
+            .build::<DefaultConfig>()
+            .await
+            .unwrap();
+        let kind = BlockSubscriptionKind::Finalized;
+        let subscription = subscribe_headers(&client, kind).await.unwrap();
+        // Give the client time to receive every notification, so that a small buffer
+        // overflows before any blocks are handed back.
+        Delay::new(Duration::from_millis(100)).await;
+        let block_hashes = BlockHashes::new(&client, kind, subscription, overflow);
+        let metrics = block_hashes.metrics();
+        let mut stream = block_hashes.into_stream().take(limit);
+
+        let mut numbers = Vec::new();
+        while let Some(block) = stream.next().await {
+            match block {
+                Ok((n, _)) => numbers.push(n),
+                Err(e) => return (numbers, Some(e), metrics),
             }
+        }
+        (numbers, None, metrics)
+    }
+
+    #[async_std::test]
+    async fn overflow_error_is_reported_when_blocks_are_missed() {
+        let mock = crate::rpc::MockRpcClient::new()
+            .set_subscription_buffer_size(1)
+            .add_subscription("chain_subscribeFinalizedHeads", headers(1..=6))
+            .add_subscription("chain_subscribeFinalizedHeads", headers(10..=10));
+
+        let (numbers, error, metrics) =
+            finalized_block_numbers(mock, SubscriptionOverflow::Error, 10).await;
+
+        // The blocks buffered before the subscription overflowed are handed back, and
+        // then the gap seen after resubscribing is reported.
+        assert!(!numbers.is_empty() && numbers.len() < 6);
+        assert_eq!(numbers, (1..=numbers.len() as u64).collect::<Vec<_>>());
+        assert_matches!(error, Some(Other(msg)) if msg.contains("buffer overflowed"));
+        assert_eq!(metrics.resubscribes(), 1);
+    }
+
+    #[async_std::test]
+    async fn overflow_error_is_not_reported_when_the_connection_closes() {
+        let mock = crate::rpc::MockRpcClient::new()
+            .add_subscription_then_close("chain_subscribeFinalizedHeads", headers(1..=3));
+
+        let (numbers, error, metrics) =
+            finalized_block_numbers(mock, SubscriptionOverflow::Error, 10).await;
+
+        // Every block is handed back, and then the subscription just ends.
+        assert_eq!(numbers, vec![1, 2, 3]);
+        assert!(error.is_none());
+        assert_eq!(metrics.resubscribes(), 0);
+    }
+
+    #[async_std::test]
+    async fn overflowing_with_backfill_hands_back_every_block() {
+        let mock = crate::rpc::MockRpcClient::new()
+            .set_subscription_buffer_size(1)
+            .add_subscription("chain_subscribeFinalizedHeads", headers(1..=6))
+            .add_subscription("chain_subscribeFinalizedHeads", headers(10..=10))
+            // The first response is the genesis hash; the hashes of the missed blocks
+            // are fetched in a single request after that.
+            .add_response("chain_getBlockHash", sp_core::H256::zero())
+            .add_response("chain_getBlockHash", vec![sp_core::H256::zero(); 10]);
+
+        let (numbers, error, metrics) =
+            finalized_block_numbers(mock, SubscriptionOverflow::Backfill, 10).await;
+
+        assert!(error.is_none());
+        assert_eq!(numbers, (1..=10).collect::<Vec<_>>());
+        assert_eq!(metrics.resubscribes(), 1);
+        assert_eq!(metrics.dropped_blocks(), 0);
+    }
+
+    #[test]
+    fn decode_events_with_custom_decoder() {
+        #[derive(Clone, Debug, PartialEq, Decode, Encode, TypeInfo)]
+        enum Event {
+            A(u8),
+        }
+
+        // An event from a pallet which the metadata knows nothing about:
+        #[derive(Clone, Debug, PartialEq, Decode, Encode)]
+        struct Unknown(u32, bool);
+
+        let metadata = metadata::<Event>();
+        let mut event_bytes = Vec::new();
+        Phase::ApplyExtrinsic(0).encode_to(&mut event_bytes);
+        (5u8, 2u8, Unknown(123, true)).encode_to(&mut event_bytes);
+        Vec::<<DefaultConfig as Config>::Hash>::new().encode_to(&mut event_bytes);
+        event_record(Phase::Finalization, Event::A(1)).encode_to(&mut event_bytes);
+
+        // Without a decoder, we can't get past the unknown event:
+        let mut events = events_raw::<Event>(&metadata, event_bytes, 2);
+        assert!(events.iter_raw().next().unwrap().is_err());
+
+        events.decoders.register(
+            (5, "New".to_string()),
+            (2, "Unknown".to_string()),
+            Arc::new(|input| Unknown::decode(input).map(|_| ())),
         );
+        let raw_events: Vec<_> = events.iter_raw().collect::<Result<_, _>>().unwrap();
+        assert_eq!(raw_events.len(), 2);
+        assert_eq!(raw_events[0].pallet, "New");
+        assert_eq!(raw_events[0].variant, "Unknown");
+        assert_eq!(raw_events[0].data.0, Unknown(123, true).encode());
+        assert_eq!(raw_events[1].pallet, "Test");
+        assert_eq!(raw_events[1].phase, Phase::Finalization);
+
+        // The unknown event can be skipped over when filtering too:
+        let events = events.filter_pallets(["Test"]);
+        assert_eq!(events.iter().count(), 1);
+        assert_eq!(events.iter_raw().count(), 1);
+    }
+
+    #[test]
+    fn chain_tracker_follows_best_chain() {
+        use super::ChainUpdate::*;
+        let mut tracker = ChainTracker::default();
+
+        // The first block is imported straight away, as are its children:
+        assert_eq!(tracker.add(10, 'a'), Some(vec![Imported('a')]));
+        assert_eq!(tracker.add(11, 'b'), None);
+        assert_eq!(tracker.add(10, 'a'), Some(vec![Imported('b')]));
+        assert_eq!(tracker.add(12, 'c'), None);
+        assert_eq!(tracker.add(11, 'b'), Some(vec![Imported('c')]));
+
+        // A new fork from 'a' becomes the best chain. We follow it back until it joins
+        // the blocks we know about, and then retract and import blocks as needed:
+        assert_eq!(tracker.add(13, 'z'), None);
+        assert_eq!(tracker.add(12, 'y'), None);
+        assert_eq!(tracker.add(11, 'x'), None);
         assert_eq!(
-            events_iter.next().unwrap().unwrap(),
-            RawEventDetails {
-                index: 1,
-                phase: Phase::ApplyExtrinsic(123),
-                pallet: "Test".to_string(),
-                pallet_index: 0,
-                variant: "B".to_string(),
-                variant_index: 1,
-                data: event_bytes(Event::B(true))
-            }
+            tracker.add(10, 'a'),
+            Some(vec![
+                Retracted('c'),
+                Retracted('b'),
+                Imported('x'),
+                Imported('y'),
+                Imported('z'),
+            ])
         );
 
-        // We'll hit an error trying to decode the third event:
-        assert!(events_iter.next().unwrap().is_err());
-        // ... and then "None" from then on.
-        assert!(events_iter.next().is_none());
-        assert!(events_iter.next().is_none());
+        // The best chain can move back to some earlier block, too:
+        assert_eq!(
+            tracker.add(11, 'x'),
+            Some(vec![Retracted('z'), Retracted('y')])
+        );
+    }
+
+    #[test]
+    fn chain_tracker_joins_the_chain_at_known_parents() {
+        use super::ChainUpdate::*;
+        let mut tracker = ChainTracker::default();
+
+        // Children of the last block are imported without walking back to their parent:
+        assert_eq!(
+            tracker.add_with_parent(10, 'a', '_'),
+            Some(vec![Imported('a')])
+        );
+        assert_eq!(
+            tracker.add_with_parent(11, 'b', 'a'),
+            Some(vec![Imported('b')])
+        );
+        assert_eq!(
+            tracker.add_with_parent(12, 'c', 'b'),
+            Some(vec![Imported('c')])
+        );
+
+        // As are forks from a block we know about:
+        assert_eq!(
+            tracker.add_with_parent(12, 'y', 'b'),
+            Some(vec![Retracted('c'), Imported('y')])
+        );
+
+        // A fork from a block we don't know about is followed back as before:
+        assert_eq!(tracker.add_with_parent(13, 'q', 'p'), None);
+        assert_eq!(
+            tracker.add_with_parent(12, 'p', 'b'),
+            Some(vec![Retracted('y'), Imported('p'), Imported('q')])
+        );
+
+        // And moving back to a block on the chain retracts the blocks after it:
+        assert_eq!(
+            tracker.add_with_parent(11, 'b', 'a'),
+            Some(vec![Retracted('q'), Retracted('p')])
+        );
+    }
+
+    #[test]
+    fn chain_tracker_gives_up_below_known_blocks() {
+        use super::ChainUpdate::*;
+        let mut tracker = ChainTracker::default();
+
+        assert_eq!(tracker.add(10, 'a'), Some(vec![Imported('a')]));
+        assert_eq!(tracker.add(11, 'b'), None);
+        assert_eq!(tracker.add(10, 'a'), Some(vec![Imported('b')]));
+
+        // This fork branches off before any block we know about:
+        assert_eq!(tracker.add(11, 'y'), None);
+        assert_eq!(tracker.add(10, 'x'), None);
+        assert_eq!(
+            tracker.add(9, 'w'),
+            Some(vec![
+                Retracted('b'),
+                Retracted('a'),
+                Imported('x'),
+                Imported('y'),
+            ])
+        );
+    }
+
+    #[test]
+    fn summarise_events() {
+        #[derive(Clone, Debug, PartialEq, Decode, Encode, TypeInfo)]
+        enum Event {
+            A(u8),
+            B(bool),
+        }
+
+        let metadata = metadata::<Event>();
+        let events = events::<Event>(
+            &metadata,
+            vec![
+                event_record(Phase::Initialization, Event::A(1)),
+                event_record(Phase::ApplyExtrinsic(0), Event::B(true)),
+                event_record(Phase::ApplyExtrinsic(1), Event::A(2)),
+            ],
+        )
+        .filter_pallets(["Other"]);
+
+        let summary = events.summary().unwrap();
+        assert_eq!(summary.total(), 3);
+        assert_eq!(summary.pallet_count("Test"), 3);
+        assert_eq!(summary.pallet_count("Other"), 0);
+        assert_eq!(summary.count("Test", "A"), 2);
+        assert_eq!(summary.count("Test", "B"), 1);
+        assert_eq!(summary.count("Test", "C"), 0);
+        assert_eq!(
+            summary.iter().collect::<Vec<_>>(),
+            vec![("Test", "A", 2), ("Test", "B", 1)]
+        );
+    }
+
+    #[test]
+    fn event_topics_storage_key() {
+        let topic = [7u8; 32];
+        let key = event_topics_key(&topic).0;
+
+        assert_eq!(key.len(), 16 + 16 + 16 + 32);
+        assert_eq!(&key[..16], &twox_128(b"System"));
+        assert_eq!(&key[16..32], &twox_128(b"EventTopics"));
+        assert_eq!(&key[32..48], &blake2_128(&topic));
+        assert_eq!(&key[48..], &topic);
     }
 
     #[test]
@@ -1151,7 +4027,8 @@ mod tests {
             vec![EventDetails {
                 index: 0,
                 phase: Phase::Finalization,
-                event: AllEvents::E(Event::A(1))
+                event: AllEvents::E(Event::A(1)),
+                topics: vec![],
             }]
         );
 
@@ -1173,7 +4050,8 @@ mod tests {
                 pallet_index: 0,
                 variant: "A".to_string(),
                 variant_index: 0,
-                data: expected_event_data.into()
+                data: expected_event_data.into(),
+                topics: vec![],
             }]
         );
     }
@@ -1209,7 +4087,8 @@ mod tests {
             vec![EventDetails {
                 index: 0,
                 phase: Phase::Finalization,
-                event: AllEvents::E(Event::A(CompactWrapper(1)))
+                event: AllEvents::E(Event::A(CompactWrapper(1))),
+                topics: vec![],
             }]
         );
 
@@ -1231,7 +4110,8 @@ mod tests {
                 pallet_index: 0,
                 variant: "A".to_string(),
                 variant_index: 0,
-                data: expected_event_data.into()
+                data: expected_event_data.into(),
+                topics: vec![],
             }]
         );
     }
@@ -1268,7 +4148,8 @@ mod tests {
             vec![EventDetails {
                 index: 0,
                 phase: Phase::Finalization,
-                event: AllEvents::E(Event::A(MyType::B))
+                event: AllEvents::E(Event::A(MyType::B)),
+                topics: vec![],
             }]
         );
 
@@ -1290,7 +4171,8 @@ mod tests {
                 pallet_index: 0,
                 variant: "A".to_string(),
                 variant_index: 0,
-                data: expected_event_data.into()
+                data: expected_event_data.into(),
+                topics: vec![],
             }]
         );
     }
@@ -1508,4 +4390,68 @@ mod tests {
         decode_and_consume_type_consumes_all_bytes(CompactV4 { val: 0u8 });
         decode_and_consume_type_consumes_all_bytes(CompactV4 { val: 1u16 });
     }
+
+    #[test]
+    fn decode_nested_compact_wrappers() {
+        #[derive(Clone, Encode, TypeInfo, codec::CompactAs)]
+        struct Inner(u64);
+
+        #[derive(Clone, Encode, TypeInfo)]
+        struct Outer(Inner);
+
+        impl codec::CompactAs for Outer {
+            type As = u64;
+            fn encode_as(&self) -> &u64 {
+                &self.0 .0
+            }
+            fn decode_from(val: u64) -> Result<Self, codec::Error> {
+                Ok(Outer(Inner(val)))
+            }
+        }
+
+        impl From<Compact<Outer>> for Outer {
+            fn from(val: Compact<Outer>) -> Self {
+                val.0
+            }
+        }
+
+        #[derive(Clone, Encode, TypeInfo)]
+        struct Wrapped {
+            #[codec(compact)]
+            a: Outer,
+            b: u8,
+        }
+
+        decode_and_consume_type_consumes_all_bytes(Wrapped {
+            a: Outer(Inner(u64::MAX)),
+            b: 1,
+        });
+    }
+
+    #[test]
+    fn decode_256_bit_integers() {
+        #[derive(Clone, Encode)]
+        struct U256Like([u8; 32]);
+
+        impl TypeInfo for U256Like {
+            type Identity = Self;
+            fn type_info() -> scale_info::Type {
+                TypeDefPrimitive::U256.into()
+            }
+        }
+
+        #[derive(Clone, Encode)]
+        struct I256Like([u8; 32]);
+
+        impl TypeInfo for I256Like {
+            type Identity = Self;
+            fn type_info() -> scale_info::Type {
+                TypeDefPrimitive::I256.into()
+            }
+        }
+
+        decode_and_consume_type_consumes_all_bytes(U256Like([1; 32]));
+        decode_and_consume_type_consumes_all_bytes(I256Like([2; 32]));
+        decode_and_consume_type_consumes_all_bytes((U256Like([3; 32]), 4u8));
+    }
 }
@@ -0,0 +1,374 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Decode extrinsics, for instance those fetched as part of a block, into their parts.
+
+use crate::{
+    error::BasicError,
+    events::decode_and_consume_type,
+    Call,
+    Config,
+    Metadata,
+};
+use codec::{
+    Compact,
+    Decode,
+    Error as CodecError,
+};
+use derivative::Derivative;
+use sp_runtime::generic::Era;
+
+/// The version of the extrinsic format supported.
+const EXTRINSIC_VERSION: u8 = 4;
+
+/// A decoded extrinsic.
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "T::Signature: Clone"),
+    Debug(bound = "T::Address: std::fmt::Debug, T::Signature: std::fmt::Debug")
+)]
+pub struct ExtrinsicDetails<T: Config> {
+    /// The signature and signed extensions, if the extrinsic is signed.
+    pub signature: Option<ExtrinsicSignature<T>>,
+    /// The name of the pallet of the call.
+    pub pallet: String,
+    /// The index of the pallet of the call.
+    pub pallet_index: u8,
+    /// The name of the call.
+    pub call: String,
+    /// The index of the call.
+    pub call_index: u8,
+    /// The SCALE encoded call data: the pallet and call indices followed by the arguments.
+    pub call_data: Vec<u8>,
+}
+
+/// The signature of a signed extrinsic, along with the values of the commonly used signed
+/// extensions.
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "T::Signature: Clone"),
+    Debug(bound = "T::Address: std::fmt::Debug, T::Signature: std::fmt::Debug")
+)]
+pub struct ExtrinsicSignature<T: Config> {
+    /// The address of the account which signed the extrinsic.
+    pub address: T::Address,
+    /// The signature.
+    pub signature: T::Signature,
+    /// The nonce, if the chain uses the `CheckNonce` extension.
+    pub nonce: Option<T::Index>,
+    /// The tip, if the chain uses the `ChargeTransactionPayment` or `ChargeAssetTxPayment`
+    /// extension.
+    pub tip: Option<u128>,
+    /// The era, if the chain uses the `CheckMortality` extension.
+    pub era: Option<Era>,
+    /// The SCALE encoded values of all of the signed extensions, in the order the metadata
+    /// lists them.
+    pub extra: Vec<u8>,
+}
+
+impl<T> ExtrinsicDetails<T>
+where
+    T: Config,
+    T::Signature: Decode,
+{
+    /// Decode a SCALE encoded (and so length prefixed) extrinsic, such as one of the
+    /// extrinsics of a block. The signed extensions are decoded using the types given by
+    /// the metadata.
+    pub fn decode(metadata: &Metadata, extrinsic: &[u8]) -> Result<Self, BasicError> {
+        let input = &mut &*extrinsic;
+        let _len = <Compact<u32>>::decode(input)?;
+
+        let version = u8::decode(input)?;
+        if version & 0b0111_1111 != EXTRINSIC_VERSION {
+            return Err(BasicError::Other(format!(
+                "Unsupported extrinsic version {}",
+                version & 0b0111_1111
+            )))
+        }
+
+        let signature = if version & 0b1000_0000 != 0 {
+            Some(ExtrinsicSignature::decode(metadata, input)?)
+        } else {
+            None
+        };
+
+        let call_data = input.to_vec();
+        let (pallet_index, call_index) = match call_data[..] {
+            [pallet_index, call_index, ..] => (pallet_index, call_index),
+            _ => return Err(BasicError::Other("Extrinsic call data is too short".into())),
+        };
+        let (pallet, call) = metadata.call_name(pallet_index, call_index)?;
+
+        Ok(ExtrinsicDetails {
+            signature,
+            pallet: pallet.to_string(),
+            pallet_index,
+            call: call.to_string(),
+            call_index,
+            call_data,
+        })
+    }
+}
+
+impl<T: Config> ExtrinsicDetails<T> {
+    /// Attempt to decode the call into a specific call, such as one of the generated call
+    /// types, handing back `None` if it's a different call.
+    pub fn as_call<C: Call + Decode>(&self) -> Result<Option<C>, CodecError> {
+        if self.pallet == C::PALLET && self.call == C::FUNCTION {
+            Ok(Some(C::decode(&mut &self.call_data[2..])?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Decode the call into the outermost call enum, which contains all of the possible
+    /// calls across all pallets (such as the generated `runtime_types::node_runtime::Call`).
+    pub fn as_root_call<C: Decode>(&self) -> Result<C, CodecError> {
+        C::decode(&mut &self.call_data[..])
+    }
+}
+
+impl<T> ExtrinsicSignature<T>
+where
+    T: Config,
+    T::Signature: Decode,
+{
+    fn decode(metadata: &Metadata, input: &mut &[u8]) -> Result<Self, BasicError> {
+        let address = T::Address::decode(input)?;
+        let signature = T::Signature::decode(input)?;
+
+        let mut nonce = None;
+        let mut tip = None;
+        let mut era = None;
+        let extra_start = *input;
+        let runtime_metadata = metadata.runtime_metadata();
+        for extension in &runtime_metadata.extrinsic.signed_extensions {
+            let start = *input;
+            decode_and_consume_type(extension.ty.id(), &runtime_metadata.types, input)?;
+            let data = &mut &start[..start.len() - input.len()];
+
+            match extension.identifier.as_str() {
+                "CheckNonce" => {
+                    let value = <Compact<u64>>::decode(data)?.0;
+                    nonce = Some(T::Index::try_from(value).map_err(|_| {
+                        BasicError::Other(format!("Nonce {} is out of range", value))
+                    })?);
+                }
+                "CheckMortality" | "CheckEra" => era = Some(Era::decode(data)?),
+                // The tip is the first field of both extensions.
+                "ChargeTransactionPayment" | "ChargeAssetTxPayment" => {
+                    tip = Some(<Compact<u128>>::decode(data)?.0)
+                }
+                _ => (),
+            }
+        }
+        let extra = extra_start[..extra_start.len() - input.len()].to_vec();
+
+        Ok(ExtrinsicSignature {
+            address,
+            signature,
+            nonce,
+            tip,
+            era,
+            extra,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        extrinsic::{
+            create_signed,
+            create_unsigned,
+            ChargeTransactionPayment,
+            CheckGenesis,
+            CheckMortality,
+            CheckNonce,
+            CheckSpecVersion,
+            CheckTxVersion,
+            CheckWeight,
+            PairSigner,
+        },
+        rpc::RuntimeVersion,
+        DefaultConfig,
+        DefaultExtra,
+        DefaultExtraParams,
+        Encoded,
+    };
+    use codec::Encode;
+    use frame_metadata::{
+        v14::{
+            ExtrinsicMetadata,
+            PalletCallMetadata,
+            PalletMetadata,
+            RuntimeMetadataLastVersion,
+            SignedExtensionMetadata,
+        },
+        RuntimeMetadataPrefixed,
+    };
+    use scale_info::{
+        meta_type,
+        TypeInfo,
+    };
+    use sp_core::{
+        sr25519,
+        Pair,
+    };
+    use std::convert::TryFrom;
+
+    #[allow(dead_code, non_camel_case_types)]
+    #[derive(TypeInfo)]
+    enum TestCall {
+        #[codec(index = 3)]
+        transfer { value: u128 },
+    }
+
+    fn extension<E: TypeInfo + 'static>(
+        identifier: &'static str,
+    ) -> SignedExtensionMetadata {
+        SignedExtensionMetadata {
+            identifier,
+            ty: meta_type::<E>(),
+            additional_signed: meta_type::<()>(),
+        }
+    }
+
+    fn metadata() -> Metadata {
+        type T = DefaultConfig;
+        let pallets = vec![PalletMetadata {
+            name: "Balances",
+            storage: None,
+            calls: Some(PalletCallMetadata {
+                ty: meta_type::<TestCall>(),
+            }),
+            event: None,
+            constants: vec![],
+            error: None,
+            index: 5,
+        }];
+        let extrinsic = ExtrinsicMetadata {
+            ty: meta_type::<()>(),
+            version: EXTRINSIC_VERSION,
+            signed_extensions: vec![
+                extension::<CheckSpecVersion<T>>("CheckSpecVersion"),
+                extension::<CheckTxVersion<T>>("CheckTxVersion"),
+                extension::<CheckGenesis<T>>("CheckGenesis"),
+                extension::<CheckMortality<T>>("CheckMortality"),
+                extension::<CheckNonce<T>>("CheckNonce"),
+                extension::<CheckWeight<T>>("CheckWeight"),
+                extension::<ChargeTransactionPayment<T>>("ChargeTransactionPayment"),
+            ],
+        };
+        let meta = RuntimeMetadataLastVersion::new(pallets, extrinsic, meta_type::<()>());
+        let runtime_metadata: RuntimeMetadataPrefixed = meta.into();
+        Metadata::try_from(runtime_metadata).unwrap()
+    }
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    struct Transfer {
+        value: u128,
+    }
+
+    impl Call for Transfer {
+        const PALLET: &'static str = "Balances";
+        const FUNCTION: &'static str = "transfer";
+    }
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    struct Remark {
+        remark: Vec<u8>,
+    }
+
+    impl Call for Remark {
+        const PALLET: &'static str = "System";
+        const FUNCTION: &'static str = "remark";
+    }
+
+    fn call() -> Encoded {
+        let mut call = vec![5, 3];
+        call.extend(1000u128.encode());
+        Encoded(call)
+    }
+
+    #[async_std::test]
+    async fn decodes_signed_extrinsic() {
+        let pair = sr25519::Pair::from_seed(&[1; 32]);
+        let signer =
+            PairSigner::<DefaultConfig, DefaultExtra<DefaultConfig>, _>::new(pair);
+        let era = Era::mortal(64, 1000);
+        let params = DefaultExtraParams::<DefaultConfig>::new()
+            .tip(5)
+            .era(era, Default::default());
+        let runtime_version = RuntimeVersion {
+            spec_version: 1,
+            transaction_version: 2,
+            other: Default::default(),
+        };
+        let extrinsic = create_signed::<DefaultConfig, DefaultExtra<DefaultConfig>>(
+            &runtime_version,
+            Default::default(),
+            7,
+            call(),
+            &signer,
+            params,
+        )
+        .await
+        .unwrap();
+
+        let details =
+            ExtrinsicDetails::<DefaultConfig>::decode(&metadata(), &extrinsic.encode())
+                .unwrap();
+
+        let (address, signature, extra) = extrinsic.signature.unwrap();
+        let decoded = details.signature.unwrap();
+        assert_eq!(decoded.address, address);
+        assert_eq!(decoded.signature, signature);
+        assert_eq!(decoded.extra, extra.encode());
+        assert_eq!(decoded.nonce, Some(7));
+        assert_eq!(decoded.tip, Some(5));
+        assert_eq!(decoded.era, Some(era));
+        assert_eq!(
+            (details.pallet.as_str(), details.call.as_str()),
+            ("Balances", "transfer")
+        );
+        assert_eq!(details.call_data, call().0);
+    }
+
+    #[test]
+    fn decodes_unsigned_extrinsic() {
+        let extrinsic =
+            create_unsigned::<DefaultConfig, DefaultExtra<DefaultConfig>>(call());
+
+        let details =
+            ExtrinsicDetails::<DefaultConfig>::decode(&metadata(), &extrinsic.encode())
+                .unwrap();
+
+        assert!(details.signature.is_none());
+        assert_eq!((details.pallet_index, details.call_index), (5, 3));
+        assert_eq!(details.call_data, call().0);
+        assert_eq!(
+            details.as_call::<Transfer>().unwrap(),
+            Some(Transfer { value: 1000 })
+        );
+        assert_eq!(details.as_call::<Remark>().unwrap(), None);
+        assert_eq!(
+            details.as_root_call::<(u8, u8, u128)>().unwrap(),
+            (5, 3, 1000)
+        );
+    }
+}
@@ -0,0 +1,647 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Signed extensions which are chosen at runtime, based on those that the chain's metadata
+//! says it expects, rather than being fixed at compile time.
+
+use super::{
+    ChargeAssetTxPayment,
+    ChargeTransactionPayment,
+    CheckMetadataHash,
+    CheckNonce,
+    DefaultExtraParams,
+    SignedExtra,
+};
+use crate::{
+    error::BasicError,
+    events::decode_and_consume_type,
+    metadata::MetadataError,
+    Config,
+    Metadata,
+    PhantomDataSendSync,
+};
+use codec::{
+    Decode,
+    Encode,
+    Input,
+    Output,
+};
+use derivative::Derivative;
+use scale_info::TypeInfo;
+use sp_runtime::{
+    generic::Era,
+    traits::{
+        DispatchInfoOf,
+        SignedExtension,
+    },
+    transaction_validity::TransactionValidityError,
+};
+
+/// The signed extensions used by [`DynamicExtra`] when the extensions expected by the
+/// chain aren't provided, matching those of [`super::DefaultExtra`].
+const DEFAULT_EXTENSIONS: [&str; 7] = [
+    "CheckSpecVersion",
+    "CheckTxVersion",
+    "CheckGenesis",
+    "CheckMortality",
+    "CheckNonce",
+    "CheckWeight",
+    "ChargeTransactionPayment",
+];
+
+/// The details available when encoding a signed extension.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct ExtensionContext<T: Config> {
+    /// The spec version of the runtime.
+    pub spec_version: u32,
+    /// The transaction version of the runtime.
+    pub tx_version: u32,
+    /// The nonce of the signing account.
+    pub nonce: T::Index,
+    /// The genesis hash of the chain.
+    pub genesis_hash: T::Hash,
+    /// The tip to give to the block author.
+    pub tip: u128,
+    /// The era of the transaction.
+    pub era: Era,
+    /// The hash of the block that the era was constructed from (or the genesis hash for
+    /// immortal transactions).
+    pub mortality_checkpoint: T::Hash,
+    /// The asset with which to pay fees, if any.
+    pub asset_id: Option<u32>,
+    /// The hash of the metadata to commit to, if any.
+    pub metadata_hash: Option<[u8; 32]>,
+}
+
+/// A signed extension encoded by an [`ExtensionRegistry`].
+#[derive(Clone, Debug, Default, Eq, PartialEq, TypeInfo)]
+pub struct EncodedExtension {
+    /// The encoded data included in the extrinsic.
+    pub extra: Vec<u8>,
+    /// The encoded data which isn't included in the extrinsic, but is signed as part of
+    /// the signing payload.
+    pub additional_signed: Vec<u8>,
+}
+
+impl EncodedExtension {
+    /// Create a new [`EncodedExtension`] from the data included in the extrinsic and the
+    /// additional signed data.
+    pub fn new(extra: impl Encode, additional_signed: impl Encode) -> Self {
+        Self {
+            extra: extra.encode(),
+            additional_signed: additional_signed.encode(),
+        }
+    }
+}
+
+/// Knows how to encode the signed extensions used by some runtime, given their identifiers.
+///
+/// Chains which use extensions unknown to [`DefaultExtensions`] can implement this, and
+/// fall back to [`DefaultExtensions`] for the standard ones:
+///
+/// ```rust
+/// use subxt::{
+///     extrinsic::{
+///         DefaultExtensions,
+///         DynamicExtra,
+///         EncodedExtension,
+///         ExtensionContext,
+///         ExtensionRegistry,
+///     },
+///     DefaultConfig,
+/// };
+///
+/// pub enum MyExtensions {}
+///
+/// impl ExtensionRegistry<DefaultConfig> for MyExtensions {
+///     fn encode(
+///         identifier: &str,
+///         context: &ExtensionContext<DefaultConfig>,
+///     ) -> Option<EncodedExtension> {
+///         match identifier {
+///             "CheckSomethingCustom" => Some(EncodedExtension::new((), context.spec_version)),
+///             _ => DefaultExtensions::encode(identifier, context),
+///         }
+///     }
+/// }
+///
+/// pub type MyExtra = DynamicExtra<DefaultConfig, MyExtensions>;
+/// ```
+pub trait ExtensionRegistry<T: Config>: Send + Sync + 'static {
+    /// Encode the signed extension with the given identifier, or return `None` if it's
+    /// not known.
+    fn encode(
+        identifier: &str,
+        context: &ExtensionContext<T>,
+    ) -> Option<EncodedExtension>;
+}
+
+/// The signed extensions provided by substrate's FRAME pallets.
+pub enum DefaultExtensions {}
+
+impl<T: Config> ExtensionRegistry<T> for DefaultExtensions {
+    fn encode(
+        identifier: &str,
+        context: &ExtensionContext<T>,
+    ) -> Option<EncodedExtension> {
+        let extension = match identifier {
+            "CheckSpecVersion" => EncodedExtension::new((), context.spec_version),
+            "CheckTxVersion" => EncodedExtension::new((), context.tx_version),
+            "CheckGenesis" => EncodedExtension::new((), context.genesis_hash),
+            "CheckMortality" | "CheckEra" => {
+                EncodedExtension::new(context.era, context.mortality_checkpoint)
+            }
+            "CheckNonce" => EncodedExtension::new(CheckNonce::<T>(context.nonce), ()),
+            "CheckWeight" | "CheckNonZeroSender" => EncodedExtension::new((), ()),
+            "ChargeTransactionPayment" => {
+                EncodedExtension::new(ChargeTransactionPayment::<T>::new(context.tip), ())
+            }
+            "ChargeAssetTxPayment" => {
+                let payment =
+                    ChargeAssetTxPayment::<T>::new(context.tip, context.asset_id);
+                EncodedExtension::new(payment, ())
+            }
+            "CheckMetadataHash" => {
+                let check = CheckMetadataHash::<T>::new(context.metadata_hash);
+                EncodedExtension::new(&check, check.metadata_hash)
+            }
+            _ => return None,
+        };
+        Some(extension)
+    }
+}
+
+/// Parameters which can be provided when signing an extrinsic using [`DynamicExtra`].
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
+pub struct DynamicExtraParams<T: Config> {
+    identifiers: Vec<String>,
+    params: DefaultExtraParams<T>,
+}
+
+impl<T: Config> Default for DynamicExtraParams<T> {
+    fn default() -> Self {
+        Self {
+            identifiers: DEFAULT_EXTENSIONS.iter().map(ToString::to_string).collect(),
+            params: Default::default(),
+        }
+    }
+}
+
+impl<T: Config> DynamicExtraParams<T> {
+    /// Create parameters which use the signed extensions that the given metadata says
+    /// the chain expects, and the given tip, era, asset and custom extension data.
+    /// Returns an error if any of the extensions isn't known to the registry `R`, and
+    /// hasn't been provided via [`DefaultExtraParams::extension`].
+    pub fn from_metadata<R: ExtensionRegistry<T>>(
+        metadata: &Metadata,
+        params: DefaultExtraParams<T>,
+    ) -> Result<Self, MetadataError> {
+        let identifiers = metadata
+            .runtime_metadata()
+            .extrinsic
+            .signed_extensions
+            .iter()
+            .map(|extension| {
+                let identifier = &extension.identifier;
+                if is_known::<T, R>(identifier, &params) {
+                    Ok(identifier.clone())
+                } else {
+                    Err(MetadataError::UnsupportedSignedExtension(
+                        identifier.clone(),
+                    ))
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            identifiers,
+            params,
+        })
+    }
+
+    /// Set the tip, era, asset and custom extension data to use when signing.
+    pub fn params(mut self, params: DefaultExtraParams<T>) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// The identifiers of the signed extensions that will be used, in order.
+    pub fn identifiers(&self) -> &[String] {
+        &self.identifiers
+    }
+}
+
+// Can the extension with the given identifier be encoded, either because its data has been
+// provided or because the registry knows about it?
+fn is_known<T: Config, R: ExtensionRegistry<T>>(
+    identifier: &str,
+    params: &DefaultExtraParams<T>,
+) -> bool {
+    let context = ExtensionContext::<T> {
+        spec_version: Default::default(),
+        tx_version: Default::default(),
+        nonce: Default::default(),
+        genesis_hash: Default::default(),
+        tip: Default::default(),
+        era: Era::Immortal,
+        mortality_checkpoint: Default::default(),
+        asset_id: Default::default(),
+        metadata_hash: Default::default(),
+    };
+    params.extensions.contains_key(identifier)
+        || R::encode(identifier, &context).is_some()
+}
+
+/// The additional signed data of a [`DynamicExtra`], which encodes as the concatenated
+/// additional signed data of each of its extensions.
+#[derive(Clone, Debug, Eq, PartialEq, TypeInfo)]
+pub struct EncodedAdditionalSigned(Vec<u8>);
+
+impl Encode for EncodedAdditionalSigned {
+    fn size_hint(&self) -> usize {
+        self.0.len()
+    }
+
+    fn encode_to<O: Output + ?Sized>(&self, dest: &mut O) {
+        dest.write(&self.0);
+    }
+}
+
+/// A `SignedExtra` whose signed extensions are determined at runtime, and encoded using the
+/// [`ExtensionRegistry`] `R`. This allows signing extrinsics for chains whose extensions
+/// differ from those of [`super::DefaultExtra`], by providing [`DynamicExtraParams`]
+/// created from the chain's metadata. Signing fails if any of the extensions isn't known
+/// to `R` and its data hasn't been provided via [`DefaultExtraParams::extension`].
+///
+/// The extensions can't be decoded without knowing their types, so use
+/// [`DynamicExtra::decode_with_metadata()`] rather than [`Decode`].
+#[derive(Derivative, TypeInfo)]
+#[derivative(
+    Clone(bound = ""),
+    Debug(bound = ""),
+    PartialEq(bound = ""),
+    Eq(bound = "")
+)]
+#[scale_info(skip_type_params(T, R))]
+pub struct DynamicExtra<T: Config, R = DefaultExtensions> {
+    extensions: Vec<EncodedExtension>,
+    marker: PhantomDataSendSync<(T, R)>,
+}
+
+impl<T: Config, R> Encode for DynamicExtra<T, R> {
+    fn size_hint(&self) -> usize {
+        self.extensions.iter().map(|ext| ext.extra.len()).sum()
+    }
+
+    fn encode_to<O: Output + ?Sized>(&self, dest: &mut O) {
+        for extension in &self.extensions {
+            dest.write(&extension.extra);
+        }
+    }
+}
+
+impl<T: Config, R> DynamicExtra<T, R> {
+    /// Decode the signed extensions of an extrinsic, using the types that the metadata
+    /// gives for them. Only the data included in the extrinsic can be decoded, so the
+    /// additional signed data of each extension is left empty.
+    pub fn decode_with_metadata(
+        metadata: &Metadata,
+        input: &mut &[u8],
+    ) -> Result<Self, BasicError> {
+        let runtime_metadata = metadata.runtime_metadata();
+        let extensions = runtime_metadata
+            .extrinsic
+            .signed_extensions
+            .iter()
+            .map(|extension| {
+                let start = *input;
+                decode_and_consume_type(
+                    extension.ty.id(),
+                    &runtime_metadata.types,
+                    input,
+                )?;
+                Ok(EncodedExtension {
+                    extra: start[..start.len() - input.len()].to_vec(),
+                    additional_signed: Vec::new(),
+                })
+            })
+            .collect::<Result<_, BasicError>>()?;
+        Ok(DynamicExtra {
+            extensions,
+            marker: PhantomDataSendSync::new(),
+        })
+    }
+}
+
+// `SignedExtension` requires `Decode`, but without the metadata we can't know how many
+// bytes each extension takes up.
+impl<T: Config, R> Decode for DynamicExtra<T, R> {
+    fn decode<I: Input>(_input: &mut I) -> Result<Self, codec::Error> {
+        Err(
+            "DynamicExtra can only be decoded with DynamicExtra::decode_with_metadata"
+                .into(),
+        )
+    }
+}
+
+impl<T, R> SignedExtra<T> for DynamicExtra<T, R>
+where
+    T: Config,
+    R: ExtensionRegistry<T>,
+{
+    type Extra = Self;
+    type Parameters = DynamicExtraParams<T>;
+
+    fn new(
+        spec_version: u32,
+        tx_version: u32,
+        nonce: T::Index,
+        genesis_hash: T::Hash,
+        additional_params: Self::Parameters,
+    ) -> Self {
+        let params = &additional_params.params;
+        let context = ExtensionContext {
+            spec_version,
+            tx_version,
+            nonce,
+            genesis_hash,
+            tip: params.tip,
+            era: params.era,
+            mortality_checkpoint: params.mortality_checkpoint.unwrap_or(genesis_hash),
+            asset_id: params.asset_id,
+            metadata_hash: params.metadata_hash,
+        };
+        // Unknown extensions are rejected by `check_parameters` before signing.
+        let extensions = additional_params
+            .identifiers
+            .iter()
+            .filter_map(|identifier| {
+                let extension = params
+                    .extensions
+                    .get(identifier)
+                    .cloned()
+                    .or_else(|| R::encode(identifier, &context));
+                if extension.is_none() {
+                    log::warn!("Ignoring unknown signed extension {}", identifier);
+                }
+                extension
+            })
+            .collect();
+        DynamicExtra {
+            extensions,
+            marker: PhantomDataSendSync::new(),
+        }
+    }
+
+    fn extra(&self) -> Self::Extra {
+        self.clone()
+    }
+
+    fn check_parameters(params: &Self::Parameters) -> Result<(), BasicError> {
+        match params
+            .identifiers
+            .iter()
+            .find(|identifier| !is_known::<T, R>(identifier, &params.params))
+        {
+            Some(identifier) => {
+                Err(MetadataError::UnsupportedSignedExtension(identifier.clone()).into())
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn default_parameters(
+        tip: u128,
+        era: Era,
+        mortality_checkpoint: Option<T::Hash>,
+    ) -> Self::Parameters {
+        DynamicExtraParams {
+            params: DefaultExtraParams::from_defaults(tip, era, mortality_checkpoint),
+            ..Default::default()
+        }
+    }
+}
+
+impl<T, R> SignedExtension for DynamicExtra<T, R>
+where
+    T: Config,
+    R: ExtensionRegistry<T>,
+{
+    const IDENTIFIER: &'static str = "DynamicExtra";
+    type AccountId = T::AccountId;
+    type Call = ();
+    type AdditionalSigned = EncodedAdditionalSigned;
+    type Pre = ();
+
+    fn additional_signed(
+        &self,
+    ) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+        let additional_signed = self
+            .extensions
+            .iter()
+            .flat_map(|ext| ext.additional_signed.iter().copied())
+            .collect();
+        Ok(EncodedAdditionalSigned(additional_signed))
+    }
+
+    fn pre_dispatch(
+        self,
+        _who: &Self::AccountId,
+        _call: &Self::Call,
+        _info: &DispatchInfoOf<Self::Call>,
+        _len: usize,
+    ) -> Result<Self::Pre, TransactionValidityError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        extrinsic::CheckSpecVersion,
+        DefaultConfig,
+        DefaultExtra,
+    };
+    use frame_metadata::{
+        v14::{
+            ExtrinsicMetadata,
+            RuntimeMetadataLastVersion,
+            SignedExtensionMetadata,
+        },
+        RuntimeMetadataPrefixed,
+    };
+    use scale_info::meta_type;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn default_dynamic_extra_matches_default_extra() {
+        let params = DefaultExtraParams::<DefaultConfig>::new()
+            .tip(1234)
+            .era(Era::mortal(32, 100), [1; 32].into());
+
+        let default_extra =
+            DefaultExtra::<DefaultConfig>::new(1, 2, 3, [4; 32].into(), params.clone());
+        let dynamic_extra = DynamicExtra::<DefaultConfig>::new(
+            1,
+            2,
+            3,
+            [4; 32].into(),
+            DynamicExtraParams::default().params(params),
+        );
+
+        assert_eq!(
+            default_extra.extra().encode(),
+            dynamic_extra.extra().encode()
+        );
+        assert_eq!(
+            default_extra.extra().additional_signed().unwrap().encode(),
+            dynamic_extra.extra().additional_signed().unwrap().encode()
+        );
+    }
+
+    #[test]
+    fn custom_extension_data_is_used() {
+        let params = DynamicExtraParams::<DefaultConfig> {
+            identifiers: vec!["CheckSpecVersion".into(), "CheckCustom".into()],
+            params: DefaultExtraParams::new()
+                .extension("CheckCustom", EncodedExtension::new(7u8, 8u16)),
+        };
+        let extra = DynamicExtra::<DefaultConfig>::new(1, 2, 3, [4; 32].into(), params);
+
+        assert_eq!(extra.extra().encode(), vec![7]);
+        assert_eq!(
+            extra.extra().additional_signed().unwrap().encode(),
+            vec![1, 0, 0, 0, 8, 0]
+        );
+    }
+
+    #[test]
+    fn metadata_hash_extension_is_encoded() {
+        let identifiers = vec!["CheckMetadataHash".to_string()];
+        let disabled = DynamicExtra::<DefaultConfig>::new(
+            1,
+            2,
+            3,
+            [4; 32].into(),
+            DynamicExtraParams {
+                identifiers: identifiers.clone(),
+                params: DefaultExtraParams::new(),
+            },
+        );
+        assert_eq!(disabled.extra().encode(), vec![0]);
+        assert_eq!(
+            disabled.extra().additional_signed().unwrap().encode(),
+            vec![0]
+        );
+
+        let enabled = DynamicExtra::<DefaultConfig>::new(
+            1,
+            2,
+            3,
+            [4; 32].into(),
+            DynamicExtraParams {
+                identifiers,
+                params: DefaultExtraParams::new().metadata_hash([5; 32]),
+            },
+        );
+        let mut expected = vec![1];
+        expected.extend_from_slice(&[5; 32]);
+        assert_eq!(enabled.extra().encode(), vec![1]);
+        assert_eq!(
+            enabled.extra().additional_signed().unwrap().encode(),
+            expected
+        );
+    }
+
+    #[test]
+    fn unknown_extensions_are_rejected_unless_provided() {
+        let identifiers = vec!["CheckSpecVersion".to_string(), "CheckCustom".into()];
+        let unknown = DynamicExtraParams::<DefaultConfig> {
+            identifiers: identifiers.clone(),
+            params: DefaultExtraParams::new(),
+        };
+        assert!(matches!(
+            DynamicExtra::<DefaultConfig>::check_parameters(&unknown),
+            Err(BasicError::Metadata(MetadataError::UnsupportedSignedExtension(id)))
+                if id == "CheckCustom"
+        ));
+
+        let provided = DynamicExtraParams::<DefaultConfig> {
+            identifiers,
+            params: DefaultExtraParams::new()
+                .extension("CheckCustom", EncodedExtension::new(7u8, ())),
+        };
+        assert!(DynamicExtra::<DefaultConfig>::check_parameters(&provided).is_ok());
+        assert!(
+            DynamicExtra::<DefaultConfig>::check_parameters(&Default::default()).is_ok()
+        );
+    }
+
+    #[test]
+    fn decode_with_metadata() {
+        type T = DefaultConfig;
+        let extension = |identifier, ty| {
+            SignedExtensionMetadata {
+                identifier,
+                ty,
+                additional_signed: meta_type::<()>(),
+            }
+        };
+        let extrinsic = ExtrinsicMetadata {
+            ty: meta_type::<()>(),
+            version: 4,
+            signed_extensions: vec![
+                extension("CheckSpecVersion", meta_type::<CheckSpecVersion<T>>()),
+                extension("CheckNonce", meta_type::<CheckNonce<T>>()),
+                extension(
+                    "ChargeTransactionPayment",
+                    meta_type::<ChargeTransactionPayment<T>>(),
+                ),
+            ],
+        };
+        let meta = RuntimeMetadataLastVersion::new(vec![], extrinsic, meta_type::<()>());
+        let runtime_metadata: RuntimeMetadataPrefixed = meta.into();
+        let metadata = Metadata::try_from(runtime_metadata).unwrap();
+
+        let params = DynamicExtraParams::<T> {
+            identifiers: vec![
+                "CheckSpecVersion".into(),
+                "CheckNonce".into(),
+                "ChargeTransactionPayment".into(),
+            ],
+            params: DefaultExtraParams::new().tip(1000),
+        };
+        let extra = DynamicExtra::<T>::new(1, 2, 300, [4; 32].into(), params);
+        let mut encoded = extra.encode();
+        encoded.push(9);
+
+        let input = &mut &encoded[..];
+        let decoded = DynamicExtra::<T>::decode_with_metadata(&metadata, input).unwrap();
+        assert_eq!(decoded.encode(), extra.encode());
+        assert_eq!(decoded.extensions.len(), 3);
+        assert!(decoded.extensions[0].extra.is_empty());
+        // Only the extensions are consumed.
+        assert_eq!(*input, &[9][..]);
+        assert!(DynamicExtra::<T>::decode(&mut &extra.encode()[..]).is_err());
+    }
+}
@@ -14,7 +14,11 @@
 // You should have received a copy of the GNU General Public License
 // along with subxt.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::PhantomDataSendSync;
+use super::EncodedExtension;
+use crate::{
+    error::BasicError,
+    PhantomDataSendSync,
+};
 use codec::{
     Decode,
     Encode,
@@ -29,6 +33,7 @@ use sp_runtime::{
     },
     transaction_validity::TransactionValidityError,
 };
+use std::collections::BTreeMap;
 
 use crate::Config;
 
@@ -173,9 +178,9 @@ impl<T: Config> SignedExtension for CheckGenesis<T> {
 ///
 /// # Note
 ///
-/// This is modified from the substrate version to allow passing in of the genesis hash, which is
-/// returned via `additional_signed()`. It assumes therefore `Era::Immortal` (The transaction is
-/// valid forever)
+/// This is modified from the substrate version to allow passing in of the hash of the block
+/// that the [`Era`] is relative to (or the genesis hash for `Era::Immortal` transactions),
+/// which is returned via `additional_signed()`.
 #[derive(Derivative, Encode, Decode, TypeInfo)]
 #[derivative(
     Clone(bound = ""),
@@ -296,6 +301,19 @@ pub struct ChargeTransactionPayment<T: Config>(
     pub PhantomDataSendSync<T>,
 );
 
+impl<T: Config> ChargeTransactionPayment<T> {
+    /// Create a new [`ChargeTransactionPayment`] which gives the provided tip
+    /// to the block author.
+    pub fn new(tip: u128) -> Self {
+        ChargeTransactionPayment(tip, PhantomDataSendSync::new())
+    }
+
+    /// The tip that will be given to the block author.
+    pub fn tip(&self) -> u128 {
+        self.0
+    }
+}
+
 impl<T: Config> SignedExtension for ChargeTransactionPayment<T> {
     const IDENTIFIER: &'static str = "ChargeTransactionPayment";
     type AccountId = T::AccountId;
@@ -333,12 +351,26 @@ pub struct ChargeAssetTxPayment<T: Config> {
     /// The tip for the block author.
     #[codec(compact)]
     pub tip: u128,
-    /// The asset with which to pay the tip.
+    /// The asset with which to pay the fee and tip, or `None` to pay with the native
+    /// token.
     pub asset_id: Option<u32>,
     /// Marker for unused type parameter.
     pub marker: PhantomDataSendSync<T>,
 }
 
+impl<T: Config> ChargeAssetTxPayment<T> {
+    /// Create a new [`ChargeAssetTxPayment`] which gives the provided tip to the block
+    /// author, and pays the fee and tip using the given asset (or the native token if
+    /// `None`).
+    pub fn new(tip: u128, asset_id: Option<u32>) -> Self {
+        ChargeAssetTxPayment {
+            tip,
+            asset_id,
+            marker: PhantomDataSendSync::new(),
+        }
+    }
+}
+
 impl<T: Config> SignedExtension for ChargeAssetTxPayment<T> {
     const IDENTIFIER: &'static str = "ChargeAssetTxPayment";
     type AccountId = T::AccountId;
@@ -361,12 +393,113 @@ impl<T: Config> SignedExtension for ChargeAssetTxPayment<T> {
     }
 }
 
+/// Whether the [`CheckMetadataHash`] extension is enabled.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub enum CheckMetadataHashMode {
+    /// The metadata hash isn't checked.
+    Disabled,
+    /// The metadata hash is included in the signed payload, and checked by the runtime.
+    Enabled,
+}
+
+/// Commit to the hash of the runtime metadata (as specified by
+/// [RFC-0078](https://polkadot-fellows.github.io/RFCs/approved/0078-merkleized-metadata.html)).
+/// This allows offline signers such as hardware wallets, which decode the transaction using
+/// a proof of the metadata rather than the full metadata, to be sure that the metadata
+/// they used is that of the runtime.
+///
+/// The extrinsic only contains the mode; when enabled, the hash is part of the additional
+/// signed data. The hash is provided via [`DefaultExtraParams::metadata_hash`], and can be
+/// computed from the client's metadata with [`crate::Client::metadata_hash()`] (or with
+/// [`super::metadata_hash()`] for other metadata).
+///
+/// Only [`super::DynamicExtra`] includes this extension (for chains whose metadata lists
+/// it); [`DefaultExtraWithTxPayment`] doesn't, and refuses to sign if a metadata hash is
+/// given.
+#[derive(Derivative, Encode, Decode, TypeInfo)]
+#[derivative(
+    Clone(bound = ""),
+    PartialEq(bound = ""),
+    Debug(bound = ""),
+    Eq(bound = "")
+)]
+#[scale_info(skip_type_params(T))]
+pub struct CheckMetadataHash<T: Config> {
+    /// Whether the metadata hash is checked.
+    pub mode: CheckMetadataHashMode,
+    /// The metadata hash, to be used for `AdditionalSigned`.
+    #[codec(skip)]
+    pub metadata_hash: Option<[u8; 32]>,
+    /// Marker for unused type parameter.
+    #[codec(skip)]
+    pub marker: PhantomDataSendSync<T>,
+}
+
+impl<T: Config> CheckMetadataHash<T> {
+    /// Create a new [`CheckMetadataHash`], which is enabled if a metadata hash is given.
+    pub fn new(metadata_hash: Option<[u8; 32]>) -> Self {
+        let mode = if metadata_hash.is_some() {
+            CheckMetadataHashMode::Enabled
+        } else {
+            CheckMetadataHashMode::Disabled
+        };
+        CheckMetadataHash {
+            mode,
+            metadata_hash,
+            marker: PhantomDataSendSync::new(),
+        }
+    }
+}
+
+impl<T: Config> SignedExtension for CheckMetadataHash<T> {
+    const IDENTIFIER: &'static str = "CheckMetadataHash";
+    type AccountId = T::AccountId;
+    type Call = ();
+    type AdditionalSigned = Option<[u8; 32]>;
+    type Pre = ();
+    fn additional_signed(
+        &self,
+    ) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+        Ok(self.metadata_hash)
+    }
+    fn pre_dispatch(
+        self,
+        _who: &Self::AccountId,
+        _call: &Self::Call,
+        _info: &DispatchInfoOf<Self::Call>,
+        _len: usize,
+    ) -> Result<Self::Pre, TransactionValidityError> {
+        Ok(())
+    }
+}
+
+/// A signed extension which charges a fee for the transaction, and which can be configured
+/// with a tip to give to the block author in order to prioritise the transaction.
+pub trait TxPaymentExtension<T: Config>:
+    SignedExtension<AccountId = T::AccountId, Call = ()>
+{
+    /// Construct the signed extension from the parameters provided at signing time.
+    fn from_params(params: &DefaultExtraParams<T>) -> Self;
+}
+
+impl<T: Config> TxPaymentExtension<T> for ChargeTransactionPayment<T> {
+    fn from_params(params: &DefaultExtraParams<T>) -> Self {
+        ChargeTransactionPayment::new(params.tip)
+    }
+}
+
+impl<T: Config> TxPaymentExtension<T> for ChargeAssetTxPayment<T> {
+    fn from_params(params: &DefaultExtraParams<T>) -> Self {
+        ChargeAssetTxPayment::new(params.tip, params.asset_id)
+    }
+}
+
 /// Trait for implementing transaction extras for a runtime.
 pub trait SignedExtra<T: Config>: SignedExtension {
     /// The type the extras.
     type Extra: SignedExtension + Send + Sync;
     /// The additional config parameters.
-    type Parameters: Default + Send + Sync;
+    type Parameters: Default + Clone + Send + Sync;
 
     /// Creates a new `SignedExtra`.
     fn new(
@@ -379,29 +512,158 @@ pub trait SignedExtra<T: Config>: SignedExtension {
 
     /// Returns the transaction extra.
     fn extra(&self) -> Self::Extra;
+
+    /// Checks that the parameters can be honoured before signing, returning an error if
+    /// not (for instance, if they enable an extension which these extras don't include).
+    ///
+    /// By default, any parameters are accepted.
+    fn check_parameters(_params: &Self::Parameters) -> Result<(), BasicError> {
+        Ok(())
+    }
+
+    /// Creates the parameters used for submissions which don't provide their own, from the
+    /// tip and era configured on the client with [`crate::ClientBuilder::set_default_tip`]
+    /// and [`crate::ClientBuilder::set_default_mortality`]. For mortal eras, the checkpoint
+    /// is the hash of the block that the era was constructed from.
+    ///
+    /// By default, the client defaults are ignored and `Default::default()` is returned.
+    fn default_parameters(
+        _tip: u128,
+        _era: Era,
+        _mortality_checkpoint: Option<T::Hash>,
+    ) -> Self::Parameters {
+        Default::default()
+    }
 }
 
-/// Default `SignedExtra` for substrate runtimes.
-#[derive(Derivative, Encode, Decode, TypeInfo)]
+/// Parameters which can be provided when signing an extrinsic using [`DefaultExtraWithTxPayment`]
+/// or [`super::DynamicExtra`].
+///
+/// # Example
+///
+/// ```rust
+/// use subxt::{
+///     sp_runtime::generic::Era,
+///     DefaultConfig,
+///     DefaultExtraParams,
+/// };
+///
+/// # let block_number = 1000;
+/// # let block_hash = Default::default();
+/// // Give a tip of 1000 to the block author to prioritise the transaction, and make
+/// // the transaction valid for 64 blocks from the block given:
+/// let params = DefaultExtraParams::<DefaultConfig>::new()
+///     .tip(1000)
+///     .era(Era::mortal(64, block_number), block_hash);
+/// ```
+#[derive(Derivative)]
 #[derivative(
     Clone(bound = ""),
-    PartialEq(bound = ""),
     Debug(bound = ""),
+    Default(bound = ""),
+    PartialEq(bound = ""),
     Eq(bound = "")
 )]
+pub struct DefaultExtraParams<T: Config> {
+    pub(super) tip: u128,
+    #[derivative(Default(value = "Era::Immortal"))]
+    pub(super) era: Era,
+    pub(super) mortality_checkpoint: Option<T::Hash>,
+    pub(super) asset_id: Option<u32>,
+    pub(super) metadata_hash: Option<[u8; 32]>,
+    pub(super) extensions: BTreeMap<String, EncodedExtension>,
+}
+
+impl<T: Config> DefaultExtraParams<T> {
+    /// Create a new set of parameters, which gives no tip and results in an immortal
+    /// transaction.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set a tip to give to the block author in addition to the transaction fee.
+    /// A higher tip increases the priority of the transaction in the pool.
+    pub fn tip(mut self, tip: u128) -> Self {
+        self.tip = tip;
+        self
+    }
+
+    /// Set the [`Era`] of the transaction. For mortal transactions, `checkpoint` must be
+    /// the hash of the block that the era was constructed from; the transaction will be
+    /// valid only for the period of blocks following it.
+    pub fn era(mut self, era: Era, checkpoint: T::Hash) -> Self {
+        self.era = era;
+        self.mortality_checkpoint = Some(checkpoint);
+        self
+    }
+
+    pub(super) fn from_defaults(
+        tip: u128,
+        era: Era,
+        mortality_checkpoint: Option<T::Hash>,
+    ) -> Self {
+        Self {
+            tip,
+            era,
+            mortality_checkpoint,
+            ..Default::default()
+        }
+    }
+
+    /// Set the asset with which to pay the transaction fee and tip, on chains which use
+    /// [`ChargeAssetTxPayment`]. This is ignored when using [`ChargeTransactionPayment`].
+    pub fn asset_id(mut self, asset_id: u32) -> Self {
+        self.asset_id = Some(asset_id);
+        self
+    }
+
+    /// Enable the [`CheckMetadataHash`] extension, committing to the given metadata hash,
+    /// on chains which use it. This is required by some hardware wallets.
+    ///
+    /// Only [`super::DynamicExtra`] honours this. [`DefaultExtraWithTxPayment`] doesn't
+    /// include the extension, so signing with it fails if a metadata hash is given.
+    pub fn metadata_hash(mut self, metadata_hash: [u8; 32]) -> Self {
+        self.metadata_hash = Some(metadata_hash);
+        self
+    }
+
+    /// Provide the encoded data for the signed extension with the given identifier. This is
+    /// used by [`super::DynamicExtra`] in preference to its [`super::ExtensionRegistry`],
+    /// which allows signing extrinsics for chains with custom signed extensions, and is
+    /// ignored by [`DefaultExtraWithTxPayment`].
+    pub fn extension(
+        mut self,
+        identifier: impl Into<String>,
+        extension: EncodedExtension,
+    ) -> Self {
+        self.extensions.insert(identifier.into(), extension);
+        self
+    }
+}
+
+/// Default `SignedExtra` for substrate runtimes.
+#[derive(Derivative, Encode, Decode, TypeInfo)]
+#[derivative(
+    Clone(bound = "X: Clone"),
+    PartialEq(bound = "X: PartialEq"),
+    Debug(bound = "X: core::fmt::Debug"),
+    Eq(bound = "X: Eq")
+)]
 #[scale_info(skip_type_params(T))]
 pub struct DefaultExtraWithTxPayment<T: Config, X> {
     spec_version: u32,
     tx_version: u32,
     nonce: T::Index,
     genesis_hash: T::Hash,
-    marker: PhantomDataSendSync<X>,
+    era: Era,
+    mortality_checkpoint: T::Hash,
+    tx_payment: X,
 }
 
 impl<T, X> SignedExtra<T> for DefaultExtraWithTxPayment<T, X>
 where
     T: Config,
-    X: SignedExtension<AccountId = T::AccountId, Call = ()> + Default,
+    X: TxPaymentExtension<T>,
 {
     type Extra = (
         CheckSpecVersion<T>,
@@ -412,21 +674,24 @@ where
         CheckWeight<T>,
         X,
     );
-    type Parameters = ();
+    type Parameters = DefaultExtraParams<T>;
 
     fn new(
         spec_version: u32,
         tx_version: u32,
         nonce: T::Index,
         genesis_hash: T::Hash,
-        _params: Self::Parameters,
+        params: Self::Parameters,
     ) -> Self {
         DefaultExtraWithTxPayment {
             spec_version,
             tx_version,
             nonce,
             genesis_hash,
-            marker: PhantomDataSendSync::new(),
+            era: params.era,
+            // Immortal transactions are checked against the genesis hash:
+            mortality_checkpoint: params.mortality_checkpoint.unwrap_or(genesis_hash),
+            tx_payment: X::from_params(&params),
         }
     }
 
@@ -436,21 +701,39 @@ where
             CheckTxVersion(PhantomDataSendSync::new(), self.tx_version),
             CheckGenesis(PhantomDataSendSync::new(), self.genesis_hash),
             CheckMortality(
-                (Era::Immortal, PhantomDataSendSync::new()),
-                self.genesis_hash,
+                (self.era, PhantomDataSendSync::new()),
+                self.mortality_checkpoint,
             ),
             CheckNonce(self.nonce),
             CheckWeight(PhantomDataSendSync::new()),
-            X::default(),
+            self.tx_payment.clone(),
         )
     }
+
+    fn check_parameters(params: &Self::Parameters) -> Result<(), BasicError> {
+        if params.metadata_hash.is_some() {
+            return Err(BasicError::Other(
+                "DefaultExtraWithTxPayment doesn't include the CheckMetadataHash extension, \
+                 so can't commit to a metadata hash; use DynamicExtra instead"
+                    .into(),
+            ))
+        }
+        Ok(())
+    }
+
+    fn default_parameters(
+        tip: u128,
+        era: Era,
+        mortality_checkpoint: Option<T::Hash>,
+    ) -> Self::Parameters {
+        DefaultExtraParams::from_defaults(tip, era, mortality_checkpoint)
+    }
 }
 
-impl<T, X: SignedExtension<AccountId = T::AccountId, Call = ()> + Default> SignedExtension
-    for DefaultExtraWithTxPayment<T, X>
+impl<T, X> SignedExtension for DefaultExtraWithTxPayment<T, X>
 where
     T: Config,
-    X: SignedExtension,
+    X: TxPaymentExtension<T>,
 {
     const IDENTIFIER: &'static str = "DefaultExtra";
     type AccountId = T::AccountId;
@@ -479,3 +762,41 @@ where
 ///
 /// Note that this must match the `SignedExtra` type in the target runtime's extrinsic definition.
 pub type DefaultExtra<T> = DefaultExtraWithTxPayment<T, ChargeTransactionPayment<T>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DefaultConfig;
+    use codec::Compact;
+
+    type T = DefaultConfig;
+
+    fn encoded_extra<X: TxPaymentExtension<T>>(params: DefaultExtraParams<T>) -> Vec<u8> {
+        DefaultExtraWithTxPayment::<T, X>::new(1, 2, 3, Default::default(), params)
+            .extra()
+            .encode()
+    }
+
+    #[test]
+    fn asset_id_is_encoded_after_the_tip() {
+        let params = DefaultExtraParams::new().tip(5).asset_id(7);
+
+        // Only the era, nonce and payment extensions contribute to the encoded extra.
+        let mut expected = (Era::Immortal, Compact(3u32), Compact(5u128)).encode();
+        assert_eq!(
+            encoded_extra::<ChargeTransactionPayment<T>>(params.clone()),
+            expected
+        );
+        expected.extend(Some(7u32).encode());
+        assert_eq!(encoded_extra::<ChargeAssetTxPayment<T>>(params), expected);
+    }
+
+    #[test]
+    fn native_token_is_encoded_without_an_asset_id() {
+        let params = DefaultExtraParams::new().tip(5);
+
+        let expected =
+            (Era::Immortal, Compact(3u32), Compact(5u128), None::<u32>).encode();
+        assert_eq!(encoded_extra::<ChargeAssetTxPayment<T>>(params), expected);
+    }
+}
@@ -0,0 +1,413 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A [`Signer`] backed by a Ledger hardware wallet running one of the substrate Ledger
+//! apps (e.g. the Polkadot or Kusama app), talking to the device over USB HID.
+//!
+//! The device parses and displays the payload itself before signing it, so:
+//!
+//! - The payload is sent to the device unhashed, even if it is longer than 256 bytes.
+//! - The app on the device must support the call being signed and the runtime's spec
+//!   and transaction versions, otherwise it refuses to sign.
+//! - Some apps only accept mortal transactions, so an explicit era should be provided
+//!   in the extrinsic parameters.
+
+use super::{
+    SignedExtra,
+    SignedPayload,
+    Signer,
+    UncheckedExtrinsic,
+};
+use crate::Config;
+use codec::{
+    Decode,
+    Encode,
+};
+use ledger_apdu::{
+    APDUAnswer,
+    APDUCommand,
+};
+use ledger_transport_hid::{
+    hidapi::HidApi,
+    LedgerHIDError,
+    TransportNativeHID,
+};
+use sp_runtime::traits::SignedExtension;
+use std::sync::Arc;
+
+const INS_GET_ADDRESS: u8 = 0x01;
+const INS_SIGN: u8 = 0x02;
+
+const SIGN_INIT: u8 = 0x00;
+const SIGN_ADD: u8 = 0x01;
+const SIGN_LAST: u8 = 0x02;
+
+const HARDENED: u32 = 0x8000_0000;
+const CHUNK_SIZE: usize = 250;
+const RETCODE_OK: u16 = 0x9000;
+
+/// Error talking to a Ledger device.
+#[derive(Debug, thiserror::Error)]
+pub enum LedgerError {
+    /// Error communicating with the device.
+    #[error("Ledger transport error: {0}")]
+    Transport(#[from] LedgerHIDError),
+    /// The device returned an error code, e.g. because the user rejected the request or
+    /// the app is not open.
+    #[error("Ledger device returned error code {0:#06x}")]
+    Device(u16),
+    /// The device returned a response which could not be interpreted.
+    #[error("Invalid response from the Ledger device: {0}")]
+    InvalidResponse(String),
+}
+
+/// A substrate Ledger app, identified by its APDU class and SLIP-0044 coin type.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LedgerApp {
+    /// The APDU class of the app.
+    pub cla: u8,
+    /// The SLIP-0044 coin type used in the BIP44 derivation path.
+    pub coin_type: u32,
+}
+
+impl LedgerApp {
+    /// The Polkadot app.
+    pub const POLKADOT: LedgerApp = LedgerApp {
+        cla: 0x90,
+        coin_type: 354,
+    };
+    /// The Kusama app.
+    pub const KUSAMA: LedgerApp = LedgerApp {
+        cla: 0x99,
+        coin_type: 434,
+    };
+}
+
+/// The signature scheme the device signs with.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LedgerScheme {
+    /// Ed25519, supported by all substrate Ledger apps.
+    Ed25519 = 0,
+    /// Sr25519, only supported by some app versions.
+    Sr25519 = 1,
+}
+
+/// Extrinsic signer using a key held on a Ledger device.
+///
+/// Signing requires the user to review and approve the transaction on the device, which
+/// happens on a separate thread so as not to block the executor.
+pub struct LedgerSigner<T: Config, E> {
+    account_id: T::AccountId,
+    nonce: Option<T::Index>,
+    transport: Arc<TransportNativeHID>,
+    app: LedgerApp,
+    scheme: LedgerScheme,
+    path: [u32; 5],
+    marker: std::marker::PhantomData<E>,
+}
+
+impl<T, E> LedgerSigner<T, E>
+where
+    T: Config,
+    E: SignedExtra<T>,
+    T::AccountId: From<[u8; 32]>,
+{
+    /// Connects to the first Ledger device found, and fetches the account with the given
+    /// BIP44 account, change and address indices (`m/44'/coin_type'/account'/change'/index'`)
+    /// from the given app.
+    pub fn new(
+        app: LedgerApp,
+        scheme: LedgerScheme,
+        account: u32,
+        change: u32,
+        index: u32,
+    ) -> Result<Self, LedgerError> {
+        let api = HidApi::new().map_err(LedgerHIDError::from)?;
+        let transport = TransportNativeHID::new(&api)?;
+        let path = [44, app.coin_type, account, change, index].map(|i| i | HARDENED);
+
+        let answer = exchange(
+            &transport,
+            &APDUCommand {
+                cla: app.cla,
+                ins: INS_GET_ADDRESS,
+                p1: 0,
+                p2: scheme as u8,
+                data: encode_path(&path),
+            },
+        )?;
+        let public: [u8; 32] = answer
+            .get(..32)
+            .and_then(|public| public.try_into().ok())
+            .ok_or_else(|| {
+                LedgerError::InvalidResponse("public key is too short".into())
+            })?;
+
+        Ok(Self {
+            account_id: public.into(),
+            nonce: None,
+            transport: Arc::new(transport),
+            app,
+            scheme,
+            path,
+            marker: Default::default(),
+        })
+    }
+
+    /// Sets the nonce to a new value.
+    pub fn set_nonce(&mut self, nonce: T::Index) {
+        self.nonce = Some(nonce);
+    }
+
+    /// Increment the nonce.
+    pub fn increment_nonce(&mut self) {
+        self.nonce = self.nonce.map(|nonce| nonce + 1u32.into());
+    }
+}
+
+#[async_trait::async_trait]
+impl<T, E> Signer<T, E> for LedgerSigner<T, E>
+where
+    T: Config,
+    E: SignedExtra<T>,
+    T::AccountId: Into<T::Address> + 'static,
+    T::Signature: Decode,
+    <<E as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned:
+        Send + Sync + 'static,
+{
+    fn account_id(&self) -> &T::AccountId {
+        &self.account_id
+    }
+
+    fn nonce(&self) -> Option<T::Index> {
+        self.nonce
+    }
+
+    async fn sign(
+        &self,
+        extrinsic: SignedPayload<T, E>,
+    ) -> Result<UncheckedExtrinsic<T, E>, String> {
+        // The device expects the raw payload, so don't use `SignedPayload::encode`, which
+        // hashes long payloads.
+        let (call, extra, additional_signed) = extrinsic.deconstruct();
+        let payload = (&call, &extra, &additional_signed).encode();
+        let extrinsic = SignedPayload::<T, E>::from_raw(call, extra, additional_signed);
+
+        let commands = sign_commands(self.app, self.scheme, &self.path, &payload);
+        let transport = self.transport.clone();
+        let (tx, rx) = futures::channel::oneshot::channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(sign_payload(&*transport, &commands));
+        });
+        let response = rx
+            .await
+            .map_err(|_| "Ledger signing thread stopped unexpectedly".to_string())?
+            .map_err(|e| e.to_string())?;
+
+        // The response is the signature prefixed with the signature scheme, in the same
+        // format as a SCALE encoded `MultiSignature`.
+        let signature = T::Signature::decode(&mut &response[..]).map_err(|e| {
+            format!(
+                "Failed to decode the signature returned by the Ledger: {}",
+                e
+            )
+        })?;
+
+        Ok(super::attach_signature::<T, E>(
+            extrinsic,
+            self.account_id.clone().into(),
+            signature,
+        ))
+    }
+}
+
+// Sends APDU commands to a device and returns its answers, so that the exchange with the
+// device can be tested without one.
+trait Transport {
+    fn exchange(
+        &self,
+        command: &APDUCommand<Vec<u8>>,
+    ) -> Result<APDUAnswer<Vec<u8>>, LedgerHIDError>;
+}
+
+impl Transport for TransportNativeHID {
+    fn exchange(
+        &self,
+        command: &APDUCommand<Vec<u8>>,
+    ) -> Result<APDUAnswer<Vec<u8>>, LedgerHIDError> {
+        TransportNativeHID::exchange(self, command)
+    }
+}
+
+fn exchange(
+    transport: &impl Transport,
+    command: &APDUCommand<Vec<u8>>,
+) -> Result<Vec<u8>, LedgerError> {
+    let answer = transport.exchange(command)?;
+    if answer.retcode() != RETCODE_OK {
+        return Err(LedgerError::Device(answer.retcode()))
+    }
+    Ok(answer.data().to_vec())
+}
+
+// Sends the commands from [`sign_commands`] one after the other, stopping at the first
+// error, and returns the answer to the last one, which is the signature.
+fn sign_payload(
+    transport: &impl Transport,
+    commands: &[APDUCommand<Vec<u8>>],
+) -> Result<Vec<u8>, LedgerError> {
+    let mut response = Vec::new();
+    for command in commands {
+        response = exchange(transport, command)?;
+    }
+    Ok(response)
+}
+
+fn encode_path(path: &[u32; 5]) -> Vec<u8> {
+    path.iter().flat_map(|i| i.to_le_bytes()).collect()
+}
+
+/// Splits signing the payload into APDU commands: the first carries the derivation path,
+/// and the payload follows in chunks of at most [`CHUNK_SIZE`] bytes.
+fn sign_commands(
+    app: LedgerApp,
+    scheme: LedgerScheme,
+    path: &[u32; 5],
+    payload: &[u8],
+) -> Vec<APDUCommand<Vec<u8>>> {
+    let command = |p1, data| {
+        APDUCommand {
+            cla: app.cla,
+            ins: INS_SIGN,
+            p1,
+            p2: scheme as u8,
+            data,
+        }
+    };
+    let chunks = payload.chunks(CHUNK_SIZE).collect::<Vec<_>>();
+    let mut commands = vec![command(SIGN_INIT, encode_path(path))];
+    for (i, chunk) in chunks.iter().enumerate() {
+        let p1 = if i + 1 == chunks.len() {
+            SIGN_LAST
+        } else {
+            SIGN_ADD
+        };
+        commands.push(command(p1, chunk.to_vec()));
+    }
+    commands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        cell::RefCell,
+        collections::VecDeque,
+    };
+
+    // Records the commands it receives and answers them in turn.
+    struct MockTransport {
+        received: RefCell<Vec<(u8, Vec<u8>)>>,
+        answers: RefCell<VecDeque<Vec<u8>>>,
+    }
+
+    impl MockTransport {
+        fn new(answers: Vec<Vec<u8>>) -> Self {
+            Self {
+                received: Default::default(),
+                answers: RefCell::new(answers.into()),
+            }
+        }
+    }
+
+    impl Transport for MockTransport {
+        fn exchange(
+            &self,
+            command: &APDUCommand<Vec<u8>>,
+        ) -> Result<APDUAnswer<Vec<u8>>, LedgerHIDError> {
+            self.received
+                .borrow_mut()
+                .push((command.p1, command.data.clone()));
+            let answer = self
+                .answers
+                .borrow_mut()
+                .pop_front()
+                .expect("unexpected command");
+            Ok(APDUAnswer::from_answer(answer).unwrap())
+        }
+    }
+
+    fn ok(data: &[u8]) -> Vec<u8> {
+        [data, &RETCODE_OK.to_be_bytes()].concat()
+    }
+
+    #[test]
+    fn payload_is_sent_in_chunks_after_the_path() {
+        let path = [44, 354, 0, 0, 0].map(|i| i | HARDENED);
+        let payload = vec![7u8; CHUNK_SIZE * 2 + 10];
+        let commands =
+            sign_commands(LedgerApp::POLKADOT, LedgerScheme::Ed25519, &path, &payload);
+
+        let p1s = commands.iter().map(|c| c.p1).collect::<Vec<_>>();
+        assert_eq!(p1s, vec![SIGN_INIT, SIGN_ADD, SIGN_ADD, SIGN_LAST]);
+        assert_eq!(commands[0].data.len(), 20);
+        assert_eq!(&commands[0].data[..4], &(44 | HARDENED).to_le_bytes());
+        assert_eq!(commands[3].data.len(), 10);
+        assert!(commands.iter().all(|c| c.cla == 0x90 && c.ins == INS_SIGN));
+    }
+
+    #[test]
+    fn payload_pages_are_sent_in_order_and_the_signature_is_returned() {
+        let path = [44, 354, 0, 0, 0].map(|i| i | HARDENED);
+        let payload = (0..CHUNK_SIZE * 2 + 10)
+            .map(|i| i as u8)
+            .collect::<Vec<_>>();
+        let commands =
+            sign_commands(LedgerApp::POLKADOT, LedgerScheme::Ed25519, &path, &payload);
+        let signature = [0u8; 65];
+        let transport =
+            MockTransport::new(vec![ok(&[]), ok(&[]), ok(&[]), ok(&signature)]);
+
+        let response = sign_payload(&transport, &commands).unwrap();
+
+        assert_eq!(response, signature);
+        let received = transport.received.into_inner();
+        let p1s = received.iter().map(|(p1, _)| *p1).collect::<Vec<_>>();
+        assert_eq!(p1s, vec![SIGN_INIT, SIGN_ADD, SIGN_ADD, SIGN_LAST]);
+        assert_eq!(received[0].1, encode_path(&path));
+        let sent = received[1..]
+            .iter()
+            .flat_map(|(_, data)| data.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(sent, payload);
+    }
+
+    #[test]
+    fn device_errors_stop_the_exchange() {
+        let path = [44, 354, 0, 0, 0].map(|i| i | HARDENED);
+        let payload = vec![7u8; CHUNK_SIZE * 2];
+        let commands =
+            sign_commands(LedgerApp::POLKADOT, LedgerScheme::Ed25519, &path, &payload);
+        // The user rejected the transaction after reviewing the first page.
+        let transport =
+            MockTransport::new(vec![ok(&[]), 0x6986u16.to_be_bytes().to_vec()]);
+
+        let result = sign_payload(&transport, &commands);
+
+        assert!(matches!(result, Err(LedgerError::Device(0x6986))));
+        assert_eq!(transport.received.borrow().len(), 2);
+    }
+}
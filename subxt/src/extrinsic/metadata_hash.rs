@@ -0,0 +1,817 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Compute the metadata hash which the [`super::CheckMetadataHash`] extension commits to, as
+//! specified by [RFC-0078](https://polkadot-fellows.github.io/RFCs/approved/0078-merkleized-metadata.html).
+//!
+//! The types which can be reached from an extrinsic (its call, address, signature and signed
+//! extensions) are put into a minimal form, each variant of an enum becoming a type of its
+//! own, and the hash of the root of the merkle tree of those types is combined with the hash
+//! of the extrinsic's own metadata and some details of the chain.
+
+use crate::{
+    metadata::MetadataError,
+    rpc::{
+        RuntimeVersion,
+        SystemProperties,
+    },
+    Metadata,
+};
+use codec::{
+    Compact,
+    Decode,
+    Encode,
+};
+use frame_metadata::RuntimeMetadataLastVersion;
+use scale_info::{
+    form::PortableForm,
+    Field,
+    PortableRegistry,
+    Type,
+    TypeDef,
+    TypeDefPrimitive,
+    Variant,
+};
+use std::collections::{
+    BTreeMap,
+    BTreeSet,
+    VecDeque,
+};
+
+type Hash = [u8; 32];
+
+/// The details of a chain, other than its metadata, which its metadata hash commits to.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MetadataHashInfo {
+    /// The spec version of the runtime.
+    pub spec_version: u32,
+    /// The spec name of the runtime.
+    pub spec_name: String,
+    /// The SS58 prefix of the chain's addresses.
+    pub base58_prefix: u16,
+    /// The number of decimals of the chain's native token.
+    pub decimals: u8,
+    /// The symbol of the chain's native token.
+    pub token_symbol: String,
+}
+
+impl MetadataHashInfo {
+    // Gather the details from the runtime version, the `System` pallet's `SS58Prefix`
+    // constant (or the `ss58Format` property if there isn't one) and the `tokenDecimals`
+    // and `tokenSymbol` properties of the chain.
+    pub(crate) fn from_chain(
+        metadata: &Metadata,
+        runtime_version: &RuntimeVersion,
+        properties: &SystemProperties,
+    ) -> Result<Self, MetadataError> {
+        let missing =
+            |what: &str| MetadataError::MetadataHash(format!("no {} given", what));
+        // Properties which can describe several tokens give the native one first.
+        let property = |name: &str| {
+            properties.get(name).and_then(|value| {
+                match value.as_array() {
+                    Some(values) => values.first(),
+                    None => Some(value),
+                }
+            })
+        };
+
+        let spec_name = runtime_version
+            .other
+            .get("specName")
+            .and_then(|name| name.as_str())
+            .ok_or_else(|| missing("spec name"))?;
+        let base58_prefix = match metadata
+            .pallet("System")
+            .and_then(|pallet| pallet.constant("SS58Prefix"))
+        {
+            Ok(constant) => {
+                u16::decode(&mut &constant.value[..])
+                    .map_err(MetadataError::ConstantValueError)?
+            }
+            Err(_) => {
+                property("ss58Format")
+                    .and_then(|format| format.as_u64())
+                    .ok_or_else(|| missing("SS58 prefix"))? as u16
+            }
+        };
+        let decimals = property("tokenDecimals")
+            .and_then(|decimals| decimals.as_u64())
+            .ok_or_else(|| missing("token decimals"))?;
+        let token_symbol = property("tokenSymbol")
+            .and_then(|symbol| symbol.as_str())
+            .ok_or_else(|| missing("token symbol"))?;
+
+        Ok(MetadataHashInfo {
+            spec_version: runtime_version.spec_version,
+            spec_name: spec_name.to_string(),
+            base58_prefix,
+            decimals: decimals as u8,
+            token_symbol: token_symbol.to_string(),
+        })
+    }
+}
+
+/// Compute the hash of the metadata given, along with the details of the chain given, which
+/// is what the [`super::CheckMetadataHash`] extension commits to.
+///
+/// The RFC describes the hash in terms of V15 metadata, whose extrinsic metadata names the
+/// address, call and signature types; these are the type parameters of the extrinsic type
+/// which V14 metadata gives instead.
+pub fn metadata_hash(
+    metadata: &RuntimeMetadataLastVersion,
+    info: &MetadataHashInfo,
+) -> Result<[u8; 32], MetadataError> {
+    let digest = metadata_digest(metadata, info).map_err(MetadataError::MetadataHash)?;
+    Ok(blake3_hash(&digest))
+}
+
+// The digest whose hash is the metadata hash.
+#[derive(Encode)]
+enum MetadataDigest {
+    #[codec(index = 1)]
+    V1 {
+        types_tree_root: Hash,
+        extrinsic_metadata_hash: Hash,
+        spec_version: u32,
+        spec_name: String,
+        base58_prefix: u16,
+        decimals: u8,
+        token_symbol: String,
+    },
+}
+
+// A reference to a type: primitive types (compact encoded or not) are referred to directly,
+// and types without any data are void.
+#[derive(Clone, Copy, Debug, Encode)]
+enum TypeRef {
+    #[codec(index = 0)]
+    Bool,
+    #[codec(index = 1)]
+    Char,
+    #[codec(index = 2)]
+    Str,
+    #[codec(index = 3)]
+    U8,
+    #[codec(index = 4)]
+    U16,
+    #[codec(index = 5)]
+    U32,
+    #[codec(index = 6)]
+    U64,
+    #[codec(index = 7)]
+    U128,
+    #[codec(index = 8)]
+    U256,
+    #[codec(index = 9)]
+    I8,
+    #[codec(index = 10)]
+    I16,
+    #[codec(index = 11)]
+    I32,
+    #[codec(index = 12)]
+    I64,
+    #[codec(index = 13)]
+    I128,
+    #[codec(index = 14)]
+    I256,
+    #[codec(index = 15)]
+    CompactU8,
+    #[codec(index = 16)]
+    CompactU16,
+    #[codec(index = 17)]
+    CompactU32,
+    #[codec(index = 18)]
+    CompactU64,
+    #[codec(index = 19)]
+    CompactU128,
+    #[codec(index = 20)]
+    CompactU256,
+    #[codec(index = 21)]
+    Void,
+    #[codec(index = 22)]
+    ById(Compact<u32>),
+}
+
+// A type in the minimal form whose hashes are the leaves of the merkle tree.
+#[derive(Debug, Encode)]
+struct MinimalType {
+    path: Vec<String>,
+    type_def: MinimalTypeDef,
+    type_id: Compact<u32>,
+}
+
+#[derive(Debug, Encode)]
+enum MinimalTypeDef {
+    #[codec(index = 0)]
+    Composite(Vec<MinimalField>),
+    // A single variant of an enum.
+    #[codec(index = 1)]
+    Enumeration {
+        name: String,
+        fields: Vec<MinimalField>,
+        index: Compact<u32>,
+    },
+    #[codec(index = 2)]
+    Sequence(TypeRef),
+    #[codec(index = 3)]
+    Array { len: u32, type_param: TypeRef },
+    #[codec(index = 4)]
+    Tuple(Vec<TypeRef>),
+    #[codec(index = 5)]
+    BitSequence {
+        num_bytes: u8,
+        least_significant_bit_first: bool,
+    },
+}
+
+#[derive(Debug, Encode)]
+struct MinimalField {
+    name: Option<String>,
+    ty: TypeRef,
+    type_name: Option<String>,
+}
+
+#[derive(Encode)]
+struct MinimalExtrinsicMetadata {
+    version: u8,
+    address_ty: TypeRef,
+    call_ty: TypeRef,
+    signature_ty: TypeRef,
+    signed_extensions: Vec<MinimalSignedExtension>,
+}
+
+#[derive(Encode)]
+struct MinimalSignedExtension {
+    identifier: String,
+    included_in_extrinsic: TypeRef,
+    included_in_signed_data: TypeRef,
+}
+
+fn blake3_hash(value: &impl Encode) -> Hash {
+    blake3::hash(&value.encode()).into()
+}
+
+fn metadata_digest(
+    metadata: &RuntimeMetadataLastVersion,
+    info: &MetadataHashInfo,
+) -> Result<MetadataDigest, String> {
+    let registry = &metadata.types;
+    let extrinsic = &metadata.extrinsic;
+    let extrinsic_ty = resolve(registry, extrinsic.ty.id())?;
+    let type_param = |name: &str| {
+        extrinsic_ty
+            .type_params()
+            .iter()
+            .find(|param| param.name().as_str() == name)
+            .and_then(|param| param.ty())
+            .map(|ty| ty.id())
+            .ok_or_else(|| format!("the extrinsic type has no {} type parameter", name))
+    };
+    let (address_ty, call_ty, signature_ty) = (
+        type_param("Address")?,
+        type_param("Call")?,
+        type_param("Signature")?,
+    );
+
+    let mut accessible = BTreeSet::new();
+    for id in [address_ty, call_ty, signature_ty] {
+        collect_accessible_types(registry, id, &mut accessible)?;
+    }
+    for extension in &extrinsic.signed_extensions {
+        collect_accessible_types(registry, extension.ty.id(), &mut accessible)?;
+        collect_accessible_types(
+            registry,
+            extension.additional_signed.id(),
+            &mut accessible,
+        )?;
+    }
+
+    // Types which are referred to directly don't get an ID of their own, and the others are
+    // numbered in the order of their IDs in the registry.
+    let mut ids = BTreeMap::new();
+    for id in accessible {
+        if has_own_id(resolve(registry, id)?) {
+            let next_id = ids.len() as u32;
+            ids.insert(id, next_id);
+        }
+    }
+    let context = Context {
+        registry,
+        ids: &ids,
+    };
+
+    // The leaves are ordered by type ID, and then by variant index.
+    let mut leaves = BTreeMap::new();
+    for (id, new_id) in &ids {
+        for (variant, ty) in context.minimal_types(*id, *new_id)? {
+            leaves.insert((*new_id, variant), ty);
+        }
+    }
+
+    let extrinsic_metadata = MinimalExtrinsicMetadata {
+        version: extrinsic.version,
+        address_ty: context.type_ref(address_ty)?,
+        call_ty: context.type_ref(call_ty)?,
+        signature_ty: context.type_ref(signature_ty)?,
+        signed_extensions: extrinsic
+            .signed_extensions
+            .iter()
+            .map(|extension| {
+                Ok(MinimalSignedExtension {
+                    identifier: extension.identifier.clone(),
+                    included_in_extrinsic: context.type_ref(extension.ty.id())?,
+                    included_in_signed_data: context
+                        .type_ref(extension.additional_signed.id())?,
+                })
+            })
+            .collect::<Result<_, String>>()?,
+    };
+
+    Ok(MetadataDigest::V1 {
+        types_tree_root: merkle_root(leaves.values().map(blake3_hash)),
+        extrinsic_metadata_hash: blake3_hash(&extrinsic_metadata),
+        spec_version: info.spec_version,
+        spec_name: info.spec_name.clone(),
+        base58_prefix: info.base58_prefix,
+        decimals: info.decimals,
+        token_symbol: info.token_symbol.clone(),
+    })
+}
+
+// The root of the merkle tree with the leaves given. Pairs of nodes are taken from the back
+// of the queue, and the node combining them is pushed to the front, until only the root is
+// left.
+fn merkle_root(leaves: impl Iterator<Item = Hash>) -> Hash {
+    let mut nodes: VecDeque<Hash> = leaves.collect();
+    while nodes.len() > 1 {
+        let right = nodes.pop_back().expect("there are at least two nodes; qed");
+        let left = nodes.pop_back().expect("there are at least two nodes; qed");
+        nodes.push_front(blake3_hash(&(left, right)));
+    }
+    nodes.pop_back().unwrap_or_default()
+}
+
+fn resolve(registry: &PortableRegistry, id: u32) -> Result<&Type<PortableForm>, String> {
+    registry
+        .resolve(id)
+        .ok_or_else(|| format!("type {} is missing from the type registry", id))
+}
+
+// Whether the type gets an ID of its own, rather than being referred to directly.
+fn has_own_id(ty: &Type<PortableForm>) -> bool {
+    match ty.type_def() {
+        TypeDef::Primitive(_) | TypeDef::Compact(_) => false,
+        TypeDef::Composite(composite) => !composite.fields().is_empty(),
+        TypeDef::Variant(variant) => !variant.variants().is_empty(),
+        TypeDef::Tuple(tuple) => !tuple.fields().is_empty(),
+        TypeDef::Sequence(_) | TypeDef::Array(_) | TypeDef::BitSequence(_) => true,
+    }
+}
+
+// Collect the IDs of the types which can be reached from the type with the given ID. The
+// types within compact encoded and bit sequence types aren't needed.
+fn collect_accessible_types(
+    registry: &PortableRegistry,
+    id: u32,
+    accessible: &mut BTreeSet<u32>,
+) -> Result<(), String> {
+    if !accessible.insert(id) {
+        return Ok(())
+    }
+    let mut collect = |ty: &<PortableForm as scale_info::form::Form>::Type| {
+        collect_accessible_types(registry, ty.id(), accessible)
+    };
+    match resolve(registry, id)?.type_def() {
+        TypeDef::Composite(composite) => {
+            composite
+                .fields()
+                .iter()
+                .try_for_each(|field| collect(field.ty()))
+        }
+        TypeDef::Variant(variant) => {
+            variant
+                .variants()
+                .iter()
+                .flat_map(|variant| variant.fields())
+                .try_for_each(|field| collect(field.ty()))
+        }
+        TypeDef::Sequence(sequence) => collect(sequence.type_param()),
+        TypeDef::Array(array) => collect(array.type_param()),
+        TypeDef::Tuple(tuple) => tuple.fields().iter().try_for_each(collect),
+        TypeDef::Primitive(_) | TypeDef::Compact(_) | TypeDef::BitSequence(_) => Ok(()),
+    }
+}
+
+// Collect the primitive types which can be reached from the type with the given ID, such
+// as the one within a compact encoded type.
+fn collect_primitives(
+    registry: &PortableRegistry,
+    id: u32,
+    visited: &mut BTreeSet<u32>,
+    found: &mut Vec<TypeDefPrimitive>,
+) -> Result<(), String> {
+    let mut visit = |ty: &<PortableForm as scale_info::form::Form>::Type| {
+        if visited.insert(ty.id()) {
+            collect_primitives(registry, ty.id(), visited, found)
+        } else {
+            Ok(())
+        }
+    };
+    match resolve(registry, id)?.type_def() {
+        TypeDef::Composite(composite) => {
+            composite
+                .fields()
+                .iter()
+                .try_for_each(|field| visit(field.ty()))
+        }
+        TypeDef::Variant(variant) => {
+            variant
+                .variants()
+                .iter()
+                .flat_map(|variant| variant.fields())
+                .try_for_each(|field| visit(field.ty()))
+        }
+        TypeDef::Sequence(sequence) => visit(sequence.type_param()),
+        TypeDef::Array(array) => visit(array.type_param()),
+        TypeDef::Tuple(tuple) => tuple.fields().iter().try_for_each(visit),
+        TypeDef::Compact(compact) => visit(compact.type_param()),
+        TypeDef::BitSequence(bits) => {
+            visit(bits.bit_order_type())?;
+            visit(bits.bit_store_type())
+        }
+        TypeDef::Primitive(primitive) => {
+            found.push(primitive.clone());
+            Ok(())
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Context<'a> {
+    registry: &'a PortableRegistry,
+    // The IDs given to the types which are referred to by ID, keyed by their IDs in the
+    // registry.
+    ids: &'a BTreeMap<u32, u32>,
+}
+
+impl<'a> Context<'a> {
+    fn type_ref(&self, id: u32) -> Result<TypeRef, String> {
+        let ty = resolve(self.registry, id)?;
+        let type_ref = match ty.type_def() {
+            TypeDef::Primitive(primitive) => {
+                match primitive {
+                    TypeDefPrimitive::Bool => TypeRef::Bool,
+                    TypeDefPrimitive::Char => TypeRef::Char,
+                    TypeDefPrimitive::Str => TypeRef::Str,
+                    TypeDefPrimitive::U8 => TypeRef::U8,
+                    TypeDefPrimitive::U16 => TypeRef::U16,
+                    TypeDefPrimitive::U32 => TypeRef::U32,
+                    TypeDefPrimitive::U64 => TypeRef::U64,
+                    TypeDefPrimitive::U128 => TypeRef::U128,
+                    TypeDefPrimitive::U256 => TypeRef::U256,
+                    TypeDefPrimitive::I8 => TypeRef::I8,
+                    TypeDefPrimitive::I16 => TypeRef::I16,
+                    TypeDefPrimitive::I32 => TypeRef::I32,
+                    TypeDefPrimitive::I64 => TypeRef::I64,
+                    TypeDefPrimitive::I128 => TypeRef::I128,
+                    TypeDefPrimitive::I256 => TypeRef::I256,
+                }
+            }
+            TypeDef::Compact(_) => {
+                let mut found = Vec::new();
+                collect_primitives(self.registry, id, &mut BTreeSet::new(), &mut found)?;
+                match &found[..] {
+                    [] => TypeRef::Void,
+                    [TypeDefPrimitive::U8] => TypeRef::CompactU8,
+                    [TypeDefPrimitive::U16] => TypeRef::CompactU16,
+                    [TypeDefPrimitive::U32] => TypeRef::CompactU32,
+                    [TypeDefPrimitive::U64] => TypeRef::CompactU64,
+                    [TypeDefPrimitive::U128] => TypeRef::CompactU128,
+                    found => {
+                        return Err(format!(
+                            "type {} is compact encoded, but made of {:?}",
+                            id, found
+                        ))
+                    }
+                }
+            }
+            _ if !has_own_id(ty) => TypeRef::Void,
+            _ => {
+                let new_id = self.ids.get(&id).ok_or_else(|| {
+                    format!("type {} can't be reached from the extrinsic", id)
+                })?;
+                TypeRef::ById(Compact(*new_id))
+            }
+        };
+        Ok(type_ref)
+    }
+
+    // The minimal form of the type with the given ID: one type for each of the variants
+    // of an enum (along with the variant's index), or a single type otherwise.
+    fn minimal_types(
+        &self,
+        id: u32,
+        new_id: u32,
+    ) -> Result<Vec<(u32, MinimalType)>, String> {
+        let ty = resolve(self.registry, id)?;
+        let path: Vec<String> = ty.path().segments().to_vec();
+        let minimal = |type_def| {
+            MinimalType {
+                path: path.clone(),
+                type_def,
+                type_id: Compact(new_id),
+            }
+        };
+        let type_def = match ty.type_def() {
+            TypeDef::Variant(variant) => {
+                let mut variants: Vec<&Variant<PortableForm>> =
+                    variant.variants().iter().collect();
+                variants.sort_by_key(|variant| variant.index());
+                return variants
+                    .into_iter()
+                    .map(|variant| {
+                        let type_def = MinimalTypeDef::Enumeration {
+                            name: variant.name().clone(),
+                            fields: self.fields(variant.fields())?,
+                            index: Compact(variant.index() as u32),
+                        };
+                        Ok((variant.index() as u32, minimal(type_def)))
+                    })
+                    .collect()
+            }
+            TypeDef::Composite(composite) => {
+                MinimalTypeDef::Composite(self.fields(composite.fields())?)
+            }
+            TypeDef::Sequence(sequence) => {
+                MinimalTypeDef::Sequence(self.type_ref(sequence.type_param().id())?)
+            }
+            TypeDef::Array(array) => {
+                MinimalTypeDef::Array {
+                    len: array.len(),
+                    type_param: self.type_ref(array.type_param().id())?,
+                }
+            }
+            TypeDef::Tuple(tuple) => {
+                MinimalTypeDef::Tuple(
+                    tuple
+                        .fields()
+                        .iter()
+                        .map(|ty| self.type_ref(ty.id()))
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+            TypeDef::BitSequence(bits) => {
+                let mut found = Vec::new();
+                collect_primitives(
+                    self.registry,
+                    bits.bit_store_type().id(),
+                    &mut BTreeSet::new(),
+                    &mut found,
+                )?;
+                let num_bytes = match &found[..] {
+                    [TypeDefPrimitive::U8] => 1,
+                    [TypeDefPrimitive::U16] => 2,
+                    [TypeDefPrimitive::U32] => 4,
+                    [TypeDefPrimitive::U64] => 8,
+                    found => {
+                        return Err(format!(
+                            "the bits of type {} are stored in {:?}",
+                            id, found
+                        ))
+                    }
+                };
+                let order = resolve(self.registry, bits.bit_order_type().id())?;
+                let order = order
+                    .path()
+                    .segments()
+                    .iter()
+                    .find(|segment| *segment == "Lsb0" || *segment == "Msb0")
+                    .ok_or_else(|| {
+                        format!("the bit order of type {} is neither Lsb0 nor Msb0", id)
+                    })?;
+                MinimalTypeDef::BitSequence {
+                    num_bytes,
+                    least_significant_bit_first: order == "Lsb0",
+                }
+            }
+            TypeDef::Primitive(_) | TypeDef::Compact(_) => return Ok(Vec::new()),
+        };
+        Ok(vec![(0, minimal(type_def))])
+    }
+
+    fn fields(
+        &self,
+        fields: &[Field<PortableForm>],
+    ) -> Result<Vec<MinimalField>, String> {
+        fields
+            .iter()
+            .map(|field| {
+                Ok(MinimalField {
+                    name: field.name().cloned(),
+                    ty: self.type_ref(field.ty().id())?,
+                    type_name: field.type_name().cloned(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codec::Compact;
+    use frame_metadata::{
+        v14::{
+            ExtrinsicMetadata,
+            SignedExtensionMetadata,
+        },
+        RuntimeMetadataPrefixed,
+    };
+    use scale_info::{
+        meta_type,
+        TypeInfo,
+    };
+    use serde_json::json;
+    use std::{
+        convert::TryFrom,
+        marker::PhantomData,
+    };
+
+    #[derive(TypeInfo)]
+    pub struct Extrinsic<Address, Call, Signature, Extra>(
+        PhantomData<(Address, Call, Signature, Extra)>,
+    );
+
+    #[derive(TypeInfo)]
+    pub enum MultiAddress {
+        Id([u8; 32]),
+        Index(#[codec(compact)] u32),
+        Raw(Vec<u8>),
+        Address32([u8; 32]),
+    }
+
+    #[derive(TypeInfo)]
+    pub enum MultiSignature {
+        Ed25519([u8; 64]),
+        Sr25519([u8; 64]),
+    }
+
+    #[derive(TypeInfo)]
+    pub struct Perbill(u32);
+
+    #[derive(TypeInfo)]
+    pub struct Unit;
+
+    #[derive(TypeInfo)]
+    pub enum Empty {}
+
+    #[derive(TypeInfo)]
+    pub struct Nested {
+        a: Option<u64>,
+        b: (u8, i128, bool),
+        c: Vec<(String, char)>,
+        #[codec(compact)]
+        d: u64,
+    }
+
+    #[derive(TypeInfo)]
+    pub enum BalancesCall {
+        #[codec(index = 3)]
+        Transfer {
+            dest: MultiAddress,
+            #[codec(compact)]
+            value: u128,
+        },
+        #[codec(index = 0)]
+        SetBalance {
+            who: MultiAddress,
+            free: Compact<u128>,
+            p: Compact<Perbill>,
+        },
+        Remark(Vec<u8>, Nested, Unit, ()),
+    }
+
+    #[derive(TypeInfo)]
+    pub enum Call {
+        #[codec(index = 5)]
+        Balances(BalancesCall),
+        #[codec(index = 1)]
+        System(SystemCall),
+    }
+
+    #[derive(TypeInfo)]
+    pub enum SystemCall {
+        Remark { remark: Vec<u8> },
+        Kill(Vec<[u8; 32]>, [u16; 3]),
+    }
+
+    #[derive(TypeInfo)]
+    pub enum Era {
+        Immortal,
+        Mortal1(u8),
+    }
+
+    #[derive(TypeInfo)]
+    pub struct Tip(#[codec(compact)] u128, Option<u32>);
+
+    fn extension<Ty: TypeInfo + 'static, AdditionalSigned: TypeInfo + 'static>(
+        identifier: &'static str,
+    ) -> SignedExtensionMetadata {
+        SignedExtensionMetadata {
+            identifier,
+            ty: meta_type::<Ty>(),
+            additional_signed: meta_type::<AdditionalSigned>(),
+        }
+    }
+
+    // Metadata whose extrinsics use enums (with variants out of order and without any),
+    // compact encoded values, tuples, arrays and sequences.
+    fn metadata() -> RuntimeMetadataPrefixed {
+        let extrinsic = ExtrinsicMetadata {
+            ty: meta_type::<Extrinsic<MultiAddress, Call, MultiSignature, (Era, Tip)>>(),
+            version: 4,
+            signed_extensions: vec![
+                extension::<(), u32>("CheckSpecVersion"),
+                extension::<(), [u8; 32]>("CheckGenesis"),
+                extension::<Era, [u8; 32]>("CheckMortality"),
+                extension::<Compact<u32>, ()>("CheckNonce"),
+                extension::<Tip, Empty>("ChargeTransactionPayment"),
+                extension::<Era, Option<[u8; 32]>>("CheckMetadataHash"),
+            ],
+        };
+        RuntimeMetadataLastVersion::new(vec![], extrinsic, meta_type::<()>()).into()
+    }
+
+    fn info() -> MetadataHashInfo {
+        MetadataHashInfo {
+            spec_version: 7,
+            spec_name: "nice".into(),
+            base58_prefix: 42,
+            decimals: 12,
+            token_symbol: "UNIT".into(),
+        }
+    }
+
+    #[test]
+    fn hash_matches_the_reference_implementation() {
+        // The hash computed by the `merkleized-metadata` crate from V15 metadata with the
+        // same types.
+        let expected = "335cc521417a3902a0106878cebc0870295d5a4186e1ddb7d30c08fb2e375374";
+        let metadata = Metadata::try_from(metadata()).unwrap();
+        let hash = metadata_hash(metadata.runtime_metadata(), &info()).unwrap();
+        assert_eq!(hex::encode(hash), expected);
+
+        let other_spec = MetadataHashInfo {
+            spec_version: 8,
+            ..info()
+        };
+        assert_ne!(
+            metadata_hash(metadata.runtime_metadata(), &other_spec).unwrap(),
+            hash
+        );
+    }
+
+    #[test]
+    fn info_is_taken_from_the_chain() {
+        let metadata = Metadata::try_from(metadata()).unwrap();
+        let runtime_version: RuntimeVersion = serde_json::from_value(json!({
+            "specName": "nice",
+            "specVersion": 7,
+            "transactionVersion": 1,
+        }))
+        .unwrap();
+        let properties = json!({
+            "ss58Format": 42,
+            "tokenDecimals": [12, 10],
+            "tokenSymbol": ["UNIT", "OTHER"],
+        });
+        let properties = properties.as_object().unwrap();
+
+        assert_eq!(
+            MetadataHashInfo::from_chain(&metadata, &runtime_version, properties)
+                .unwrap(),
+            info()
+        );
+
+        let mut no_symbol = properties.clone();
+        no_symbol.remove("tokenSymbol");
+        assert!(matches!(
+            MetadataHashInfo::from_chain(&metadata, &runtime_version, &no_symbol),
+            Err(MetadataError::MetadataHash(msg)) if msg.contains("token symbol")
+        ));
+    }
+}
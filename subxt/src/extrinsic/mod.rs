@@ -16,30 +16,76 @@
 
 //! Create signed or unsigned extrinsics.
 
+mod details;
+mod dynamic;
 mod extra;
+#[cfg(feature = "ledger")]
+mod ledger;
+mod metadata_hash;
 mod signer;
+mod uos;
 
 pub use self::{
+    details::{
+        ExtrinsicDetails,
+        ExtrinsicSignature,
+    },
+    dynamic::{
+        DefaultExtensions,
+        DynamicExtra,
+        DynamicExtraParams,
+        EncodedAdditionalSigned,
+        EncodedExtension,
+        ExtensionContext,
+        ExtensionRegistry,
+    },
     extra::{
         ChargeAssetTxPayment,
         ChargeTransactionPayment,
         CheckGenesis,
+        CheckMetadataHash,
+        CheckMetadataHashMode,
         CheckMortality,
         CheckNonce,
         CheckSpecVersion,
         CheckTxVersion,
         CheckWeight,
         DefaultExtra,
+        DefaultExtraParams,
         DefaultExtraWithTxPayment,
         SignedExtra,
+        TxPaymentExtension,
+    },
+    metadata_hash::{
+        metadata_hash,
+        MetadataHashInfo,
     },
     signer::{
+        EcdsaSignature,
+        EcdsaSigner,
         PairSigner,
         Signer,
     },
+    uos::{
+        decode_uos_signature,
+        UosSigningRequest,
+        UOS_FRAME_SIZE,
+    },
 };
 
-use sp_runtime::traits::SignedExtension;
+#[cfg(feature = "ledger")]
+pub use self::ledger::{
+    LedgerApp,
+    LedgerError,
+    LedgerScheme,
+    LedgerSigner,
+};
+
+use codec::Encode;
+use sp_runtime::traits::{
+    Hash,
+    SignedExtension,
+};
 
 use crate::{
     error::BasicError,
@@ -60,6 +106,23 @@ pub type UncheckedExtrinsic<T, X> = sp_runtime::generic::UncheckedExtrinsic<
 pub type SignedPayload<T, X> =
     sp_runtime::generic::SignedPayload<Encoded, <X as SignedExtra<T>>::Extra>;
 
+/// Extension methods for [`SignedPayload`].
+pub trait SignedPayloadExt {
+    /// The bytes which must be signed to produce the signature of the extrinsic: the SCALE
+    /// encoded call, signed extensions and additional signed data or, if that's longer
+    /// than 256 bytes, its blake2-256 hash.
+    fn signing_bytes(&self) -> Vec<u8>;
+}
+
+impl<E: SignedExtension> SignedPayloadExt
+    for sp_runtime::generic::SignedPayload<Encoded, E>
+{
+    fn signing_bytes(&self) -> Vec<u8> {
+        // `using_encoded` hashes payloads longer than 256 bytes.
+        self.using_encoded(|payload| payload.to_vec())
+    }
+}
+
 /// Creates a signed extrinsic
 pub async fn create_signed<T, X>(
     runtime_version: &RuntimeVersion,
@@ -74,6 +137,74 @@ where
     X: SignedExtra<T>,
     <X::Extra as SignedExtension>::AdditionalSigned: Send + Sync,
 {
+    let payload = create_signed_payload::<T, X>(
+        runtime_version,
+        genesis_hash,
+        nonce,
+        call,
+        additional_params,
+    )?;
+    let signed = signer.sign(payload).await?;
+    Ok(signed)
+}
+
+/// Signs several calls for one account in a single pass, assigning them the sequential
+/// nonces `first_nonce..first_nonce + calls.len()`.
+///
+/// No connection to a node is required, so this can be used to prepare a batch of
+/// transactions offline, for instance from cold storage. The extrinsics can later be
+/// SCALE encoded and broadcast (in order) with [`Client::submit_and_watch_raw`].
+///
+/// [`Client::submit_and_watch_raw`]: crate::Client::submit_and_watch_raw
+pub async fn create_signed_sequence<T, X>(
+    runtime_version: &RuntimeVersion,
+    genesis_hash: T::Hash,
+    first_nonce: T::Index,
+    calls: impl IntoIterator<Item = Encoded>,
+    signer: &(dyn Signer<T, X> + Send + Sync),
+    additional_params: X::Parameters,
+) -> Result<Vec<UncheckedExtrinsic<T, X>>, BasicError>
+where
+    T: Config,
+    X: SignedExtra<T>,
+    <X::Extra as SignedExtension>::AdditionalSigned: Send + Sync,
+{
+    let mut nonce = first_nonce;
+    let mut extrinsics = Vec::new();
+    for call in calls {
+        let extrinsic = create_signed::<T, X>(
+            runtime_version,
+            genesis_hash,
+            nonce,
+            call,
+            signer,
+            additional_params.clone(),
+        )
+        .await?;
+        extrinsics.push(extrinsic);
+        nonce += 1u32.into();
+    }
+    Ok(extrinsics)
+}
+
+/// Creates the payload which needs to be signed in order to construct a signed extrinsic.
+///
+/// The bytes which should be signed are given by [`SignedPayloadExt::signing_bytes()`]
+/// (payloads longer than 256 bytes are hashed first). This allows the payload to be signed
+/// by something other than a [`Signer`], for instance an HSM, after which the extrinsic can
+/// be constructed using [`attach_signature`].
+pub fn create_signed_payload<T, X>(
+    runtime_version: &RuntimeVersion,
+    genesis_hash: T::Hash,
+    nonce: T::Index,
+    call: Encoded,
+    additional_params: X::Parameters,
+) -> Result<SignedPayload<T, X>, BasicError>
+where
+    T: Config,
+    X: SignedExtra<T>,
+{
+    X::check_parameters(&additional_params)?;
     let spec_version = runtime_version.spec_version;
     let tx_version = runtime_version.transaction_version;
     let extra = X::new(
@@ -84,6 +215,315 @@ where
         additional_params,
     );
     let payload = SignedPayload::<T, X>::new(call, extra.extra())?;
-    let signed = signer.sign(payload).await?;
-    Ok(signed)
+    Ok(payload)
+}
+
+/// Constructs a signed extrinsic from a [`SignedPayload`] and a signature of its
+/// [`SignedPayloadExt::signing_bytes()`], produced by the account with the given address.
+pub fn attach_signature<T, X>(
+    payload: SignedPayload<T, X>,
+    address: T::Address,
+    signature: T::Signature,
+) -> UncheckedExtrinsic<T, X>
+where
+    T: Config,
+    X: SignedExtra<T>,
+{
+    let (call, extra, _) = payload.deconstruct();
+    UncheckedExtrinsic::<T, X>::new_signed(call, address, signature, extra)
+}
+
+/// Computes the hash of a SCALE encoded extrinsic using the runtime's hashing algorithm.
+/// This is the hash that the transaction pool, block explorers and the
+/// [`TransactionProgress`](crate::TransactionProgress) returned on submission identify the
+/// extrinsic by, so it can be computed before the extrinsic is submitted.
+pub fn extrinsic_hash<T: Config>(extrinsic: &[u8]) -> T::Hash {
+    T::Hashing::hash(extrinsic)
+}
+
+/// Creates an unsigned extrinsic. Such extrinsics are only accepted for calls which the
+/// runtime explicitly allows to be submitted unsigned (for instance `im_online::heartbeat`).
+pub fn create_unsigned<T, X>(call: Encoded) -> UncheckedExtrinsic<T, X>
+where
+    T: Config,
+    X: SignedExtra<T>,
+{
+    UncheckedExtrinsic::<T, X>::new_unsigned(call)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        DefaultConfig,
+        DefaultExtra,
+        DefaultExtraParams,
+    };
+    use codec::Encode;
+    use sp_core::{
+        ecdsa,
+        ed25519,
+        sr25519,
+        Pair,
+    };
+    use sp_runtime::traits::{
+        IdentifyAccount,
+        Verify,
+    };
+
+    type Extra = DefaultExtra<DefaultConfig>;
+
+    fn runtime_version() -> RuntimeVersion {
+        RuntimeVersion {
+            spec_version: 1,
+            transaction_version: 2,
+            other: Default::default(),
+        }
+    }
+
+    #[async_std::test]
+    async fn detached_signature_matches_signer() {
+        // ed25519 signatures are deterministic, so we can compare the results:
+        let pair = ed25519::Pair::from_seed(&[1; 32]);
+        let signer = PairSigner::<DefaultConfig, Extra, _>::new(pair.clone());
+        let call = Encoded(vec![4, 0, 1, 2, 3]);
+
+        let expected = create_signed::<DefaultConfig, Extra>(
+            &runtime_version(),
+            Default::default(),
+            5,
+            call.clone(),
+            &signer,
+            Default::default(),
+        )
+        .await
+        .unwrap();
+
+        let payload = create_signed_payload::<DefaultConfig, Extra>(
+            &runtime_version(),
+            Default::default(),
+            5,
+            call,
+            Default::default(),
+        )
+        .unwrap();
+        let signature = pair.sign(&payload.signing_bytes());
+        let extrinsic = attach_signature::<DefaultConfig, Extra>(
+            payload,
+            signer.account_id().clone().into(),
+            signature.into(),
+        );
+
+        assert_eq!(extrinsic.encode(), expected.encode());
+    }
+
+    #[async_std::test]
+    async fn long_payloads_are_hashed_before_signing() {
+        let pair = sr25519::Pair::from_seed(&[1; 32]);
+        let signer = PairSigner::<DefaultConfig, Extra, _>::new(pair.clone());
+        let mut call = vec![4, 0];
+        call.extend(vec![7; 300]);
+        let call = Encoded(call);
+
+        let payload = || {
+            create_signed_payload::<DefaultConfig, Extra>(
+                &runtime_version(),
+                Default::default(),
+                5,
+                call.clone(),
+                Default::default(),
+            )
+            .unwrap()
+        };
+        let raw = payload().deconstruct().encode();
+        assert!(raw.len() > 256);
+        let payload = payload();
+        let signing_bytes = payload.signing_bytes();
+        assert_eq!(signing_bytes, sp_core::blake2_256(&raw).to_vec());
+
+        // A detached signature of the signing bytes is valid for the extrinsic.
+        let signature: sp_runtime::MultiSignature = pair.sign(&signing_bytes).into();
+        assert!(signature.verify(&signing_bytes[..], signer.account_id()));
+        let extrinsic = attach_signature::<DefaultConfig, Extra>(
+            payload,
+            signer.account_id().clone().into(),
+            signature,
+        );
+        let (_, signature, _) = extrinsic.signature.unwrap();
+        assert!(signature.verify(&signing_bytes[..], signer.account_id()));
+
+        // And so is the signature produced by a signer.
+        let signed = create_signed::<DefaultConfig, Extra>(
+            &runtime_version(),
+            Default::default(),
+            5,
+            call,
+            &signer,
+            Default::default(),
+        )
+        .await
+        .unwrap();
+        let (_, signature, _) = signed.signature.unwrap();
+        assert!(signature.verify(&signing_bytes[..], signer.account_id()));
+    }
+
+    async fn ecdsa_signed<T: Config>(
+        signer: &EcdsaSigner<T, DefaultExtra<T>>,
+    ) -> (Vec<u8>, T::Signature)
+    where
+        T::AccountId: Into<T::Address>,
+        T::Signature: EcdsaSignature,
+    {
+        let call = Encoded(vec![4, 0, 1, 2, 3]);
+        let payload = create_signed_payload::<T, DefaultExtra<T>>(
+            &runtime_version(),
+            Default::default(),
+            5u32.into(),
+            call.clone(),
+            Default::default(),
+        )
+        .unwrap();
+        let extrinsic = create_signed::<T, DefaultExtra<T>>(
+            &runtime_version(),
+            Default::default(),
+            5u32.into(),
+            call,
+            signer,
+            Default::default(),
+        )
+        .await
+        .unwrap();
+        let (_, signature, _) = extrinsic.signature.unwrap();
+        (payload.signing_bytes(), signature)
+    }
+
+    #[async_std::test]
+    async fn ecdsa_signer_uses_multi_signature_variant() {
+        let pair = ecdsa::Pair::from_seed(&[1; 32]);
+        let signer = EcdsaSigner::<DefaultConfig, Extra>::new(pair.clone());
+        assert_eq!(
+            signer.account_id(),
+            &sp_runtime::MultiSigner::from(pair.public()).into_account()
+        );
+
+        let (payload, signature) = ecdsa_signed(&signer).await;
+        assert!(matches!(signature, sp_runtime::MultiSignature::Ecdsa(_)));
+        assert!(signature.verify(&payload[..], signer.account_id()));
+    }
+
+    #[async_std::test]
+    async fn ecdsa_signer_signs_keccak_hash_for_ethereum_accounts() {
+        use crate::ethereum::{
+            AccountId20,
+            EthereumConfig,
+        };
+
+        let pair = ecdsa::Pair::from_seed(&[1; 32]);
+        let signer = EcdsaSigner::<EthereumConfig, DefaultExtra<EthereumConfig>>::new(
+            pair.clone(),
+        );
+        assert_eq!(signer.account_id(), &AccountId20::from(pair.public()));
+
+        let (payload, signature) = ecdsa_signed(&signer).await;
+        assert!(signature.verify(&payload[..], signer.account_id()));
+    }
+
+    #[async_std::test]
+    async fn signed_sequence_uses_sequential_nonces() {
+        let signer = PairSigner::<DefaultConfig, Extra, _>::new(
+            ed25519::Pair::from_seed(&[1; 32]),
+        );
+        let calls = vec![Encoded(vec![4, 0, 1]), Encoded(vec![4, 0, 2])];
+
+        let extrinsics = create_signed_sequence::<DefaultConfig, Extra>(
+            &runtime_version(),
+            Default::default(),
+            7,
+            calls.clone(),
+            &signer,
+            Default::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(extrinsics.len(), 2);
+        for (nonce, (call, extrinsic)) in (7..).zip(calls.into_iter().zip(extrinsics)) {
+            let expected = create_signed::<DefaultConfig, Extra>(
+                &runtime_version(),
+                Default::default(),
+                nonce,
+                call,
+                &signer,
+                Default::default(),
+            )
+            .await
+            .unwrap();
+            assert_eq!(extrinsic.encode(), expected.encode());
+        }
+    }
+
+    #[test]
+    fn pair_signer_from_phrase_derives_path() {
+        use sp_core::{
+            crypto::{
+                SecretStringError,
+                DEV_PHRASE,
+            },
+            sr25519,
+        };
+        use sp_keyring::AccountKeyring;
+
+        let alice = PairSigner::<DefaultConfig, Extra, sr25519::Pair>::from_phrase(
+            DEV_PHRASE, None, "//Alice",
+        )
+        .unwrap();
+        assert_eq!(alice.account_id(), &AccountKeyring::Alice.to_account_id());
+
+        let soft = PairSigner::<DefaultConfig, Extra, sr25519::Pair>::from_phrase(
+            DEV_PHRASE,
+            None,
+            "//Alice/0",
+        )
+        .unwrap();
+        assert_ne!(soft.account_id(), alice.account_id());
+
+        assert!(matches!(
+            PairSigner::<DefaultConfig, Extra, ed25519::Pair>::from_phrase(
+                DEV_PHRASE,
+                None,
+                "//Alice/0",
+            ),
+            Err(SecretStringError::InvalidPath)
+        ));
+    }
+
+    #[test]
+    fn default_extra_rejects_metadata_hash() {
+        let res = create_signed_payload::<DefaultConfig, Extra>(
+            &runtime_version(),
+            Default::default(),
+            5,
+            Encoded(vec![4, 0, 1]),
+            DefaultExtraParams::new().metadata_hash([1; 32]),
+        );
+        assert!(matches!(res, Err(BasicError::Other(_))));
+    }
+
+    #[test]
+    fn extrinsic_hash_uses_runtime_hasher() {
+        let extrinsic = create_unsigned::<DefaultConfig, Extra>(Encoded(vec![4, 0, 1]));
+        let bytes = extrinsic.encode();
+        assert_eq!(
+            extrinsic_hash::<DefaultConfig>(&bytes),
+            sp_core::H256(sp_core::hashing::blake2_256(&bytes))
+        );
+    }
+
+    #[test]
+    fn unsigned_extrinsic_has_unsigned_version_byte() {
+        let extrinsic = create_unsigned::<DefaultConfig, Extra>(Encoded(vec![4, 0, 1]));
+        // Compact encoded length, followed by the version byte (4, without the signed bit),
+        // followed by the call data.
+        assert_eq!(extrinsic.encode(), vec![16, 4, 4, 0, 1]);
+    }
 }
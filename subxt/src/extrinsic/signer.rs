@@ -22,9 +22,16 @@ use super::{
     SignedPayload,
     UncheckedExtrinsic,
 };
-use crate::Config;
+use crate::{
+    ethereum::EthereumSignature,
+    Config,
+};
 use codec::Encode;
-use sp_core::Pair;
+use sp_core::{
+    crypto::SecretStringError,
+    ecdsa,
+    Pair,
+};
 use sp_runtime::traits::{
     IdentifyAccount,
     SignedExtension,
@@ -32,6 +39,35 @@ use sp_runtime::traits::{
 };
 
 /// Extrinsic signer.
+///
+/// Signing is asynchronous, so implementations are free to hand the payload to a remote
+/// signing service (for instance a KMS, a vault or a signing microservice) and await its
+/// response without blocking the executor. The bytes to sign are given by
+/// [`SignedPayloadExt::signing_bytes()`](super::SignedPayloadExt::signing_bytes), which
+/// hashes long payloads as the runtime expects. Implement it with [`async_trait`]:
+///
+/// ```ignore
+/// #[async_trait::async_trait]
+/// impl Signer<DefaultConfig, DefaultExtra<DefaultConfig>> for RemoteSigner {
+///     fn account_id(&self) -> &AccountId32 {
+///         &self.account_id
+///     }
+///
+///     fn nonce(&self) -> Option<u32> {
+///         None
+///     }
+///
+///     async fn sign(
+///         &self,
+///         payload: SignedPayload<DefaultConfig, DefaultExtra<DefaultConfig>>,
+///     ) -> Result<UncheckedExtrinsic<DefaultConfig, DefaultExtra<DefaultConfig>>, String> {
+///         let signature = self.service.sign(payload.signing_bytes()).await?;
+///         Ok(attach_signature(payload, self.account_id.clone().into(), signature))
+///     }
+/// }
+/// ```
+///
+/// [`async_trait`]: https://docs.rs/async-trait
 #[async_trait::async_trait]
 pub trait Signer<T: Config, E: SignedExtra<T>> {
     /// Returns the account id.
@@ -80,6 +116,24 @@ where
         }
     }
 
+    /// Creates a new `Signer` from a BIP39 mnemonic phrase, an optional password, and a
+    /// derivation path such as `//polkadot//0/1`.
+    ///
+    /// Hard junctions in the derivation path are prefixed with `//` and soft junctions
+    /// with `/`. Only sr25519 keys support soft junctions; deriving ed25519 or ECDSA keys
+    /// with a soft junction fails with [`SecretStringError::InvalidPath`].
+    pub fn from_phrase(
+        mnemonic: &str,
+        password: Option<&str>,
+        derivation_path: &str,
+    ) -> Result<Self, SecretStringError> {
+        if !derivation_path.is_empty() && !derivation_path.starts_with('/') {
+            return Err(SecretStringError::InvalidPath)
+        }
+        let suri = format!("{}{}", mnemonic, derivation_path);
+        Ok(Self::new(P::from_string(&suri, password)?))
+    }
+
     /// Sets the nonce to a new value.
     pub fn set_nonce(&mut self, nonce: T::Index) {
         self.nonce = Some(nonce);
@@ -120,12 +174,122 @@ where
         extrinsic: SignedPayload<T, E>,
     ) -> Result<UncheckedExtrinsic<T, E>, String> {
         let signature = extrinsic.using_encoded(|payload| self.signer.sign(payload));
-        let (call, extra, _) = extrinsic.deconstruct();
-        let extrinsic = UncheckedExtrinsic::<T, E>::new_signed(
-            call,
+        let extrinsic = super::attach_signature::<T, E>(
+            extrinsic,
             self.account_id.clone().into(),
             signature.into(),
-            extra,
+        );
+        Ok(extrinsic)
+    }
+}
+
+/// Signature types which can be produced by signing with a secp256k1 ECDSA key.
+///
+/// Runtimes differ in which hash of the payload they expect to be signed: substrate's
+/// `MultiSignature::Ecdsa` is a signature over the `blake2_256` hash, while Ethereum
+/// compatible runtimes expect a signature over the `keccak256` hash.
+pub trait EcdsaSignature: Sized {
+    /// Signs the message with the given pair, hashing it as the runtime expects.
+    fn sign(pair: &ecdsa::Pair, message: &[u8]) -> Self;
+}
+
+impl EcdsaSignature for ecdsa::Signature {
+    fn sign(pair: &ecdsa::Pair, message: &[u8]) -> Self {
+        pair.sign(message)
+    }
+}
+
+impl EcdsaSignature for sp_runtime::MultiSignature {
+    fn sign(pair: &ecdsa::Pair, message: &[u8]) -> Self {
+        sp_runtime::MultiSignature::Ecdsa(pair.sign(message))
+    }
+}
+
+impl EcdsaSignature for EthereumSignature {
+    fn sign(pair: &ecdsa::Pair, message: &[u8]) -> Self {
+        EthereumSignature::sign(pair, message)
+    }
+}
+
+/// Extrinsic signer using a secp256k1 ECDSA private key.
+///
+/// Unlike a [`PairSigner`] with an [`ecdsa::Pair`], which always signs the `blake2_256`
+/// hash of the payload, this signs the payload the way the configured runtime's
+/// signature type expects (see [`EcdsaSignature`]). The account id is derived from the
+/// public key by the runtime's signer type, e.g. by hashing it with `blake2_256` for
+/// `MultiSigner` or with `keccak256` for [`EthereumSigner`](crate::ethereum::EthereumSigner).
+#[derive(Clone, Debug)]
+pub struct EcdsaSigner<T: Config, E> {
+    account_id: T::AccountId,
+    nonce: Option<T::Index>,
+    signer: ecdsa::Pair,
+    marker: std::marker::PhantomData<E>,
+}
+
+impl<T, E> EcdsaSigner<T, E>
+where
+    T: Config,
+    E: SignedExtra<T>,
+    T::Signature: EcdsaSignature,
+    <T::Signature as Verify>::Signer:
+        From<ecdsa::Public> + IdentifyAccount<AccountId = T::AccountId>,
+{
+    /// Creates a new `Signer` from an ECDSA `Pair`.
+    pub fn new(signer: ecdsa::Pair) -> Self {
+        let account_id =
+            <T::Signature as Verify>::Signer::from(signer.public()).into_account();
+        Self {
+            account_id,
+            nonce: None,
+            signer,
+            marker: Default::default(),
+        }
+    }
+
+    /// Sets the nonce to a new value.
+    pub fn set_nonce(&mut self, nonce: T::Index) {
+        self.nonce = Some(nonce);
+    }
+
+    /// Increment the nonce.
+    pub fn increment_nonce(&mut self) {
+        self.nonce = self.nonce.map(|nonce| nonce + 1u32.into());
+    }
+
+    /// Returns the signer.
+    pub fn signer(&self) -> &ecdsa::Pair {
+        &self.signer
+    }
+}
+
+#[async_trait::async_trait]
+impl<T, E> Signer<T, E> for EcdsaSigner<T, E>
+where
+    T: Config,
+    E: SignedExtra<T>,
+    T::AccountId: Into<T::Address> + 'static,
+    T::Signature: EcdsaSignature,
+    <<E as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned:
+        Send + Sync + 'static,
+{
+    fn account_id(&self) -> &T::AccountId {
+        &self.account_id
+    }
+
+    fn nonce(&self) -> Option<T::Index> {
+        self.nonce
+    }
+
+    async fn sign(
+        &self,
+        extrinsic: SignedPayload<T, E>,
+    ) -> Result<UncheckedExtrinsic<T, E>, String> {
+        let signature =
+            extrinsic.using_encoded(|payload| T::Signature::sign(&self.signer, payload));
+        let extrinsic = super::attach_signature::<T, E>(
+            extrinsic,
+            self.account_id.clone().into(),
+            signature,
         );
         Ok(extrinsic)
     }
@@ -0,0 +1,249 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Support for signing extrinsics with an air-gapped device such as Parity Signer, using the
+//! [Universal Offline Signatures](https://github.com/maciejhirsz/uos) (UOS) QR code format.
+
+use super::{
+    SignedExtra,
+    SignedPayload,
+    UncheckedExtrinsic,
+};
+use crate::{
+    error::BasicError,
+    Config,
+    Encoded,
+};
+use codec::{
+    Decode,
+    Encode,
+};
+use sp_runtime::{
+    traits::{
+        IdentifyAccount,
+        SignedExtension,
+        Verify,
+    },
+    MultiSigner,
+};
+
+/// Identifies a Substrate payload.
+const SUBSTRATE_ID: u8 = 0x53;
+/// Command to sign a (mortal or immortal) transaction payload.
+const CMD_SIGN_TX: u8 = 0x02;
+/// Prefix of every frame in a multipart QR code sequence.
+const MULTIPART: u8 = 0x00;
+
+/// The default maximum number of payload bytes in a single QR code frame.
+pub const UOS_FRAME_SIZE: usize = 1024;
+
+/// A request to sign an extrinsic, which can be encoded as a sequence of QR codes to be
+/// scanned by an offline signer. The signature returned by the signer can be decoded with
+/// [`decode_uos_signature`] and attached with [`UosSigningRequest::attach_signature`].
+pub struct UosSigningRequest<T: Config, X: SignedExtra<T>> {
+    signer: MultiSigner,
+    genesis_hash: T::Hash,
+    call: Encoded,
+    extra: X::Extra,
+    additional_signed: <X::Extra as SignedExtension>::AdditionalSigned,
+}
+
+impl<T, X> UosSigningRequest<T, X>
+where
+    T: Config,
+    X: SignedExtra<T>,
+{
+    /// Creates a signing request for the given payload, which must be signed by the
+    /// account with the given public key on the chain with the given genesis hash.
+    pub fn new(
+        payload: SignedPayload<T, X>,
+        signer: MultiSigner,
+        genesis_hash: T::Hash,
+    ) -> Self {
+        let (call, extra, additional_signed) = payload.deconstruct();
+        Self {
+            signer,
+            genesis_hash,
+            call,
+            extra,
+            additional_signed,
+        }
+    }
+
+    /// Encodes the request in the UOS format. This contains the full (unhashed) signing
+    /// payload, so that the offline signer is able to display its contents. The call is
+    /// length prefixed, since signers read it as a byte vector to find where the signed
+    /// extensions start.
+    pub fn encode(&self) -> Vec<u8> {
+        let (crypto, public_key): (u8, &[u8]) = match &self.signer {
+            MultiSigner::Ed25519(public) => (0x00, public.as_ref()),
+            MultiSigner::Sr25519(public) => (0x01, public.as_ref()),
+            MultiSigner::Ecdsa(public) => (0x02, public.as_ref()),
+        };
+        let mut encoded = vec![SUBSTRATE_ID, crypto, CMD_SIGN_TX];
+        encoded.extend_from_slice(public_key);
+        self.call.0.encode_to(&mut encoded);
+        (&self.extra, &self.additional_signed).encode_to(&mut encoded);
+        self.genesis_hash.encode_to(&mut encoded);
+        encoded
+    }
+
+    /// Splits the encoded request into multipart frames of at most [`UOS_FRAME_SIZE`]
+    /// payload bytes, each of which should be displayed as a separate QR code.
+    pub fn frames(&self) -> Vec<Vec<u8>> {
+        self.frames_with_size(UOS_FRAME_SIZE)
+    }
+
+    /// Splits the encoded request into multipart frames of at most `frame_size` payload
+    /// bytes, each of which should be displayed as a separate QR code.
+    pub fn frames_with_size(&self, frame_size: usize) -> Vec<Vec<u8>> {
+        let encoded = self.encode();
+        let chunks = encoded.chunks(frame_size.max(1));
+        let count = chunks.len() as u16;
+        chunks
+            .enumerate()
+            .map(|(index, chunk)| {
+                let mut frame = vec![MULTIPART];
+                frame.extend_from_slice(&count.to_be_bytes());
+                frame.extend_from_slice(&(index as u16).to_be_bytes());
+                frame.extend_from_slice(chunk);
+                frame
+            })
+            .collect()
+    }
+
+    /// Constructs the signed extrinsic from the signature returned by the offline signer.
+    pub fn attach_signature(self, signature: T::Signature) -> UncheckedExtrinsic<T, X>
+    where
+        <T::Signature as Verify>::Signer:
+            From<MultiSigner> + IdentifyAccount<AccountId = T::AccountId>,
+        T::AccountId: Into<T::Address>,
+    {
+        let account_id =
+            <T::Signature as Verify>::Signer::from(self.signer).into_account();
+        UncheckedExtrinsic::<T, X>::new_signed(
+            self.call,
+            account_id.into(),
+            signature,
+            self.extra,
+        )
+    }
+}
+
+/// Decodes the signature QR code returned by an offline signer, which contains the hex
+/// encoded signature (prefixed by its crypto type).
+pub fn decode_uos_signature<T>(data: &str) -> Result<T::Signature, BasicError>
+where
+    T: Config,
+    T::Signature: Decode,
+{
+    let data = data.trim();
+    let data = data.strip_prefix("0x").unwrap_or(data);
+    let bytes = hex::decode(data)
+        .map_err(|e| BasicError::Other(format!("Invalid signature QR code: {}", e)))?;
+    Ok(T::Signature::decode(&mut &bytes[..])?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        extrinsic::create_signed_payload,
+        rpc::RuntimeVersion,
+        DefaultConfig,
+        DefaultExtra,
+    };
+    use sp_core::{
+        sr25519,
+        Pair,
+    };
+    use sp_runtime::MultiSignature;
+
+    type Extra = DefaultExtra<DefaultConfig>;
+
+    fn signing_request(call: Vec<u8>) -> UosSigningRequest<DefaultConfig, Extra> {
+        let version = RuntimeVersion {
+            spec_version: 1,
+            transaction_version: 2,
+            other: Default::default(),
+        };
+        let payload = create_signed_payload::<DefaultConfig, Extra>(
+            &version,
+            Default::default(),
+            0,
+            Encoded(call),
+            Default::default(),
+        )
+        .unwrap();
+        let public = sr25519::Pair::from_seed(&[1; 32]).public();
+        UosSigningRequest::new(payload, public.into(), Default::default())
+    }
+
+    #[test]
+    fn encodes_uos_prelude() {
+        let request = signing_request(vec![1, 2, 3]);
+        let public = sr25519::Pair::from_seed(&[1; 32]).public();
+        let encoded = request.encode();
+
+        assert_eq!(&encoded[..3], &[0x53, 0x01, 0x02]);
+        assert_eq!(&encoded[3..35], public.as_ref());
+        assert_eq!(&encoded[35..39], &[12, 1, 2, 3]);
+        assert_eq!(&encoded[encoded.len() - 32..], &[0; 32]);
+    }
+
+    #[test]
+    fn decodes_like_an_offline_signer() {
+        let call = vec![7; 300];
+        let request = signing_request(call.clone());
+        let encoded = request.encode();
+
+        // Signers read the prelude and public key, then the call as a length prefixed
+        // byte vector; what follows is the signed extensions and then the genesis hash.
+        let input = &mut &encoded[35..];
+        assert_eq!(<Vec<u8>>::decode(input).unwrap(), call);
+        let (extensions, genesis_hash) = input.split_at(input.len() - 32);
+        assert_eq!(
+            extensions,
+            &(&request.extra, &request.additional_signed).encode()[..]
+        );
+        assert_eq!(genesis_hash, &[0; 32]);
+    }
+
+    #[test]
+    fn splits_into_frames() {
+        let request = signing_request(vec![0; 300]);
+        let encoded = request.encode();
+        let frames = request.frames_with_size(256);
+
+        assert_eq!(frames.len(), 2);
+        assert_eq!(&frames[0][..5], &[0x00, 0x00, 0x02, 0x00, 0x00]);
+        assert_eq!(&frames[1][..5], &[0x00, 0x00, 0x02, 0x00, 0x01]);
+        assert_eq!([&frames[0][5..], &frames[1][5..]].concat(), encoded);
+    }
+
+    #[test]
+    fn decodes_signature() {
+        let signature = sr25519::Pair::from_seed(&[1; 32]).sign(b"payload");
+        let expected = MultiSignature::from(signature);
+        let data = hex::encode(expected.encode());
+
+        let decoded = decode_uos_signature::<DefaultConfig>(&data).unwrap();
+        assert_eq!(decoded, expected);
+        let decoded = decode_uos_signature::<DefaultConfig>(&format!("0x{}", data));
+        assert_eq!(decoded.unwrap(), expected);
+        assert!(decode_uos_signature::<DefaultConfig>("0xzz").is_err());
+    }
+}
@@ -0,0 +1,419 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Fetch and verify proofs that blocks have been finalized by GRANDPA, for instance to
+//! follow a chain from a bridge or light verifier without trusting the node.
+//!
+//! The types here are copied from `sp-finality-grandpa`, `sc-finality-grandpa` and
+//! `finality-grandpa` to avoid a dependency on those crates. Therefore they must be kept
+//! compatible with those types from the target substrate version.
+
+use crate::{
+    error::BasicError,
+    rpc::{
+        NumberOrHex,
+        Rpc,
+    },
+    Config,
+};
+use codec::{
+    Decode,
+    Encode,
+};
+use derivative::Derivative;
+use sp_core::{
+    ed25519,
+    Pair,
+};
+use sp_runtime::traits::{
+    Header,
+    UniqueSaturatedInto,
+};
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+
+/// The GRANDPA authorities, along with the weight of each of their votes.
+pub type AuthorityList = Vec<(ed25519::Public, u64)>;
+
+/// A proof that a block has been finalized, as returned from the RPC call
+/// `grandpa_proveFinality`.
+#[derive(Derivative, Encode, Decode)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct FinalityProof<T: Config> {
+    /// The hash of the block which the justification finalizes. This is the block asked
+    /// about, or a descendant of it.
+    pub block: T::Hash,
+    /// The SCALE encoded [`GrandpaJustification`] finalizing the block.
+    pub justification: Vec<u8>,
+    /// The headers of the blocks after the block asked about, up to and including the one
+    /// finalized. This is empty if the block asked about is the one finalized.
+    pub unknown_headers: Vec<T::Header>,
+}
+
+impl<T: Config> FinalityProof<T> {
+    /// Decode the justification finalizing the block.
+    pub fn justification(&self) -> Result<GrandpaJustification<T>, BasicError> {
+        Ok(GrandpaJustification::decode(&mut &self.justification[..])?)
+    }
+
+    /// Check that this proof shows the block with the hash given to be finalized: the
+    /// block must be [`FinalityProof::block`], or be linked to it by the
+    /// [`FinalityProof::unknown_headers`], and the justification must finalize
+    /// [`FinalityProof::block`] and have been signed by enough of the authorities given,
+    /// which belong to the authority set with the ID given. See
+    /// [`GrandpaJustification::verify()`].
+    pub fn verify(
+        &self,
+        block: T::Hash,
+        set_id: u64,
+        authorities: &[(ed25519::Public, u64)],
+    ) -> Result<GrandpaJustification<T>, BasicError> {
+        self.check_ancestry(block)?;
+        let justification = self.justification()?;
+        if justification.commit.target_hash != self.block {
+            return Err(
+                "The justification doesn't finalize the block that the proof is for"
+                    .into(),
+            )
+        }
+        justification.verify(set_id, authorities)?;
+        Ok(justification)
+    }
+
+    // Check that the unknown headers lead from the block with the hash given to the block
+    // finalized, each being the child of the one before.
+    fn check_ancestry(&self, block: T::Hash) -> Result<(), BasicError> {
+        let mut hash = block;
+        for header in &self.unknown_headers {
+            if *header.parent_hash() != hash {
+                return Err(format!(
+                    "The proof's headers don't link block {:?} to the block finalized",
+                    block
+                )
+                .into())
+            }
+            hash = header.hash();
+        }
+        if hash != self.block {
+            return Err(format!(
+                "The proof's headers don't lead from block {:?} to the block finalized",
+                block
+            )
+            .into())
+        }
+        Ok(())
+    }
+}
+
+/// A justification that a block has been finalized: the precommits for it from a round of
+/// GRANDPA voting.
+#[derive(Derivative, Encode, Decode)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct GrandpaJustification<T: Config> {
+    /// The round of voting that finalized the block.
+    pub round: u64,
+    /// The precommits for the block.
+    pub commit: Commit<T>,
+    /// The headers needed to show that the blocks precommitted to descend from the block
+    /// finalized.
+    pub votes_ancestries: Vec<T::Header>,
+}
+
+/// The precommits for a block from a round of GRANDPA voting.
+#[derive(Derivative, Encode, Decode)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct Commit<T: Config> {
+    /// The hash of the block finalized.
+    pub target_hash: T::Hash,
+    /// The number of the block finalized.
+    pub target_number: T::BlockNumber,
+    /// The signed precommits, each for the block finalized or one of its descendants.
+    pub precommits: Vec<SignedPrecommit<T>>,
+}
+
+/// A precommit along with the signature of the authority who made it.
+#[derive(Derivative, Encode, Decode)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct SignedPrecommit<T: Config> {
+    /// The precommit.
+    pub precommit: Precommit<T>,
+    /// The signature of the authority.
+    pub signature: ed25519::Signature,
+    /// The ID of the authority.
+    pub id: ed25519::Public,
+}
+
+/// A vote to finalize a block.
+#[derive(Derivative, Encode, Decode)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct Precommit<T: Config> {
+    /// The hash of the block voted for.
+    pub target_hash: T::Hash,
+    /// The number of the block voted for.
+    pub target_number: T::BlockNumber,
+}
+
+// The index of the precommit variant of the GRANDPA `Message` enum, which is what
+// authorities sign.
+const PRECOMMIT_MESSAGE_INDEX: u8 = 1;
+
+impl<T: Config> GrandpaJustification<T> {
+    /// Check that this justification has been signed by enough of the authorities given,
+    /// which belong to the authority set with the ID given: each precommit must be
+    /// correctly signed by a distinct authority, and must be for the block finalized or a
+    /// descendant of it, and the authorities precommitting must carry over two thirds of
+    /// the total weight.
+    pub fn verify(
+        &self,
+        set_id: u64,
+        authorities: &[(ed25519::Public, u64)],
+    ) -> Result<(), BasicError> {
+        let weights: HashMap<_, _> = authorities.iter().cloned().collect();
+        let ancestry: HashMap<T::Hash, T::Hash> = self
+            .votes_ancestries
+            .iter()
+            .map(|header| (header.hash(), *header.parent_hash()))
+            .collect();
+
+        let mut signers = HashSet::new();
+        let mut signed_weight = 0u64;
+        for signed in &self.commit.precommits {
+            let weight = weights.get(&signed.id).ok_or_else(|| {
+                BasicError::Other(format!(
+                    "Precommit from {:?}, which isn't in the authority set",
+                    signed.id
+                ))
+            })?;
+            if !signers.insert(signed.id) {
+                return Err(
+                    format!("Several precommits from authority {:?}", signed.id).into()
+                )
+            }
+
+            let mut message = vec![PRECOMMIT_MESSAGE_INDEX];
+            signed.precommit.encode_to(&mut message);
+            self.round.encode_to(&mut message);
+            set_id.encode_to(&mut message);
+            if !ed25519::Pair::verify(&signed.signature, &message, &signed.id) {
+                return Err(format!(
+                    "Invalid precommit signature from authority {:?}",
+                    signed.id
+                )
+                .into())
+            }
+
+            if !self.descends(signed.precommit.target_hash, &ancestry) {
+                return Err(format!(
+                    "Precommit from {:?} is for a block which isn't known to descend from \
+                     the block finalized",
+                    signed.id
+                )
+                .into())
+            }
+            signed_weight = signed_weight.saturating_add(*weight);
+        }
+
+        // The same threshold as `finality-grandpa`: more than two thirds of the weight,
+        // tolerating up to a third (rounded down) of it being faulty.
+        let total_weight = authorities
+            .iter()
+            .fold(0u64, |total, (_, weight)| total.saturating_add(*weight));
+        let threshold = total_weight - total_weight.saturating_sub(1) / 3;
+        if signed_weight < threshold {
+            return Err(format!(
+                "Precommits carry a weight of {}, which is below the threshold of {}",
+                signed_weight, threshold
+            )
+            .into())
+        }
+        Ok(())
+    }
+
+    // Whether the block with the hash given is the block finalized, or is shown to descend
+    // from it by the ancestry given.
+    fn descends(&self, mut hash: T::Hash, ancestry: &HashMap<T::Hash, T::Hash>) -> bool {
+        loop {
+            if hash == self.commit.target_hash {
+                return true
+            }
+            match ancestry.get(&hash) {
+                Some(parent) => hash = *parent,
+                None => return false,
+            }
+        }
+    }
+}
+
+/// Fetch a proof that the block with the hash given has been finalized, or `None` if it
+/// hasn't been (or the node can't prove it, for instance because it's an old block from a
+/// previous authority set, or it isn't a known block). An error is returned if the proof
+/// handed back isn't linked to the block; the justification still needs verifying with
+/// [`FinalityProof::verify()`].
+pub async fn prove_finality<T: Config>(
+    rpc: &Rpc<T>,
+    block: T::Hash,
+) -> Result<Option<FinalityProof<T>>, BasicError> {
+    let header = match rpc.header(Some(block)).await? {
+        Some(header) => header,
+        None => return Ok(None),
+    };
+    let number: u64 = (*header.number()).unique_saturated_into();
+    let proof = match rpc
+        .prove_finality(NumberOrHex::Number(number).into())
+        .await?
+    {
+        Some(proof) => FinalityProof::<T>::decode(&mut &proof[..])?,
+        None => return Ok(None),
+    };
+    proof.check_ancestry(block)?;
+    Ok(Some(proof))
+}
+
+/// Fetch the GRANDPA authorities at the given block (or the latest block if `None` is
+/// given), using the `GrandpaApi_grandpa_authorities` runtime API.
+pub async fn authorities<T: Config>(
+    rpc: &Rpc<T>,
+    at: Option<T::Hash>,
+) -> Result<AuthorityList, BasicError> {
+    let bytes = rpc.call("GrandpaApi_grandpa_authorities", &[], at).await?;
+    Ok(AuthorityList::decode(&mut &bytes[..])?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DefaultConfig;
+    use sp_core::H256;
+
+    type T = DefaultConfig;
+
+    fn signed_precommit(
+        pair: &ed25519::Pair,
+        target_hash: H256,
+        round: u64,
+        set_id: u64,
+    ) -> SignedPrecommit<T> {
+        let precommit = Precommit {
+            target_hash,
+            target_number: 1,
+        };
+        let mut message = vec![PRECOMMIT_MESSAGE_INDEX];
+        precommit.encode_to(&mut message);
+        round.encode_to(&mut message);
+        set_id.encode_to(&mut message);
+        SignedPrecommit {
+            signature: pair.sign(&message),
+            id: pair.public(),
+            precommit,
+        }
+    }
+
+    #[test]
+    fn justifications_are_verified() {
+        let pairs: Vec<_> = (1..=4u8)
+            .map(|seed| ed25519::Pair::from_seed(&[seed; 32]))
+            .collect();
+        let authorities: AuthorityList =
+            pairs.iter().map(|pair| (pair.public(), 1)).collect();
+        let target_hash = H256::repeat_byte(1);
+        let justification = |signers: &[ed25519::Pair]| {
+            GrandpaJustification::<T> {
+                round: 3,
+                commit: Commit {
+                    target_hash,
+                    target_number: 1,
+                    precommits: signers
+                        .iter()
+                        .map(|pair| signed_precommit(pair, target_hash, 3, 7))
+                        .collect(),
+                },
+                votes_ancestries: vec![],
+            }
+        };
+
+        // Three of the four authorities are enough, but two aren't.
+        assert!(justification(&pairs[..3]).verify(7, &authorities).is_ok());
+        assert!(justification(&pairs[..2]).verify(7, &authorities).is_err());
+        // Signatures are for a specific authority set.
+        assert!(justification(&pairs[..3]).verify(8, &authorities).is_err());
+
+        // The proof decodes and checks the justification.
+        let proof = FinalityProof::<T> {
+            block: target_hash,
+            justification: justification(&pairs).encode(),
+            unknown_headers: vec![],
+        };
+        let proof = FinalityProof::<T>::decode(&mut &proof.encode()[..]).unwrap();
+        assert!(proof.verify(target_hash, 7, &authorities).is_ok());
+    }
+
+    #[test]
+    fn proofs_must_link_the_block_to_the_one_finalized() {
+        let pairs: Vec<_> = (1..=3u8)
+            .map(|seed| ed25519::Pair::from_seed(&[seed; 32]))
+            .collect();
+        let authorities: AuthorityList =
+            pairs.iter().map(|pair| (pair.public(), 1)).collect();
+        let header = |number, parent_hash| {
+            <T as Config>::Header::new(
+                number,
+                Default::default(),
+                Default::default(),
+                parent_hash,
+                Default::default(),
+            )
+        };
+        // Block 1 is asked about, and block 3 is the one finalized.
+        let block = H256::repeat_byte(1);
+        let child = header(2, block);
+        let finalized = header(3, child.hash());
+        let proof = |unknown_headers| {
+            let justification = GrandpaJustification::<T> {
+                round: 3,
+                commit: Commit {
+                    target_hash: finalized.hash(),
+                    target_number: 3,
+                    precommits: pairs
+                        .iter()
+                        .map(|pair| signed_precommit(pair, finalized.hash(), 3, 7))
+                        .collect(),
+                },
+                votes_ancestries: vec![],
+            };
+            FinalityProof::<T> {
+                block: finalized.hash(),
+                justification: justification.encode(),
+                unknown_headers,
+            }
+        };
+
+        let linked = proof(vec![child.clone(), finalized.clone()]);
+        assert!(linked.verify(block, 7, &authorities).is_ok());
+        assert!(linked.verify(finalized.hash(), 7, &authorities).is_err());
+        // A missing header breaks the link.
+        let gap = proof(vec![finalized.clone()]);
+        assert!(gap.verify(block, 7, &authorities).is_err());
+        // Headers which don't reach the block finalized don't prove anything either.
+        let short = proof(vec![child.clone()]);
+        assert!(short.verify(block, 7, &authorities).is_err());
+        // Without headers, only the block finalized itself is proven.
+        let none = proof(vec![]);
+        assert!(none.verify(finalized.hash(), 7, &authorities).is_ok());
+        assert!(none.verify(block, 7, &authorities).is_err());
+    }
+}
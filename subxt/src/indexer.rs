@@ -0,0 +1,266 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A small framework for indexing events. Handlers are registered for the
+//! events that are of interest, and an [`EventIndexer`] then walks through
+//! finalized blocks (starting with historical ones, if asked to) and hands
+//! each matching event to the handlers registered for it.
+//!
+//! ```no_run
+//! # use subxt::{ClientBuilder, DefaultConfig};
+//! # #[derive(codec::Decode)]
+//! # struct Transfer { from: subxt::sp_core::crypto::AccountId32, amount: u128 }
+//! # impl subxt::Event for Transfer {
+//! #     const PALLET: &'static str = "Balances";
+//! #     const EVENT: &'static str = "Transfer";
+//! # }
+//! # #[async_std::main]
+//! # async fn main() -> Result<(), subxt::BasicError> {
+//! let client = ClientBuilder::new().build::<DefaultConfig>().await?;
+//!
+//! client
+//!     .event_indexer()
+//!     .from_block(1_000)
+//!     .on::<Transfer, _, _>(|transfer, ctx| async move {
+//!         println!("#{}: {} transferred {}", ctx.block_number, transfer.from, transfer.amount);
+//!         Ok(())
+//!     })
+//!     .run()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    error::BasicError,
+    events::{
+        self,
+        BlockHashes,
+        BlockSubscriptionKind,
+        RawEventDetails,
+        SubscriptionMetrics,
+        SubscriptionOverflow,
+    },
+    Client,
+    Config,
+    Event,
+    Phase,
+};
+use codec::Error as CodecError;
+use derivative::Derivative;
+use futures::{
+    Future,
+    StreamExt,
+};
+use std::pin::Pin;
+
+/// The details of where an event was found, which are handed to an
+/// [`EventIndexer`] handler along with the event itself.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct EventContext<'a, T: Config> {
+    /// The client driving the indexer, which can be used to look up anything
+    /// else that's needed (for instance, storage at this block).
+    #[derivative(Debug = "ignore")]
+    pub client: &'a Client<T>,
+    /// The number of the block that the event was emitted in.
+    pub block_number: u64,
+    /// The hash of the block that the event was emitted in.
+    pub block_hash: T::Hash,
+    /// During which [`Phase`] was the event produced?
+    pub phase: Phase,
+    /// What index is this event in the stored events for this block.
+    pub index: u32,
+    /// The topics that the event was deposited with.
+    pub topics: Vec<T::Hash>,
+}
+
+impl<'a, T: Config> EventContext<'a, T> {
+    /// The index of the extrinsic which produced this event, if it was produced
+    /// while applying an extrinsic.
+    pub fn extrinsic_index(&self) -> Option<u32> {
+        match self.phase {
+            Phase::ApplyExtrinsic(idx) => Some(idx),
+            _ => None,
+        }
+    }
+}
+
+type HandlerFuture<'a> = Pin<Box<dyn Future<Output = Result<(), BasicError>> + 'a>>;
+
+// A handler for some event, with the event type erased.
+struct Handler<'a, T: Config> {
+    pallet: &'static str,
+    event: &'static str,
+    handle: Box<
+        dyn Fn(
+                &RawEventDetails<T::Hash>,
+                EventContext<'a, T>,
+            ) -> Result<HandlerFuture<'a>, CodecError>
+            + 'a,
+    >,
+}
+
+/// Walks through finalized blocks, handing each event that a handler has been registered
+/// for (via [`EventIndexer::on()`]) to that handler. Obtain one with
+/// [`Client::event_indexer()`], and start it with [`EventIndexer::run()`].
+///
+/// Blocks are handled one at a time and in order, and the events within each block
+/// are handed to handlers in the order they were emitted, each handler being awaited
+/// before moving on. No finalized blocks are skipped; if the indexer falls behind, or
+/// the block subscription has to be re-established, any missed blocks are backfilled.
+#[derive(Derivative)]
+#[derivative(Debug(bound = ""))]
+pub struct EventIndexer<'a, T: Config> {
+    client: &'a Client<T>,
+    #[derivative(Debug = "ignore")]
+    handlers: Vec<Handler<'a, T>>,
+    from_block: Option<u64>,
+    to_block: Option<u64>,
+    max_resubscribes: Option<usize>,
+    metrics: SubscriptionMetrics,
+}
+
+impl<'a, T: Config> EventIndexer<'a, T> {
+    pub(crate) fn new(client: &'a Client<T>) -> Self {
+        EventIndexer {
+            client,
+            handlers: Vec::new(),
+            from_block: None,
+            to_block: None,
+            max_resubscribes: None,
+            metrics: SubscriptionMetrics::default(),
+        }
+    }
+
+    /// Register an async handler to be called with every `Ev` that's seen, along with an
+    /// [`EventContext`] describing where it was found. Several handlers can be registered
+    /// for the same event; they're called in the order they were registered in. If a
+    /// handler returns an error, the indexer stops and hands it back from [`EventIndexer::run()`].
+    pub fn on<Ev, F, Fut>(mut self, handler: F) -> Self
+    where
+        Ev: Event,
+        F: Fn(Ev, EventContext<'a, T>) -> Fut + 'a,
+        Fut: Future<Output = Result<(), BasicError>> + 'a,
+    {
+        self.handlers.push(Handler {
+            pallet: Ev::PALLET,
+            event: Ev::EVENT,
+            handle: Box::new(move |raw, ctx| {
+                let ev = Ev::decode(&mut &raw.data[..])?;
+                Ok(Box::pin(handler(ev, ctx)))
+            }),
+        });
+        self
+    }
+
+    /// Start from the given (historical) block number, working through every finalized
+    /// block from there up to the head of the chain before following new blocks. By
+    /// default, the indexer starts from the latest finalized block.
+    pub fn from_block(mut self, block_number: u64) -> Self {
+        self.from_block = Some(block_number);
+        self
+    }
+
+    /// Stop once the block with the given number has been handled, rather than following
+    /// new blocks forever. Useful for indexing some fixed range of historical blocks.
+    pub fn to_block(mut self, block_number: u64) -> Self {
+        self.to_block = Some(block_number);
+        self
+    }
+
+    /// The number of times the indexer will resubscribe to finalized blocks if the
+    /// subscription fails or ends. By default, it will always resubscribe.
+    pub fn max_resubscribes(mut self, max_resubscribes: usize) -> Self {
+        self.max_resubscribes = Some(max_resubscribes);
+        self
+    }
+
+    /// A handle to the health metrics of the indexer's block subscription, which can be
+    /// handed to some other task to keep an eye on how far behind the head of the chain
+    /// the indexer is (including while it works through historical blocks).
+    pub fn metrics(&self) -> SubscriptionMetrics {
+        self.metrics.clone()
+    }
+
+    /// Run the indexer. This only returns once the block given to [`EventIndexer::to_block()`]
+    /// has been handled, or if something goes wrong (including a handler returning an error).
+    pub async fn run(self) -> Result<(), BasicError> {
+        if self
+            .from_block
+            .zip(self.to_block)
+            .map_or(false, |(from, to)| from > to)
+        {
+            return Ok(())
+        }
+
+        let kind = BlockSubscriptionKind::Finalized;
+        let subscription = events::subscribe_headers(self.client, kind).await?;
+        let mut block_hashes = BlockHashes::new(
+            self.client,
+            kind,
+            subscription,
+            SubscriptionOverflow::Backfill,
+        );
+        block_hashes.max_resubscribes(self.max_resubscribes);
+        block_hashes.report_to(self.metrics.clone());
+        if let Some(from) = self.from_block {
+            block_hashes.start_from(from);
+        }
+        let mut block_hashes = block_hashes.into_stream();
+
+        while let Some(block) = block_hashes.next().await {
+            let (block_number, block_hash) = block?;
+            if self.from_block.map_or(false, |from| block_number < from) {
+                continue
+            }
+            self.handle_block(block_number, block_hash).await?;
+            if self.to_block.map_or(false, |to| block_number >= to) {
+                return Ok(())
+            }
+        }
+        Err(BasicError::Other(
+            "Finalized block subscription ended".into(),
+        ))
+    }
+
+    async fn handle_block(
+        &self,
+        block_number: u64,
+        block_hash: T::Hash,
+    ) -> Result<(), BasicError> {
+        let events = events::at::<T, ()>(self.client, block_hash).await?;
+        for raw in events.iter_raw() {
+            let raw = raw?;
+            let handlers = self
+                .handlers
+                .iter()
+                .filter(|h| h.pallet == raw.pallet && h.event == raw.variant);
+            for handler in handlers {
+                let ctx = EventContext {
+                    client: self.client,
+                    block_number,
+                    block_hash,
+                    phase: raw.phase.clone(),
+                    index: raw.index,
+                    topics: raw.topics.clone(),
+                };
+                (handler.handle)(&raw, ctx)?.await?;
+            }
+        }
+        Ok(())
+    }
+}
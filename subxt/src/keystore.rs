@@ -0,0 +1,342 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Import accounts exported from polkadot-js (e.g. the browser extension or apps UI) as
+//! encrypted JSON files.
+//!
+//! ```no_run
+//! # use subxt::{DefaultConfig, DefaultExtra, PairSigner, keystore::KeystoreJson};
+//! # use subxt::sp_core::sr25519;
+//! let json = std::fs::read_to_string("account.json").unwrap();
+//! let keystore: KeystoreJson = json.parse().unwrap();
+//! let signer: PairSigner<DefaultConfig, DefaultExtra<DefaultConfig>, sr25519::Pair> =
+//!     keystore.pair_signer("password").unwrap();
+//! ```
+
+use crate::{
+    extrinsic::SignedExtra,
+    Config,
+    PairSigner,
+};
+use serde::Deserialize;
+use sp_core::{
+    ecdsa,
+    ed25519,
+    sr25519,
+    Pair,
+};
+use sp_runtime::traits::{
+    IdentifyAccount,
+    Verify,
+};
+use xsalsa20poly1305::{
+    aead::{
+        Aead,
+        NewAead,
+    },
+    Key,
+    Nonce,
+    XSalsa20Poly1305,
+};
+
+const SCRYPT_LENGTH: usize = 32 + 3 * 4;
+const NONCE_LENGTH: usize = 24;
+const PKCS8_HEADER: [u8; 16] = [48, 83, 2, 1, 1, 48, 5, 6, 3, 43, 101, 112, 4, 34, 4, 32];
+const PKCS8_DIVIDER: [u8; 5] = [161, 35, 3, 33, 0];
+
+/// Error decrypting a keystore file.
+#[derive(Debug, thiserror::Error)]
+pub enum KeystoreError {
+    /// The file is not valid JSON, or not in the expected format.
+    #[error("Invalid keystore JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    /// The encoded key is not valid base64.
+    #[error("Invalid base64 in keystore: {0}")]
+    Base64(#[from] base64::DecodeError),
+    /// The file uses an encryption or content type which is not supported.
+    #[error("Unsupported keystore encoding: {0}")]
+    UnsupportedEncoding(String),
+    /// The key could not be decrypted, most likely because the password is wrong.
+    #[error("Unable to decrypt keystore, the password is probably wrong")]
+    InvalidPassword,
+    /// The decrypted key is malformed.
+    #[error("Invalid key in keystore: {0}")]
+    InvalidKey(String),
+}
+
+/// The encoding of the key in a [`KeystoreJson`].
+#[derive(Clone, Debug, Deserialize)]
+pub struct KeystoreEncoding {
+    /// The content of the decrypted key, e.g. `["pkcs8", "sr25519"]`.
+    pub content: Vec<String>,
+    /// The encryption of the key, e.g. `["scrypt", "xsalsa20-poly1305"]`.
+    #[serde(rename = "type")]
+    pub ty: Vec<String>,
+    /// The version of the format.
+    pub version: String,
+}
+
+/// An account exported from polkadot-js as JSON.
+#[derive(Clone, Debug, Deserialize)]
+pub struct KeystoreJson {
+    /// The base64 encoded, encrypted key.
+    pub encoded: String,
+    /// How the key is encoded.
+    pub encoding: KeystoreEncoding,
+    /// The SS58 address of the account.
+    pub address: String,
+    /// Metadata about the account, such as its name.
+    #[serde(default)]
+    pub meta: serde_json::Value,
+}
+
+impl std::str::FromStr for KeystoreJson {
+    type Err = KeystoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(serde_json::from_str(s)?)
+    }
+}
+
+/// Key pairs which can be stored in a polkadot-js keystore.
+pub trait KeystorePair: Pair {
+    /// The key type as named in the keystore's content encoding.
+    const KEY_TYPE: &'static str;
+
+    /// Creates the pair from the decrypted secret key.
+    fn from_keystore_secret(secret: &[u8]) -> Result<Self, KeystoreError>;
+}
+
+impl KeystorePair for sr25519::Pair {
+    const KEY_TYPE: &'static str = "sr25519";
+
+    fn from_keystore_secret(secret: &[u8]) -> Result<Self, KeystoreError> {
+        // polkadot-js stores sr25519 secret keys in their ed25519 compatible form.
+        let secret = schnorrkel::SecretKey::from_ed25519_bytes(secret)
+            .map_err(|e| KeystoreError::InvalidKey(e.to_string()))?;
+        Ok(secret.into())
+    }
+}
+
+impl KeystorePair for ed25519::Pair {
+    const KEY_TYPE: &'static str = "ed25519";
+
+    fn from_keystore_secret(secret: &[u8]) -> Result<Self, KeystoreError> {
+        // The secret key is the 32 byte seed followed by the public key.
+        let seed = secret.get(..32).ok_or_else(|| {
+            KeystoreError::InvalidKey("ed25519 secret key is too short".into())
+        })?;
+        ed25519::Pair::from_seed_slice(seed)
+            .map_err(|e| KeystoreError::InvalidKey(format!("{:?}", e)))
+    }
+}
+
+impl KeystorePair for ecdsa::Pair {
+    const KEY_TYPE: &'static str = "ecdsa";
+
+    fn from_keystore_secret(secret: &[u8]) -> Result<Self, KeystoreError> {
+        ecdsa::Pair::from_seed_slice(secret)
+            .map_err(|e| KeystoreError::InvalidKey(format!("{:?}", e)))
+    }
+}
+
+impl KeystoreJson {
+    /// Returns the type of the key stored, e.g. `sr25519`.
+    pub fn key_type(&self) -> Option<&str> {
+        self.encoding
+            .content
+            .iter()
+            .map(String::as_str)
+            .find(|content| *content != "pkcs8")
+    }
+
+    /// Decrypts the stored key with the given password.
+    pub fn decrypt<P: KeystorePair>(&self, password: &str) -> Result<P, KeystoreError> {
+        if self.key_type() != Some(P::KEY_TYPE) {
+            return Err(KeystoreError::UnsupportedEncoding(format!(
+                "expected a {} key, found {:?}",
+                P::KEY_TYPE,
+                self.encoding.content
+            )))
+        }
+        if !self.encoding.ty.iter().any(|ty| ty == "xsalsa20-poly1305") {
+            return Err(KeystoreError::UnsupportedEncoding(format!(
+                "unencrypted keystores are not supported, found {:?}",
+                self.encoding.ty
+            )))
+        }
+
+        let encoded = base64::decode(&self.encoded)?;
+        let (key, encrypted) = if self.encoding.ty.iter().any(|ty| ty == "scrypt") {
+            scrypt_key(password, &encoded)?
+        } else {
+            // Older keystores use the password, padded to 32 bytes, as the key.
+            let mut key = [0u8; 32];
+            let password = password.as_bytes();
+            let len = password.len().min(32);
+            key[..len].copy_from_slice(&password[..len]);
+            (key, &encoded[..])
+        };
+
+        if encrypted.len() < NONCE_LENGTH {
+            return Err(KeystoreError::InvalidKey(
+                "encrypted key is too short".into(),
+            ))
+        }
+        let (nonce, ciphertext) = encrypted.split_at(NONCE_LENGTH);
+        let decrypted = XSalsa20Poly1305::new(Key::from_slice(&key))
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| KeystoreError::InvalidPassword)?;
+
+        let (secret, public) = decode_pkcs8(&decrypted)?;
+        let pair = P::from_keystore_secret(secret)?;
+        if pair.public().as_ref() != public {
+            return Err(KeystoreError::InvalidKey(
+                "public key does not match the secret key".into(),
+            ))
+        }
+        Ok(pair)
+    }
+
+    /// Decrypts the stored key with the given password, and creates a [`PairSigner`]
+    /// from it.
+    pub fn pair_signer<T, E, P>(
+        &self,
+        password: &str,
+    ) -> Result<PairSigner<T, E, P>, KeystoreError>
+    where
+        T: Config,
+        E: SignedExtra<T>,
+        T::Signature: From<P::Signature>,
+        <T::Signature as Verify>::Signer:
+            From<P::Public> + IdentifyAccount<AccountId = T::AccountId>,
+        P: KeystorePair,
+    {
+        Ok(PairSigner::new(self.decrypt::<P>(password)?))
+    }
+}
+
+/// Derives the encryption key from the password using the scrypt parameters at the start
+/// of the encoded data, returning the key and the remaining encrypted data.
+fn scrypt_key<'a>(
+    password: &str,
+    encoded: &'a [u8],
+) -> Result<([u8; 32], &'a [u8]), KeystoreError> {
+    if encoded.len() < SCRYPT_LENGTH {
+        return Err(KeystoreError::InvalidKey(
+            "missing scrypt parameters".into(),
+        ))
+    }
+    let (salt, rest) = encoded.split_at(32);
+    let param = |i: usize| {
+        u32::from_le_bytes(rest[i * 4..(i + 1) * 4].try_into().expect("4 bytes; qed"))
+    };
+    let (n, p, r) = (param(0), param(1), param(2));
+    if !n.is_power_of_two() {
+        return Err(KeystoreError::UnsupportedEncoding(format!(
+            "invalid scrypt N parameter {}",
+            n
+        )))
+    }
+    let params = scrypt::Params::new(n.trailing_zeros() as u8, r, p)
+        .map_err(|e| KeystoreError::UnsupportedEncoding(e.to_string()))?;
+
+    let mut key = [0u8; 32];
+    scrypt::scrypt(password.as_bytes(), salt, &params, &mut key)
+        .map_err(|e| KeystoreError::UnsupportedEncoding(e.to_string()))?;
+    Ok((key, &encoded[SCRYPT_LENGTH..]))
+}
+
+/// Splits a decrypted PKCS8 key into its secret and public key.
+fn decode_pkcs8(decrypted: &[u8]) -> Result<(&[u8], &[u8]), KeystoreError> {
+    let invalid = || KeystoreError::InvalidKey("invalid PKCS8 encoding".into());
+    let rest = decrypted
+        .strip_prefix(&PKCS8_HEADER[..])
+        .ok_or_else(invalid)?;
+    let divider = rest
+        .windows(PKCS8_DIVIDER.len())
+        .rposition(|window| window == PKCS8_DIVIDER)
+        .ok_or_else(invalid)?;
+    Ok((&rest[..divider], &rest[divider + PKCS8_DIVIDER.len()..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keystore_json(pair: &sr25519::Pair, secret: &[u8], password: &str) -> String {
+        let salt = [3u8; 32];
+        let (n, p, r) = (1u32 << 10, 1u32, 8u32);
+        let params = scrypt::Params::new(10, r, p).unwrap();
+        let mut key = [0u8; 32];
+        scrypt::scrypt(password.as_bytes(), &salt, &params, &mut key).unwrap();
+
+        let plaintext = [
+            &PKCS8_HEADER[..],
+            secret,
+            &PKCS8_DIVIDER[..],
+            pair.public().as_ref(),
+        ]
+        .concat();
+        let nonce = [5u8; NONCE_LENGTH];
+        let ciphertext = XSalsa20Poly1305::new(Key::from_slice(&key))
+            .encrypt(Nonce::from_slice(&nonce), &plaintext[..])
+            .unwrap();
+
+        let encoded = [
+            &salt[..],
+            &n.to_le_bytes(),
+            &p.to_le_bytes(),
+            &r.to_le_bytes(),
+            &nonce,
+            &ciphertext,
+        ]
+        .concat();
+        serde_json::json!({
+            "encoded": base64::encode(&encoded),
+            "encoding": {
+                "content": ["pkcs8", "sr25519"],
+                "type": ["scrypt", "xsalsa20-poly1305"],
+                "version": "3"
+            },
+            "address": "",
+            "meta": { "name": "test" }
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn decrypts_sr25519_keystore() {
+        let secret = schnorrkel::MiniSecretKey::from_bytes(&[1; 32])
+            .unwrap()
+            .expand(schnorrkel::ExpansionMode::Ed25519);
+        let pair = sr25519::Pair::from(secret.clone());
+        let json = keystore_json(&pair, &secret.to_ed25519_bytes(), "password");
+        let keystore: KeystoreJson = json.parse().unwrap();
+
+        assert_eq!(keystore.key_type(), Some("sr25519"));
+        let decrypted = keystore.decrypt::<sr25519::Pair>("password").unwrap();
+        assert_eq!(decrypted.public(), pair.public());
+        assert!(matches!(
+            keystore.decrypt::<sr25519::Pair>("wrong"),
+            Err(KeystoreError::InvalidPassword)
+        ));
+        assert!(matches!(
+            keystore.decrypt::<ed25519::Pair>("password"),
+            Err(KeystoreError::UnsupportedEncoding(_))
+        ));
+    }
+}
@@ -45,6 +45,8 @@ pub use subxt_macro::subxt;
 
 pub use bitvec;
 pub use codec;
+#[cfg(feature = "metrics")]
+pub use prometheus;
 pub use sp_core;
 pub use sp_runtime;
 
@@ -56,17 +58,36 @@ use codec::{
 use core::fmt::Debug;
 use derivative::Derivative;
 
+pub mod babe;
+mod blocks;
 mod client;
 mod config;
 mod error;
+pub mod ethereum;
 pub mod events;
 pub mod extrinsic;
+pub mod grandpa;
+pub mod indexer;
+pub mod keystore;
 mod metadata;
+pub mod multisig;
+pub mod proxy;
 pub mod rpc;
+mod runtime_api;
+pub mod session;
 pub mod storage;
+pub mod sudo;
 mod transaction;
 
 pub use crate::{
+    blocks::{
+        AllFinalizedHeaders,
+        BlockDetails,
+        BlockExtrinsic,
+        BlocksClient,
+        HeaderExt,
+        HeaderSubscription,
+    },
     client::{
         Client,
         ClientBuilder,
@@ -78,20 +99,28 @@ pub use crate::{
         DefaultConfig,
     },
     error::{
+        ArithmeticError,
         BasicError,
+        DispatchErrorKind,
         Error,
         GenericError,
         RuntimeError,
+        TokenError,
         TransactionError,
+        TransactionalError,
     },
     events::{
         EventDetails,
         Events,
         RawEventDetails,
+        SubscriptionMetrics,
+        SubscriptionOverflow,
     },
     extrinsic::{
         DefaultExtra,
+        DefaultExtraParams,
         DefaultExtraWithTxPayment,
+        EcdsaSigner,
         PairSigner,
         SignedExtra,
         Signer,
@@ -104,10 +133,31 @@ pub use crate::{
     },
     rpc::{
         BlockNumber,
+        ChainHeadBackend,
+        ChainType,
+        ConnectionEvent,
+        DispatchClass,
+        DryRunResult,
+        ExponentialBackoff,
+        ExtrinsicOrHash,
+        FeeDetails,
+        InclusionFee,
+        LoadBalancedClient,
+        MockRpcClient,
+        PeerInfo,
+        RateLimitedClient,
         ReadProof,
+        ReconnectingClient,
+        RetryPolicy,
+        RetryingClient,
         RpcClient,
+        RpcConnection,
+        RuntimeDispatchInfo,
+        SyncState,
+        SystemHealth,
         SystemProperties,
     },
+    runtime_api::RuntimeApiClient,
     storage::{
         KeyIter,
         StorageEntry,
@@ -115,8 +165,12 @@ pub use crate::{
         StorageMapKey,
     },
     transaction::{
+        DispatchInfo,
+        FinalizedTransaction,
+        Pays,
         TransactionEvents,
         TransactionInBlock,
+        TransactionMonitor,
         TransactionProgress,
         TransactionStatus,
     },
@@ -160,7 +214,7 @@ impl codec::Encode for Encoded {
 }
 
 /// A phase of a block's execution.
-#[derive(Clone, Debug, Eq, PartialEq, Decode, Encode)]
+#[derive(Clone, Debug, Eq, PartialEq, Decode, Encode, serde::Serialize)]
 pub enum Phase {
     /// Applying an extrinsic.
     ApplyExtrinsic(u32),
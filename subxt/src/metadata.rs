@@ -19,7 +19,11 @@ use std::{
     convert::TryFrom,
 };
 
-use codec::Error as CodecError;
+use codec::{
+    Decode,
+    Error as CodecError,
+    Input,
+};
 
 use frame_metadata::{
     PalletConstantMetadata,
@@ -52,6 +56,9 @@ pub enum MetadataError {
     /// Call is not in metadata.
     #[error("Call {0} not found")]
     CallNotFound(&'static str),
+    /// Call index is not in the metadata of the pallet with the given index.
+    #[error("Pallet {0}, Call {1} not found")]
+    CallIndexNotFound(u8, u8),
     /// Event is not in metadata.
     #[error("Pallet {0}, Event {0} not found")]
     EventNotFound(u8, u8),
@@ -73,9 +80,15 @@ pub enum MetadataError {
     /// Constant is not in metadata.
     #[error("Constant {0} not found")]
     ConstantNotFound(&'static str),
+    /// Signed extension is not known, and so cannot be constructed.
+    #[error("Signed extension {0} is not supported")]
+    UnsupportedSignedExtension(String),
     /// Type is not in metadata.
     #[error("Type {0} missing from type registry")]
     TypeNotFound(u32),
+    /// The metadata hash can't be computed.
+    #[error("Can't compute the metadata hash: {0}")]
+    MetadataHash(String),
 }
 
 /// Runtime metadata.
@@ -107,6 +120,25 @@ impl Metadata {
         Ok(event)
     }
 
+    /// Returns the names of the pallet and call at the given pallet and call indices.
+    pub fn call_name(
+        &self,
+        pallet_index: u8,
+        call_index: u8,
+    ) -> Result<(&str, &str), MetadataError> {
+        self.pallets
+            .values()
+            .find(|pallet| pallet.index == pallet_index)
+            .and_then(|pallet| {
+                pallet
+                    .calls
+                    .iter()
+                    .find(|(_, index)| **index == call_index)
+                    .map(|(call, _)| (pallet.name.as_str(), call.as_str()))
+            })
+            .ok_or(MetadataError::CallIndexNotFound(pallet_index, call_index))
+    }
+
     /// Resolve a type definition.
     pub fn resolve_type(&self, id: u32) -> Option<&Type<PortableForm>> {
         self.metadata.types.resolve(id)
@@ -205,6 +237,15 @@ pub enum InvalidMetadataError {
     TypeDefNotVariant(u32),
 }
 
+impl Decode for Metadata {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let metadata = RuntimeMetadataPrefixed::decode(input)?;
+        Metadata::try_from(metadata).map_err(|e| {
+            CodecError::from("Invalid runtime metadata").chain(e.to_string())
+        })
+    }
+}
+
 impl TryFrom<RuntimeMetadataPrefixed> for Metadata {
     type Error = InvalidMetadataError;
 
@@ -300,3 +341,29 @@ impl TryFrom<RuntimeMetadataPrefixed> for Metadata {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codec::Encode;
+    use frame_metadata::v14::ExtrinsicMetadata;
+    use scale_info::meta_type;
+
+    #[test]
+    fn decoding_errors_say_what_is_wrong_with_the_metadata() {
+        let extrinsic = ExtrinsicMetadata {
+            ty: meta_type::<()>(),
+            version: 4,
+            signed_extensions: vec![],
+        };
+        let metadata =
+            RuntimeMetadataLastVersion::new(vec![], extrinsic, meta_type::<()>());
+        let encoded = RuntimeMetadataPrefixed(0, RuntimeMetadata::V14(metadata)).encode();
+
+        let err = Metadata::decode(&mut &encoded[..]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid prefix:\n\tInvalid runtime metadata\n"
+        );
+    }
+}
@@ -0,0 +1,415 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Helpers for working with multisig accounts, as provided by `pallet_multisig`.
+//!
+//! Approving a multisig operation requires the account ids of the other signatories (in
+//! sorted order), the hash of the call being approved, and for every approval after the
+//! first, the [`Timepoint`] at which the operation was created.
+
+use crate::{
+    error::{
+        BasicError,
+        TransactionError,
+    },
+    Call,
+    Client,
+    Config,
+    Metadata,
+    TransactionEvents,
+    WrapperKeepOpaque,
+};
+use codec::{
+    Decode,
+    Encode,
+};
+use derivative::Derivative;
+use sp_core::hashing::blake2_256;
+use sp_runtime::traits::{
+    Header,
+    TrailingZeroInput,
+};
+
+/// The point in time at which a multisig operation was created: the block number and index
+/// of the extrinsic which first called `as_multi` or `approve_as_multi`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Encode, Decode)]
+pub struct Timepoint<BlockNumber> {
+    /// The height of the chain at the point in time.
+    pub height: BlockNumber,
+    /// The index of the extrinsic at the point in time.
+    pub index: u32,
+}
+
+impl<BlockNumber> Timepoint<BlockNumber> {
+    /// Fetches the timepoint of the extrinsic which produced the given events.
+    pub async fn from_events<T, Evs>(
+        client: &Client<T>,
+        events: &TransactionEvents<'_, T, Evs>,
+    ) -> Result<Self, BasicError>
+    where
+        T: Config<BlockNumber = BlockNumber>,
+        Evs: Decode,
+    {
+        let header = client
+            .rpc()
+            .header(Some(events.block_hash()))
+            .await?
+            .ok_or(TransactionError::BlockHashNotFound)?;
+        Ok(Timepoint {
+            height: *header.number(),
+            index: events.extrinsic_index(),
+        })
+    }
+}
+
+/// The `as_multi` call of `pallet_multisig`, which approves a call from a multisig account
+/// and dispatches it once it has enough approvals.
+///
+/// `RuntimeCall` should be the runtime's outer `Call` type, for instance the generated
+/// `runtime_types::polkadot_runtime::Call`.
+#[derive(Clone, Debug, Encode)]
+pub struct AsMulti<AccountId, BlockNumber, RuntimeCall> {
+    /// The number of approvals needed to dispatch the call.
+    pub threshold: u16,
+    /// The sorted signatories of the multisig, other than the one submitting the call.
+    pub other_signatories: Vec<AccountId>,
+    /// The timepoint at which the operation was created, or `None` for the first approval.
+    pub maybe_timepoint: Option<Timepoint<BlockNumber>>,
+    /// The call to dispatch, encoded as the runtime's outer `Call`.
+    pub call: WrapperKeepOpaque<RuntimeCall>,
+    /// Whether to store the call on chain if it's not dispatched by this approval.
+    pub store_call: bool,
+    /// The maximum weight that dispatching the call may take.
+    pub max_weight: u64,
+}
+
+impl<AccountId, BlockNumber, RuntimeCall> Call
+    for AsMulti<AccountId, BlockNumber, RuntimeCall>
+where
+    AccountId: Encode,
+    BlockNumber: Encode,
+    RuntimeCall: Encode,
+{
+    const PALLET: &'static str = "Multisig";
+    const FUNCTION: &'static str = "as_multi";
+}
+
+/// The `approve_as_multi` call of `pallet_multisig`, which approves a call from a multisig
+/// account by its hash, without dispatching it.
+#[derive(Clone, Debug, Encode)]
+pub struct ApproveAsMulti<AccountId, BlockNumber> {
+    /// The number of approvals needed to dispatch the call.
+    pub threshold: u16,
+    /// The sorted signatories of the multisig, other than the one submitting the call.
+    pub other_signatories: Vec<AccountId>,
+    /// The timepoint at which the operation was created, or `None` for the first approval.
+    pub maybe_timepoint: Option<Timepoint<BlockNumber>>,
+    /// The hash of the call to approve.
+    pub call_hash: [u8; 32],
+    /// The maximum weight that dispatching the call may take.
+    pub max_weight: u64,
+}
+
+impl<AccountId: Encode, BlockNumber: Encode> Call
+    for ApproveAsMulti<AccountId, BlockNumber>
+{
+    const PALLET: &'static str = "Multisig";
+    const FUNCTION: &'static str = "approve_as_multi";
+}
+
+/// A multisig account, made up of a set of signatories and the number of approvals needed
+/// to dispatch a call from it.
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = ""),
+    Debug(bound = ""),
+    Eq(bound = ""),
+    PartialEq(bound = "")
+)]
+pub struct Multisig<T: Config> {
+    signatories: Vec<T::AccountId>,
+    threshold: u16,
+}
+
+impl<T> Multisig<T>
+where
+    T: Config,
+    T::AccountId: Ord,
+{
+    /// Creates a new multisig from the given signatories (in any order) and threshold.
+    pub fn new(
+        signatories: impl IntoIterator<Item = T::AccountId>,
+        threshold: u16,
+    ) -> Self {
+        let mut signatories = signatories.into_iter().collect::<Vec<_>>();
+        signatories.sort();
+        signatories.dedup();
+        Self {
+            signatories,
+            threshold,
+        }
+    }
+
+    /// Returns the sorted signatories of the multisig.
+    pub fn signatories(&self) -> &[T::AccountId] {
+        &self.signatories
+    }
+
+    /// Returns the number of approvals needed to dispatch a call.
+    pub fn threshold(&self) -> u16 {
+        self.threshold
+    }
+
+    /// Returns the account id of the multisig, derived in the same way as the runtime does.
+    pub fn account_id(&self) -> Result<T::AccountId, BasicError> {
+        let entropy = (b"modlpy/utilisuba", &self.signatories, self.threshold)
+            .using_encoded(blake2_256);
+        let account_id = T::AccountId::decode(&mut TrailingZeroInput::new(&entropy[..]))?;
+        Ok(account_id)
+    }
+
+    /// Returns the sorted signatories other than the given one, as expected by the
+    /// `other_signatories` argument of `as_multi` and `approve_as_multi`.
+    pub fn other_signatories(&self, signatory: &T::AccountId) -> Vec<T::AccountId> {
+        self.signatories
+            .iter()
+            .filter(|account_id| *account_id != signatory)
+            .cloned()
+            .collect()
+    }
+
+    /// Wraps the given call in an `as_multi` call approving it as the given signatory, which
+    /// dispatches it if this is the last approval needed.
+    ///
+    /// `maybe_timepoint` must be `None` for the first approval, and the [`Timepoint`] at
+    /// which the operation was created for every approval after that.
+    pub fn as_multi<C: Call, R: Decode>(
+        &self,
+        metadata: &Metadata,
+        signatory: &T::AccountId,
+        maybe_timepoint: Option<Timepoint<T::BlockNumber>>,
+        call: &C,
+        store_call: bool,
+        max_weight: u64,
+    ) -> Result<AsMulti<T::AccountId, T::BlockNumber, R>, BasicError> {
+        Ok(AsMulti {
+            threshold: self.threshold,
+            other_signatories: self.other_signatories(signatory),
+            maybe_timepoint,
+            call: opaque_call(metadata, call)?,
+            store_call,
+            max_weight,
+        })
+    }
+
+    /// Wraps the hash of the given call in an `approve_as_multi` call approving it as the
+    /// given signatory.
+    ///
+    /// `maybe_timepoint` must be `None` for the first approval, and the [`Timepoint`] at
+    /// which the operation was created for every approval after that.
+    pub fn approve_as_multi<C: Call>(
+        &self,
+        metadata: &Metadata,
+        signatory: &T::AccountId,
+        maybe_timepoint: Option<Timepoint<T::BlockNumber>>,
+        call: &C,
+        max_weight: u64,
+    ) -> Result<ApproveAsMulti<T::AccountId, T::BlockNumber>, BasicError> {
+        Ok(ApproveAsMulti {
+            threshold: self.threshold,
+            other_signatories: self.other_signatories(signatory),
+            maybe_timepoint,
+            call_hash: call_hash(metadata, call)?,
+            max_weight,
+        })
+    }
+}
+
+/// Returns the hash of the given call, as expected by the `call_hash` argument of
+/// `approve_as_multi` and `cancel_as_multi`.
+pub fn call_hash<C: Call>(metadata: &Metadata, call: &C) -> Result<[u8; 32], BasicError> {
+    let encoded = metadata.pallet(C::PALLET)?.encode_call(call)?;
+    Ok(blake2_256(&encoded.0))
+}
+
+/// Returns the given call encoded as the runtime's outer `Call` type, as expected by the
+/// `call` argument of `as_multi`.
+pub fn opaque_call<C: Call, R: Decode>(
+    metadata: &Metadata,
+    call: &C,
+) -> Result<WrapperKeepOpaque<R>, BasicError> {
+    let encoded = metadata.pallet(C::PALLET)?.encode_call(call)?;
+    Ok(WrapperKeepOpaque::from_encoded(encoded.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DefaultConfig;
+    use frame_metadata::{
+        v14::{
+            ExtrinsicMetadata,
+            PalletCallMetadata,
+            PalletMetadata,
+            RuntimeMetadataLastVersion,
+        },
+        RuntimeMetadataPrefixed,
+    };
+    use scale_info::{
+        meta_type,
+        TypeInfo,
+    };
+    use sp_runtime::AccountId32;
+    use std::convert::TryFrom;
+
+    #[allow(dead_code, non_camel_case_types)]
+    #[derive(TypeInfo, Encode, Decode)]
+    enum TestCall {
+        remark { remark: Vec<u8> },
+    }
+
+    #[derive(Encode)]
+    struct Remark {
+        remark: Vec<u8>,
+    }
+
+    impl Call for Remark {
+        const PALLET: &'static str = "System";
+        const FUNCTION: &'static str = "remark";
+    }
+
+    fn account(byte: u8) -> AccountId32 {
+        AccountId32::new([byte; 32])
+    }
+
+    fn metadata() -> Metadata {
+        let pallets = vec![PalletMetadata {
+            name: "System",
+            storage: None,
+            calls: Some(PalletCallMetadata {
+                ty: meta_type::<TestCall>(),
+            }),
+            event: None,
+            constants: vec![],
+            error: None,
+            index: 7,
+        }];
+        let extrinsic = ExtrinsicMetadata {
+            ty: meta_type::<()>(),
+            version: 4,
+            signed_extensions: vec![],
+        };
+        let metadata: RuntimeMetadataPrefixed =
+            RuntimeMetadataLastVersion::new(pallets, extrinsic, meta_type::<()>()).into();
+        Metadata::try_from(metadata).unwrap()
+    }
+
+    fn remark() -> Remark {
+        Remark {
+            remark: b"hello".to_vec(),
+        }
+    }
+
+    // The remark encoded as the runtime's outer call: pallet index, call index, arguments.
+    fn encoded_remark() -> Vec<u8> {
+        let mut encoded = vec![7, 0];
+        encoded.extend(remark().encode());
+        encoded
+    }
+
+    #[test]
+    fn multisig_account_id_is_independent_of_signatory_order() {
+        let a =
+            Multisig::<DefaultConfig>::new(vec![account(1), account(2), account(3)], 2);
+        let b =
+            Multisig::<DefaultConfig>::new(vec![account(3), account(1), account(2)], 2);
+        let c =
+            Multisig::<DefaultConfig>::new(vec![account(3), account(1), account(2)], 3);
+
+        assert_eq!(a.account_id().unwrap(), b.account_id().unwrap());
+        assert_ne!(a.account_id().unwrap(), c.account_id().unwrap());
+    }
+
+    #[test]
+    fn other_signatories_are_sorted() {
+        let multisig =
+            Multisig::<DefaultConfig>::new(vec![account(3), account(1), account(2)], 2);
+
+        assert_eq!(
+            multisig.other_signatories(&account(2)),
+            vec![account(1), account(3)]
+        );
+    }
+
+    #[test]
+    fn as_multi_carries_the_opaque_call() {
+        let multisig =
+            Multisig::<DefaultConfig>::new(vec![account(3), account(1), account(2)], 2);
+        let timepoint = Timepoint {
+            height: 10,
+            index: 1,
+        };
+
+        let call: AsMulti<_, _, TestCall> = multisig
+            .as_multi(
+                &metadata(),
+                &account(2),
+                Some(timepoint),
+                &remark(),
+                true,
+                1_000,
+            )
+            .unwrap();
+
+        assert_eq!(call.other_signatories, vec![account(1), account(3)]);
+        assert_eq!(call.call.encoded(), &encoded_remark()[..]);
+        assert_eq!(
+            call.encode(),
+            (
+                2u16,
+                vec![account(1), account(3)],
+                Some(timepoint),
+                encoded_remark(),
+                true,
+                1_000u64
+            )
+                .encode()
+        );
+    }
+
+    #[test]
+    fn approve_as_multi_carries_the_call_hash() {
+        let multisig =
+            Multisig::<DefaultConfig>::new(vec![account(3), account(1), account(2)], 2);
+
+        let call = multisig
+            .approve_as_multi(&metadata(), &account(1), None, &remark(), 1_000)
+            .unwrap();
+
+        assert_eq!(call.call_hash, blake2_256(&encoded_remark()));
+        assert_eq!(
+            call.encode(),
+            (
+                2u16,
+                vec![account(2), account(3)],
+                None::<Timepoint<u32>>,
+                blake2_256(&encoded_remark()),
+                1_000u64
+            )
+                .encode()
+        );
+    }
+}
@@ -0,0 +1,86 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Helpers for dispatching calls through a proxy account, as provided by `pallet_proxy`.
+
+use crate::{
+    error::{
+        BasicError,
+        Error,
+    },
+    Call,
+    Config,
+    Encoded,
+    Metadata,
+    TransactionEvents,
+};
+use codec::{
+    Decode,
+    Encode,
+};
+
+/// The `proxy` call of `pallet_proxy`, which dispatches the wrapped call on behalf of the
+/// `real` account.
+#[derive(Clone, Debug, Encode)]
+pub struct Proxy<AccountId, ProxyType> {
+    /// The account that the proxy will make the call on behalf of.
+    pub real: AccountId,
+    /// The exact proxy type to be used and checked for this call, if any.
+    pub force_proxy_type: Option<ProxyType>,
+    /// The call to be made by the `real` account, encoded as the runtime's outer `Call`.
+    pub call: Encoded,
+}
+
+impl<AccountId: Encode, ProxyType: Encode> Call for Proxy<AccountId, ProxyType> {
+    const PALLET: &'static str = "Proxy";
+    const FUNCTION: &'static str = "proxy";
+}
+
+/// Wraps the given call in a `proxy` call, so that it's dispatched on behalf of the `real`
+/// account when submitted by one of its proxies.
+///
+/// `ProxyType` should be the runtime's proxy type, for instance the generated
+/// `runtime_types::polkadot_runtime::ProxyType`.
+pub fn wrap_as_proxy<AccountId, ProxyType, C>(
+    metadata: &Metadata,
+    real: AccountId,
+    force_proxy_type: Option<ProxyType>,
+    call: &C,
+) -> Result<Proxy<AccountId, ProxyType>, BasicError>
+where
+    C: Call,
+{
+    let call = metadata.pallet(C::PALLET)?.encode_call(call)?;
+    Ok(Proxy {
+        real,
+        force_proxy_type,
+        call,
+    })
+}
+
+/// Returns the result of the call dispatched by a `proxy` call, which is reported by its
+/// `ProxyExecuted` event. The `proxy` extrinsic itself succeeds even when the wrapped call
+/// fails, so this should be checked in addition to the extrinsic's success.
+pub fn proxy_result<T, Evs, E>(
+    events: &TransactionEvents<'_, T, Evs>,
+) -> Result<(), Error<E>>
+where
+    T: Config,
+    Evs: Decode,
+    E: Decode,
+{
+    events.find_dispatch_result("Proxy", "ProxyExecuted")
+}
@@ -0,0 +1,201 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+    rpc_params,
+    ClientT,
+    Rpc,
+};
+use crate::{
+    error::BasicError,
+    Config,
+};
+use codec::Decode;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use sp_core::{
+    storage::{
+        StorageData,
+        StorageKey,
+    },
+    Bytes,
+};
+
+// The outcome of a call to `archive_unstable_call`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ArchiveCallResult {
+    success: bool,
+    value: Option<Bytes>,
+    error: Option<String>,
+}
+
+// An item to query with `archive_unstable_storage`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArchiveStorageQuery<'a> {
+    key: &'a StorageKey,
+    #[serde(rename = "type")]
+    query_type: &'static str,
+}
+
+// The items found by `archive_unstable_storage`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ArchiveStorageResult {
+    result: Vec<ArchiveStorageItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ArchiveStorageItem {
+    key: StorageKey,
+    value: Option<StorageData>,
+}
+
+// Wrappers for the `archive` methods of the new JSON-RPC spec (which are still prefixed with
+// `unstable` there). Unlike the `chainHead` methods, these can query any block the node has
+// kept, and keep working for old blocks once the legacy `state_` methods are deprecated.
+impl<T: Config> Rpc<T> {
+    /// Fetch the hashes of the blocks at the given height: the finalized one if the height
+    /// has been finalized, or otherwise any which the node knows of.
+    pub async fn archive_hash_by_height(
+        &self,
+        height: u64,
+    ) -> Result<Vec<T::Hash>, BasicError> {
+        let hashes = self
+            .client
+            .request("archive_unstable_hashByHeight", rpc_params![height])
+            .await?;
+        Ok(hashes)
+    }
+
+    /// Fetch the header of any block the node has kept, or `None` if it doesn't know of it.
+    pub async fn archive_header(
+        &self,
+        hash: T::Hash,
+    ) -> Result<Option<T::Header>, BasicError> {
+        let header: Option<Bytes> = self
+            .client
+            .request("archive_unstable_header", rpc_params![hash])
+            .await?;
+        header
+            .map(|header| Ok(Decode::decode(&mut &header[..])?))
+            .transpose()
+    }
+
+    /// Fetch the SCALE encoded extrinsics in the body of any block the node has kept, or
+    /// `None` if it doesn't know of it.
+    pub async fn archive_body(
+        &self,
+        hash: T::Hash,
+    ) -> Result<Option<Vec<Bytes>>, BasicError> {
+        let body = self
+            .client
+            .request("archive_unstable_body", rpc_params![hash])
+            .await?;
+        Ok(body)
+    }
+
+    /// Fetch the raw value under a storage key at any block the node has kept.
+    pub async fn archive_storage(
+        &self,
+        hash: T::Hash,
+        key: &StorageKey,
+    ) -> Result<Option<StorageData>, BasicError> {
+        let items = [ArchiveStorageQuery {
+            key,
+            query_type: "value",
+        }];
+        let found: ArchiveStorageResult = self
+            .client
+            .request(
+                "archive_unstable_storage",
+                rpc_params![hash, items, Option::<StorageKey>::None],
+            )
+            .await?;
+        Ok(found
+            .result
+            .into_iter()
+            .find(|item| &item.key == key)
+            .and_then(|item| item.value))
+    }
+
+    /// Call a runtime API function (for instance `Metadata_metadata`) with the SCALE
+    /// encoded parameters given at any block the node has kept, handing back its SCALE
+    /// encoded output.
+    pub async fn archive_call(
+        &self,
+        hash: T::Hash,
+        function: &str,
+        call_parameters: &[u8],
+    ) -> Result<Bytes, BasicError> {
+        let call_parameters = Bytes(call_parameters.to_vec());
+        let result: ArchiveCallResult = self
+            .client
+            .request(
+                "archive_unstable_call",
+                rpc_params![hash, function, call_parameters],
+            )
+            .await?;
+        match result {
+            ArchiveCallResult {
+                success: true,
+                value: Some(value),
+                ..
+            } => Ok(value),
+            ArchiveCallResult { error, .. } => {
+                Err(format!(
+                    "Runtime call {} failed: {}",
+                    function,
+                    error.unwrap_or_else(|| "no output".into())
+                )
+                .into())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn archive_results_are_deserialized() {
+        let success: ArchiveCallResult =
+            serde_json::from_str(r#"{"success":true,"value":"0x0102"}"#).unwrap();
+        assert_eq!(success.value, Some(Bytes(vec![1, 2])));
+
+        let failure: ArchiveCallResult =
+            serde_json::from_str(r#"{"success":false,"error":"trap"}"#).unwrap();
+        assert!(!failure.success);
+        assert_eq!(failure.error.as_deref(), Some("trap"));
+
+        let storage: ArchiveStorageResult = serde_json::from_str(
+            r#"{"result":[{"key":"0x01","value":"0x02"}],"discardedItems":0}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            storage.result,
+            vec![ArchiveStorageItem {
+                key: StorageKey(vec![1]),
+                value: Some(StorageData(vec![2])),
+            }]
+        );
+    }
+}
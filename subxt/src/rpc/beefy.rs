@@ -0,0 +1,296 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+    rpc_params,
+    ClientT,
+    Rpc,
+    Subscription,
+    SubscriptionClientT,
+};
+use crate::{
+    error::BasicError,
+    Config,
+};
+use codec::{
+    Decode,
+    Encode,
+};
+use futures::StreamExt;
+use sp_core::{
+    ecdsa,
+    Bytes,
+};
+use std::marker::PhantomData;
+
+/// The ID of the MMR root in a BEEFY [`Payload`].
+pub const MMR_ROOT_ID: [u8; 2] = *b"mh";
+
+/// The data that BEEFY validators sign, as a list of SCALE encoded values (such as the MMR
+/// root) identified by two byte IDs.
+///
+/// # Note
+///
+/// This is copied from `beefy-primitives` to avoid a dependency on that crate. Therefore it
+/// must be kept compatible with that type from the target substrate version.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct Payload(pub Vec<([u8; 2], Vec<u8>)>);
+
+impl Payload {
+    /// Find the value with the ID given, and decode it into `V`.
+    pub fn get_decoded<V: Decode>(&self, id: &[u8; 2]) -> Option<V> {
+        self.0
+            .iter()
+            .find(|(value_id, _)| value_id == id)
+            .and_then(|(_, value)| V::decode(&mut &value[..]).ok())
+    }
+}
+
+/// What the BEEFY validators agree on for a block.
+///
+/// # Note
+///
+/// This is copied from `beefy-primitives` to avoid a dependency on that crate. Therefore it
+/// must be kept compatible with that type from the target substrate version.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct Commitment<BlockNumber> {
+    /// The data signed, such as the MMR root at the block.
+    pub payload: Payload,
+    /// The number of the block the commitment is for.
+    pub block_number: BlockNumber,
+    /// The ID of the validator set which signed the commitment.
+    pub validator_set_id: u64,
+}
+
+/// A [`Commitment`] along with the signatures of the validators, as handed out by the
+/// `beefy_subscribeJustifications` subscription.
+///
+/// Rather than an `Option` for every validator, this is SCALE encoded as the commitment,
+/// followed by a bitfield of which validators signed (most significant bit first), the
+/// number of validators as a `u32`, and then only the signatures given.
+///
+/// # Note
+///
+/// This is copied from `beefy-primitives` to avoid a dependency on that crate. Therefore it
+/// must be kept compatible with that type from the target substrate version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedCommitment<BlockNumber> {
+    /// The commitment signed.
+    pub commitment: Commitment<BlockNumber>,
+    /// The signature of each validator in the set, in order, or `None` for those which
+    /// didn't sign.
+    pub signatures: Vec<Option<ecdsa::Signature>>,
+}
+
+// Whether the bit for the validator with the index given is set in the bitfield of which
+// validators signed a commitment.
+fn has_signed(signatures_from: &[u8], validator: usize) -> bool {
+    signatures_from[validator / 8] & (0b1000_0000 >> (validator % 8)) != 0
+}
+
+impl<BlockNumber: Encode> Encode for SignedCommitment<BlockNumber> {
+    fn encode_to<W: codec::Output + ?Sized>(&self, dest: &mut W) {
+        // Like `beefy-primitives`, this always has room for at least one bit to spare.
+        let mut signatures_from = vec![0u8; self.signatures.len() / 8 + 1];
+        for (validator, signature) in self.signatures.iter().enumerate() {
+            if signature.is_some() {
+                signatures_from[validator / 8] |= 0b1000_0000 >> (validator % 8);
+            }
+        }
+        let signatures: Vec<&ecdsa::Signature> =
+            self.signatures.iter().flatten().collect();
+
+        self.commitment.encode_to(dest);
+        signatures_from.encode_to(dest);
+        (self.signatures.len() as u32).encode_to(dest);
+        signatures.encode_to(dest);
+    }
+}
+
+impl<BlockNumber: Decode> Decode for SignedCommitment<BlockNumber> {
+    fn decode<I: codec::Input>(input: &mut I) -> Result<Self, codec::Error> {
+        let commitment = Commitment::decode(input)?;
+        let signatures_from = Vec::<u8>::decode(input)?;
+        let validator_set_len = u32::decode(input)? as usize;
+        let mut signatures_compact = Vec::<ecdsa::Signature>::decode(input)?.into_iter();
+
+        if signatures_from.len() * 8 < validator_set_len {
+            return Err("Too few bits for the number of validators".into())
+        }
+        let mut signatures = Vec::with_capacity(validator_set_len);
+        for validator in 0..validator_set_len {
+            if has_signed(&signatures_from, validator) {
+                let signature = signatures_compact
+                    .next()
+                    .ok_or("Fewer signatures than validators which signed")?;
+                signatures.push(Some(signature));
+            } else {
+                signatures.push(None);
+            }
+        }
+        if signatures_compact.next().is_some() {
+            return Err("More signatures than validators which signed".into())
+        }
+        Ok(SignedCommitment {
+            commitment,
+            signatures,
+        })
+    }
+}
+
+/// A subscription to the BEEFY justifications finalized by the chain, created by
+/// [`Rpc::subscribe_beefy_justifications()`].
+pub struct BeefyJustifications<T: Config> {
+    subscription: Subscription<Bytes>,
+    marker: PhantomData<T>,
+}
+
+impl<T: Config> BeefyJustifications<T> {
+    /// Wait for the next justification, decoded into its signed commitment. Returns `None`
+    /// if the subscription has ended.
+    pub async fn next(
+        &mut self,
+    ) -> Option<Result<SignedCommitment<T::BlockNumber>, BasicError>> {
+        let res = self.subscription.next().await?;
+        Some(
+            res.map_err(Into::into)
+                .and_then(|bytes| Ok(SignedCommitment::decode(&mut &bytes[..])?)),
+        )
+    }
+}
+
+impl<T: Config> Rpc<T> {
+    /// Subscribe to the BEEFY justifications finalized by the chain, as needed by bridge
+    /// relayers.
+    pub async fn subscribe_beefy_justifications(
+        &self,
+    ) -> Result<BeefyJustifications<T>, BasicError> {
+        let subscription = self
+            .client
+            .subscribe(
+                "beefy_subscribeJustifications",
+                rpc_params![],
+                "beefy_unsubscribeJustifications",
+            )
+            .await?;
+        Ok(BeefyJustifications {
+            subscription,
+            marker: PhantomData,
+        })
+    }
+
+    /// Fetch the hash of the latest block finalized by BEEFY.
+    pub async fn beefy_finalized_head(&self) -> Result<T::Hash, BasicError> {
+        Ok(self
+            .client
+            .request("beefy_getFinalizedHead", rpc_params![])
+            .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_core::H256;
+
+    #[test]
+    fn signed_commitments_are_decoded() {
+        let mmr_root = H256::repeat_byte(1);
+        let signed = SignedCommitment {
+            commitment: Commitment {
+                payload: Payload(vec![(MMR_ROOT_ID, mmr_root.encode())]),
+                block_number: 5u32,
+                validator_set_id: 2,
+            },
+            signatures: vec![None, Some(ecdsa::Signature::from_raw([3; 65]))],
+        };
+
+        let decoded = SignedCommitment::<u32>::decode(&mut &signed.encode()[..]).unwrap();
+        assert_eq!(decoded, signed);
+        assert_eq!(
+            decoded.commitment.payload.get_decoded::<H256>(&MMR_ROOT_ID),
+            Some(mmr_root)
+        );
+
+        // A whole number of bytes' worth of validators still gets a spare byte of bits.
+        let mut signatures = vec![None; 16];
+        signatures[15] = Some(ecdsa::Signature::from_raw([4; 65]));
+        let signed = SignedCommitment {
+            signatures,
+            ..signed
+        };
+        let encoded = signed.encode();
+        assert_eq!(
+            SignedCommitment::<u32>::decode(&mut &encoded[..]).unwrap(),
+            signed
+        );
+    }
+
+    #[test]
+    fn signed_commitments_are_encoded_like_beefy_primitives() {
+        // The layout of the encoded commitment in `beefy-primitives`' own tests, signed
+        // by the last two of four validators (with placeholder signatures).
+        let encoded = hex::decode(
+            [
+                // The payload: the MMR root ID, and the encoded "Hello World!".
+                "04",
+                "6d68",
+                "34",
+                "3048656c6c6f20576f726c6421",
+                // The block number (as a `u128`) and validator set ID.
+                "05000000000000000000000000000000",
+                "0000000000000000",
+                // The bitfield of which validators signed: 0b0011_0000.
+                "04",
+                "30",
+                // The number of validators.
+                "04000000",
+                // The two signatures.
+                "08",
+                "01".repeat(65).as_str(),
+                "02".repeat(65).as_str(),
+            ]
+            .concat(),
+        )
+        .unwrap();
+
+        let decoded = SignedCommitment::<u128>::decode(&mut &encoded[..]).unwrap();
+        assert_eq!(
+            decoded,
+            SignedCommitment {
+                commitment: Commitment {
+                    payload: Payload(vec![(MMR_ROOT_ID, "Hello World!".encode())]),
+                    block_number: 5,
+                    validator_set_id: 0,
+                },
+                signatures: vec![
+                    None,
+                    None,
+                    Some(ecdsa::Signature::from_raw([1; 65])),
+                    Some(ecdsa::Signature::from_raw([2; 65])),
+                ],
+            }
+        );
+        assert_eq!(decoded.encode(), encoded);
+
+        // The signatures given have to match the validators which signed.
+        let mut missing_signature = encoded.clone();
+        missing_signature.truncate(encoded.len() - 65);
+        let len = missing_signature.len() - 66;
+        missing_signature[len] = 0x04;
+        assert!(SignedCommitment::<u128>::decode(&mut &missing_signature[..]).is_err());
+    }
+}
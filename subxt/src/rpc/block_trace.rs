@@ -0,0 +1,185 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+    rpc_params,
+    ClientT,
+    Rpc,
+};
+use crate::{
+    error::BasicError,
+    Config,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use sp_core::storage::StorageKey;
+use std::collections::HashMap;
+
+// The response to `state_traceBlock`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum TraceBlockResponse {
+    TraceError(TraceError),
+    BlockTrace(BlockTrace),
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TraceError {
+    error: String,
+}
+
+/// The spans and events recorded while re-executing a block, as returned from the RPC call
+/// `state_traceBlock`.
+///
+/// # Note
+///
+/// This is copied from `sp-rpc` to avoid a dependency on that crate. Therefore it must be
+/// kept compatible with that type from the target substrate version.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockTrace {
+    /// The hash of the block traced.
+    pub block_hash: String,
+    /// The hash of its parent.
+    pub parent_hash: String,
+    /// The tracing targets that were recorded.
+    pub tracing_targets: String,
+    /// The storage keys that events were recorded for.
+    pub storage_keys: String,
+    /// The RPC methods that events were recorded for.
+    pub methods: String,
+    /// The spans recorded, such as the execution of each extrinsic.
+    pub spans: Vec<TraceSpan>,
+    /// The events recorded, such as each storage access.
+    pub events: Vec<TraceEvent>,
+}
+
+/// A span recorded while re-executing a block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceSpan {
+    /// The ID of the span, which is unique within the trace.
+    pub id: u64,
+    /// The ID of the span which this one is nested in, if any.
+    pub parent_id: Option<u64>,
+    /// The name of the span.
+    pub name: String,
+    /// The target of the span.
+    pub target: String,
+    /// Whether the span was recorded within the Wasm runtime.
+    pub wasm: bool,
+}
+
+/// An event recorded while re-executing a block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceEvent {
+    /// The target of the event. Storage accesses have the target `state`.
+    pub target: String,
+    /// The values recorded with the event, such as the `key` and `method` of a storage
+    /// access.
+    pub data: TraceEventData,
+    /// The ID of the span which the event was recorded in, if any.
+    pub parent_id: Option<u64>,
+}
+
+/// The values recorded with a [`TraceEvent`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceEventData {
+    /// The values recorded, by name.
+    pub string_values: HashMap<String, String>,
+}
+
+impl<T: Config> Rpc<T> {
+    /// Re-execute a block, handing back the spans and events recorded while doing so. This
+    /// shows, for instance, which storage keys each extrinsic read and wrote, which is
+    /// useful when debugging weights.
+    ///
+    /// `targets` is a comma separated list of the tracing targets to record (the node's
+    /// defaults are used if `None` is given), and events are only recorded for the
+    /// `storage_keys` given, or for every key if none are given. `methods` is a comma
+    /// separated list of the RPC methods to record events for.
+    ///
+    /// This is an unsafe RPC method, and the node must be run with `--rpc-methods unsafe`
+    /// and built with the `with-tracing` feature to answer it.
+    pub async fn trace_block(
+        &self,
+        block: T::Hash,
+        targets: Option<&str>,
+        storage_keys: &[StorageKey],
+        methods: Option<&str>,
+    ) -> Result<BlockTrace, BasicError> {
+        // The node expects the keys as a comma separated list of unprefixed hex strings.
+        let storage_keys = if storage_keys.is_empty() {
+            None
+        } else {
+            let keys: Vec<_> =
+                storage_keys.iter().map(|key| hex::encode(&key.0)).collect();
+            Some(keys.join(","))
+        };
+        let response: TraceBlockResponse = self
+            .client
+            .request(
+                "state_traceBlock",
+                rpc_params![block, targets, storage_keys, methods],
+            )
+            .await?;
+        match response {
+            TraceBlockResponse::BlockTrace(trace) => Ok(trace),
+            TraceBlockResponse::TraceError(TraceError { error }) => {
+                Err(format!("Failed to trace block: {}", error).into())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_traces_are_deserialized() {
+        let response: TraceBlockResponse = serde_json::from_str(
+            r#"{"blockTrace":{
+                "blockHash":"0x01","parentHash":"0x00","tracingTargets":"state",
+                "storageKeys":"","methods":"",
+                "spans":[{"id":1,"parentId":null,"name":"apply_extrinsic","target":"runtime","wasm":true}],
+                "events":[{"target":"state","data":{"stringValues":{"key":"aa"}},"parentId":1}]
+            }}"#,
+        )
+        .unwrap();
+        let trace = match response {
+            TraceBlockResponse::BlockTrace(trace) => trace,
+            other => panic!("Unexpected response {:?}", other),
+        };
+        assert_eq!(trace.spans[0].name, "apply_extrinsic");
+        assert_eq!(trace.events[0].parent_id, Some(1));
+        assert_eq!(trace.events[0].data.string_values["key"], "aa");
+
+        let error: TraceBlockResponse =
+            serde_json::from_str(r#"{"traceError":{"error":"no tracing"}}"#).unwrap();
+        assert_eq!(
+            error,
+            TraceBlockResponse::TraceError(TraceError {
+                error: "no tracing".into()
+            })
+        );
+    }
+}
@@ -0,0 +1,789 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+    rpc_params,
+    DeserializeOwned,
+    JsonValue,
+    ParamsSer,
+    Rpc,
+    RpcError,
+    RuntimeVersion,
+    Subscription,
+};
+use crate::{
+    error::BasicError,
+    storage::{
+        StorageEntry,
+        StorageKeyPrefix,
+    },
+    Config,
+    Metadata,
+};
+use codec::Decode;
+use frame_metadata::RuntimeMetadataPrefixed;
+use futures::{
+    FutureExt,
+    StreamExt,
+};
+use jsonrpsee::core::client::SubscriptionKind;
+use serde::Deserialize;
+use sp_core::{
+    storage::{
+        StorageData,
+        StorageKey,
+    },
+    Bytes,
+};
+use std::{
+    collections::HashSet,
+    convert::TryFrom,
+};
+
+/// An event reported by a `chainHead_unstable_follow` subscription, describing how the
+/// chain that the node is following changes.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "event")]
+pub enum FollowEvent<Hash> {
+    /// The first event reported, giving the latest finalized block. This and every block
+    /// reported after it are pinned until they're unpinned.
+    #[serde(rename_all = "camelCase")]
+    Initialized {
+        /// The hash of the latest finalized block.
+        finalized_block_hash: Hash,
+        /// The runtime of that block, if runtime updates were asked for.
+        finalized_block_runtime: Option<JsonValue>,
+    },
+    /// A new block has been added to the chain (though not necessarily to the best chain).
+    #[serde(rename_all = "camelCase")]
+    NewBlock {
+        /// The hash of the new block.
+        block_hash: Hash,
+        /// The hash of its parent.
+        parent_block_hash: Hash,
+        /// The runtime of the block, if runtime updates were asked for and it differs from
+        /// the runtime of its parent.
+        new_runtime: Option<JsonValue>,
+    },
+    /// The best block has changed.
+    #[serde(rename_all = "camelCase")]
+    BestBlockChanged {
+        /// The hash of the new best block.
+        best_block_hash: Hash,
+    },
+    /// Some blocks have been finalized, and others pruned as a result.
+    #[serde(rename_all = "camelCase")]
+    Finalized {
+        /// The hashes of the newly finalized blocks, oldest first.
+        finalized_block_hashes: Vec<Hash>,
+        /// The hashes of the blocks which are no longer part of the chain.
+        pruned_block_hashes: Vec<Hash>,
+    },
+    /// The node has stopped following the chain for us (for instance because it couldn't
+    /// keep up), and a new subscription needs making.
+    Stop,
+}
+
+// The version of a runtime reported by a `chainHead_unstable_follow` subscription (in
+// `FollowEvent::Initialized` or `FollowEvent::NewBlock`), or an error if the node reported
+// the runtime as invalid.
+pub(crate) fn runtime_version_of(
+    runtime: &JsonValue,
+) -> Result<RuntimeVersion, BasicError> {
+    match runtime.get("type").and_then(JsonValue::as_str) {
+        Some("valid") => Ok(RuntimeVersion::deserialize(&runtime["spec"])?),
+        _ => {
+            let error = runtime.get("error").and_then(JsonValue::as_str);
+            Err(RpcError::Custom(format!(
+                "The node reported an invalid runtime: {}",
+                error.unwrap_or("no reason given")
+            ))
+            .into())
+        }
+    }
+}
+
+// The outcome of an operation (such as fetching storage) on a pinned block, as reported by
+// the subscription which the operation hands back.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "event")]
+enum OperationEvent<T> {
+    Done {
+        #[serde(alias = "output")]
+        value: T,
+    },
+    Inaccessible,
+    Error {
+        error: String,
+    },
+    Disjoint,
+}
+
+/// A backend which talks to the node using the `chainHead` methods of the new JSON-RPC
+/// spec (which are still prefixed with `unstable` there) rather than the legacy `state_`
+/// and `chain_` ones, so that it works with light clients and with nodes which no longer
+/// support the legacy methods.
+///
+/// The backend follows the chain with a `chainHead_unstable_follow` subscription, whose
+/// events are handed back by [`ChainHeadBackend::next_event()`]. Storage, runtime calls,
+/// headers and bodies can only be fetched for blocks which that subscription has reported
+/// and which haven't been unpinned (see [`ChainHeadBackend::unpin()`]) since. Obtain one
+/// with [`Rpc::chain_head()`]; a client built with
+/// [`crate::ClientBuilder::set_chain_head_backend()`] keeps its own, which it unpins blocks
+/// on as the chain moves on.
+///
+/// If the node stops following the chain for us, or the subscription ends (for instance
+/// because the connection to the node dropped), [`FollowEvent::Stop`] is handed back, and
+/// the chain is followed afresh the next time an event is asked for, starting with a new
+/// [`FollowEvent::Initialized`] event. Any blocks pinned before then can no longer be
+/// queried. Following afresh uses the same RPC client, so after the connection drops it only
+/// succeeds if the client is able to reconnect.
+pub struct ChainHeadBackend<T: Config> {
+    rpc: Rpc<T>,
+    with_runtime: bool,
+    // `None` once the node has stopped following the chain for us.
+    subscription: Option<Subscription<FollowEvent<T::Hash>>>,
+    // The ID of the follow subscription, which every other call needs to be given.
+    follow_id: JsonValue,
+}
+
+impl<T: Config> std::fmt::Debug for ChainHeadBackend<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChainHeadBackend")
+            .field("with_runtime", &self.with_runtime)
+            .field("follow_id", &self.follow_id)
+            .finish()
+    }
+}
+
+impl<T: Config> ChainHeadBackend<T> {
+    pub(crate) async fn follow(
+        rpc: Rpc<T>,
+        with_runtime: bool,
+    ) -> Result<Self, BasicError> {
+        let (subscription, follow_id) = Self::subscribe(&rpc, with_runtime).await?;
+        Ok(ChainHeadBackend {
+            rpc,
+            with_runtime,
+            subscription: Some(subscription),
+            follow_id,
+        })
+    }
+
+    async fn subscribe(
+        rpc: &Rpc<T>,
+        with_runtime: bool,
+    ) -> Result<(Subscription<FollowEvent<T::Hash>>, JsonValue), BasicError> {
+        let subscription: Subscription<FollowEvent<T::Hash>> = rpc
+            .subscribe(
+                "chainHead_unstable_follow",
+                rpc_params![with_runtime],
+                "chainHead_unstable_unfollow",
+            )
+            .await?;
+        let follow_id = match subscription.kind() {
+            SubscriptionKind::Subscription(id) => serde_json::to_value(id)?,
+            _ => {
+                return Err(RpcError::Custom(
+                    "chainHead_unstable_follow didn't hand back a subscription ID".into(),
+                )
+                .into())
+            }
+        };
+        Ok((subscription, follow_id))
+    }
+
+    /// Wait for the next change to the chain being followed. If the node stopped following
+    /// the chain for us, it's followed afresh first; an error is handed back if that fails,
+    /// and following afresh is tried again next time.
+    pub async fn next_event(&mut self) -> Result<FollowEvent<T::Hash>, BasicError> {
+        let subscription = match &mut self.subscription {
+            Some(subscription) => subscription,
+            None => {
+                let (subscription, follow_id) =
+                    Self::subscribe(&self.rpc, self.with_runtime).await?;
+                self.follow_id = follow_id;
+                self.subscription.insert(subscription)
+            }
+        };
+        match subscription.next().await {
+            Some(Ok(FollowEvent::Stop)) | None => {
+                self.subscription = None;
+                Ok(FollowEvent::Stop)
+            }
+            Some(res) => res.map_err(Into::into),
+        }
+    }
+
+    // Wait for the `Initialized` event that a follow subscription starts with, handing
+    // back the hash of the latest finalized block, and the version of its runtime if
+    // runtime updates were asked for.
+    pub(crate) async fn initialized(
+        &mut self,
+    ) -> Result<(T::Hash, Option<RuntimeVersion>), BasicError> {
+        match self.next_event().await? {
+            FollowEvent::Initialized {
+                finalized_block_hash,
+                finalized_block_runtime,
+            } => {
+                let runtime_version = finalized_block_runtime
+                    .as_ref()
+                    .map(runtime_version_of)
+                    .transpose()?;
+                Ok((finalized_block_hash, runtime_version))
+            }
+            event => {
+                Err(RpcError::Custom(format!(
+                    "Expected chainHead_unstable_follow to start with an initialized \
+                     event, got {:?}",
+                    event
+                ))
+                .into())
+            }
+        }
+    }
+
+    /// Fetch the hash of the genesis block.
+    pub async fn genesis_hash(&self) -> Result<T::Hash, BasicError> {
+        Ok(self
+            .rpc
+            .request("chainHead_unstable_genesisHash", rpc_params![])
+            .await?)
+    }
+
+    /// Fetch the metadata of the runtime of a pinned block.
+    pub async fn metadata(&self, hash: T::Hash) -> Result<Metadata, BasicError> {
+        let bytes = self.call(hash, "Metadata_metadata", &[]).await?;
+        // The metadata is handed back as a SCALE encoded `Vec<u8>`.
+        let bytes: Vec<u8> = Decode::decode(&mut &bytes[..])?;
+        let meta: RuntimeMetadataPrefixed = Decode::decode(&mut &bytes[..])?;
+        Ok(Metadata::try_from(meta)?)
+    }
+
+    /// Fetch the header of a pinned block.
+    pub async fn header(&self, hash: T::Hash) -> Result<Option<T::Header>, BasicError> {
+        let header: Option<Bytes> = self
+            .rpc
+            .request(
+                "chainHead_unstable_header",
+                rpc_params![&self.follow_id, hash],
+            )
+            .await?;
+        header
+            .map(|header| Ok(Decode::decode(&mut &header[..])?))
+            .transpose()
+    }
+
+    /// Fetch the SCALE encoded extrinsics in the body of a pinned block.
+    pub async fn body(&self, hash: T::Hash) -> Result<Vec<Bytes>, BasicError> {
+        self.operation(
+            "chainHead_unstable_body",
+            rpc_params![&self.follow_id, hash],
+            "chainHead_unstable_stopBody",
+        )
+        .await
+    }
+
+    /// Fetch the raw value under a storage key at a pinned block.
+    pub async fn storage(
+        &self,
+        hash: T::Hash,
+        key: &StorageKey,
+    ) -> Result<Option<StorageData>, BasicError> {
+        self.operation(
+            "chainHead_unstable_storage",
+            rpc_params![&self.follow_id, hash, key],
+            "chainHead_unstable_stopStorage",
+        )
+        .await
+    }
+
+    /// Fetch and decode a storage entry at a pinned block.
+    pub async fn fetch<F: StorageEntry>(
+        &self,
+        hash: T::Hash,
+        store: &F,
+    ) -> Result<Option<F::Value>, BasicError> {
+        let prefix = StorageKeyPrefix::new::<F>();
+        let key = store.key().final_key(prefix);
+        match self.storage(hash, &key).await? {
+            Some(data) => Ok(Some(Decode::decode(&mut &data.0[..])?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Call a runtime API function (for instance `Metadata_metadata`) with the SCALE
+    /// encoded parameters given at a pinned block, handing back its SCALE encoded output.
+    pub async fn call(
+        &self,
+        hash: T::Hash,
+        function: &str,
+        call_parameters: &[u8],
+    ) -> Result<Bytes, BasicError> {
+        let call_parameters = Bytes(call_parameters.to_vec());
+        self.operation(
+            "chainHead_unstable_call",
+            rpc_params![&self.follow_id, hash, function, call_parameters],
+            "chainHead_unstable_stopCall",
+        )
+        .await
+    }
+
+    /// Let the node know that we're done with a block, so that it doesn't need to keep it
+    /// around for us any longer.
+    pub async fn unpin(&self, hash: T::Hash) -> Result<(), BasicError> {
+        let _: JsonValue = self
+            .rpc
+            .request(
+                "chainHead_unstable_unpin",
+                rpc_params![&self.follow_id, hash],
+            )
+            .await?;
+        Ok(())
+    }
+
+    // Start an operation on a pinned block, and wait for its outcome.
+    async fn operation<'a, R: DeserializeOwned>(
+        &self,
+        method: &'a str,
+        params: Option<ParamsSer<'a>>,
+        stop_method: &'a str,
+    ) -> Result<R, BasicError> {
+        let mut subscription: Subscription<OperationEvent<R>> =
+            self.rpc.subscribe(method, params, stop_method).await?;
+        let reason = match subscription.next().await {
+            Some(Ok(OperationEvent::Done { value })) => return Ok(value),
+            Some(Ok(OperationEvent::Inaccessible)) => {
+                "the node couldn't access what was asked for".to_string()
+            }
+            Some(Ok(OperationEvent::Error { error })) => error,
+            Some(Ok(OperationEvent::Disjoint)) => {
+                "the follow subscription has stopped".to_string()
+            }
+            Some(Err(e)) => return Err(e.into()),
+            None => "the subscription was dropped".to_string(),
+        };
+        Err(RpcError::Custom(format!("{} failed: {}", method, reason)).into())
+    }
+}
+
+// A `ChainHeadBackend` along with the blocks it has pinned, which a client routes storage
+// requests through. The follow subscription is caught up with before each request, and
+// blocks are unpinned once they've been pruned, or once a later block has been finalized,
+// so that the node only needs to keep around the latest finalized block and those which
+// descend from it.
+pub(crate) struct FollowedChain<T: Config> {
+    backend: ChainHeadBackend<T>,
+    pinned: HashSet<T::Hash>,
+    // `None` while the chain is being followed afresh.
+    finalized: Option<T::Hash>,
+    best: Option<T::Hash>,
+}
+
+impl<T: Config> FollowedChain<T> {
+    // Start from the latest finalized block, as reported by the `Initialized` event which
+    // the backend's subscription started with.
+    pub(crate) fn new(backend: ChainHeadBackend<T>, finalized: T::Hash) -> Self {
+        FollowedChain {
+            backend,
+            pinned: std::iter::once(finalized).collect(),
+            finalized: Some(finalized),
+            best: Some(finalized),
+        }
+    }
+
+    // Fetch the raw value under a storage key at the given block, or at the best block if
+    // none is given. `None` is handed back if that block isn't pinned, or the request
+    // can't be made with the backend for some other reason, in which case the legacy
+    // methods should be used instead.
+    pub(crate) async fn storage(
+        &mut self,
+        key: &StorageKey,
+        hash: Option<T::Hash>,
+    ) -> Option<Option<StorageData>> {
+        if let Err(e) = self.catch_up().await {
+            log::debug!("Couldn't catch up with the followed chain: {}", e);
+            return None
+        }
+        let hash = hash.or(self.best)?;
+        if !self.pinned.contains(&hash) {
+            return None
+        }
+        match self.backend.storage(hash, key).await {
+            Ok(data) => Some(data),
+            Err(e) => {
+                log::debug!("Couldn't fetch storage at {:?}: {}", hash, e);
+                None
+            }
+        }
+    }
+
+    // Handle the follow events that have arrived since last time, without waiting for
+    // more. If the node stopped following the chain for us, it's followed afresh.
+    async fn catch_up(&mut self) -> Result<(), BasicError> {
+        loop {
+            let event = match &mut self.backend.subscription {
+                Some(subscription) => {
+                    match subscription.next().now_or_never() {
+                        None => return Ok(()),
+                        Some(Some(Ok(event))) => event,
+                        Some(Some(Err(e))) => return Err(e.into()),
+                        // The subscription has ended; `next_event()` will follow the
+                        // chain afresh.
+                        Some(None) => {
+                            self.backend.subscription = None;
+                            FollowEvent::Stop
+                        }
+                    }
+                }
+                None => self.backend.next_event().await?,
+            };
+            for hash in self.handle(event) {
+                if let Err(e) = self.backend.unpin(hash).await {
+                    log::debug!("Couldn't unpin {:?}: {}", hash, e);
+                }
+            }
+        }
+    }
+
+    // Keep track of the blocks pinned by a follow event, handing back those which should
+    // now be unpinned.
+    fn handle(&mut self, event: FollowEvent<T::Hash>) -> Vec<T::Hash> {
+        match event {
+            FollowEvent::Initialized {
+                finalized_block_hash,
+                ..
+            } => {
+                self.pinned = std::iter::once(finalized_block_hash).collect();
+                self.finalized = Some(finalized_block_hash);
+                self.best = Some(finalized_block_hash);
+                Vec::new()
+            }
+            FollowEvent::NewBlock { block_hash, .. } => {
+                self.pinned.insert(block_hash);
+                Vec::new()
+            }
+            FollowEvent::BestBlockChanged { best_block_hash } => {
+                self.best = Some(best_block_hash);
+                Vec::new()
+            }
+            FollowEvent::Finalized {
+                finalized_block_hashes,
+                pruned_block_hashes,
+            } => {
+                let latest = match finalized_block_hashes.last() {
+                    Some(latest) => *latest,
+                    None => return Vec::new(),
+                };
+                // Only the latest of the finalized blocks is kept pinned.
+                let unpin: Vec<_> = self
+                    .finalized
+                    .replace(latest)
+                    .into_iter()
+                    .chain(finalized_block_hashes)
+                    .chain(pruned_block_hashes)
+                    .filter(|hash| *hash != latest && self.pinned.remove(hash))
+                    .collect();
+                if !self.best.map_or(false, |best| self.pinned.contains(&best)) {
+                    self.best = Some(latest);
+                }
+                unpin
+            }
+            // The node unpins every block when it stops following the chain for us.
+            FollowEvent::Stop => {
+                self.pinned.clear();
+                self.finalized = None;
+                self.best = None;
+                Vec::new()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rpc::MockRpcClient,
+        Client,
+        ClientBuilder,
+        DefaultConfig,
+    };
+    use codec::Encode;
+    use frame_metadata::v14::{
+        ExtrinsicMetadata,
+        RuntimeMetadataLastVersion,
+    };
+    use scale_info::meta_type;
+    use serde_json::json;
+    use sp_core::H256;
+
+    fn initialized(byte: u8, runtime: Option<JsonValue>) -> JsonValue {
+        json!({
+            "event": "initialized",
+            "finalizedBlockHash": H256::repeat_byte(byte),
+            "finalizedBlockRuntime": runtime,
+        })
+    }
+
+    fn runtime(spec_version: u32) -> JsonValue {
+        json!({
+            "type": "valid",
+            "spec": { "specVersion": spec_version, "transactionVersion": 1 },
+        })
+    }
+
+    #[test]
+    fn follow_events_are_deserialized() {
+        let initialized: FollowEvent<H256> = serde_json::from_str(&format!(
+            r#"{{"event":"initialized","finalizedBlockHash":"{:?}"}}"#,
+            H256::repeat_byte(1)
+        ))
+        .unwrap();
+        assert_eq!(
+            initialized,
+            FollowEvent::Initialized {
+                finalized_block_hash: H256::repeat_byte(1),
+                finalized_block_runtime: None,
+            }
+        );
+
+        let finalized: FollowEvent<H256> = serde_json::from_str(&format!(
+            r#"{{"event":"finalized","finalizedBlockHashes":["{:?}"],"prunedBlockHashes":[]}}"#,
+            H256::repeat_byte(2)
+        ))
+        .unwrap();
+        assert_eq!(
+            finalized,
+            FollowEvent::Finalized {
+                finalized_block_hashes: vec![H256::repeat_byte(2)],
+                pruned_block_hashes: vec![],
+            }
+        );
+
+        let stop: FollowEvent<H256> =
+            serde_json::from_str(r#"{"event":"stop"}"#).unwrap();
+        assert_eq!(stop, FollowEvent::Stop);
+    }
+
+    #[test]
+    fn operation_events_are_deserialized() {
+        let storage: OperationEvent<Option<StorageData>> =
+            serde_json::from_str(r#"{"event":"done","value":"0x0102"}"#).unwrap();
+        assert_eq!(
+            storage,
+            OperationEvent::Done {
+                value: Some(StorageData(vec![1, 2]))
+            }
+        );
+
+        let call: OperationEvent<Bytes> =
+            serde_json::from_str(r#"{"event":"done","output":"0x03"}"#).unwrap();
+        assert_eq!(
+            call,
+            OperationEvent::Done {
+                value: Bytes(vec![3])
+            }
+        );
+
+        let inaccessible: OperationEvent<Bytes> =
+            serde_json::from_str(r#"{"event":"inaccessible"}"#).unwrap();
+        assert_eq!(inaccessible, OperationEvent::Inaccessible);
+    }
+
+    #[test]
+    fn runtime_versions_are_parsed() {
+        let version = runtime_version_of(&runtime(5)).unwrap();
+        assert_eq!((version.spec_version, version.transaction_version), (5, 1));
+
+        let invalid = json!({ "type": "invalid", "error": "no Core_version" });
+        assert!(matches!(
+            runtime_version_of(&invalid),
+            Err(BasicError::Rpc(RpcError::Custom(msg))) if msg.contains("no Core_version")
+        ));
+    }
+
+    #[async_std::test]
+    async fn follows_the_chain_afresh_after_stopping() {
+        let mock = MockRpcClient::new()
+            .add_subscription(
+                "chainHead_unstable_follow",
+                vec![initialized(1, None), json!({ "event": "stop" })],
+            )
+            .add_subscription("chainHead_unstable_follow", vec![initialized(2, None)])
+            .add_response("chainHead_unstable_unpin", JsonValue::Null);
+        let rpc = Rpc::<DefaultConfig>::new(mock.clone());
+        let mut backend = rpc.chain_head(false).await.unwrap();
+
+        assert_eq!(
+            backend.initialized().await.unwrap(),
+            (H256::repeat_byte(1), None)
+        );
+        assert_eq!(backend.next_event().await.unwrap(), FollowEvent::Stop);
+        assert_eq!(
+            backend.initialized().await.unwrap(),
+            (H256::repeat_byte(2), None)
+        );
+
+        // Blocks are pinned by the new subscription from then on.
+        backend.unpin(H256::repeat_byte(2)).await.unwrap();
+        assert_eq!(
+            mock.requests_to("chainHead_unstable_unpin"),
+            vec![json!(["mock_subscription_1", H256::repeat_byte(2)])]
+        );
+    }
+
+    #[async_std::test]
+    async fn a_dropped_connection_stops_following() {
+        let mock = MockRpcClient::new().add_subscription_then_close(
+            "chainHead_unstable_follow",
+            vec![initialized(1, None)],
+        );
+        let rpc = Rpc::<DefaultConfig>::new(mock);
+        let mut backend = rpc.chain_head(false).await.unwrap();
+
+        backend.initialized().await.unwrap();
+        assert_eq!(backend.next_event().await.unwrap(), FollowEvent::Stop);
+        // The connection can't be re-established, so following afresh fails.
+        assert!(backend.next_event().await.is_err());
+    }
+
+    // A mock answering the requests made to build a client with the chainHead backend,
+    // whose follow subscription hands back the events given after the initialized one.
+    fn chain_head_mock(events: Vec<JsonValue>) -> MockRpcClient {
+        let extrinsic = ExtrinsicMetadata {
+            ty: meta_type::<()>(),
+            version: 4,
+            signed_extensions: vec![],
+        };
+        let meta = RuntimeMetadataLastVersion::new(vec![], extrinsic, meta_type::<()>());
+        let metadata: RuntimeMetadataPrefixed = meta.into();
+        // `Metadata_metadata` hands back the metadata as a SCALE encoded `Vec<u8>`.
+        let output = Bytes(metadata.encode().encode());
+        let events = std::iter::once(initialized(1, Some(runtime(7)))).chain(events);
+        MockRpcClient::new()
+            .add_subscription("chainHead_unstable_follow", events)
+            .add_subscription(
+                "chainHead_unstable_call",
+                vec![json!({ "event": "done", "output": output })],
+            )
+            .add_response("chainHead_unstable_genesisHash", H256::repeat_byte(9))
+            .add_response("system_properties", json!({}))
+    }
+
+    async fn chain_head_client(mock: &MockRpcClient) -> Client<DefaultConfig> {
+        ClientBuilder::new()
+            .set_client(mock.clone())
+            .set_chain_head_backend(true)
+            .build()
+            .await
+            .unwrap()
+    }
+
+    fn new_block(byte: u8, parent: u8) -> JsonValue {
+        json!({
+            "event": "newBlock",
+            "blockHash": H256::repeat_byte(byte),
+            "parentBlockHash": H256::repeat_byte(parent),
+            "newRuntime": null,
+        })
+    }
+
+    #[async_std::test]
+    async fn client_can_be_built_with_the_chain_head_backend() {
+        let mock = chain_head_mock(vec![]);
+        let client = chain_head_client(&mock).await;
+
+        assert_eq!(*client.genesis(), H256::repeat_byte(9));
+        // The metadata was fetched at the finalized block, and the legacy methods weren't
+        // used for any of it.
+        let call = mock.requests_to("chainHead_unstable_call");
+        assert_eq!(call.len(), 1);
+        assert_eq!(call[0][1], json!(H256::repeat_byte(1)));
+        assert_eq!(call[0][2], json!("Metadata_metadata"));
+        assert!(mock.requests_to("state_getMetadata").is_empty());
+        assert!(mock.requests_to("state_getRuntimeVersion").is_empty());
+    }
+
+    #[async_std::test]
+    async fn storage_is_fetched_at_the_blocks_being_followed() {
+        let mock = chain_head_mock(vec![
+            new_block(2, 1),
+            new_block(3, 1),
+            json!({ "event": "bestBlockChanged", "bestBlockHash": H256::repeat_byte(2) }),
+            json!({
+                "event": "finalized",
+                "finalizedBlockHashes": [H256::repeat_byte(2)],
+                "prunedBlockHashes": [H256::repeat_byte(3)],
+            }),
+        ])
+        .add_subscription(
+            "chainHead_unstable_storage",
+            vec![json!({ "event": "done", "value": "0x0102" })],
+        )
+        .add_response("chainHead_unstable_unpin", JsonValue::Null)
+        .add_response("state_getStorage", "0x03");
+        let client = chain_head_client(&mock).await;
+        let key = StorageKey(vec![0xaa]);
+
+        // The best block is pinned, so it's fetched from with the backend.
+        let value = client.storage().fetch_raw(key.clone(), None).await.unwrap();
+        assert_eq!(value, Some(StorageData(vec![1, 2])));
+        let storage = mock.requests_to("chainHead_unstable_storage");
+        assert_eq!(storage.len(), 1);
+        assert_eq!(storage[0][1], json!(H256::repeat_byte(2)));
+        assert_eq!(storage[0][2], json!(key));
+        // The previously finalized block and the pruned one have been unpinned.
+        let unpinned: Vec<_> = mock
+            .requests_to("chainHead_unstable_unpin")
+            .into_iter()
+            .map(|params| params[1].clone())
+            .collect();
+        assert_eq!(
+            unpinned,
+            vec![json!(H256::repeat_byte(1)), json!(H256::repeat_byte(3))]
+        );
+
+        // Blocks that have been unpinned are fetched from with the legacy methods.
+        let value = client
+            .storage()
+            .fetch_raw(key, Some(H256::repeat_byte(1)))
+            .await
+            .unwrap();
+        assert_eq!(value, Some(StorageData(vec![3])));
+        assert_eq!(mock.requests_to("chainHead_unstable_storage").len(), 1);
+        assert_eq!(mock.requests_to("state_getStorage").len(), 1);
+    }
+
+    #[async_std::test]
+    async fn submissions_fall_back_to_the_legacy_method() {
+        let mock = chain_head_mock(vec![])
+            .add_subscription("author_submitAndWatchExtrinsic", vec!["ready"]);
+        let client = chain_head_client(&mock).await;
+
+        // The node doesn't support `transactionWatch_unstable_submitAndWatch`.
+        client
+            .submit_and_watch_raw::<(), ()>(vec![1, 2, 3])
+            .await
+            .unwrap();
+        assert_eq!(
+            mock.requests_to("transactionWatch_unstable_submitAndWatch")
+                .len(),
+            1
+        );
+        assert_eq!(mock.requests_to("author_submitAndWatchExtrinsic").len(), 1);
+    }
+}
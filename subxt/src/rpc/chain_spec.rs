@@ -0,0 +1,126 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+    rpc_params,
+    ChainType,
+    ClientT,
+    JsonValue,
+    Rpc,
+    SystemProperties,
+};
+use crate::{
+    error::BasicError,
+    Config,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+/// A chain spec, which describes a chain (its genesis state, boot nodes and so on) to a
+/// node or light client.
+///
+/// Only the commonly used fields are parsed; the rest are preserved in
+/// [`ChainSpec::other`], so a chain spec serializes back to what it was parsed from.
+///
+/// # Note
+///
+/// This is copied from `sc-chain-spec` to avoid a dependency on that crate. Therefore it
+/// must be kept compatible with that type from the target substrate version.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainSpec {
+    /// The name of the chain.
+    pub name: String,
+    /// The ID of the chain, which is also the name of its directory in the node's base
+    /// path.
+    pub id: String,
+    /// The type of chain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_type: Option<ChainType>,
+    /// The addresses of the nodes to connect to first.
+    #[serde(default)]
+    pub boot_nodes: Vec<String>,
+    /// The ID of the peer to peer protocol used by the chain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol_id: Option<String>,
+    /// The properties of the chain, such as its token symbol and decimals.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub properties: Option<SystemProperties>,
+    /// The state a node needs to start syncing from the latest finalized block rather than
+    /// from genesis, as added by the RPC call `sync_state_genSyncSpec`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub light_sync_state: Option<JsonValue>,
+    /// The genesis state of the chain, either as the config of each pallet or, for raw
+    /// chain specs, as the storage entries themselves.
+    pub genesis: JsonValue,
+    /// The other fields of the chain spec.
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, JsonValue>,
+}
+
+impl ChainSpec {
+    /// Serialize the chain spec to JSON, for instance to start a light client with (see
+    /// `rpc::light_client()`).
+    pub fn to_json(&self) -> Result<String, BasicError> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+impl<T: Config> Rpc<T> {
+    /// Fetch the chain spec of the chain that the node is running, along with the state a
+    /// light client needs to start syncing from the latest finalized block rather than from
+    /// genesis. The genesis state is given as raw storage entries if `raw` is true.
+    ///
+    /// The node must be run with the sync state RPC methods enabled (as Polkadot's are).
+    pub async fn sync_state_gen_sync_spec(
+        &self,
+        raw: bool,
+    ) -> Result<ChainSpec, BasicError> {
+        Ok(self
+            .client
+            .request("sync_state_genSyncSpec", rpc_params![raw])
+            .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chain_specs_round_trip() {
+        let json = serde_json::json!({
+            "name": "Development",
+            "id": "dev",
+            "chainType": "Development",
+            "bootNodes": [],
+            "telemetryEndpoints": null,
+            "protocolId": null,
+            "properties": { "tokenDecimals": 12 },
+            "codeSubstitutes": {},
+            "genesis": { "raw": { "top": {}, "childrenDefault": {} } },
+        });
+        let spec: ChainSpec = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(spec.chain_type, Some(ChainType::Development));
+        assert_eq!(spec.other["codeSubstitutes"], serde_json::json!({}));
+
+        let mut expected = json;
+        expected.as_object_mut().unwrap().remove("protocolId");
+        assert_eq!(serde_json::to_value(&spec).unwrap(), expected);
+    }
+}
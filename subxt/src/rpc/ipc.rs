@@ -0,0 +1,221 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+    ws_client_builder,
+    RpcClient,
+    RpcError,
+    WsClientConfig,
+};
+use jsonrpsee::core::client::{
+    TransportReceiverT,
+    TransportSenderT,
+};
+use serde::de::IgnoredAny;
+use std::path::Path;
+use tokio::{
+    io::{
+        AsyncReadExt,
+        AsyncWriteExt,
+    },
+    net::{
+        unix::{
+            OwnedReadHalf,
+            OwnedWriteHalf,
+        },
+        UnixStream,
+    },
+};
+
+// How much more to read from the socket at a time while waiting for a whole message.
+const READ_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, thiserror::Error)]
+enum IpcError {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("The node sent invalid JSON: {0}")]
+    InvalidJson(serde_json::Error),
+    #[error("The node closed the connection")]
+    Closed,
+}
+
+/// Connect to a co-located node over the Unix domain socket at the path given (the node's
+/// IPC endpoint), rather than over WebSocket, so that its RPC port needn't be exposed.
+/// Hand the client to [`crate::ClientBuilder::set_client()`]. Available on Unix with the
+/// `ipc` feature.
+///
+/// Requests are written to the socket one JSON message per line, and the node's responses
+/// and notifications are read back as a stream of JSON messages, with or without newlines
+/// between them.
+pub async fn ipc_client(path: impl AsRef<Path>) -> Result<RpcClient, RpcError> {
+    ipc_client_with_config(path, &Default::default()).await
+}
+
+/// Connect to a co-located node over the Unix domain socket at the path given, configured
+/// as given (only the buffer size and request timeout apply). See [`ipc_client()`].
+pub async fn ipc_client_with_config(
+    path: impl AsRef<Path>,
+    config: &WsClientConfig,
+) -> Result<RpcClient, RpcError> {
+    let stream = UnixStream::connect(path)
+        .await
+        .map_err(|e| RpcError::Transport(e.into()))?;
+    let (reader, writer) = stream.into_split();
+    let receiver = IpcReceiver {
+        reader,
+        buffer: Vec::new(),
+    };
+    Ok(ws_client_builder(config).build(IpcSender { writer }, receiver))
+}
+
+struct IpcSender {
+    writer: OwnedWriteHalf,
+}
+
+#[async_trait::async_trait]
+impl TransportSenderT for IpcSender {
+    type Error = IpcError;
+
+    async fn send(&mut self, msg: String) -> Result<(), Self::Error> {
+        self.writer.write_all(msg.as_bytes()).await?;
+        self.writer.write_all(b"\n").await?;
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<(), Self::Error> {
+        self.writer.shutdown().await?;
+        Ok(())
+    }
+}
+
+struct IpcReceiver {
+    reader: OwnedReadHalf,
+    // What's been read from the socket but not yet handed to the client.
+    buffer: Vec<u8>,
+}
+
+#[async_trait::async_trait]
+impl TransportReceiverT for IpcReceiver {
+    type Error = IpcError;
+
+    async fn receive(&mut self) -> Result<String, Self::Error> {
+        loop {
+            if let Some(message) = next_message(&mut self.buffer)? {
+                return Ok(message)
+            }
+            self.buffer.reserve(READ_SIZE);
+            if self.reader.read_buf(&mut self.buffer).await? == 0 {
+                return Err(IpcError::Closed)
+            }
+        }
+    }
+}
+
+// Take the first JSON message from the buffer, if the whole of it has been read yet.
+fn next_message(buffer: &mut Vec<u8>) -> Result<Option<String>, IpcError> {
+    let mut messages =
+        serde_json::Deserializer::from_slice(buffer).into_iter::<IgnoredAny>();
+    match messages.next() {
+        Some(Ok(_)) => {
+            let end = messages.byte_offset();
+            let message: Vec<u8> = buffer.drain(..end).collect();
+            let message = String::from_utf8(message)
+                .expect("JSON which was parsed from the bytes is valid UTF-8; qed");
+            Ok(Some(message.trim_start().to_string()))
+        }
+        Some(Err(e)) if e.is_eof() => Ok(None),
+        Some(Err(e)) => Err(IpcError::InvalidJson(e)),
+        // There's nothing but whitespace.
+        None => {
+            buffer.clear();
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::{
+        rpc_params,
+        ClientT,
+        JsonValue,
+    };
+    use serde_json::json;
+    use tokio::{
+        io::{
+            AsyncBufReadExt,
+            BufReader,
+        },
+        net::UnixListener,
+    };
+
+    #[test]
+    fn messages_are_split_wherever_they_end() {
+        let mut buffer = br#"{"id":1} [2, {"a":"}"}]"#.to_vec();
+        buffer.extend_from_slice(b"\n{\"id\":");
+        assert_eq!(next_message(&mut buffer).unwrap().unwrap(), r#"{"id":1}"#);
+        assert_eq!(
+            next_message(&mut buffer).unwrap().unwrap(),
+            r#"[2, {"a":"}"}]"#
+        );
+        // The rest of the message hasn't been read yet.
+        assert!(next_message(&mut buffer).unwrap().is_none());
+        buffer.extend_from_slice(b"3}\n");
+        assert_eq!(next_message(&mut buffer).unwrap().unwrap(), r#"{"id":3}"#);
+        assert!(next_message(&mut buffer).unwrap().is_none());
+        assert!(buffer.is_empty());
+
+        buffer.extend_from_slice(b"{]");
+        assert!(matches!(
+            next_message(&mut buffer),
+            Err(IpcError::InvalidJson(_))
+        ));
+    }
+
+    #[async_std::test]
+    async fn requests_are_answered_over_the_socket() {
+        let dir = tempdir::TempDir::new("subxt-ipc").unwrap();
+        let path = dir.path().join("node.ipc");
+        let listener = UnixListener::bind(&path).unwrap();
+        // A node which answers each request with its method, in two halves.
+        async_std::task::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            let mut requests = BufReader::new(reader).lines();
+            while let Some(request) = requests.next_line().await.unwrap() {
+                let request: JsonValue = serde_json::from_str(&request).unwrap();
+                let response = json!({
+                    "jsonrpc": "2.0",
+                    "result": request["method"],
+                    "id": request["id"],
+                })
+                .to_string();
+                let (first, second) = response.split_at(response.len() / 2);
+                writer.write_all(first.as_bytes()).await.unwrap();
+                writer.flush().await.unwrap();
+                writer.write_all(second.as_bytes()).await.unwrap();
+            }
+        });
+
+        let client = ipc_client(&path).await.unwrap();
+        for method in ["system_name", "system_chain"] {
+            let result: String = client.request(method, rpc_params![]).await.unwrap();
+            assert_eq!(result, method);
+        }
+    }
+}
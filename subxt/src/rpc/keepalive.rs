@@ -0,0 +1,333 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+use futures::{
+    future::{
+        self,
+        BoxFuture,
+        Either,
+    },
+    lock::Mutex,
+    FutureExt,
+};
+use futures_timer::Delay;
+use jsonrpsee::core::client::{
+    TransportReceiverT,
+    TransportSenderT,
+};
+use std::{
+    sync::Arc,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+// The ID of the requests sent to keep the connection alive, whose responses are dropped
+// rather than handed to the client.
+const KEEPALIVE_ID: &str = "subxt_keepalive";
+
+// A cheap request, which any node answers (even if only with an error).
+const KEEPALIVE_REQUEST: &str =
+    r#"{"jsonrpc":"2.0","id":"subxt_keepalive","method":"system_health","params":[]}"#;
+
+// An error from a transport wrapped by `keepalive()`.
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum KeepaliveError {
+    #[error("{0}")]
+    Transport(Box<dyn std::error::Error + Send + Sync>),
+    #[error("No messages received from the node for {0:?}")]
+    Inactive(Duration),
+}
+
+impl KeepaliveError {
+    fn transport(e: impl std::error::Error + Send + Sync + 'static) -> Self {
+        KeepaliveError::Transport(Box::new(e))
+    }
+}
+
+// Called with the reason once the connection is closed, or is found to be dead.
+pub(crate) type OnClose = Box<dyn FnOnce(String) + Send>;
+
+// Wrap a transport so that, if nothing is received from the node for `ping_interval`, a
+// request is sent to it to keep the connection alive, and if nothing is received for
+// `max_inactivity`, the connection is considered dead and the receiver hands back an
+// error (closing the client, so that a `ReconnectingClient` reconnects).
+pub(crate) fn keepalive<S, R>(
+    sender: S,
+    receiver: R,
+    ping_interval: Option<Duration>,
+    max_inactivity: Option<Duration>,
+) -> (KeepaliveSender<S>, KeepaliveReceiver<S, R>)
+where
+    S: TransportSenderT + Send,
+    R: TransportReceiverT + Send + 'static,
+{
+    let sender = Arc::new(Mutex::new(sender));
+    let now = Instant::now();
+    let receiver = KeepaliveReceiver {
+        inner: Some(receiver),
+        receiving: None,
+        sender: sender.clone(),
+        ping_interval,
+        max_inactivity,
+        last_received: now,
+        last_pinged: now,
+        on_close: None,
+    };
+    (KeepaliveSender { inner: sender }, receiver)
+}
+
+pub(crate) struct KeepaliveSender<S> {
+    inner: Arc<Mutex<S>>,
+}
+
+#[async_trait::async_trait]
+impl<S: TransportSenderT + Send> TransportSenderT for KeepaliveSender<S> {
+    type Error = KeepaliveError;
+
+    async fn send(&mut self, msg: String) -> Result<(), Self::Error> {
+        let mut sender = self.inner.lock().await;
+        sender.send(msg).await.map_err(KeepaliveError::transport)
+    }
+
+    async fn close(&mut self) -> Result<(), Self::Error> {
+        let mut sender = self.inner.lock().await;
+        sender.close().await.map_err(KeepaliveError::transport)
+    }
+}
+
+// Receiving a message, handing back the receiver along with it once done. This is kept
+// between calls to `receive()` rather than being dropped when it's time to send a ping,
+// since receiving isn't safe to cancel part way through a message.
+type Receiving<R> =
+    BoxFuture<'static, (R, Result<String, <R as TransportReceiverT>::Error>)>;
+
+pub(crate) struct KeepaliveReceiver<S, R: TransportReceiverT> {
+    // The receiver, unless it's busy in `receiving`.
+    inner: Option<R>,
+    receiving: Option<Receiving<R>>,
+    // The sender is shared so that pings can be sent while waiting to receive messages.
+    sender: Arc<Mutex<S>>,
+    ping_interval: Option<Duration>,
+    max_inactivity: Option<Duration>,
+    last_received: Instant,
+    last_pinged: Instant,
+    on_close: Option<OnClose>,
+}
+
+impl<S, R> KeepaliveReceiver<S, R>
+where
+    S: TransportSenderT + Send,
+    R: TransportReceiverT + Send + 'static,
+{
+    // Call the function given once the receiver fails, which closes the client. Since the
+    // client receives in the background, this happens as soon as the connection is closed
+    // (or found to be dead), rather than when the client is next used.
+    pub(crate) fn on_close(mut self, on_close: OnClose) -> Self {
+        self.on_close = Some(on_close);
+        self
+    }
+
+    // How long until we next need to do something other than wait for a message: send a
+    // ping, or give up on the connection. `None` if that's never.
+    fn next_deadline(&self) -> Option<Instant> {
+        let ping = self
+            .ping_interval
+            .map(|interval| self.last_received.max(self.last_pinged) + interval);
+        let inactive = self
+            .max_inactivity
+            .map(|max_inactivity| self.last_received + max_inactivity);
+        match (ping, inactive) {
+            (Some(ping), Some(inactive)) => Some(ping.min(inactive)),
+            (deadline, None) | (None, deadline) => deadline,
+        }
+    }
+
+    async fn receive_message(&mut self) -> Result<String, KeepaliveError> {
+        loop {
+            let mut receiving = match (self.receiving.take(), self.inner.take()) {
+                (Some(receiving), _) => receiving,
+                (None, Some(mut inner)) => {
+                    async move {
+                        let msg = inner.receive().await;
+                        (inner, msg)
+                    }
+                    .boxed()
+                }
+                (None, None) => unreachable!("the receiver is always put back; qed"),
+            };
+
+            let received = match self.next_deadline() {
+                Some(deadline) => {
+                    let wait = deadline.saturating_duration_since(Instant::now());
+                    match future::select(receiving, Delay::new(wait)).await {
+                        Either::Left((received, _)) => Some(received),
+                        Either::Right((_, receiving)) => {
+                            self.receiving = Some(receiving);
+                            None
+                        }
+                    }
+                }
+                None => Some(receiving.await),
+            };
+
+            match received {
+                Some((inner, msg)) => {
+                    self.inner = Some(inner);
+                    let msg = msg.map_err(KeepaliveError::transport)?;
+                    self.last_received = Instant::now();
+                    if !is_keepalive_response(&msg) {
+                        return Ok(msg)
+                    }
+                }
+                None => {
+                    let now = Instant::now();
+                    if let Some(max_inactivity) = self.max_inactivity {
+                        if now >= self.last_received + max_inactivity {
+                            return Err(KeepaliveError::Inactive(max_inactivity))
+                        }
+                    }
+                    if let Some(ping_interval) = self.ping_interval {
+                        if now >= self.last_received.max(self.last_pinged) + ping_interval
+                        {
+                            self.last_pinged = now;
+                            let mut sender = self.sender.lock().await;
+                            sender
+                                .send(KEEPALIVE_REQUEST.to_string())
+                                .await
+                                .map_err(KeepaliveError::transport)?;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<S, R> TransportReceiverT for KeepaliveReceiver<S, R>
+where
+    S: TransportSenderT + Send,
+    R: TransportReceiverT + Send + 'static,
+{
+    type Error = KeepaliveError;
+
+    async fn receive(&mut self) -> Result<String, Self::Error> {
+        let received = self.receive_message().await;
+        if let Err(e) = &received {
+            if let Some(on_close) = self.on_close.take() {
+                on_close(e.to_string());
+            }
+        }
+        received
+    }
+}
+
+fn is_keepalive_response(msg: &str) -> bool {
+    // Only parse the messages which could be responses to our requests.
+    msg.contains(KEEPALIVE_ID)
+        && serde_json::from_str::<serde_json::Value>(msg)
+            .map(|msg| msg.get("id").and_then(|id| id.as_str()) == Some(KEEPALIVE_ID))
+            .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{
+        channel::mpsc,
+        StreamExt,
+    };
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("The channel has been closed")]
+    struct Closed;
+
+    struct ChannelSender(mpsc::UnboundedSender<String>);
+
+    #[async_trait::async_trait]
+    impl TransportSenderT for ChannelSender {
+        type Error = Closed;
+
+        async fn send(&mut self, msg: String) -> Result<(), Self::Error> {
+            self.0.unbounded_send(msg).map_err(|_| Closed)
+        }
+    }
+
+    struct ChannelReceiver(mpsc::UnboundedReceiver<String>);
+
+    #[async_trait::async_trait]
+    impl TransportReceiverT for ChannelReceiver {
+        type Error = Closed;
+
+        async fn receive(&mut self) -> Result<String, Self::Error> {
+            self.0.next().await.ok_or(Closed)
+        }
+    }
+
+    #[async_std::test]
+    async fn idle_connections_are_pinged_and_then_closed() {
+        let (to_node, mut sent) = mpsc::unbounded();
+        let (from_node, received) = mpsc::unbounded();
+        let (_, mut receiver) = keepalive(
+            ChannelSender(to_node),
+            ChannelReceiver(received),
+            Some(Duration::from_millis(20)),
+            Some(Duration::from_millis(100)),
+        );
+
+        // Responses to pings are dropped, but keep the connection alive.
+        from_node
+            .unbounded_send(
+                r#"{"jsonrpc":"2.0","id":"subxt_keepalive","result":{}}"#.into(),
+            )
+            .unwrap();
+        from_node
+            .unbounded_send(r#"{"jsonrpc":"2.0","id":1,"result":{}}"#.into())
+            .unwrap();
+        let msg = receiver.receive().await.unwrap();
+        assert_eq!(msg, r#"{"jsonrpc":"2.0","id":1,"result":{}}"#);
+
+        // With nothing received, pings are sent until the connection is given up on.
+        let err = receiver.receive().await.unwrap_err();
+        assert!(matches!(err, KeepaliveError::Inactive(_)));
+        assert_eq!(sent.next().await.unwrap(), KEEPALIVE_REQUEST);
+    }
+
+    #[async_std::test]
+    async fn closing_is_reported_once() {
+        let (to_node, _sent) = mpsc::unbounded();
+        let (from_node, received) = mpsc::unbounded::<String>();
+        let (closed, mut reasons) = mpsc::unbounded();
+        let (_, receiver) = keepalive(
+            ChannelSender(to_node),
+            ChannelReceiver(received),
+            None,
+            None,
+        );
+        let mut receiver = receiver.on_close(Box::new(move |reason| {
+            closed.unbounded_send(reason).unwrap();
+        }));
+
+        drop(from_node);
+        assert!(receiver.receive().await.is_err());
+        assert!(receiver.receive().await.is_err());
+        assert_eq!(reasons.next().await.unwrap(), "The channel has been closed");
+        // The function is dropped once called, closing the channel.
+        assert_eq!(reasons.next().await, None);
+    }
+}
@@ -0,0 +1,240 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+    RpcClient,
+    RpcClientBuilder,
+    RpcError,
+};
+use jsonrpsee::core::client::{
+    TransportReceiverT,
+    TransportSenderT,
+};
+use smoldot_light::{
+    platform::default::DefaultPlatform,
+    AddChainConfig,
+    AddChainConfigJsonRpc,
+    AddChainSuccess,
+    ChainId,
+    JsonRpcResponses,
+};
+use std::{
+    iter,
+    num::NonZeroU32,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+type SmoldotClient = smoldot_light::Client<Arc<DefaultPlatform>>;
+
+// The most requests that can be waiting for a response from the light client at once.
+const MAX_PENDING_REQUESTS: u32 = 128;
+
+// The most subscriptions that can be open on the light client at once.
+const MAX_SUBSCRIPTIONS: u32 = 1024;
+
+/// An error from the embedded light client.
+#[derive(Debug, thiserror::Error)]
+pub enum LightClientError {
+    /// The chain spec couldn't be used to start syncing the chain.
+    #[error("Failed to add the chain to the light client: {0}")]
+    AddChain(String),
+    /// The light client refused a request (for instance, because too many are pending).
+    #[error("The light client refused the request: {0}")]
+    Request(String),
+    /// The light client has stopped.
+    #[error("The light client has stopped")]
+    Stopped,
+}
+
+/// Start an embedded [smoldot](https://github.com/paritytech/smoldot) light client syncing
+/// the chain described by the chain spec given (the contents of a chain spec JSON file), and
+/// hand back a client which sends requests to it rather than to a node. This lets the chain
+/// be queried and submitted to without trusting any RPC endpoint.
+///
+/// The light client connects to the boot nodes listed in the chain spec, and only serves
+/// requests once it has synced to the head of the chain. Being a light client, it only
+/// keeps recent blocks, so queries of old state may fail. Its background tasks run on a
+/// small pool of threads shared between them. Available with the `light-client` feature.
+pub fn light_client(chain_spec: &str) -> Result<RpcClient, RpcError> {
+    let platform = DefaultPlatform::new(
+        env!("CARGO_PKG_NAME").into(),
+        env!("CARGO_PKG_VERSION").into(),
+    );
+    let mut client = SmoldotClient::new(platform);
+
+    let AddChainSuccess {
+        chain_id,
+        json_rpc_responses,
+    } = client
+        .add_chain(AddChainConfig {
+            user_data: (),
+            specification: chain_spec,
+            database_content: "",
+            potential_relay_chains: iter::empty(),
+            json_rpc: AddChainConfigJsonRpc::Enabled {
+                max_pending_requests: NonZeroU32::new(MAX_PENDING_REQUESTS)
+                    .expect("not zero; qed"),
+                max_subscriptions: MAX_SUBSCRIPTIONS,
+            },
+        })
+        .map_err(|e| {
+            RpcError::Transport(LightClientError::AddChain(e.to_string()).into())
+        })?;
+    let responses = json_rpc_responses.expect("JSON-RPC is enabled for the chain; qed");
+
+    let sender = LightClientSender {
+        client: Arc::new(Mutex::new(client)),
+        chain_id,
+    };
+    let receiver = LightClientReceiver { responses };
+    Ok(RpcClientBuilder::default().build(sender, receiver))
+}
+
+// Hands requests to the light client.
+struct LightClientSender {
+    client: Arc<Mutex<SmoldotClient>>,
+    chain_id: ChainId,
+}
+
+#[async_trait::async_trait]
+impl TransportSenderT for LightClientSender {
+    type Error = LightClientError;
+
+    async fn send(&mut self, msg: String) -> Result<(), Self::Error> {
+        self.client
+            .lock()
+            .expect("lock is never poisoned; qed")
+            .json_rpc_request(msg, self.chain_id)
+            .map_err(|e| LightClientError::Request(e.to_string()))
+    }
+}
+
+// Hands back the responses and notifications from the light client.
+struct LightClientReceiver {
+    responses: JsonRpcResponses,
+}
+
+#[async_trait::async_trait]
+impl TransportReceiverT for LightClientReceiver {
+    type Error = LightClientError;
+
+    async fn receive(&mut self) -> Result<String, Self::Error> {
+        self.responses.next().await.ok_or(LightClientError::Stopped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::{
+        rpc_params,
+        ClientT,
+    };
+    use codec::Encode;
+    use serde_json::json;
+
+    // A Wasm section with the ID and contents given.
+    fn section(id: u8, contents: Vec<u8>) -> Vec<u8> {
+        let mut section = vec![id];
+        let mut len = contents.len();
+        loop {
+            let byte = (len & 0x7f) as u8;
+            len >>= 7;
+            if len == 0 {
+                section.push(byte);
+                break
+            }
+            section.push(byte | 0x80);
+        }
+        section.extend(contents);
+        section
+    }
+
+    // A Wasm custom section with the name and contents given.
+    fn custom_section(name: &str, contents: Vec<u8>) -> Vec<u8> {
+        let mut named = vec![name.len() as u8];
+        named.extend(name.as_bytes());
+        named.extend(contents);
+        section(0, named)
+    }
+
+    // A runtime with no code at all, but with its version in custom sections, which is all
+    // the light client needs to start (since it never runs the runtime without peers).
+    fn runtime() -> Vec<u8> {
+        let mut runtime = b"\0asm\x01\0\0\0".to_vec();
+        // One page of memory.
+        runtime.extend(section(5, vec![1, 0, 1]));
+        // An immutable i32 global, set to 1024.
+        runtime.extend(section(6, vec![1, 0x7f, 0, 0x41, 0x80, 0x08, 0x0b]));
+        // Exported as `memory` and `__heap_base`.
+        let mut exports = vec![2, 6];
+        exports.extend(b"memory");
+        exports.extend([2, 0]);
+        exports.push(11);
+        exports.extend(b"__heap_base");
+        exports.extend([3, 0]);
+        runtime.extend(section(7, exports));
+        let apis: Vec<([u8; 8], u32)> = Vec::new();
+        let version = ("test", "test", 1u32, 1u32, 1u32, apis, 1u32, 1u8);
+        runtime.extend(custom_section("runtime_version", version.encode()));
+        runtime.extend(custom_section("runtime_apis", Vec::new()));
+        runtime
+    }
+
+    fn chain_spec(genesis_storage: serde_json::Value) -> String {
+        json!({
+            "name": "Development",
+            "id": "dev",
+            "chainType": "Development",
+            "bootNodes": [],
+            "telemetryEndpoints": null,
+            "protocolId": null,
+            "properties": null,
+            "codeSubstitutes": {},
+            "genesis": { "raw": { "top": genesis_storage, "childrenDefault": {} } },
+        })
+        .to_string()
+    }
+
+    #[async_std::test]
+    async fn chain_specs_with_a_runtime_are_accepted() {
+        // The key is `:code`, hex encoded.
+        let spec = chain_spec(
+            json!({ "0x3a636f6465": format!("0x{}", hex::encode(runtime())) }),
+        );
+        let client = light_client(&spec).unwrap();
+
+        let chain: String = client.request("system_chain", rpc_params![]).await.unwrap();
+        assert_eq!(chain, "Development");
+    }
+
+    #[test]
+    fn invalid_chain_specs_are_rejected() {
+        for spec in ["not a chain spec", "{}", &chain_spec(json!({}))] {
+            match light_client(spec) {
+                Ok(_) => panic!("{} was accepted", spec),
+                Err(e) => {
+                    assert!(e
+                        .to_string()
+                        .contains("Failed to add the chain to the light client"))
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,160 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+    is_read_only,
+    ws_client,
+    ClientT,
+    DeserializeOwned,
+    RpcConnection,
+    RpcError,
+    Subscription,
+    SubscriptionClientT,
+};
+use jsonrpsee::types::ParamsSer;
+use std::sync::atomic::{
+    AtomicUsize,
+    Ordering,
+};
+
+/// A client which spreads read only requests (such as fetching storage, headers or
+/// events) across several connections in turn, to share the load between several nodes.
+/// Anything else (submitting extrinsics, and all subscriptions) is always sent through
+/// the first connection, so that what it sees stays consistent.
+///
+/// The nodes are expected to be following the same chain. Note that they may not all have
+/// seen the latest blocks at the same time, so asking for the latest state or block (by
+/// not providing a block hash) might hand back results from slightly different blocks.
+pub struct LoadBalancedClient {
+    connections: Vec<RpcConnection>,
+    next: AtomicUsize,
+}
+
+impl LoadBalancedClient {
+    /// Spread read only requests across the connections given. The first connection is the
+    /// one that submissions and subscriptions are pinned to.
+    pub fn new(connections: Vec<RpcConnection>) -> Result<Self, RpcError> {
+        if connections.is_empty() {
+            return Err(RpcError::Custom("No connections given to balance".into()))
+        }
+        Ok(LoadBalancedClient {
+            connections,
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    /// Connect to each of the URLs given, spreading read only requests across them. The
+    /// first URL is the one that submissions and subscriptions are pinned to.
+    pub async fn connect<I, P>(urls: I) -> Result<Self, RpcError>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<str>,
+    {
+        let mut connections = Vec::new();
+        for url in urls {
+            connections.push(ws_client(url.as_ref()).await?.into());
+        }
+        Self::new(connections)
+    }
+
+    /// The connections that requests are spread across.
+    pub fn connections(&self) -> &[RpcConnection] {
+        &self.connections
+    }
+
+    // The connection that submissions and subscriptions are pinned to.
+    fn pinned(&self) -> &RpcConnection {
+        &self.connections[0]
+    }
+
+    // The connection to send the next read only request through.
+    fn next_connection(&self) -> &RpcConnection {
+        let next = self.next.fetch_add(1, Ordering::Relaxed);
+        &self.connections[next % self.connections.len()]
+    }
+
+    fn connection_for(&self, method: &str) -> &RpcConnection {
+        if is_read_only(method) {
+            self.next_connection()
+        } else {
+            self.pinned()
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ClientT for LoadBalancedClient {
+    async fn notification<'a>(
+        &self,
+        method: &'a str,
+        params: Option<ParamsSer<'a>>,
+    ) -> Result<(), RpcError> {
+        self.pinned().notification(method, params).await
+    }
+
+    async fn request<'a, R>(
+        &self,
+        method: &'a str,
+        params: Option<ParamsSer<'a>>,
+    ) -> Result<R, RpcError>
+    where
+        R: DeserializeOwned,
+    {
+        self.connection_for(method).request(method, params).await
+    }
+
+    async fn batch_request<'a, R>(
+        &self,
+        batch: Vec<(&'a str, Option<ParamsSer<'a>>)>,
+    ) -> Result<Vec<R>, RpcError>
+    where
+        R: DeserializeOwned + Default + Clone,
+    {
+        let connection = if batch.iter().all(|(method, _)| is_read_only(method)) {
+            self.next_connection()
+        } else {
+            self.pinned()
+        };
+        connection.batch_request(batch).await
+    }
+}
+
+#[async_trait::async_trait]
+impl SubscriptionClientT for LoadBalancedClient {
+    async fn subscribe<'a, Notif>(
+        &self,
+        subscribe_method: &'a str,
+        params: Option<ParamsSer<'a>>,
+        unsubscribe_method: &'a str,
+    ) -> Result<Subscription<Notif>, RpcError>
+    where
+        Notif: DeserializeOwned,
+    {
+        self.pinned()
+            .subscribe(subscribe_method, params, unsubscribe_method)
+            .await
+    }
+
+    async fn subscribe_to_method<'a, Notif>(
+        &self,
+        method: &'a str,
+    ) -> Result<Subscription<Notif>, RpcError>
+    where
+        Notif: DeserializeOwned,
+    {
+        self.pinned().subscribe_to_method(method).await
+    }
+}
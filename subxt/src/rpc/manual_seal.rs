@@ -0,0 +1,135 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+    rpc_params,
+    ClientT,
+    Rpc,
+};
+use crate::{
+    error::BasicError,
+    Config,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use sp_runtime::Justification;
+
+/// A block created by [`Rpc::engine_create_block()`].
+///
+/// # Note
+///
+/// This is copied from `sc-consensus-manual-seal` to avoid a dependency on that crate.
+/// Therefore it must be kept compatible with that type from the target substrate version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CreatedBlock<Hash> {
+    /// The hash of the block.
+    pub hash: Hash,
+    /// Details of how the block was imported.
+    pub aux: ImportedAux,
+}
+
+/// Details of how a block was imported.
+///
+/// # Note
+///
+/// This is copied from `sc-consensus` to avoid a dependency on that crate. Therefore it
+/// must be kept compatible with that type from the target substrate version.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedAux {
+    /// Only the header was imported.
+    pub header_only: bool,
+    /// Justification requests for the block should be cleared.
+    pub clear_justification_requests: bool,
+    /// The block needs a justification.
+    pub needs_justification: bool,
+    /// The justification received for the block was bad.
+    pub bad_justification: bool,
+    /// The block became the new best block.
+    pub is_new_best: bool,
+}
+
+impl<T: Config> Rpc<T> {
+    /// Create a new block on a node run with `--sealing manual` (or `instant-seal`),
+    /// rather than waiting for one to be authored. The block includes any extrinsics in
+    /// the transaction pool, and is only created with none if `create_empty` is true. It's
+    /// built on top of `parent_hash`, or the best block if `None` is given, and is
+    /// finalized straight away if `finalize` is true.
+    ///
+    /// ```no_run
+    /// # #[async_std::main]
+    /// # async fn main() -> Result<(), subxt::BasicError> {
+    /// use subxt::{ClientBuilder, DefaultConfig};
+    ///
+    /// let client = ClientBuilder::new().build::<DefaultConfig>().await?;
+    /// let block = client.rpc().engine_create_block(true, true, None).await?;
+    /// println!("Created block {:?}", block.hash);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn engine_create_block(
+        &self,
+        create_empty: bool,
+        finalize: bool,
+        parent_hash: Option<T::Hash>,
+    ) -> Result<CreatedBlock<T::Hash>, BasicError> {
+        Ok(self
+            .client
+            .request(
+                "engine_createBlock",
+                rpc_params![create_empty, finalize, parent_hash],
+            )
+            .await?)
+    }
+
+    /// Finalize a block (and its ancestors) on a node run with `--sealing manual`, along
+    /// with an optional justification for it. Returns whether the block was finalized.
+    pub async fn engine_finalize_block(
+        &self,
+        hash: T::Hash,
+        justification: Option<Justification>,
+    ) -> Result<bool, BasicError> {
+        Ok(self
+            .client
+            .request("engine_finalizeBlock", rpc_params![hash, justification])
+            .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_core::H256;
+
+    #[test]
+    fn created_blocks_are_deserialized() {
+        let json = serde_json::json!({
+            "hash": H256::repeat_byte(1),
+            "aux": {
+                "headerOnly": false,
+                "clearJustificationRequests": false,
+                "needsJustification": false,
+                "badJustification": false,
+                "isNewBest": true,
+            },
+        });
+        let block: CreatedBlock<H256> = serde_json::from_value(json).unwrap();
+        assert_eq!(block.hash, H256::repeat_byte(1));
+        assert!(block.aux.is_new_best);
+    }
+}
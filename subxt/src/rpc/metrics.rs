@@ -0,0 +1,594 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+    ClientT,
+    DeserializeOwned,
+    JsonValue,
+    RpcClient,
+    RpcClientBuilder,
+    RpcConnection,
+    RpcError,
+    Subscription,
+    SubscriptionClientT,
+    DEFAULT_SUBSCRIPTION_BUFFER_SIZE,
+};
+use futures::{
+    channel::mpsc,
+    future::{
+        self,
+        Either,
+    },
+    StreamExt,
+};
+use jsonrpsee::{
+    core::client::{
+        SubscriptionKind,
+        TransportReceiverT,
+        TransportSenderT,
+    },
+    types::ParamsSer,
+};
+use prometheus::{
+    HistogramOpts,
+    HistogramVec,
+    IntCounter,
+    IntCounterVec,
+    IntGauge,
+    IntGaugeVec,
+    Opts,
+    Registry,
+};
+use serde_json::json;
+use std::{
+    sync::{
+        Arc,
+        Mutex as StdMutex,
+    },
+    time::Instant,
+};
+
+/// Prometheus metrics describing the calls made to a node, as recorded by a
+/// [`MeteredClient`]. The metrics are:
+///
+/// - `subxt_rpc_requests_total`: the number of requests made, by method.
+/// - `subxt_rpc_errors_total`: the number of requests (and subscriptions) which failed, by
+///   method.
+/// - `subxt_rpc_request_duration_seconds`: how long requests took, by method.
+/// - `subxt_rpc_sent_bytes_total` and `subxt_rpc_received_bytes_total`: the size of the
+///   parameters sent and results received, as JSON. This doesn't include the JSON-RPC
+///   envelope around them, or notifications received on subscriptions.
+/// - `subxt_rpc_subscriptions_total`: the number of subscriptions made, by method.
+/// - `subxt_rpc_active_subscriptions`: the number of subscriptions currently open, by
+///   method. A subscription is open until it's dropped or ended by the node. Subscriptions
+///   to methods (made with [`SubscriptionClientT::subscribe_to_method()`]) aren't counted,
+///   since nothing tells the node when they're dropped.
+///
+/// Batch requests are recorded under the method `batch`.
+#[derive(Clone)]
+pub struct RpcMetrics {
+    requests: IntCounterVec,
+    errors: IntCounterVec,
+    durations: HistogramVec,
+    sent_bytes: IntCounter,
+    received_bytes: IntCounter,
+    subscriptions: IntCounterVec,
+    active_subscriptions: IntGaugeVec,
+}
+
+impl RpcMetrics {
+    /// Create the metrics, and register them with the registry given so that they're
+    /// exposed alongside any others.
+    pub fn register(registry: &Registry) -> Result<Self, prometheus::Error> {
+        let metrics = RpcMetrics {
+            requests: IntCounterVec::new(
+                Opts::new("subxt_rpc_requests_total", "Number of RPC requests made"),
+                &["method"],
+            )?,
+            errors: IntCounterVec::new(
+                Opts::new("subxt_rpc_errors_total", "Number of RPC calls which failed"),
+                &["method"],
+            )?,
+            durations: HistogramVec::new(
+                HistogramOpts::new(
+                    "subxt_rpc_request_duration_seconds",
+                    "How long RPC requests took to complete",
+                ),
+                &["method"],
+            )?,
+            sent_bytes: IntCounter::new(
+                "subxt_rpc_sent_bytes_total",
+                "Size of the RPC parameters sent, as JSON",
+            )?,
+            received_bytes: IntCounter::new(
+                "subxt_rpc_received_bytes_total",
+                "Size of the RPC results received, as JSON",
+            )?,
+            subscriptions: IntCounterVec::new(
+                Opts::new(
+                    "subxt_rpc_subscriptions_total",
+                    "Number of RPC subscriptions made",
+                ),
+                &["method"],
+            )?,
+            active_subscriptions: IntGaugeVec::new(
+                Opts::new(
+                    "subxt_rpc_active_subscriptions",
+                    "Number of RPC subscriptions currently open",
+                ),
+                &["method"],
+            )?,
+        };
+        registry.register(Box::new(metrics.requests.clone()))?;
+        registry.register(Box::new(metrics.errors.clone()))?;
+        registry.register(Box::new(metrics.durations.clone()))?;
+        registry.register(Box::new(metrics.sent_bytes.clone()))?;
+        registry.register(Box::new(metrics.received_bytes.clone()))?;
+        registry.register(Box::new(metrics.subscriptions.clone()))?;
+        registry.register(Box::new(metrics.active_subscriptions.clone()))?;
+        Ok(metrics)
+    }
+
+    fn record_sent(&self, params: &Option<ParamsSer<'_>>) {
+        if let Some(params) = params {
+            self.sent_bytes.inc_by(json_len(params));
+        }
+    }
+
+    fn record_received(&self, result: &JsonValue) {
+        self.received_bytes.inc_by(json_len(result));
+    }
+
+    fn record_response<R>(
+        &self,
+        method: &str,
+        started: Instant,
+        res: &Result<R, RpcError>,
+    ) {
+        self.requests.with_label_values(&[method]).inc();
+        self.durations
+            .with_label_values(&[method])
+            .observe(started.elapsed().as_secs_f64());
+        if res.is_err() {
+            self.errors.with_label_values(&[method]).inc();
+        }
+    }
+
+    fn record_subscription<R>(&self, method: &str, res: &Result<R, RpcError>) {
+        self.subscriptions.with_label_values(&[method]).inc();
+        if res.is_err() {
+            self.errors.with_label_values(&[method]).inc();
+        }
+    }
+}
+
+// Counts a subscription as active for as long as it's alive.
+struct ActiveSubscription(IntGauge);
+
+impl ActiveSubscription {
+    fn new(gauge: IntGauge) -> Self {
+        gauge.inc();
+        ActiveSubscription(gauge)
+    }
+}
+
+impl Drop for ActiveSubscription {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("The subscription has ended")]
+struct WatchEnded;
+
+// Hands the subscription given to the caller through a client of its own, so that we find
+// out when it's dropped. The watch's client makes two requests: the subscription (answered
+// with the ID of the subscription being watched) and, once the caller drops the
+// subscription, the unsubscription. The watch ends then, or when the node ends the
+// subscription, at which point the subscription is no longer counted as active.
+async fn watch<'a, Notif>(
+    subscription: Subscription<JsonValue>,
+    active: ActiveSubscription,
+    subscribe_method: &'a str,
+    params: Option<ParamsSer<'a>>,
+    unsubscribe_method: &'a str,
+) -> Result<Subscription<Notif>, RpcError>
+where
+    Notif: DeserializeOwned,
+{
+    let id = match subscription.kind() {
+        SubscriptionKind::Subscription(id) => serde_json::to_value(id)?,
+        _ => "subxt_metered_subscription".into(),
+    };
+    let (to_receiver, from_sender) = mpsc::unbounded();
+    let own_client = Arc::new(StdMutex::new(None));
+    let receiver = WatchReceiver {
+        from_sender,
+        subscribe_method: subscribe_method.to_string(),
+        id,
+        subscription,
+        own_client: own_client.clone(),
+        _active: active,
+        ended: false,
+    };
+    let client = RpcClientBuilder::default()
+        .max_notifs_per_subscription(DEFAULT_SUBSCRIPTION_BUFFER_SIZE)
+        .build(WatchSender { to_receiver }, receiver);
+    let subscription = client
+        .subscribe(subscribe_method, params, unsubscribe_method)
+        .await?;
+    // Dropping the client would end the subscription, so it's kept by the watch until the
+    // watch ends.
+    *own_client.lock().expect("lock is never poisoned; qed") = Some(client);
+    Ok(subscription)
+}
+
+// Passes the requests made by the watch's client on to its receiver, which answers them.
+struct WatchSender {
+    to_receiver: mpsc::UnboundedSender<JsonValue>,
+}
+
+#[async_trait::async_trait]
+impl TransportSenderT for WatchSender {
+    type Error = WatchEnded;
+
+    async fn send(&mut self, msg: String) -> Result<(), Self::Error> {
+        let request = serde_json::from_str(&msg).map_err(|_| WatchEnded)?;
+        self.to_receiver
+            .unbounded_send(request)
+            .map_err(|_| WatchEnded)
+    }
+}
+
+struct WatchReceiver {
+    from_sender: mpsc::UnboundedReceiver<JsonValue>,
+    subscribe_method: String,
+    // The ID that the caller knows the subscription by.
+    id: JsonValue,
+    subscription: Subscription<JsonValue>,
+    own_client: Arc<StdMutex<Option<RpcClient>>>,
+    _active: ActiveSubscription,
+    // Whether the caller has unsubscribed.
+    ended: bool,
+}
+
+impl WatchReceiver {
+    // Answer a request from the watch's client.
+    fn answer(&mut self, request: JsonValue) -> String {
+        let method = request.get("method").and_then(JsonValue::as_str);
+        let result = if method == Some(self.subscribe_method.as_str()) {
+            self.id.clone()
+        } else {
+            self.ended = true;
+            true.into()
+        };
+        json!({ "jsonrpc": "2.0", "result": result, "id": request.get("id") }).to_string()
+    }
+}
+
+#[async_trait::async_trait]
+impl TransportReceiverT for WatchReceiver {
+    type Error = WatchEnded;
+
+    async fn receive(&mut self) -> Result<String, Self::Error> {
+        loop {
+            if self.ended {
+                // Let go of the watch's client, which is done with.
+                self.own_client
+                    .lock()
+                    .expect("lock is never poisoned; qed")
+                    .take();
+                return Err(WatchEnded)
+            }
+            let next = future::select(
+                self.from_sender.next(),
+                Box::pin(self.subscription.next()),
+            )
+            .await;
+            match next {
+                Either::Left((Some(request), _)) => return Ok(self.answer(request)),
+                Either::Left((None, _)) | Either::Right((None, _)) => {
+                    return Err(WatchEnded)
+                }
+                Either::Right((Some(Ok(item)), _)) => {
+                    let notification = json!({
+                        "jsonrpc": "2.0",
+                        "method": self.subscribe_method,
+                        "params": { "subscription": self.id, "result": item },
+                    });
+                    return Ok(notification.to_string())
+                }
+                Either::Right((Some(Err(e)), _)) => {
+                    log::warn!(
+                        "Dropping a notification from {}: {:?}",
+                        self.subscribe_method,
+                        e
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// A client which records [`RpcMetrics`] for the calls made through the connection it
+/// wraps. Available with the `metrics` feature.
+pub struct MeteredClient {
+    connection: RpcConnection,
+    metrics: RpcMetrics,
+}
+
+impl MeteredClient {
+    /// Record metrics for the calls made through the connection given.
+    pub fn new(connection: impl Into<RpcConnection>, metrics: RpcMetrics) -> Self {
+        MeteredClient {
+            connection: connection.into(),
+            metrics,
+        }
+    }
+
+    /// The connection that calls are made through.
+    pub fn connection(&self) -> &RpcConnection {
+        &self.connection
+    }
+
+    /// The metrics recorded.
+    pub fn metrics(&self) -> &RpcMetrics {
+        &self.metrics
+    }
+}
+
+// The length of the value given as JSON, worked out without building the JSON up.
+fn json_len<T: serde::Serialize + ?Sized>(value: &T) -> u64 {
+    struct Counter(u64);
+
+    impl std::io::Write for Counter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0 += buf.len() as u64;
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut counter = Counter(0);
+    serde_json::to_writer(&mut counter, value).map_or(0, |_| counter.0)
+}
+
+#[async_trait::async_trait]
+impl ClientT for MeteredClient {
+    async fn notification<'a>(
+        &self,
+        method: &'a str,
+        params: Option<ParamsSer<'a>>,
+    ) -> Result<(), RpcError> {
+        self.metrics.record_sent(&params);
+        let started = Instant::now();
+        let res = self.connection.notification(method, params).await;
+        self.metrics.record_response(method, started, &res);
+        res
+    }
+
+    async fn request<'a, R>(
+        &self,
+        method: &'a str,
+        params: Option<ParamsSer<'a>>,
+    ) -> Result<R, RpcError>
+    where
+        R: DeserializeOwned,
+    {
+        self.metrics.record_sent(&params);
+        let started = Instant::now();
+        // jsonrpsee parses each response into JSON before handing its result over, so take
+        // the result as that JSON, measure it, and then convert it into `R`. The response
+        // itself is only parsed once.
+        let res = self
+            .connection
+            .request::<JsonValue>(method, params)
+            .await
+            .and_then(|result| {
+                self.metrics.record_received(&result);
+                serde_json::from_value(result).map_err(RpcError::ParseError)
+            });
+        self.metrics.record_response(method, started, &res);
+        res
+    }
+
+    async fn batch_request<'a, R>(
+        &self,
+        batch: Vec<(&'a str, Option<ParamsSer<'a>>)>,
+    ) -> Result<Vec<R>, RpcError>
+    where
+        R: DeserializeOwned + Default + Clone,
+    {
+        for (_, params) in &batch {
+            self.metrics.record_sent(params);
+        }
+        let started = Instant::now();
+        let res = self
+            .connection
+            .batch_request::<JsonValue>(batch)
+            .await
+            .and_then(|results| {
+                results
+                    .into_iter()
+                    .map(|result| {
+                        self.metrics.record_received(&result);
+                        serde_json::from_value(result).map_err(RpcError::ParseError)
+                    })
+                    .collect()
+            });
+        self.metrics.record_response("batch", started, &res);
+        res
+    }
+}
+
+#[async_trait::async_trait]
+impl SubscriptionClientT for MeteredClient {
+    async fn subscribe<'a, Notif>(
+        &self,
+        subscribe_method: &'a str,
+        params: Option<ParamsSer<'a>>,
+        unsubscribe_method: &'a str,
+    ) -> Result<Subscription<Notif>, RpcError>
+    where
+        Notif: DeserializeOwned,
+    {
+        self.metrics.record_sent(&params);
+        let res = self
+            .connection
+            .subscribe::<JsonValue>(subscribe_method, params.clone(), unsubscribe_method)
+            .await;
+        self.metrics.record_subscription(subscribe_method, &res);
+        let active = ActiveSubscription::new(
+            self.metrics
+                .active_subscriptions
+                .with_label_values(&[subscribe_method]),
+        );
+        watch(res?, active, subscribe_method, params, unsubscribe_method).await
+    }
+
+    async fn subscribe_to_method<'a, Notif>(
+        &self,
+        method: &'a str,
+    ) -> Result<Subscription<Notif>, RpcError>
+    where
+        Notif: DeserializeOwned,
+    {
+        let res = self.connection.subscribe_to_method(method).await;
+        self.metrics.record_subscription(method, &res);
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::{
+        rpc_params,
+        MockRpcClient,
+    };
+    use futures_timer::Delay;
+    use std::time::Duration;
+
+    // Wait (for a while) for the number of active subscriptions to `method` to be as given.
+    async fn wait_for_active(metrics: &RpcMetrics, method: &str, active: i64) {
+        for _ in 0..100 {
+            if metrics
+                .active_subscriptions
+                .with_label_values(&[method])
+                .get()
+                == active
+            {
+                return
+            }
+            Delay::new(Duration::from_millis(10)).await;
+        }
+        panic!("expected {} active subscriptions to {}", active, method);
+    }
+
+    #[test]
+    fn metrics_are_registered_and_recorded() {
+        let registry = Registry::new();
+        let metrics = RpcMetrics::register(&registry).unwrap();
+
+        let started = Instant::now();
+        metrics.record_response("chain_getBlockHash", started, &Ok(()));
+        metrics.record_response::<()>(
+            "chain_getBlockHash",
+            started,
+            &Err(RpcError::RequestTimeout),
+        );
+        metrics.record_received(&JsonValue::String("0x00".into()));
+
+        assert_eq!(
+            metrics
+                .requests
+                .with_label_values(&["chain_getBlockHash"])
+                .get(),
+            2
+        );
+        assert_eq!(
+            metrics
+                .errors
+                .with_label_values(&["chain_getBlockHash"])
+                .get(),
+            1
+        );
+        assert_eq!(metrics.received_bytes.get(), 6);
+        assert_eq!(
+            json_len(&serde_json::json!({ "a": [1, 2], "b": null })),
+            r#"{"a":[1,2],"b":null}"#.len() as u64
+        );
+
+        let names: Vec<_> = registry
+            .gather()
+            .iter()
+            .map(|family| family.get_name().to_string())
+            .collect();
+        assert!(names.contains(&"subxt_rpc_request_duration_seconds".to_string()));
+
+        // The same metrics can't be registered twice.
+        assert!(RpcMetrics::register(&registry).is_err());
+    }
+
+    #[async_std::test]
+    async fn subscriptions_are_active_until_dropped() {
+        let mock =
+            MockRpcClient::new().add_subscription("chain_subscribeNewHeads", vec![1, 2]);
+        let metrics = RpcMetrics::register(&Registry::new()).unwrap();
+        let client = MeteredClient::new(mock, metrics.clone());
+
+        let mut subscription: Subscription<u32> = client
+            .subscribe(
+                "chain_subscribeNewHeads",
+                rpc_params![],
+                "chain_unsubscribeNewHeads",
+            )
+            .await
+            .unwrap();
+        wait_for_active(&metrics, "chain_subscribeNewHeads", 1).await;
+        assert_eq!(subscription.next().await.unwrap().unwrap(), 1);
+        assert_eq!(subscription.next().await.unwrap().unwrap(), 2);
+
+        drop(subscription);
+        wait_for_active(&metrics, "chain_subscribeNewHeads", 0).await;
+    }
+
+    #[async_std::test]
+    async fn subscriptions_are_active_until_ended() {
+        let mock = MockRpcClient::new()
+            .add_subscription_then_close("chain_subscribeNewHeads", vec![1]);
+        let metrics = RpcMetrics::register(&Registry::new()).unwrap();
+        let client = MeteredClient::new(mock, metrics.clone());
+
+        let mut subscription: Subscription<u32> = client
+            .subscribe(
+                "chain_subscribeNewHeads",
+                rpc_params![],
+                "chain_unsubscribeNewHeads",
+            )
+            .await
+            .unwrap();
+        wait_for_active(&metrics, "chain_subscribeNewHeads", 1).await;
+        assert_eq!(subscription.next().await.unwrap().unwrap(), 1);
+        assert!(subscription.next().await.is_none());
+
+        wait_for_active(&metrics, "chain_subscribeNewHeads", 0).await;
+    }
+}
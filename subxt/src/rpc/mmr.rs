@@ -0,0 +1,179 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+    rpc_params,
+    ClientT,
+    Rpc,
+};
+use crate::{
+    error::BasicError,
+    Config,
+};
+use codec::{
+    Decode,
+    Encode,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use sp_core::{
+    Bytes,
+    H256,
+};
+
+/// A proof that a leaf is in the Merkle Mountain Range (MMR) of a chain, as returned from
+/// the RPC call `mmr_generateProof`.
+///
+/// # Note
+///
+/// This is copied from `pallet-mmr-rpc` to avoid a dependency on that crate. Therefore it
+/// must be kept compatible with that type from the target substrate version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeafProof<Hash> {
+    /// The hash of the block the proof was generated at.
+    pub block_hash: Hash,
+    /// The SCALE encoded leaf.
+    pub leaf: Bytes,
+    /// The SCALE encoded [`MmrProof`].
+    pub proof: Bytes,
+}
+
+impl<Hash> LeafProof<Hash> {
+    /// Decode the leaf into the type used by the runtime, such as [`MmrLeaf`] for chains
+    /// using BEEFY.
+    pub fn decode_leaf<L: Decode>(&self) -> Result<L, BasicError> {
+        // The leaf is SCALE encoded again as opaque bytes.
+        let leaf = <Vec<u8>>::decode(&mut &self.leaf[..])?;
+        Ok(L::decode(&mut &leaf[..])?)
+    }
+
+    /// Decode the proof, which uses the hashes of the MMR given by `H`.
+    pub fn decode_proof<H: Decode>(&self) -> Result<MmrProof<H>, BasicError> {
+        Ok(MmrProof::decode(&mut &self.proof[..])?)
+    }
+}
+
+/// The proof that a leaf is in an MMR.
+///
+/// # Note
+///
+/// This is copied from `sp-mmr-primitives` to avoid a dependency on that crate. Therefore it
+/// must be kept compatible with that type from the target substrate version.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct MmrProof<Hash> {
+    /// The index of the leaf the proof is for.
+    pub leaf_index: u64,
+    /// The number of leaves in the MMR.
+    pub leaf_count: u64,
+    /// The hashes of the nodes needed to recompute the root of the MMR.
+    pub items: Vec<Hash>,
+}
+
+/// The leaf that chains using BEEFY add to their MMR for each block.
+///
+/// # Note
+///
+/// This is copied from `beefy-primitives` to avoid a dependency on that crate. Therefore it
+/// must be kept compatible with that type from the target substrate version.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct MmrLeaf<BlockNumber, Hash> {
+    /// The version of the leaf format.
+    pub version: u8,
+    /// The number and hash of the parent of the block the leaf was added in.
+    pub parent_number_and_hash: (BlockNumber, Hash),
+    /// The next BEEFY authority set.
+    pub beefy_next_authority_set: BeefyNextAuthoritySet,
+    /// Extra data added by the runtime, such as the root of the parachain heads on a relay
+    /// chain.
+    pub leaf_extra: Vec<u8>,
+}
+
+/// Details of the next BEEFY authority set, as committed to in each [`MmrLeaf`].
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct BeefyNextAuthoritySet {
+    /// The ID of the authority set.
+    pub id: u64,
+    /// The number of authorities in the set.
+    pub len: u32,
+    /// The Merkle root of the authorities' Ethereum addresses.
+    pub root: H256,
+}
+
+impl<T: Config> Rpc<T> {
+    /// Generate a proof that the MMR leaf with the index given is in the MMR of the chain
+    /// at the given block (or the latest block if `None` is given).
+    pub async fn mmr_generate_proof(
+        &self,
+        leaf_index: u64,
+        at: Option<T::Hash>,
+    ) -> Result<LeafProof<T::Hash>, BasicError> {
+        Ok(self
+            .client
+            .request("mmr_generateProof", rpc_params![leaf_index, at])
+            .await?)
+    }
+
+    /// Check a proof generated by [`Rpc::mmr_generate_proof()`] against the MMR of the
+    /// chain, handing back whether it's valid. This is an unsafe RPC method on some
+    /// versions of substrate.
+    pub async fn mmr_verify_proof(
+        &self,
+        proof: &LeafProof<T::Hash>,
+    ) -> Result<bool, BasicError> {
+        Ok(self
+            .client
+            .request("mmr_verifyProof", rpc_params![proof])
+            .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaves_and_proofs_are_decoded() {
+        let leaf = MmrLeaf {
+            version: 0,
+            parent_number_and_hash: (5u32, H256::repeat_byte(1)),
+            beefy_next_authority_set: BeefyNextAuthoritySet {
+                id: 2,
+                len: 3,
+                root: H256::repeat_byte(4),
+            },
+            leaf_extra: vec![5],
+        };
+        let proof = MmrProof {
+            leaf_index: 5,
+            leaf_count: 6,
+            items: vec![H256::repeat_byte(7)],
+        };
+        let leaf_proof = LeafProof {
+            block_hash: H256::repeat_byte(8),
+            leaf: Bytes(leaf.encode().encode()),
+            proof: Bytes(proof.encode()),
+        };
+
+        assert_eq!(
+            leaf_proof.decode_leaf::<MmrLeaf<u32, H256>>().unwrap(),
+            leaf
+        );
+        assert_eq!(leaf_proof.decode_proof::<H256>().unwrap(), proof);
+    }
+}
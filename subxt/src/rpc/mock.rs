@@ -0,0 +1,491 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+    JsonValue,
+    RpcClient,
+    RpcClientBuilder,
+    RpcConnection,
+};
+use futures::{
+    channel::mpsc,
+    StreamExt,
+};
+use jsonrpsee::core::client::{
+    TransportReceiverT,
+    TransportSenderT,
+};
+use serde::Serialize;
+use serde_json::json;
+use std::{
+    collections::{
+        HashMap,
+        HashSet,
+        VecDeque,
+    },
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+/// A client for unit tests which, rather than talking to a node, answers requests with
+/// responses registered beforehand, so that logic built on subxt can be tested without
+/// running one. Hand it to [`crate::ClientBuilder::set_client()`] or
+/// [`super::Rpc::new()`].
+///
+/// Several responses (or subscriptions) can be registered for the same method, in which
+/// case they're handed back in turn, with the last one repeating from then on. Requests
+/// to methods with nothing registered fail with a "method not found" error.
+///
+/// Note that [`crate::ClientBuilder::build()`] fetches the metadata, genesis hash,
+/// runtime version and system properties of the chain (`state_getMetadata`,
+/// `chain_getBlockHash`, `state_getRuntimeVersion` and `system_properties`), so responses
+/// need registering for those for it to succeed.
+///
+/// The requests sent to the mock are recorded, and can be inspected with
+/// [`MockRpcClient::requests()`]. Clones of the mock share the record, so keep a clone
+/// around to inspect it after handing the mock over.
+///
+/// ```no_run
+/// # #[async_std::main]
+/// # async fn main() -> Result<(), subxt::BasicError> {
+/// use subxt::{
+///     rpc::{
+///         rpc_params,
+///         MockRpcClient,
+///         Rpc,
+///     },
+///     DefaultConfig,
+/// };
+///
+/// let mock = MockRpcClient::new()
+///     .add_response("system_name", "mock-node")
+///     .add_subscription("chain_subscribeNewHeads", vec![serde_json::json!({})]);
+/// let rpc = Rpc::<DefaultConfig>::new(mock);
+/// let name: String = rpc.request("system_name", rpc_params![]).await?;
+/// assert_eq!(name, "mock-node");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MockRpcClient {
+    responses: HashMap<String, VecDeque<MockResponse>>,
+    subscriptions: HashMap<String, VecDeque<MockSubscription>>,
+    requests: Arc<Mutex<Vec<JsonValue>>>,
+    buffer_size: Option<usize>,
+}
+
+#[derive(Clone, Debug)]
+enum MockResponse {
+    Result(JsonValue),
+    Error { code: i32, message: String },
+}
+
+#[derive(Clone, Debug)]
+struct MockSubscription {
+    items: Vec<JsonValue>,
+    // Close the connection once the items have been handed back.
+    then_close: bool,
+}
+
+impl MockRpcClient {
+    /// Create a client with no responses registered.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer requests to `method` with the result given.
+    pub fn add_response(
+        mut self,
+        method: impl Into<String>,
+        result: impl Serialize,
+    ) -> Self {
+        let result = serde_json::to_value(result)
+            .expect("mock responses can be serialized to JSON; qed");
+        self.responses
+            .entry(method.into())
+            .or_default()
+            .push_back(MockResponse::Result(result));
+        self
+    }
+
+    /// Answer requests to `method` with the JSON-RPC error given.
+    pub fn add_error(
+        mut self,
+        method: impl Into<String>,
+        code: i32,
+        message: impl Into<String>,
+    ) -> Self {
+        self.responses
+            .entry(method.into())
+            .or_default()
+            .push_back(MockResponse::Error {
+                code,
+                message: message.into(),
+            });
+        self
+    }
+
+    /// Answer subscriptions to `method` with a subscription which hands back the items
+    /// given in turn, and then nothing more.
+    pub fn add_subscription<I, T>(self, method: impl Into<String>, items: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Serialize,
+    {
+        self.push_subscription(method.into(), items, false)
+    }
+
+    /// Answer subscriptions to `method` with a subscription which hands back the items
+    /// given in turn, after which the connection is closed, as it is when a node shuts
+    /// down. Every subscription ends, and every request from then on fails.
+    pub fn add_subscription_then_close<I, T>(
+        self,
+        method: impl Into<String>,
+        items: I,
+    ) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Serialize,
+    {
+        self.push_subscription(method.into(), items, true)
+    }
+
+    /// Set the maximum number of notifications buffered for each subscription, as
+    /// [`crate::ClientBuilder::set_subscription_buffer_size()`] does for the default
+    /// client, so that subscriptions which fall behind can be tested.
+    pub fn set_subscription_buffer_size(mut self, size: usize) -> Self {
+        self.buffer_size = Some(size);
+        self
+    }
+
+    fn push_subscription<I, T>(
+        mut self,
+        method: String,
+        items: I,
+        then_close: bool,
+    ) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Serialize,
+    {
+        let items = items
+            .into_iter()
+            .map(|item| {
+                serde_json::to_value(item)
+                    .expect("mock subscription items can be serialized to JSON; qed")
+            })
+            .collect();
+        self.subscriptions
+            .entry(method)
+            .or_default()
+            .push_back(MockSubscription { items, then_close });
+        self
+    }
+
+    /// The requests sent to this mock (or any clone of it) so far, in the order they
+    /// were sent. The requests of a batch are recorded individually.
+    pub fn requests(&self) -> Vec<JsonValue> {
+        self.requests
+            .lock()
+            .expect("the lock is never held across a panic; qed")
+            .clone()
+    }
+
+    /// The parameters of the requests to `method` sent to this mock so far.
+    pub fn requests_to(&self, method: &str) -> Vec<JsonValue> {
+        self.requests()
+            .into_iter()
+            .filter(|request| {
+                request.get("method").and_then(JsonValue::as_str) == Some(method)
+            })
+            .map(|request| request.get("params").cloned().unwrap_or_default())
+            .collect()
+    }
+
+    // Register the responses which `ClientBuilder::build()` needs, for a chain with the
+    // metadata given.
+    #[cfg(test)]
+    pub(crate) fn with_chain(
+        self,
+        metadata: frame_metadata::RuntimeMetadataPrefixed,
+    ) -> Self {
+        use codec::Encode;
+        self.add_response("state_getMetadata", sp_core::Bytes(metadata.encode()))
+            .add_response("chain_getBlockHash", sp_core::H256::repeat_byte(1))
+            .add_response(
+                "state_getRuntimeVersion",
+                json!({ "specVersion": 1, "transactionVersion": 1 }),
+            )
+            .add_response("system_properties", json!({}))
+    }
+
+    /// Build a jsonrpsee client which is answered by this mock.
+    pub fn build(self) -> RpcClient {
+        let (to_receiver, from_sender) = mpsc::unbounded();
+        let mut builder = RpcClientBuilder::default();
+        if let Some(size) = self.buffer_size {
+            builder = builder.max_notifs_per_subscription(size);
+        }
+        let sender = MockSender {
+            mock: self,
+            to_receiver,
+            open_subscriptions: HashSet::new(),
+            next_subscription_id: 0,
+            closing: false,
+        };
+        let receiver = MockReceiver { from_sender };
+        builder.build(sender, receiver)
+    }
+}
+
+impl From<MockRpcClient> for RpcConnection {
+    fn from(mock: MockRpcClient) -> Self {
+        mock.build().into()
+    }
+}
+
+// Hand back the next of the items registered, leaving the last one to repeat.
+fn next_in_turn<T: Clone>(items: &mut VecDeque<T>) -> Option<T> {
+    if items.len() > 1 {
+        items.pop_front()
+    } else {
+        items.front().cloned()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("The mock client has been closed")]
+struct MockClosed;
+
+// Answers the requests sent by the client, passing the responses to the receiver.
+struct MockSender {
+    mock: MockRpcClient,
+    to_receiver: mpsc::UnboundedSender<String>,
+    open_subscriptions: HashSet<String>,
+    next_subscription_id: u64,
+    // Whether to close the connection once the current response has been sent.
+    closing: bool,
+}
+
+impl MockSender {
+    // Work out the response to a single request, along with any notifications to send
+    // after it.
+    fn respond(&mut self, request: &JsonValue) -> (Option<JsonValue>, Vec<JsonValue>) {
+        self.mock
+            .requests
+            .lock()
+            .expect("the lock is never held across a panic; qed")
+            .push(request.clone());
+        let id = match request.get("id") {
+            Some(id) if !id.is_null() => id.clone(),
+            // Notifications don't get a response.
+            _ => return (None, Vec::new()),
+        };
+        let method = request
+            .get("method")
+            .and_then(JsonValue::as_str)
+            .unwrap_or_default();
+        let result =
+            |result: JsonValue| json!({ "jsonrpc": "2.0", "result": result, "id": id });
+        let error = |code: i32, message: &str| {
+            json!({
+                "jsonrpc": "2.0",
+                "error": { "code": code, "message": message },
+                "id": id,
+            })
+        };
+
+        if let Some(MockSubscription { items, then_close }) = self
+            .mock
+            .subscriptions
+            .get_mut(method)
+            .and_then(next_in_turn)
+        {
+            let subscription = format!("mock_subscription_{}", self.next_subscription_id);
+            self.next_subscription_id += 1;
+            self.open_subscriptions.insert(subscription.clone());
+            self.closing |= then_close;
+            let notifications = items
+                .into_iter()
+                .map(|item| {
+                    json!({
+                        "jsonrpc": "2.0",
+                        "method": method,
+                        "params": { "subscription": subscription, "result": item },
+                    })
+                })
+                .collect();
+            return (Some(result(subscription.into())), notifications)
+        }
+
+        match self.mock.responses.get_mut(method).and_then(next_in_turn) {
+            Some(MockResponse::Result(res)) => (Some(result(res)), Vec::new()),
+            Some(MockResponse::Error { code, message }) => {
+                (Some(error(code, &message)), Vec::new())
+            }
+            None => {
+                // Unsubscribing from a subscription we handed out always succeeds.
+                let unsubscribed = request
+                    .get("params")
+                    .and_then(|params| params.get(0))
+                    .and_then(JsonValue::as_str)
+                    .map_or(false, |sub| self.open_subscriptions.remove(sub));
+                if unsubscribed {
+                    (Some(result(true.into())), Vec::new())
+                } else {
+                    (Some(error(-32601, "Method not found")), Vec::new())
+                }
+            }
+        }
+    }
+
+    fn send_to_receiver(&self, msg: &JsonValue) -> Result<(), MockClosed> {
+        self.to_receiver
+            .unbounded_send(msg.to_string())
+            .map_err(|_| MockClosed)
+    }
+}
+
+#[async_trait::async_trait]
+impl TransportSenderT for MockSender {
+    type Error = MockClosed;
+
+    async fn send(&mut self, msg: String) -> Result<(), Self::Error> {
+        let request: JsonValue = match serde_json::from_str(&msg) {
+            Ok(request) => request,
+            Err(e) => {
+                log::warn!("Mock client sent invalid JSON ({}): {}", e, msg);
+                return Ok(())
+            }
+        };
+        match request {
+            JsonValue::Array(batch) => {
+                let responses: Vec<_> = batch
+                    .iter()
+                    .filter_map(|request| self.respond(request).0)
+                    .collect();
+                self.send_to_receiver(&responses.into())
+            }
+            request => {
+                let (response, notifications) = self.respond(&request);
+                for msg in response.iter().chain(&notifications) {
+                    self.send_to_receiver(msg)?;
+                }
+                if self.closing {
+                    // Anything already sent is still received before the connection
+                    // closes.
+                    self.to_receiver.close_channel();
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+// Hands the responses worked out by the sender back to the client.
+struct MockReceiver {
+    from_sender: mpsc::UnboundedReceiver<String>,
+}
+
+#[async_trait::async_trait]
+impl TransportReceiverT for MockReceiver {
+    type Error = MockClosed;
+
+    async fn receive(&mut self) -> Result<String, Self::Error> {
+        self.from_sender.next().await.ok_or(MockClosed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rpc::{
+            rpc_params,
+            Rpc,
+        },
+        BasicError,
+        DefaultConfig,
+    };
+
+    #[async_std::test]
+    async fn mock_responses_and_subscriptions() {
+        let mock = MockRpcClient::new()
+            .add_response("system_name", "first")
+            .add_response("system_name", "second")
+            .add_error("system_version", 1, "nope")
+            .add_subscription("test_subscribe", vec![1, 2, 3]);
+        let rpc = Rpc::<DefaultConfig>::new(mock);
+
+        // Responses are handed back in turn, with the last one repeating.
+        for expected in ["first", "second", "second"] {
+            let name: String = rpc.request("system_name", rpc_params![]).await.unwrap();
+            assert_eq!(name, expected);
+        }
+        assert!(matches!(
+            rpc.request::<String>("system_version", rpc_params![]).await,
+            Err(BasicError::Rpc(_))
+        ));
+        assert!(matches!(
+            rpc.request::<String>("system_chain", rpc_params![]).await,
+            Err(BasicError::Rpc(_))
+        ));
+
+        let mut sub = rpc
+            .subscribe::<u32>("test_subscribe", rpc_params![], "test_unsubscribe")
+            .await
+            .unwrap();
+        let mut items = Vec::new();
+        for _ in 0..3 {
+            items.push(sub.next().await.unwrap().unwrap());
+        }
+        assert_eq!(items, vec![1, 2, 3]);
+    }
+
+    #[async_std::test]
+    async fn mock_records_requests() {
+        let mock = MockRpcClient::new().add_response("system_name", "mock-node");
+        let rpc = Rpc::<DefaultConfig>::new(mock.clone());
+
+        let _: String = rpc.request("system_name", rpc_params![1]).await.unwrap();
+        let _ = rpc.request::<String>("system_chain", rpc_params![]).await;
+
+        assert_eq!(mock.requests().len(), 2);
+        assert_eq!(mock.requests_to("system_name"), vec![json!([1])]);
+    }
+
+    #[async_std::test]
+    async fn mock_closes_the_connection_after_a_subscription() {
+        let mock = MockRpcClient::new()
+            .add_response("system_name", "mock-node")
+            .add_subscription_then_close("test_subscribe", vec![1, 2]);
+        let rpc = Rpc::<DefaultConfig>::new(mock);
+
+        let mut sub = rpc
+            .subscribe::<u32>("test_subscribe", rpc_params![], "test_unsubscribe")
+            .await
+            .unwrap();
+        assert_eq!(sub.next().await.unwrap().unwrap(), 1);
+        assert_eq!(sub.next().await.unwrap().unwrap(), 2);
+        assert!(sub.next().await.is_none());
+        assert!(rpc
+            .request::<String>("system_name", rpc_params![])
+            .await
+            .is_err());
+    }
+}
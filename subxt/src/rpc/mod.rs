@@ -0,0 +1,1961 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC types and client for interacting with a substrate node.
+
+// jsonrpsee subscriptions are interminable.
+// Allows `while let status = subscription.next().await {}`
+// Related: https://github.com/paritytech/subxt/issues/66
+#![allow(irrefutable_let_patterns)]
+
+mod archive;
+mod beefy;
+mod block_trace;
+mod chain_head;
+mod chain_spec;
+#[cfg(all(feature = "ipc", unix))]
+mod ipc;
+mod keepalive;
+#[cfg(feature = "light-client")]
+mod light_client;
+mod load_balancing;
+#[cfg(feature = "manual-seal")]
+mod manual_seal;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod mmr;
+mod mock;
+#[cfg(feature = "proxy")]
+mod proxy;
+mod rate_limiting;
+mod reconnecting;
+mod recording;
+mod retrying;
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+mod tls;
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+mod transport;
+
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::Duration,
+};
+
+pub(crate) use self::chain_head::FollowedChain;
+#[cfg(all(feature = "ipc", unix))]
+pub use self::ipc::{
+    ipc_client,
+    ipc_client_with_config,
+};
+use self::keepalive::{
+    keepalive,
+    OnClose,
+};
+#[cfg(feature = "light-client")]
+pub use self::light_client::{
+    light_client,
+    LightClientError,
+};
+#[cfg(feature = "manual-seal")]
+pub use self::manual_seal::{
+    CreatedBlock,
+    ImportedAux,
+};
+#[cfg(feature = "metrics")]
+pub use self::metrics::{
+    MeteredClient,
+    RpcMetrics,
+};
+#[cfg(feature = "proxy")]
+pub use self::proxy::Proxy;
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+pub use self::tls::Certificate;
+// With the `native-tls` or `rustls` feature, subxt makes WebSocket connections itself, so
+// that TLS (and any proxy) can be configured beyond what jsonrpsee's transport allows.
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+use self::transport::connect as ws_transport;
+pub use self::{
+    beefy::{
+        BeefyJustifications,
+        Commitment,
+        Payload,
+        SignedCommitment,
+        MMR_ROOT_ID,
+    },
+    block_trace::{
+        BlockTrace,
+        TraceEvent,
+        TraceEventData,
+        TraceSpan,
+    },
+    chain_head::{
+        ChainHeadBackend,
+        FollowEvent,
+    },
+    chain_spec::ChainSpec,
+    load_balancing::LoadBalancedClient,
+    mmr::{
+        BeefyNextAuthoritySet,
+        LeafProof,
+        MmrLeaf,
+        MmrProof,
+    },
+    mock::MockRpcClient,
+    rate_limiting::RateLimitedClient,
+    reconnecting::{
+        ConnectionEvent,
+        ExponentialBackoff,
+        ReconnectingClient,
+        DEFAULT_PRIMARY_CHECK_INTERVAL,
+    },
+    recording::recording_ws_client,
+    retrying::{
+        is_transient,
+        RetryPolicy,
+        RetryingClient,
+    },
+};
+use crate::{
+    babe::EpochAuthorship,
+    error::BasicError,
+    storage::StorageKeyPrefix,
+    Config,
+    Metadata,
+};
+use codec::{
+    Decode,
+    Encode,
+};
+use core::{
+    convert::TryInto,
+    marker::PhantomData,
+};
+use frame_metadata::RuntimeMetadataPrefixed;
+use futures_timer::Delay;
+use jsonrpsee::core::client::{
+    TransportReceiverT,
+    TransportSenderT,
+};
+pub use jsonrpsee::{
+    client_transport::ws::{
+        InvalidUri,
+        Receiver as WsReceiver,
+        Sender as WsSender,
+        Uri,
+        WsTransportClientBuilder,
+    },
+    core::{
+        client::{
+            CertificateStore,
+            Client as RpcClient,
+            ClientBuilder as RpcClientBuilder,
+            ClientT,
+            Subscription,
+            SubscriptionClientT,
+        },
+        to_json_value,
+        DeserializeOwned,
+        Error as RpcError,
+        JsonValue,
+    },
+    rpc_params,
+    types::ParamsSer,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use sp_core::{
+    storage::{
+        StorageChangeSet,
+        StorageData,
+        StorageKey,
+    },
+    Bytes,
+    U256,
+};
+use sp_runtime::{
+    generic::{
+        Block,
+        SignedBlock,
+    },
+    transaction_validity::TransactionValidityError,
+};
+
+/// A number type that can be serialized both as a number or a string that encodes a number in a
+/// string.
+///
+/// We allow two representations of the block number as input. Either we deserialize to the type
+/// that is specified in the block type or we attempt to parse given hex value.
+///
+/// The primary motivation for having this type is to avoid overflows when using big integers in
+/// JavaScript (which we consider as an important RPC API consumer).
+#[derive(Copy, Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum NumberOrHex {
+    /// The number represented directly.
+    Number(u64),
+    /// Hex representation of the number.
+    Hex(U256),
+}
+
+/// RPC list or value wrapper.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum ListOrValue<T> {
+    /// A list of values of given type.
+    List(Vec<T>),
+    /// A single value of given type.
+    Value(T),
+}
+
+/// Alias for the type of a block returned by `chain_getBlock`
+pub type ChainBlock<T> =
+    SignedBlock<Block<<T as Config>::Header, <T as Config>::Extrinsic>>;
+
+/// Wrapper for NumberOrHex to allow custom From impls
+#[derive(Serialize)]
+pub struct BlockNumber(NumberOrHex);
+
+impl From<NumberOrHex> for BlockNumber {
+    fn from(x: NumberOrHex) -> Self {
+        BlockNumber(x)
+    }
+}
+
+impl From<u32> for BlockNumber {
+    fn from(x: u32) -> Self {
+        NumberOrHex::Number(x.into()).into()
+    }
+}
+
+/// Arbitrary properties defined in the chain spec as a JSON object.
+pub type SystemProperties = serde_json::Map<String, serde_json::Value>;
+
+/// Possible transaction status events.
+///
+/// # Note
+///
+/// This is copied from `sp-transaction-pool` to avoid a dependency on that crate. Therefore it
+/// must be kept compatible with that type from the target substrate version.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SubstrateTransactionStatus<Hash, BlockHash> {
+    /// Transaction is part of the future queue.
+    Future,
+    /// Transaction is part of the ready queue.
+    Ready,
+    /// The transaction has been broadcast to the given peers.
+    Broadcast(Vec<String>),
+    /// Transaction has been included in block with given hash.
+    InBlock(BlockHash),
+    /// The block this transaction was included in has been retracted.
+    Retracted(BlockHash),
+    /// Maximum number of finality watchers has been reached,
+    /// old watchers are being removed.
+    FinalityTimeout(BlockHash),
+    /// Transaction has been finalized by a finality-gadget, e.g GRANDPA
+    Finalized(BlockHash),
+    /// Transaction has been replaced in the pool, by another transaction
+    /// that provides the same tags. (e.g. same (sender, nonce)).
+    Usurped(Hash),
+    /// Transaction has been dropped from the pool because of the limit.
+    Dropped,
+    /// Transaction is no longer valid in the current state.
+    Invalid,
+}
+
+/// An event reported by a `transactionWatch_unstable_submitAndWatch` subscription (from the
+/// new JSON-RPC spec) about the progress of a transaction.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "event")]
+pub enum TransactionWatchEvent<Hash> {
+    /// The transaction is valid, and is in the node's transaction pool.
+    Validated,
+    /// The transaction has been broadcast to other nodes.
+    #[serde(rename_all = "camelCase")]
+    Broadcasted {
+        /// The number of peers that the transaction has been broadcast to.
+        num_peers: usize,
+    },
+    /// The transaction has been included in a block of the best chain, or if `None`, the
+    /// block it was included in is no longer part of the best chain.
+    BestChainBlockIncluded {
+        /// The block that the transaction is in.
+        block: Option<TransactionWatchBlock<Hash>>,
+    },
+    /// The block that the transaction is in has been finalized.
+    Finalized {
+        /// The block that the transaction is in.
+        block: TransactionWatchBlock<Hash>,
+    },
+    /// The node failed to keep watching the transaction (though it may still make it into a
+    /// block).
+    Error {
+        /// What went wrong.
+        error: String,
+    },
+    /// The transaction is invalid.
+    Invalid {
+        /// Why the transaction is invalid.
+        error: String,
+    },
+    /// The transaction has been dropped from the node's transaction pool.
+    Dropped {
+        /// Whether the transaction was broadcast to other nodes before being dropped (in
+        /// which case it may still make it into a block).
+        broadcasted: bool,
+        /// Why the transaction was dropped.
+        error: String,
+    },
+}
+
+/// The block that a transaction is in, as reported by a [`TransactionWatchEvent`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TransactionWatchBlock<Hash> {
+    /// The hash of the block.
+    pub hash: Hash,
+}
+
+/// This contains the runtime version information necessary to make transactions, as obtained from
+/// the RPC call `state_getRuntimeVersion`,
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeVersion {
+    /// Version of the runtime specification. A full-node will not attempt to use its native
+    /// runtime in substitute for the on-chain Wasm runtime unless all of `spec_name`,
+    /// `spec_version` and `authoring_version` are the same between Wasm and native.
+    pub spec_version: u32,
+
+    /// All existing dispatches are fully compatible when this number doesn't change. If this
+    /// number changes, then `spec_version` must change, also.
+    ///
+    /// This number must change when an existing dispatchable (module ID, dispatch ID) is changed,
+    /// either through an alteration in its user-level semantics, a parameter
+    /// added/removed/changed, a dispatchable being removed, a module being removed, or a
+    /// dispatchable/module changing its index.
+    ///
+    /// It need *not* change when a new module is added or when a dispatchable is added.
+    pub transaction_version: u32,
+
+    /// The other fields present may vary and aren't necessary for `subxt`; they are preserved in
+    /// this map.
+    #[serde(flatten)]
+    pub other: HashMap<String, serde_json::Value>,
+}
+
+/// The class of a dispatchable call, which determines how its weight is accounted for.
+///
+/// # Note
+///
+/// This is copied from `frame-support` to avoid a dependency on that crate. Therefore it
+/// must be kept compatible with that type from the target substrate version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
+#[serde(rename_all = "camelCase")]
+pub enum DispatchClass {
+    /// A normal dispatch.
+    Normal,
+    /// An operational dispatch.
+    Operational,
+    /// A mandatory dispatch, which is always included regardless of its weight.
+    Mandatory,
+}
+
+/// Information about the weight and fee of an extrinsic, as returned from the RPC call
+/// `payment_queryInfo`.
+///
+/// # Note
+///
+/// This is copied from `pallet-transaction-payment-rpc-runtime-api` to avoid a dependency
+/// on that crate. Therefore it must be kept compatible with that type from the target
+/// substrate version.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeDispatchInfo {
+    /// The weight of the extrinsic.
+    pub weight: u64,
+    /// The class of the extrinsic.
+    pub class: DispatchClass,
+    /// The inclusion fee of the extrinsic, which does not include any tip or other
+    /// fees that are only known once the extrinsic has been dispatched.
+    #[serde(deserialize_with = "deserialize_number_or_string")]
+    pub partial_fee: u128,
+}
+
+/// A breakdown of the inclusion fee of an extrinsic, as returned from the RPC call
+/// `payment_queryFeeDetails`.
+///
+/// # Note
+///
+/// This is copied from `pallet-transaction-payment` to avoid a dependency on that crate.
+/// Therefore it must be kept compatible with that type from the target substrate version.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeDetails {
+    /// The minimum fee for a transaction to be included in a block. This is `None` for
+    /// unsigned extrinsics, which pay no fee.
+    pub inclusion_fee: Option<InclusionFee>,
+}
+
+/// The fees which make up the inclusion fee of an extrinsic.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InclusionFee {
+    /// The minimum amount a user pays for a transaction.
+    #[serde(deserialize_with = "deserialize_number_or_string")]
+    pub base_fee: u128,
+    /// The amount paid for the encoded length (in bytes) of the transaction.
+    #[serde(deserialize_with = "deserialize_number_or_string")]
+    pub len_fee: u128,
+    /// The weight fee of the transaction, adjusted by the fee multiplier.
+    #[serde(deserialize_with = "deserialize_number_or_string")]
+    pub adjusted_weight_fee: u128,
+}
+
+impl InclusionFee {
+    /// The total inclusion fee; the sum of the base, length and adjusted weight fees.
+    pub fn inclusion_fee(&self) -> u128 {
+        self.base_fee
+            .saturating_add(self.len_fee)
+            .saturating_add(self.adjusted_weight_fee)
+    }
+}
+
+// Balances are returned either as plain numbers, as decimal strings (to avoid precision
+// issues with JavaScript consumers) or as hex strings (see [`NumberOrHex`]) depending on
+// the RPC method and node version. Accept any of them.
+fn deserialize_number_or_string<'de, D>(deserializer: D) -> Result<u128, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NumberOrString {
+        Number(u64),
+        String(String),
+    }
+
+    match NumberOrString::deserialize(deserializer)? {
+        NumberOrString::Number(n) => Ok(n.into()),
+        NumberOrString::String(s) => {
+            let res = match s.strip_prefix("0x") {
+                Some(hex) => u128::from_str_radix(hex, 16),
+                None => s.parse(),
+            };
+            res.map_err(serde::de::Error::custom)
+        }
+    }
+}
+
+/// The result of dry running an extrinsic using the RPC call `system_dryRun`.
+///
+/// This is a more convenient representation of the `ApplyExtrinsicResult` returned from
+/// the runtime, which is generic over the `DispatchError` type `E` of the target runtime.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DryRunResult<E> {
+    /// The extrinsic would be applied and dispatched successfully.
+    Success,
+    /// The extrinsic would be applied, but dispatching it would fail with the given error.
+    DispatchError(E),
+    /// The extrinsic is not valid, and so would not be included in a block at all.
+    TransactionValidityError(TransactionValidityError),
+}
+
+impl<E: Decode> DryRunResult<E> {
+    /// Decode a [`DryRunResult`] from the SCALE encoded `ApplyExtrinsicResult` bytes
+    /// returned from `system_dryRun`.
+    pub fn decode(bytes: &[u8]) -> Result<Self, codec::Error> {
+        let result =
+            <Result<Result<(), E>, TransactionValidityError>>::decode(&mut &*bytes)?;
+        Ok(match result {
+            Ok(Ok(())) => DryRunResult::Success,
+            Ok(Err(e)) => DryRunResult::DispatchError(e),
+            Err(e) => DryRunResult::TransactionValidityError(e),
+        })
+    }
+}
+
+/// ReadProof struct returned by the RPC
+///
+/// # Note
+///
+/// This is copied from `sc-rpc-api` to avoid a dependency on that crate. Therefore it
+/// must be kept compatible with that type from the target substrate version.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadProof<Hash> {
+    /// Block hash used to generate the proof
+    pub at: Hash,
+    /// A proof used to prove that storage entries are included in the storage trie
+    pub proof: Vec<Bytes>,
+}
+
+/// An extrinsic to remove from the transaction pool with the RPC call
+/// `author_removeExtrinsic`, given either by its hash or as the SCALE encoded extrinsic.
+///
+/// # Note
+///
+/// This is copied from `sc-rpc-api` to avoid a dependency on that crate. Therefore it
+/// must be kept compatible with that type from the target substrate version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExtrinsicOrHash<Hash> {
+    /// The hash of the extrinsic.
+    Hash(Hash),
+    /// The SCALE encoded extrinsic.
+    Extrinsic(Bytes),
+}
+
+/// The health of a node, as returned from the RPC call `system_health`.
+///
+/// # Note
+///
+/// This is copied from `sc-rpc-api` to avoid a dependency on that crate. Therefore it
+/// must be kept compatible with that type from the target substrate version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemHealth {
+    /// The number of peers the node is connected to.
+    pub peers: usize,
+    /// Whether the node is syncing.
+    pub is_syncing: bool,
+    /// Whether the node should be connected to peers. This is `false` for nodes which are
+    /// expected to run alone, such as development nodes.
+    pub should_have_peers: bool,
+}
+
+/// A peer that a node is connected to, as returned from the RPC call `system_peers`.
+///
+/// # Note
+///
+/// This is copied from `sc-rpc-api` to avoid a dependency on that crate. Therefore it
+/// must be kept compatible with that type from the target substrate version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerInfo<Hash> {
+    /// The peer's ID.
+    pub peer_id: String,
+    /// The peer's roles, for instance `FULL` or `AUTHORITY`.
+    pub roles: String,
+    /// The hash of the peer's best block.
+    pub best_hash: Hash,
+    /// The number of the peer's best block.
+    pub best_number: u64,
+}
+
+/// How far a node has synced, as returned from the RPC call `system_syncState`.
+///
+/// # Note
+///
+/// This is copied from `sc-rpc-api` to avoid a dependency on that crate. Therefore it
+/// must be kept compatible with that type from the target substrate version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncState {
+    /// The block number that the node started syncing from.
+    pub starting_block: u64,
+    /// The number of the node's best block.
+    pub current_block: u64,
+    /// The highest block number that the node's peers know of, if it knows.
+    pub highest_block: Option<u64>,
+}
+
+/// The type of chain a node is running, as returned from the RPC call `system_chainType`.
+///
+/// # Note
+///
+/// This is copied from `sc-chain-spec` to avoid a dependency on that crate. Therefore it
+/// must be kept compatible with that type from the target substrate version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChainType {
+    /// A development chain that runs mainly on one node.
+    Development,
+    /// A local chain that runs locally on multiple nodes for testing purposes.
+    Local,
+    /// A live chain.
+    Live,
+    /// Some custom chain type.
+    Custom(String),
+}
+
+/// The connection to a node that [`Rpc`] sends requests and subscriptions through.
+#[derive(Clone)]
+pub enum RpcConnection {
+    /// A jsonrpsee client, used as it is.
+    Client(Arc<RpcClient>),
+    /// A client which reconnects to the node if the connection is lost.
+    Reconnecting(Arc<ReconnectingClient>),
+    /// A client which spreads read only requests across several connections.
+    LoadBalanced(Arc<LoadBalancedClient>),
+    /// A client which limits the rate at which calls are made through another connection.
+    RateLimited(Arc<RateLimitedClient>),
+    /// A client which retries calls that fail for transient reasons through another
+    /// connection.
+    Retrying(Arc<RetryingClient>),
+    /// A client which records metrics for the calls made through another connection.
+    #[cfg(feature = "metrics")]
+    Metered(Arc<MeteredClient>),
+}
+
+impl From<RpcClient> for RpcConnection {
+    fn from(client: RpcClient) -> Self {
+        RpcConnection::Client(Arc::new(client))
+    }
+}
+
+impl From<ReconnectingClient> for RpcConnection {
+    fn from(client: ReconnectingClient) -> Self {
+        RpcConnection::Reconnecting(Arc::new(client))
+    }
+}
+
+impl From<LoadBalancedClient> for RpcConnection {
+    fn from(client: LoadBalancedClient) -> Self {
+        RpcConnection::LoadBalanced(Arc::new(client))
+    }
+}
+
+impl From<RateLimitedClient> for RpcConnection {
+    fn from(client: RateLimitedClient) -> Self {
+        RpcConnection::RateLimited(Arc::new(client))
+    }
+}
+
+impl From<RetryingClient> for RpcConnection {
+    fn from(client: RetryingClient) -> Self {
+        RpcConnection::Retrying(Arc::new(client))
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl From<MeteredClient> for RpcConnection {
+    fn from(client: MeteredClient) -> Self {
+        RpcConnection::Metered(Arc::new(client))
+    }
+}
+
+// Hand a call to whichever client the connection holds.
+macro_rules! with_client {
+    ($connection:expr, $client:ident => $call:expr) => {
+        match $connection {
+            RpcConnection::Client($client) => $call,
+            RpcConnection::Reconnecting($client) => $call,
+            RpcConnection::LoadBalanced($client) => $call,
+            RpcConnection::RateLimited($client) => $call,
+            RpcConnection::Retrying($client) => $call,
+            #[cfg(feature = "metrics")]
+            RpcConnection::Metered($client) => $call,
+        }
+    };
+}
+
+#[async_trait::async_trait]
+impl ClientT for RpcConnection {
+    async fn notification<'a>(
+        &self,
+        method: &'a str,
+        params: Option<ParamsSer<'a>>,
+    ) -> Result<(), RpcError> {
+        with_client!(self, client => client.notification(method, params).await)
+    }
+
+    async fn request<'a, R>(
+        &self,
+        method: &'a str,
+        params: Option<ParamsSer<'a>>,
+    ) -> Result<R, RpcError>
+    where
+        R: DeserializeOwned,
+    {
+        with_client!(self, client => client.request(method, params).await)
+    }
+
+    async fn batch_request<'a, R>(
+        &self,
+        batch: Vec<(&'a str, Option<ParamsSer<'a>>)>,
+    ) -> Result<Vec<R>, RpcError>
+    where
+        R: DeserializeOwned + Default + Clone,
+    {
+        with_client!(self, client => client.batch_request(batch).await)
+    }
+}
+
+#[async_trait::async_trait]
+impl SubscriptionClientT for RpcConnection {
+    async fn subscribe<'a, Notif>(
+        &self,
+        subscribe_method: &'a str,
+        params: Option<ParamsSer<'a>>,
+        unsubscribe_method: &'a str,
+    ) -> Result<Subscription<Notif>, RpcError>
+    where
+        Notif: DeserializeOwned,
+    {
+        with_client!(self, client => {
+            client
+                .subscribe(subscribe_method, params, unsubscribe_method)
+                .await
+        })
+    }
+
+    async fn subscribe_to_method<'a, Notif>(
+        &self,
+        method: &'a str,
+    ) -> Result<Subscription<Notif>, RpcError>
+    where
+        Notif: DeserializeOwned,
+    {
+        with_client!(self, client => client.subscribe_to_method(method).await)
+    }
+}
+
+/// Client for substrate rpc interfaces
+pub struct Rpc<T: Config> {
+    /// Rpc client for sending requests.
+    pub client: RpcConnection,
+    marker: PhantomData<T>,
+}
+
+impl<T: Config> Clone for Rpc<T> {
+    fn clone(&self) -> Self {
+        Self {
+            client: self.client.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Config> Rpc<T> {
+    /// Create a new [`Rpc`]
+    pub fn new<C: Into<RpcConnection>>(client: C) -> Self {
+        Self {
+            client: client.into(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Fetch a storage key
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(key = %hex::encode(&key.0), block_hash = ?hash)
+        )
+    )]
+    pub async fn storage(
+        &self,
+        key: &StorageKey,
+        hash: Option<T::Hash>,
+    ) -> Result<Option<StorageData>, BasicError> {
+        let params = rpc_params![key, hash];
+        let data = self.client.request("state_getStorage", params).await?;
+        Ok(data)
+    }
+
+    /// Fetch the values of several storage keys at once, in a single batch request.
+    /// The values are handed back in the same order as the keys.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(keys = keys.len(), block_hash = ?hash))
+    )]
+    pub async fn storage_values(
+        &self,
+        keys: &[StorageKey],
+        hash: Option<T::Hash>,
+    ) -> Result<Vec<Option<StorageData>>, BasicError> {
+        let batch = keys
+            .iter()
+            .map(|key| ("state_getStorage", rpc_params![key, hash]))
+            .collect();
+        self.batch_request(batch).await
+    }
+
+    /// Returns the keys with prefix with pagination support.
+    /// Up to `count` keys will be returned.
+    /// If `start_key` is passed, return next keys in storage in lexicographic order.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(count = count, block_hash = ?hash))
+    )]
+    pub async fn storage_keys_paged(
+        &self,
+        prefix: Option<StorageKeyPrefix>,
+        count: u32,
+        start_key: Option<StorageKey>,
+        hash: Option<T::Hash>,
+    ) -> Result<Vec<StorageKey>, BasicError> {
+        let prefix = prefix.map(|p| p.to_storage_key());
+        let params = rpc_params![prefix, count, start_key, hash];
+        let data = self.client.request("state_getKeysPaged", params).await?;
+        Ok(data)
+    }
+
+    /// Query historical storage entries
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(keys = keys.len(), from = ?from, to = ?to))
+    )]
+    pub async fn query_storage(
+        &self,
+        keys: Vec<StorageKey>,
+        from: T::Hash,
+        to: Option<T::Hash>,
+    ) -> Result<Vec<StorageChangeSet<T::Hash>>, BasicError> {
+        let params = rpc_params![keys, from, to];
+        self.client
+            .request("state_queryStorage", params)
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Query historical storage entries
+    pub async fn query_storage_at(
+        &self,
+        keys: &[StorageKey],
+        at: Option<T::Hash>,
+    ) -> Result<Vec<StorageChangeSet<T::Hash>>, BasicError> {
+        let params = rpc_params![keys, at];
+        self.client
+            .request("state_queryStorageAt", params)
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Fetch the genesis hash
+    pub async fn genesis_hash(&self) -> Result<T::Hash, BasicError> {
+        let block_zero = Some(ListOrValue::Value(NumberOrHex::Number(0)));
+        let params = rpc_params![block_zero];
+        let list_or_value: ListOrValue<Option<T::Hash>> =
+            self.client.request("chain_getBlockHash", params).await?;
+        match list_or_value {
+            ListOrValue::Value(genesis_hash) => {
+                genesis_hash.ok_or_else(|| "Genesis hash not found".into())
+            }
+            ListOrValue::List(_) => Err("Expected a Value, got a List".into()),
+        }
+    }
+
+    /// Fetch the metadata
+    pub async fn metadata(&self) -> Result<Metadata, BasicError> {
+        self.metadata_at(None).await
+    }
+
+    /// Fetch the metadata of the runtime at the given block, or the latest block if
+    /// `None` is given.
+    pub async fn metadata_at(&self, at: Option<T::Hash>) -> Result<Metadata, BasicError> {
+        let bytes: Bytes = self
+            .client
+            .request("state_getMetadata", rpc_params![at])
+            .await?;
+        let meta: RuntimeMetadataPrefixed = Decode::decode(&mut &bytes[..])?;
+        let metadata: Metadata = meta.try_into()?;
+        Ok(metadata)
+    }
+
+    /// Call a runtime API function (for instance `Metadata_metadata`) with the SCALE
+    /// encoded parameters given at the given block (or the latest block if `None` is
+    /// given), handing back its SCALE encoded output. See [`crate::RuntimeApiClient`] for
+    /// a typed version of this.
+    pub async fn call(
+        &self,
+        function: &str,
+        call_parameters: &[u8],
+        at: Option<T::Hash>,
+    ) -> Result<Bytes, BasicError> {
+        let call_parameters = Bytes(call_parameters.to_vec());
+        let params = rpc_params![function, call_parameters, at];
+        Ok(self.client.request("state_call", params).await?)
+    }
+
+    /// Fetch system properties
+    pub async fn system_properties(&self) -> Result<SystemProperties, BasicError> {
+        Ok(self
+            .client
+            .request("system_properties", rpc_params![])
+            .await?)
+    }
+
+    /// Fetch system chain
+    pub async fn system_chain(&self) -> Result<String, BasicError> {
+        Ok(self.client.request("system_chain", rpc_params![]).await?)
+    }
+
+    /// Fetch system name
+    pub async fn system_name(&self) -> Result<String, BasicError> {
+        Ok(self.client.request("system_name", rpc_params![]).await?)
+    }
+
+    /// Fetch system version
+    pub async fn system_version(&self) -> Result<String, BasicError> {
+        Ok(self.client.request("system_version", rpc_params![]).await?)
+    }
+
+    /// Fetch the health of the node: how many peers it has, and whether it's syncing.
+    pub async fn system_health(&self) -> Result<SystemHealth, BasicError> {
+        Ok(self.client.request("system_health", rpc_params![]).await?)
+    }
+
+    /// Fetch the peers that the node is connected to. This is an unsafe RPC method, so
+    /// the node will only answer it if it's run with `--rpc-methods unsafe` (or is only
+    /// listening locally).
+    pub async fn system_peers(&self) -> Result<Vec<PeerInfo<T::Hash>>, BasicError> {
+        Ok(self.client.request("system_peers", rpc_params![]).await?)
+    }
+
+    /// Fetch how far the node has synced.
+    pub async fn system_sync_state(&self) -> Result<SyncState, BasicError> {
+        Ok(self
+            .client
+            .request("system_syncState", rpc_params![])
+            .await?)
+    }
+
+    /// Fetch the base58 encoded ID of the node on the peer to peer network.
+    pub async fn system_local_peer_id(&self) -> Result<String, BasicError> {
+        Ok(self
+            .client
+            .request("system_localPeerId", rpc_params![])
+            .await?)
+    }
+
+    /// Fetch the type of chain that the node is running.
+    pub async fn system_chain_type(&self) -> Result<ChainType, BasicError> {
+        Ok(self
+            .client
+            .request("system_chainType", rpc_params![])
+            .await?)
+    }
+
+    /// Get a header
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(block_hash = ?hash))
+    )]
+    pub async fn header(
+        &self,
+        hash: Option<T::Hash>,
+    ) -> Result<Option<T::Header>, BasicError> {
+        let params = rpc_params![hash];
+        let header = self.client.request("chain_getHeader", params).await?;
+        Ok(header)
+    }
+
+    /// Get the headers of several blocks at once, in a single batch request. The headers
+    /// are handed back in the same order as the hashes.
+    pub async fn headers(
+        &self,
+        hashes: &[T::Hash],
+    ) -> Result<Vec<Option<T::Header>>, BasicError> {
+        let batch = hashes
+            .iter()
+            .map(|hash| ("chain_getHeader", rpc_params![hash]))
+            .collect();
+        self.batch_request(batch).await
+    }
+
+    /// Get a block hash, returns hash of latest block by default
+    pub async fn block_hash(
+        &self,
+        block_number: Option<BlockNumber>,
+    ) -> Result<Option<T::Hash>, BasicError> {
+        let block_number = block_number.map(ListOrValue::Value);
+        let params = rpc_params![block_number];
+        let list_or_value = self.client.request("chain_getBlockHash", params).await?;
+        match list_or_value {
+            ListOrValue::Value(hash) => Ok(hash),
+            ListOrValue::List(_) => Err("Expected a Value, got a List".into()),
+        }
+    }
+
+    /// Get the hashes of several blocks at once, in a single request. The hashes are handed
+    /// back in the same order as the block numbers.
+    pub async fn block_hashes(
+        &self,
+        block_numbers: Vec<BlockNumber>,
+    ) -> Result<Vec<Option<T::Hash>>, BasicError> {
+        if block_numbers.is_empty() {
+            return Ok(Vec::new())
+        }
+        let params = rpc_params![ListOrValue::List(block_numbers)];
+        let list_or_value = self.client.request("chain_getBlockHash", params).await?;
+        match list_or_value {
+            ListOrValue::List(hashes) => Ok(hashes),
+            ListOrValue::Value(_) => Err("Expected a List, got a Value".into()),
+        }
+    }
+
+    /// Make a request to any JSON-RPC method, for instance one from a custom RPC extension
+    /// provided by the node that [`Rpc`] has no method for, and deserialize the response
+    /// into `R`. Parameters are most easily built with [`rpc_params!`].
+    ///
+    /// ```no_run
+    /// # #[async_std::main]
+    /// # async fn main() -> Result<(), subxt::BasicError> {
+    /// use subxt::{rpc::rpc_params, ClientBuilder, DefaultConfig};
+    ///
+    /// let client = ClientBuilder::new().build::<DefaultConfig>().await?;
+    /// let chain: String = client.rpc().request("system_chain", rpc_params![]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn request<'a, R: DeserializeOwned>(
+        &self,
+        method: &'a str,
+        params: Option<ParamsSer<'a>>,
+    ) -> Result<R, BasicError> {
+        Ok(self.client.request(method, params).await?)
+    }
+
+    /// Much like [`Rpc::request()`], but giving up with [`RpcError::RequestTimeout`] if
+    /// there's no response within the timeout given, rather than the one the client was
+    /// built with (see [`crate::ClientBuilder::set_request_timeout()`]). The client's own
+    /// timeout still applies too, so this can only shorten how long is waited.
+    pub async fn request_with_timeout<'a, R: DeserializeOwned>(
+        &self,
+        method: &'a str,
+        params: Option<ParamsSer<'a>>,
+        timeout: Duration,
+    ) -> Result<R, BasicError> {
+        let request = self.client.request(method, params);
+        match futures::future::select(Box::pin(request), Delay::new(timeout)).await {
+            futures::future::Either::Left((res, _)) => Ok(res?),
+            futures::future::Either::Right(_) => Err(RpcError::RequestTimeout.into()),
+        }
+    }
+
+    /// Subscribe via any JSON-RPC subscription method, for instance one from a custom RPC
+    /// extension provided by the node, deserializing each notification into `N`. The
+    /// `unsubscribe_method` is called when the subscription is dropped.
+    ///
+    /// ```no_run
+    /// # #[async_std::main]
+    /// # async fn main() -> Result<(), subxt::BasicError> {
+    /// use subxt::{rpc::rpc_params, ClientBuilder, DefaultConfig};
+    ///
+    /// let client = ClientBuilder::new().build::<DefaultConfig>().await?;
+    /// let mut sub = client
+    ///     .rpc()
+    ///     .subscribe::<serde_json::Value>(
+    ///         "chain_subscribeAllHeads",
+    ///         rpc_params![],
+    ///         "chain_unsubscribeAllHeads",
+    ///     )
+    ///     .await?;
+    /// let header = sub.next().await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn subscribe<'a, N: DeserializeOwned>(
+        &self,
+        subscribe_method: &'a str,
+        params: Option<ParamsSer<'a>>,
+        unsubscribe_method: &'a str,
+    ) -> Result<Subscription<N>, BasicError> {
+        Ok(self
+            .client
+            .subscribe(subscribe_method, params, unsubscribe_method)
+            .await?)
+    }
+
+    /// Send several requests to the node at once, as a single JSON-RPC batch, and hand
+    /// back their responses in the same order. This saves on round trips when lots of
+    /// independent requests need making. If any of the requests fails, so does the batch.
+    pub async fn batch_request<'a, R>(
+        &self,
+        batch: Vec<(&'a str, Option<ParamsSer<'a>>)>,
+    ) -> Result<Vec<R>, BasicError>
+    where
+        R: DeserializeOwned + Default + Clone,
+    {
+        if batch.is_empty() {
+            return Ok(Vec::new())
+        }
+        Ok(self.client.batch_request(batch).await?)
+    }
+
+    /// Get a block hash of the latest finalized block
+    pub async fn finalized_head(&self) -> Result<T::Hash, BasicError> {
+        let hash = self
+            .client
+            .request("chain_getFinalizedHead", rpc_params![])
+            .await?;
+        Ok(hash)
+    }
+
+    /// Get a Block
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(block_hash = ?hash))
+    )]
+    pub async fn block(
+        &self,
+        hash: Option<T::Hash>,
+    ) -> Result<Option<ChainBlock<T>>, BasicError> {
+        let params = rpc_params![hash];
+        let block = self.client.request("chain_getBlock", params).await?;
+        Ok(block)
+    }
+
+    /// Get proof of storage entries at a specific block's state.
+    pub async fn read_proof(
+        &self,
+        keys: Vec<StorageKey>,
+        hash: Option<T::Hash>,
+    ) -> Result<ReadProof<T::Hash>, BasicError> {
+        let params = rpc_params![keys, hash];
+        let proof = self.client.request("state_getReadProof", params).await?;
+        Ok(proof)
+    }
+
+    /// Fetch the runtime version
+    pub async fn runtime_version(
+        &self,
+        at: Option<T::Hash>,
+    ) -> Result<RuntimeVersion, BasicError> {
+        let params = rpc_params![at];
+        let version = self
+            .client
+            .request("state_getRuntimeVersion", params)
+            .await?;
+        Ok(version)
+    }
+
+    /// Subscribe to blocks.
+    pub async fn subscribe_blocks(&self) -> Result<Subscription<T::Header>, BasicError> {
+        let subscription = self
+            .client
+            .subscribe(
+                "chain_subscribeNewHeads",
+                rpc_params![],
+                "chain_unsubscribeNewHeads",
+            )
+            .await?;
+
+        Ok(subscription)
+    }
+
+    /// Subscribe to finalized blocks.
+    pub async fn subscribe_finalized_blocks(
+        &self,
+    ) -> Result<Subscription<T::Header>, BasicError> {
+        let subscription = self
+            .client
+            .subscribe(
+                "chain_subscribeFinalizedHeads",
+                rpc_params![],
+                "chain_unsubscribeFinalizedHeads",
+            )
+            .await?;
+        Ok(subscription)
+    }
+
+    /// Start following the chain using the `chainHead` methods of the new JSON-RPC spec,
+    /// handing back a [`ChainHeadBackend`] through which the blocks being followed can be
+    /// queried. If `with_runtime` is true, the runtime of each block is reported where it
+    /// changes.
+    pub async fn chain_head(
+        &self,
+        with_runtime: bool,
+    ) -> Result<ChainHeadBackend<T>, BasicError> {
+        ChainHeadBackend::follow(self.clone(), with_runtime).await
+    }
+
+    /// Subscribe to changes to the storage entries with the given keys (or to all storage,
+    /// if `None` is given) in each new best block.
+    pub async fn subscribe_storage(
+        &self,
+        keys: Option<Vec<StorageKey>>,
+    ) -> Result<Subscription<StorageChangeSet<T::Hash>>, BasicError> {
+        let subscription = self
+            .client
+            .subscribe(
+                "state_subscribeStorage",
+                rpc_params![keys],
+                "state_unsubscribeStorage",
+            )
+            .await?;
+        Ok(subscription)
+    }
+
+    /// Create and submit an extrinsic and return corresponding Hash if successful
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(tx_hash = tracing::field::Empty))
+    )]
+    pub async fn submit_extrinsic<X: Encode>(
+        &self,
+        extrinsic: X,
+    ) -> Result<T::Hash, BasicError> {
+        let bytes: Bytes = extrinsic.encode().into();
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record(
+            "tx_hash",
+            &tracing::field::debug(crate::extrinsic::extrinsic_hash::<T>(&bytes)),
+        );
+        let params = rpc_params![bytes];
+        let xt_hash = self
+            .client
+            .request("author_submitExtrinsic", params)
+            .await?;
+        Ok(xt_hash)
+    }
+
+    /// Create and submit an extrinsic and return a subscription to the events triggered.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(tx_hash = tracing::field::Empty))
+    )]
+    pub async fn watch_extrinsic<X: Encode>(
+        &self,
+        extrinsic: X,
+    ) -> Result<Subscription<SubstrateTransactionStatus<T::Hash, T::Hash>>, BasicError>
+    {
+        let bytes: Bytes = extrinsic.encode().into();
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record(
+            "tx_hash",
+            &tracing::field::debug(crate::extrinsic::extrinsic_hash::<T>(&bytes)),
+        );
+        let params = rpc_params![bytes];
+        let subscription = self
+            .client
+            .subscribe(
+                "author_submitAndWatchExtrinsic",
+                params,
+                "author_unwatchExtrinsic",
+            )
+            .await?;
+        Ok(subscription)
+    }
+
+    /// Submit an extrinsic using `transactionWatch_unstable_submitAndWatch` from the new
+    /// JSON-RPC spec, and return a subscription to the events reported about its progress.
+    /// [`crate::TransactionProgress::from_transaction_watch()`] turns this into the same
+    /// statuses as [`Rpc::watch_extrinsic()`] reports.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(tx_hash = tracing::field::Empty))
+    )]
+    pub async fn transaction_watch<X: Encode>(
+        &self,
+        extrinsic: X,
+    ) -> Result<Subscription<TransactionWatchEvent<T::Hash>>, BasicError> {
+        let bytes: Bytes = extrinsic.encode().into();
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record(
+            "tx_hash",
+            &tracing::field::debug(crate::extrinsic::extrinsic_hash::<T>(&bytes)),
+        );
+        let subscription = self
+            .client
+            .subscribe(
+                "transactionWatch_unstable_submitAndWatch",
+                rpc_params![bytes],
+                "transactionWatch_unstable_unwatch",
+            )
+            .await?;
+        Ok(subscription)
+    }
+
+    /// Query the weight and inclusion fee of the provided SCALE encoded extrinsic.
+    pub async fn payment_query_info(
+        &self,
+        encoded_signed: &[u8],
+        at: Option<T::Hash>,
+    ) -> Result<RuntimeDispatchInfo, BasicError> {
+        let bytes: Bytes = encoded_signed.to_vec().into();
+        let params = rpc_params![bytes, at];
+        let info = self.client.request("payment_queryInfo", params).await?;
+        Ok(info)
+    }
+
+    /// Query a detailed breakdown of the inclusion fee of the provided SCALE encoded extrinsic.
+    pub async fn payment_query_fee_details(
+        &self,
+        encoded_signed: &[u8],
+        at: Option<T::Hash>,
+    ) -> Result<FeeDetails, BasicError> {
+        let bytes: Bytes = encoded_signed.to_vec().into();
+        let params = rpc_params![bytes, at];
+        let details = self
+            .client
+            .request("payment_queryFeeDetails", params)
+            .await?;
+        Ok(details)
+    }
+
+    /// Dry run the provided SCALE encoded extrinsic at some block (or the best block if
+    /// none is given), returning the SCALE encoded `ApplyExtrinsicResult`. Use
+    /// [`DryRunResult::decode`] to decode it.
+    ///
+    /// **Note:** this is an unsafe RPC method, and so may not be available on nodes
+    /// that are not configured to expose it.
+    pub async fn dry_run(
+        &self,
+        encoded_signed: &[u8],
+        at: Option<T::Hash>,
+    ) -> Result<Bytes, BasicError> {
+        let bytes: Bytes = encoded_signed.to_vec().into();
+        let params = rpc_params![bytes, at];
+        let result_bytes = self.client.request("system_dryRun", params).await?;
+        Ok(result_bytes)
+    }
+
+    /// Insert a key into the keystore.
+    pub async fn insert_key(
+        &self,
+        key_type: String,
+        suri: String,
+        public: Bytes,
+    ) -> Result<(), BasicError> {
+        let params = rpc_params![key_type, suri, public];
+        self.client.request("author_insertKey", params).await?;
+        Ok(())
+    }
+
+    /// Fetch a SCALE encoded proof that the block with the number given has been
+    /// finalized by GRANDPA, or `None` if it can't be proven. Use
+    /// [`crate::grandpa::prove_finality()`] to decode and verify it.
+    pub async fn prove_finality(
+        &self,
+        block: BlockNumber,
+    ) -> Result<Option<Bytes>, BasicError> {
+        Ok(self
+            .client
+            .request("grandpa_proveFinality", rpc_params![block])
+            .await?)
+    }
+
+    /// Fetch the slots of the current BABE epoch in which each of the node's BABE keys may
+    /// author a block, by the SS58 address of the key. Use [`crate::babe::current_epoch()`]
+    /// to find out when the epoch starts and ends.
+    ///
+    /// This is an unsafe RPC method, so the node will only answer it if it's run with
+    /// `--rpc-methods unsafe` (or is only listening locally).
+    pub async fn epoch_authorship(
+        &self,
+    ) -> Result<HashMap<String, EpochAuthorship>, BasicError> {
+        Ok(self
+            .client
+            .request("babe_epochAuthorship", rpc_params![])
+            .await?)
+    }
+
+    /// Fetch the SCALE encoded extrinsics waiting in the node's transaction pool.
+    pub async fn pending_extrinsics(&self) -> Result<Vec<Bytes>, BasicError> {
+        Ok(self
+            .client
+            .request("author_pendingExtrinsics", rpc_params![])
+            .await?)
+    }
+
+    /// Remove the extrinsics given from the node's transaction pool, along with any which
+    /// depend on them (such as later transactions from the same account), handing back
+    /// the hashes of every extrinsic removed. This is useful for clearing out a
+    /// transaction which will never be included but is holding up others.
+    ///
+    /// This is an unsafe RPC method, so the node will only answer it if it's run with
+    /// `--rpc-methods unsafe` (or is only listening locally).
+    pub async fn remove_extrinsic(
+        &self,
+        extrinsics: Vec<ExtrinsicOrHash<T::Hash>>,
+    ) -> Result<Vec<T::Hash>, BasicError> {
+        Ok(self
+            .client
+            .request("author_removeExtrinsic", rpc_params![extrinsics])
+            .await?)
+    }
+
+    /// Generate new session keys and returns the corresponding public keys.
+    pub async fn rotate_keys(&self) -> Result<Bytes, BasicError> {
+        Ok(self
+            .client
+            .request("author_rotateKeys", rpc_params![])
+            .await?)
+    }
+
+    /// Checks if the keystore has private keys for the given session public keys.
+    ///
+    /// `session_keys` is the SCALE encoded session keys object from the runtime.
+    ///
+    /// Returns `true` iff all private keys could be found.
+    pub async fn has_session_keys(
+        &self,
+        session_keys: Bytes,
+    ) -> Result<bool, BasicError> {
+        let params = rpc_params![session_keys];
+        Ok(self.client.request("author_hasSessionKeys", params).await?)
+    }
+
+    /// Checks if the keystore has private keys for the given public key and key type.
+    ///
+    /// Returns `true` if a private key could be found.
+    pub async fn has_key(
+        &self,
+        public_key: Bytes,
+        key_type: String,
+    ) -> Result<bool, BasicError> {
+        let params = rpc_params![public_key, key_type];
+        Ok(self.client.request("author_hasKey", params).await?)
+    }
+}
+
+// The prefixes of the methods which only read from the chain (including the subscriptions
+// to chain data), and so answer the same whichever node is asked.
+const CHAIN_READ_PREFIXES: &[&str] = &["state_", "childstate_", "chain_", "payment_"];
+
+// Where the answer to a call which only reads comes from.
+#[derive(Clone, Copy, PartialEq)]
+enum ReadsFrom {
+    // The chain, so any node following it gives the same answer.
+    Chain,
+    // The node itself (its peers, keys, transaction pool and so on), or something tied to
+    // the node, such as a `chainHead` subscription.
+    Node,
+}
+
+// The other methods which only read. Calls to anything not listed (such as submitting
+// extrinsics, inserting keys, or sealing blocks) may have side effects.
+const READ_ONLY_METHODS: &[(&str, ReadsFrom)] = &[
+    ("system_name", ReadsFrom::Node),
+    ("system_version", ReadsFrom::Node),
+    ("system_chain", ReadsFrom::Chain),
+    ("system_chainType", ReadsFrom::Chain),
+    ("system_properties", ReadsFrom::Chain),
+    ("system_health", ReadsFrom::Node),
+    ("system_localPeerId", ReadsFrom::Node),
+    ("system_localListenAddresses", ReadsFrom::Node),
+    ("system_peers", ReadsFrom::Node),
+    ("system_nodeRoles", ReadsFrom::Node),
+    ("system_syncState", ReadsFrom::Node),
+    // Counts the transactions in the node's pool too.
+    ("system_accountNextIndex", ReadsFrom::Node),
+    ("system_dryRun", ReadsFrom::Chain),
+    ("author_pendingExtrinsics", ReadsFrom::Node),
+    ("author_hasKey", ReadsFrom::Node),
+    ("author_hasSessionKeys", ReadsFrom::Node),
+    ("babe_epochAuthorship", ReadsFrom::Node),
+    ("grandpa_roundState", ReadsFrom::Node),
+    ("grandpa_proveFinality", ReadsFrom::Node),
+    ("grandpa_subscribeJustifications", ReadsFrom::Node),
+    ("beefy_getFinalizedHead", ReadsFrom::Node),
+    ("beefy_subscribeJustifications", ReadsFrom::Node),
+    ("mmr_generateProof", ReadsFrom::Node),
+    ("mmr_verifyProof", ReadsFrom::Node),
+    ("chainHead_unstable_follow", ReadsFrom::Node),
+    ("chainHead_unstable_genesisHash", ReadsFrom::Node),
+    ("sync_state_genSyncSpec", ReadsFrom::Node),
+    ("rpc_methods", ReadsFrom::Node),
+];
+
+// Where the answer to a call to the given method comes from, if it only reads.
+fn reads_from(method: &str) -> Option<ReadsFrom> {
+    if CHAIN_READ_PREFIXES
+        .iter()
+        .any(|prefix| method.starts_with(prefix))
+    {
+        return Some(ReadsFrom::Chain)
+    }
+    READ_ONLY_METHODS
+        .iter()
+        .find(|(read_only, _)| *read_only == method)
+        .map(|(_, from)| *from)
+}
+
+// Can a call to the given method be made again without any unwanted side effects if we don't
+// know whether it got through? Only methods known to just read are.
+pub(crate) fn is_idempotent(method: &str) -> bool {
+    reads_from(method).is_some()
+}
+
+// Can a call to the given method be sent to any node, because it only reads from the chain?
+pub(crate) fn is_read_only(method: &str) -> bool {
+    reads_from(method) == Some(ReadsFrom::Chain)
+}
+
+/// The default maximum number of notifications buffered for each subscription.
+pub const DEFAULT_SUBSCRIPTION_BUFFER_SIZE: usize = 4096;
+
+/// The default time to wait for a connection to a node to be established.
+pub const DEFAULT_CONNECTION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The default time to wait for the response to a request.
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How to build a WS RPC client.
+#[derive(Clone)]
+pub struct WsClientConfig {
+    /// The maximum number of notifications buffered for each subscription. A subscription
+    /// which falls further behind than this is closed.
+    pub buffer_size: usize,
+    /// How long to wait for the connection to the node to be established.
+    pub connection_timeout: Duration,
+    /// How long to wait for the response to each request before giving up on it with
+    /// [`RpcError::RequestTimeout`].
+    pub request_timeout: Duration,
+    /// Extra headers (as name and value) to send with the WebSocket handshake, for instance
+    /// to authenticate with RPC providers that require an `Authorization` or API key header.
+    pub headers: Vec<(String, String)>,
+    /// Where to find the root certificates trusted when connecting over TLS (`wss://`).
+    /// Defaults to [`CertificateStore::Native`], which trusts the certificates trusted by
+    /// the operating system. [`CertificateStore::WebPki`] trusts a bundled set of well
+    /// known authorities instead, unless the `native-tls` feature is enabled, in which case
+    /// the operating system's store is always used.
+    pub certificate_store: CertificateStore,
+    /// Extra root certificates to trust when connecting over TLS, in addition to those in
+    /// the `certificate_store`, for instance that of a private certificate authority used
+    /// for internal endpoints. Available with the `native-tls` or `rustls` feature.
+    #[cfg(any(feature = "native-tls", feature = "rustls"))]
+    pub root_certificates: Vec<Certificate>,
+    /// Accept any certificate the node presents when connecting over TLS, without checking
+    /// who issued it or which host it's for. This lets anyone intercept the connection, so
+    /// should only be used for development nodes with self-signed certificates. Available
+    /// with the `native-tls` or `rustls` feature.
+    #[cfg(any(feature = "native-tls", feature = "rustls"))]
+    pub accept_invalid_certificates: bool,
+    /// How long the connection can go without receiving anything from the node before a
+    /// request is sent to keep it alive. Load balancers and proxies often drop idle
+    /// connections without closing them, which otherwise leaves subscriptions silently
+    /// waiting forever. By default, nothing is sent.
+    pub ping_interval: Option<Duration>,
+    /// How long the connection can go without receiving anything from the node (including
+    /// the responses to the requests sent every `ping_interval`) before it's considered
+    /// dead and closed, failing any requests and subscriptions (and so reconnecting if
+    /// using a [`ReconnectingClient`]). This should be longer than `ping_interval`. By
+    /// default, the connection is never considered dead.
+    pub max_inactivity: Option<Duration>,
+    /// A SOCKS5 or HTTP proxy to connect to the node through. By default, the connection
+    /// is made directly. Available with the `proxy` feature.
+    #[cfg(feature = "proxy")]
+    pub proxy: Option<Proxy>,
+}
+
+impl Default for WsClientConfig {
+    fn default() -> Self {
+        WsClientConfig {
+            buffer_size: DEFAULT_SUBSCRIPTION_BUFFER_SIZE,
+            connection_timeout: DEFAULT_CONNECTION_TIMEOUT,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            headers: Vec::new(),
+            certificate_store: CertificateStore::Native,
+            #[cfg(any(feature = "native-tls", feature = "rustls"))]
+            root_certificates: Vec::new(),
+            #[cfg(any(feature = "native-tls", feature = "rustls"))]
+            accept_invalid_certificates: false,
+            ping_interval: None,
+            max_inactivity: None,
+            #[cfg(feature = "proxy")]
+            proxy: None,
+        }
+    }
+}
+
+// The header values are left out, since they're often credentials.
+impl std::fmt::Debug for WsClientConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let headers: Vec<_> = self
+            .headers
+            .iter()
+            .map(|(name, _)| (name, "<redacted>"))
+            .collect();
+        let mut f = f.debug_struct("WsClientConfig");
+        f.field("buffer_size", &self.buffer_size)
+            .field("connection_timeout", &self.connection_timeout)
+            .field("request_timeout", &self.request_timeout)
+            .field("headers", &headers)
+            .field("certificate_store", &self.certificate_store)
+            .field("ping_interval", &self.ping_interval)
+            .field("max_inactivity", &self.max_inactivity);
+        #[cfg(any(feature = "native-tls", feature = "rustls"))]
+        f.field("root_certificates", &self.root_certificates).field(
+            "accept_invalid_certificates",
+            &self.accept_invalid_certificates,
+        );
+        #[cfg(feature = "proxy")]
+        f.field("proxy", &self.proxy);
+        f.finish()
+    }
+}
+
+/// Build WS RPC client from URL
+pub async fn ws_client(url: &str) -> Result<RpcClient, RpcError> {
+    ws_client_with_config(url, &Default::default()).await
+}
+
+/// Build WS RPC client from URL, which buffers at most `buffer_size` notifications for
+/// each subscription. A subscription which falls further behind than this is closed.
+pub async fn ws_client_with_buffer_size(
+    url: &str,
+    buffer_size: usize,
+) -> Result<RpcClient, RpcError> {
+    let config = WsClientConfig {
+        buffer_size,
+        ..Default::default()
+    };
+    ws_client_with_config(url, &config).await
+}
+
+/// Build WS RPC client from URL, configured as given.
+pub async fn ws_client_with_config(
+    url: &str,
+    config: &WsClientConfig,
+) -> Result<RpcClient, RpcError> {
+    connect_ws_client(url, config, None).await
+}
+
+// Build WS RPC client from URL, configured as given, which calls `on_close` with the reason
+// as soon as the connection is closed (or found to be dead).
+pub(crate) async fn ws_client_reporting_close(
+    url: &str,
+    config: &WsClientConfig,
+    on_close: OnClose,
+) -> Result<RpcClient, RpcError> {
+    connect_ws_client(url, config, Some(on_close)).await
+}
+
+async fn connect_ws_client(
+    url: &str,
+    config: &WsClientConfig,
+    on_close: Option<OnClose>,
+) -> Result<RpcClient, RpcError> {
+    let (sender, receiver) = ws_transport(url, config).await?;
+    Ok(client_over(sender, receiver, config, on_close))
+}
+
+// Build a client over the transport given, which keeps the connection alive as configured,
+// and calls `on_close` (if given) once the connection is closed.
+fn client_over<S, R>(
+    sender: S,
+    receiver: R,
+    config: &WsClientConfig,
+    on_close: Option<OnClose>,
+) -> RpcClient
+where
+    S: TransportSenderT + Send + 'static,
+    R: TransportReceiverT + Send + 'static,
+{
+    let (sender, receiver) = keepalive(
+        sender,
+        receiver,
+        config.ping_interval,
+        config.max_inactivity,
+    );
+    let builder = ws_client_builder(config);
+    match on_close {
+        Some(on_close) => builder.build(sender, receiver.on_close(on_close)),
+        None => builder.build(sender, receiver),
+    }
+}
+
+fn ws_client_builder(config: &WsClientConfig) -> RpcClientBuilder {
+    RpcClientBuilder::default()
+        .max_notifs_per_subscription(config.buffer_size)
+        .request_timeout(config.request_timeout)
+}
+
+#[cfg(not(any(feature = "native-tls", feature = "rustls")))]
+async fn ws_transport(
+    url: &str,
+    config: &WsClientConfig,
+) -> Result<(WsSender, WsReceiver), RpcError> {
+    let url: Uri = url
+        .parse()
+        .map_err(|e: InvalidUri| RpcError::Transport(e.into()))?;
+    let mut builder = WsTransportClientBuilder::default()
+        .connection_timeout(config.connection_timeout)
+        .certificate_store(config.certificate_store);
+    for (name, value) in &config.headers {
+        builder = builder.add_header(name, value);
+    }
+    builder
+        .build(url)
+        .await
+        .map_err(|e| RpcError::Transport(e.into()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn only_reads_are_balanced() {
+        assert!(is_read_only("state_getStorage"));
+        assert!(is_read_only("chain_getHeader"));
+        assert!(is_read_only("payment_queryInfo"));
+        assert!(!is_read_only("author_submitExtrinsic"));
+        assert!(!is_read_only("author_rotateKeys"));
+        assert!(!is_read_only("engine_createBlock"));
+        assert!(is_read_only("system_properties"));
+        assert!(!is_read_only("system_accountNextIndex"));
+        assert!(!is_read_only("system_addReservedPeer"));
+        assert!(!is_read_only("chainHead_unstable_follow"));
+    }
+
+    #[test]
+    fn only_idempotent_methods_are_retried() {
+        assert!(is_idempotent("state_getStorage"));
+        assert!(is_idempotent("chain_subscribeFinalizedHeads"));
+        assert!(!is_idempotent("author_submitExtrinsic"));
+        assert!(!is_idempotent("author_submitAndWatchExtrinsic"));
+        assert!(!is_idempotent("author_insertKey"));
+        assert!(!is_idempotent("system_addReservedPeer"));
+        assert!(!is_idempotent("some_unknownMethod"));
+        assert!(is_idempotent("system_health"));
+        assert!(is_idempotent("grandpa_subscribeJustifications"));
+    }
+
+    #[test]
+    fn sealing_blocks_is_not_retried() {
+        // Retrying these could seal or finalize a second block.
+        assert!(!is_idempotent("engine_createBlock"));
+        assert!(!is_idempotent("engine_finalizeBlock"));
+    }
+
+    #[async_std::test]
+    async fn requests_can_time_out_sooner() {
+        let mock = MockRpcClient::new().add_response("system_chain", "Development");
+        let rpc = Rpc::<crate::DefaultConfig>::new(mock);
+
+        let chain: String = rpc
+            .request_with_timeout("system_chain", rpc_params![], Duration::from_secs(10))
+            .await
+            .unwrap();
+        assert_eq!(chain, "Development");
+
+        let res = rpc
+            .request_with_timeout::<String>("system_chain", rpc_params![], Duration::ZERO)
+            .await;
+        assert!(matches!(
+            res,
+            Err(BasicError::Rpc(RpcError::RequestTimeout))
+        ));
+    }
+
+    #[test]
+    fn header_values_are_not_printed() {
+        let config = WsClientConfig {
+            headers: vec![("Authorization".into(), "Bearer secret".into())],
+            ..Default::default()
+        };
+        let printed = format!("{:?}", config);
+        assert!(printed.contains("Authorization"));
+        assert!(!printed.contains("secret"));
+    }
+
+    #[test]
+    fn test_ser_extrinsic_or_hash() {
+        let by_hash = ExtrinsicOrHash::Hash(sp_core::H256::zero());
+        assert_eq!(
+            serde_json::to_value(&by_hash).unwrap(),
+            serde_json::json!({ "hash": format!("{:?}", sp_core::H256::zero()) })
+        );
+        let by_extrinsic = ExtrinsicOrHash::<sp_core::H256>::Extrinsic(Bytes(vec![1, 2]));
+        assert_eq!(
+            serde_json::to_value(&by_extrinsic).unwrap(),
+            serde_json::json!({ "extrinsic": "0x0102" })
+        );
+    }
+
+    #[test]
+    fn test_deser_system_types() {
+        let health: SystemHealth = serde_json::from_str(
+            r#"{"peers":3,"isSyncing":false,"shouldHavePeers":true}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            health,
+            SystemHealth {
+                peers: 3,
+                is_syncing: false,
+                should_have_peers: true,
+            }
+        );
+
+        let sync_state: SyncState = serde_json::from_str(
+            r#"{"startingBlock":0,"currentBlock":10,"highestBlock":null}"#,
+        )
+        .unwrap();
+        assert_eq!(sync_state.current_block, 10);
+        assert_eq!(sync_state.highest_block, None);
+
+        let chain_type: ChainType = serde_json::from_str(r#""Development""#).unwrap();
+        assert_eq!(chain_type, ChainType::Development);
+        let chain_type: ChainType =
+            serde_json::from_str(r#"{"Custom":"testnet"}"#).unwrap();
+        assert_eq!(chain_type, ChainType::Custom("testnet".into()));
+    }
+
+    #[test]
+    fn test_deser_runtime_version() {
+        let val: RuntimeVersion = serde_json::from_str(
+            r#"{
+            "specVersion": 123,
+            "transactionVersion": 456,
+            "foo": true,
+            "wibble": [1,2,3]
+        }"#,
+        )
+        .expect("deserializing failed");
+
+        let mut m = std::collections::HashMap::new();
+        m.insert("foo".to_owned(), serde_json::json!(true));
+        m.insert("wibble".to_owned(), serde_json::json!([1, 2, 3]));
+
+        assert_eq!(
+            val,
+            RuntimeVersion {
+                spec_version: 123,
+                transaction_version: 456,
+                other: m
+            }
+        );
+    }
+
+    #[test]
+    fn test_deser_runtime_dispatch_info() {
+        let expected = RuntimeDispatchInfo {
+            weight: 195000000,
+            class: DispatchClass::Normal,
+            partial_fee: 1000000000000000000000,
+        };
+
+        // Newer nodes serialize the fee as a string:
+        let val: RuntimeDispatchInfo = serde_json::from_str(
+            r#"{
+            "weight": 195000000,
+            "class": "normal",
+            "partialFee": "1000000000000000000000"
+        }"#,
+        )
+        .expect("deserializing failed");
+        assert_eq!(val, expected);
+
+        // Older nodes serialize the fee as a number:
+        let val: RuntimeDispatchInfo = serde_json::from_str(
+            r#"{
+            "weight": 195000000,
+            "class": "operational",
+            "partialFee": 1234
+        }"#,
+        )
+        .expect("deserializing failed");
+        assert_eq!(
+            val,
+            RuntimeDispatchInfo {
+                class: DispatchClass::Operational,
+                partial_fee: 1234,
+                ..expected
+            }
+        );
+    }
+
+    #[test]
+    fn test_decode_dry_run_result() {
+        use sp_runtime::transaction_validity::InvalidTransaction;
+
+        let bytes =
+            <Result<Result<(), u8>, TransactionValidityError>>::Ok(Ok(())).encode();
+        assert_eq!(
+            DryRunResult::<u8>::decode(&bytes).unwrap(),
+            DryRunResult::Success
+        );
+
+        let bytes =
+            <Result<Result<(), u8>, TransactionValidityError>>::Ok(Err(3)).encode();
+        assert_eq!(
+            DryRunResult::<u8>::decode(&bytes).unwrap(),
+            DryRunResult::DispatchError(3)
+        );
+
+        let validity_err = TransactionValidityError::Invalid(InvalidTransaction::Stale);
+        let bytes = <Result<Result<(), u8>, TransactionValidityError>>::Err(validity_err)
+            .encode();
+        assert_eq!(
+            DryRunResult::<u8>::decode(&bytes).unwrap(),
+            DryRunResult::TransactionValidityError(validity_err)
+        );
+    }
+
+    #[test]
+    fn test_deser_fee_details() {
+        let val: FeeDetails = serde_json::from_str(
+            r#"{
+            "inclusionFee": {
+                "baseFee": "0x3b9aca00",
+                "lenFee": 1280000000,
+                "adjustedWeightFee": "0x1d1a94a2000"
+            }
+        }"#,
+        )
+        .expect("deserializing failed");
+
+        let inclusion_fee = val.inclusion_fee.expect("inclusion fee expected");
+        assert_eq!(
+            inclusion_fee,
+            InclusionFee {
+                base_fee: 1_000_000_000,
+                len_fee: 1_280_000_000,
+                adjusted_weight_fee: 2_000_000_000_000,
+            }
+        );
+        assert_eq!(inclusion_fee.inclusion_fee(), 2_002_280_000_000);
+
+        let val: FeeDetails =
+            serde_json::from_str(r#"{ "inclusionFee": null }"#).unwrap();
+        assert_eq!(val.inclusion_fee, None);
+    }
+
+    #[test]
+    fn test_deser_transaction_watch_event() {
+        let val: TransactionWatchEvent<u8> =
+            serde_json::from_str(r#"{ "event": "broadcasted", "numPeers": 3 }"#).unwrap();
+        assert_eq!(val, TransactionWatchEvent::Broadcasted { num_peers: 3 });
+
+        let val: TransactionWatchEvent<u8> = serde_json::from_str(
+            r#"{ "event": "bestChainBlockIncluded", "block": { "hash": 1, "index": 0 } }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            val,
+            TransactionWatchEvent::BestChainBlockIncluded {
+                block: Some(TransactionWatchBlock { hash: 1 })
+            }
+        );
+
+        let val: TransactionWatchEvent<u8> = serde_json::from_str(
+            r#"{ "event": "bestChainBlockIncluded", "block": null }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            val,
+            TransactionWatchEvent::BestChainBlockIncluded { block: None }
+        );
+
+        let val: TransactionWatchEvent<u8> = serde_json::from_str(
+            r#"{ "event": "dropped", "broadcasted": false, "error": "full" }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            val,
+            TransactionWatchEvent::Dropped {
+                broadcasted: false,
+                error: "full".into()
+            }
+        );
+    }
+}
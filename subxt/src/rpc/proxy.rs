@@ -0,0 +1,228 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::transport::Target;
+use tokio::{
+    io::{
+        AsyncReadExt,
+        AsyncWriteExt,
+    },
+    net::TcpStream,
+};
+use tokio_socks::tcp::Socks5Stream;
+
+/// A proxy to connect to the node through, for networks which don't allow connecting to
+/// it directly. The connection to the node (including TLS, for `wss://` URLs) is tunnelled
+/// through the proxy, which only sees the host and port of the node.
+#[derive(Clone, PartialEq, Eq)]
+pub enum Proxy {
+    /// A SOCKS5 proxy. The proxy resolves the host name of the node.
+    Socks5 {
+        /// The address of the proxy, as `host:port`.
+        address: String,
+        /// The username and password to authenticate with, if the proxy requires them.
+        credentials: Option<(String, String)>,
+    },
+    /// An HTTP proxy, which is asked to tunnel the connection with a `CONNECT` request.
+    Http {
+        /// The address of the proxy, as `host:port`.
+        address: String,
+        /// The username and password to authenticate with (using basic authentication),
+        /// if the proxy requires them.
+        credentials: Option<(String, String)>,
+    },
+}
+
+// The password is left out, since it's a credential.
+impl std::fmt::Debug for Proxy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (kind, address, credentials) = match self {
+            Proxy::Socks5 {
+                address,
+                credentials,
+            } => ("Socks5", address, credentials),
+            Proxy::Http {
+                address,
+                credentials,
+            } => ("Http", address, credentials),
+        };
+        let credentials = credentials
+            .as_ref()
+            .map(|(username, _)| (username, "<redacted>"));
+        f.debug_struct(kind)
+            .field("address", address)
+            .field("credentials", &credentials)
+            .finish()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(super) enum ProxyError {
+    #[error("Failed to connect to the proxy: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("SOCKS5 proxy error: {0}")]
+    Socks5(#[from] tokio_socks::Error),
+    #[error("The HTTP proxy refused to connect to the node: {0}")]
+    HttpRefused(String),
+}
+
+// The longest response to a `CONNECT` request that we'll read.
+const MAX_CONNECT_RESPONSE_SIZE: usize = 8 * 1024;
+
+// Open a connection to the node through the proxy.
+pub(super) async fn tunnel(
+    proxy: &Proxy,
+    target: &Target,
+) -> Result<TcpStream, ProxyError> {
+    let node = (target.host.as_str(), target.port);
+    let stream = match proxy {
+        Proxy::Socks5 {
+            address,
+            credentials: None,
+        } => Socks5Stream::connect(address.as_str(), node).await?,
+        Proxy::Socks5 {
+            address,
+            credentials: Some((username, password)),
+        } => {
+            Socks5Stream::connect_with_password(
+                address.as_str(),
+                node,
+                username,
+                password,
+            )
+            .await?
+        }
+        Proxy::Http {
+            address,
+            credentials,
+        } => return http_connect(address, credentials.as_ref(), target).await,
+    };
+    Ok(stream.into_inner())
+}
+
+// Ask the HTTP proxy at the address given to tunnel a connection to the node.
+async fn http_connect(
+    address: &str,
+    credentials: Option<&(String, String)>,
+    target: &Target,
+) -> Result<TcpStream, ProxyError> {
+    let mut stream = TcpStream::connect(address).await?;
+    let host = target.host_header();
+    let mut request = format!("CONNECT {host} HTTP/1.1\r\nHost: {host}\r\n", host = host);
+    if let Some((username, password)) = credentials {
+        let token = base64::encode(format!("{}:{}", username, password));
+        request.push_str(&format!("Proxy-Authorization: Basic {}\r\n", token));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes()).await?;
+
+    // Read the response a byte at a time, so as not to read past its end into the tunnel.
+    let mut response = Vec::new();
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() == MAX_CONNECT_RESPONSE_SIZE {
+            return Err(ProxyError::HttpRefused("the response is too long".into()))
+        }
+        response.push(stream.read_u8().await?);
+    }
+    let response = String::from_utf8_lossy(&response);
+    let status_line = response.lines().next().unwrap_or_default();
+    match status_line.split_whitespace().nth(1) {
+        Some(status) if status.starts_with('2') => Ok(stream),
+        _ => Err(ProxyError::HttpRefused(status_line.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::{
+        rpc_params,
+        ws_client_with_config,
+        ClientT,
+        WsClientConfig,
+    };
+    use futures::io::{
+        BufReader,
+        BufWriter,
+    };
+    use serde_json::json;
+    use soketto::handshake::{
+        server::Response,
+        Server,
+    };
+    use tokio::net::TcpListener;
+    use tokio_util::compat::TokioAsyncReadCompatExt;
+
+    #[async_std::test]
+    async fn requests_are_sent_through_an_http_proxy() {
+        // A proxy which checks the CONNECT request, and then plays the node itself,
+        // answering each request with its method.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        async_std::task::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            while !request.ends_with(b"\r\n\r\n") {
+                request.push(stream.read_u8().await.unwrap());
+            }
+            let request = String::from_utf8(request).unwrap();
+            assert!(request.starts_with("CONNECT node.invalid:9944 HTTP/1.1\r\n"));
+            // "user:pass", base64 encoded.
+            assert!(request.contains("Proxy-Authorization: Basic dXNlcjpwYXNz\r\n"));
+            stream
+                .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+                .await
+                .unwrap();
+
+            let mut server = Server::new(BufReader::new(BufWriter::new(stream.compat())));
+            let key = server.receive_request().await.unwrap().key();
+            let accept = Response::Accept {
+                key,
+                protocol: None,
+            };
+            server.send_response(&accept).await.unwrap();
+            let (mut sender, mut receiver) = server.into_builder().finish();
+            loop {
+                let mut message = Vec::new();
+                if receiver.receive_data(&mut message).await.is_err() {
+                    break
+                }
+                let request: serde_json::Value =
+                    serde_json::from_slice(&message).unwrap();
+                let response = json!({
+                    "jsonrpc": "2.0",
+                    "result": request["method"],
+                    "id": request["id"],
+                });
+                sender.send_text(response.to_string()).await.unwrap();
+                sender.flush().await.unwrap();
+            }
+        });
+
+        let config = WsClientConfig {
+            proxy: Some(Proxy::Http {
+                address,
+                credentials: Some(("user".into(), "pass".into())),
+            }),
+            ..Default::default()
+        };
+        let client = ws_client_with_config("ws://node.invalid:9944", &config)
+            .await
+            .unwrap();
+        let result: String = client.request("system_name", rpc_params![]).await.unwrap();
+        assert_eq!(result, "system_name");
+    }
+}
@@ -0,0 +1,214 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+    ClientT,
+    DeserializeOwned,
+    RpcConnection,
+    RpcError,
+    Subscription,
+    SubscriptionClientT,
+};
+use futures_timer::Delay;
+use jsonrpsee::types::ParamsSer;
+use std::{
+    sync::Mutex,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+/// A client which limits the rate at which calls are made through the connection it
+/// wraps, so as to stay within the limits imposed by (for instance) public RPC endpoints.
+/// Calls are allowed through at up to `requests_per_second` on average, with bursts of
+/// up to `burst` calls at once; any more have to wait their turn.
+///
+/// Every request and subscription counts as a call, as does every request in a batch.
+/// Notifications received on subscriptions don't count.
+pub struct RateLimitedClient {
+    connection: RpcConnection,
+    bucket: Mutex<TokenBucket>,
+}
+
+impl RateLimitedClient {
+    /// Limit the calls made through the connection given to `requests_per_second` on
+    /// average, allowing bursts of up to `burst` calls at once.
+    pub fn new(
+        connection: impl Into<RpcConnection>,
+        requests_per_second: u32,
+        burst: u32,
+    ) -> Self {
+        RateLimitedClient {
+            connection: connection.into(),
+            bucket: Mutex::new(TokenBucket::new(
+                requests_per_second,
+                burst,
+                Instant::now(),
+            )),
+        }
+    }
+
+    /// The connection that calls are made through.
+    pub fn connection(&self) -> &RpcConnection {
+        &self.connection
+    }
+
+    // Wait until we're allowed to make the given number of calls.
+    async fn acquire(&self, calls: usize) {
+        loop {
+            let wait = self
+                .bucket
+                .lock()
+                .expect("lock is never poisoned; qed")
+                .take(calls, Instant::now());
+            match wait {
+                Some(wait) => Delay::new(wait).await,
+                None => return,
+            }
+        }
+    }
+}
+
+// Tokens are added to the bucket at a steady rate, up to some maximum, and each call
+// takes one out.
+#[derive(Debug)]
+struct TokenBucket {
+    tokens_per_second: f64,
+    capacity: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(requests_per_second: u32, burst: u32, now: Instant) -> Self {
+        let capacity = f64::from(burst.max(1));
+        TokenBucket {
+            tokens_per_second: f64::from(requests_per_second.max(1)),
+            capacity,
+            tokens: capacity,
+            last_refill: now,
+        }
+    }
+
+    // Take a token for each call, if there are enough. Otherwise, hand back how long to
+    // wait until there will be. Batches bigger than the bucket only need a full bucket.
+    fn take(&mut self, calls: usize, now: Instant) -> Option<Duration> {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.tokens_per_second)
+            .min(self.capacity);
+        self.last_refill = now;
+
+        let needed = (calls.max(1) as f64).min(self.capacity);
+        if self.tokens >= needed {
+            self.tokens -= needed;
+            None
+        } else {
+            let missing = needed - self.tokens;
+            Some(Duration::from_secs_f64(missing / self.tokens_per_second))
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ClientT for RateLimitedClient {
+    async fn notification<'a>(
+        &self,
+        method: &'a str,
+        params: Option<ParamsSer<'a>>,
+    ) -> Result<(), RpcError> {
+        self.acquire(1).await;
+        self.connection.notification(method, params).await
+    }
+
+    async fn request<'a, R>(
+        &self,
+        method: &'a str,
+        params: Option<ParamsSer<'a>>,
+    ) -> Result<R, RpcError>
+    where
+        R: DeserializeOwned,
+    {
+        self.acquire(1).await;
+        self.connection.request(method, params).await
+    }
+
+    async fn batch_request<'a, R>(
+        &self,
+        batch: Vec<(&'a str, Option<ParamsSer<'a>>)>,
+    ) -> Result<Vec<R>, RpcError>
+    where
+        R: DeserializeOwned + Default + Clone,
+    {
+        self.acquire(batch.len()).await;
+        self.connection.batch_request(batch).await
+    }
+}
+
+#[async_trait::async_trait]
+impl SubscriptionClientT for RateLimitedClient {
+    async fn subscribe<'a, Notif>(
+        &self,
+        subscribe_method: &'a str,
+        params: Option<ParamsSer<'a>>,
+        unsubscribe_method: &'a str,
+    ) -> Result<Subscription<Notif>, RpcError>
+    where
+        Notif: DeserializeOwned,
+    {
+        self.acquire(1).await;
+        self.connection
+            .subscribe(subscribe_method, params, unsubscribe_method)
+            .await
+    }
+
+    async fn subscribe_to_method<'a, Notif>(
+        &self,
+        method: &'a str,
+    ) -> Result<Subscription<Notif>, RpcError>
+    where
+        Notif: DeserializeOwned,
+    {
+        self.acquire(1).await;
+        self.connection.subscribe_to_method(method).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_bucket_limits_rate() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(10, 2, start);
+
+        // The burst is allowed straight away:
+        assert_eq!(bucket.take(1, start), None);
+        assert_eq!(bucket.take(1, start), None);
+
+        // .. but then we have to wait for a token to be added:
+        assert_eq!(bucket.take(1, start), Some(Duration::from_millis(100)));
+        let later = start + Duration::from_millis(100);
+        assert_eq!(bucket.take(1, later), None);
+
+        // Tokens don't build up beyond the burst size, and big batches only need a full
+        // bucket:
+        let much_later = later + Duration::from_secs(10);
+        assert_eq!(bucket.take(5, much_later), None);
+        assert_eq!(bucket.take(1, much_later), Some(Duration::from_millis(100)));
+    }
+}
@@ -0,0 +1,1152 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+    is_idempotent,
+    keepalive::OnClose,
+    rpc_params,
+    ws_client_reporting_close,
+    ClientT,
+    DeserializeOwned,
+    JsonValue,
+    RpcClient,
+    RpcClientBuilder,
+    RpcError,
+    Subscription,
+    SubscriptionClientT,
+    WsClientConfig,
+};
+use futures::{
+    channel::mpsc,
+    future::{
+        self,
+        BoxFuture,
+        Either,
+    },
+    lock::Mutex,
+    task::noop_waker_ref,
+    FutureExt,
+    StreamExt,
+};
+use futures_timer::Delay;
+use jsonrpsee::{
+    core::client::{
+        SubscriptionKind,
+        TransportReceiverT,
+        TransportSenderT,
+    },
+    types::ParamsSer,
+};
+use serde_json::json;
+use std::{
+    sync::{
+        atomic::{
+            AtomicBool,
+            AtomicU64,
+            AtomicUsize,
+            Ordering,
+        },
+        Arc,
+        Mutex as StdMutex,
+        RwLock,
+        Weak,
+    },
+    task::{
+        Context,
+        Poll,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+/// How long to wait between successive attempts to do something, such as reconnecting
+/// to a node. The delay starts at `initial_delay`, and doubles after every failed attempt
+/// up to `max_delay`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExponentialBackoff {
+    /// How long to wait before the first retry.
+    pub initial_delay: Duration,
+    /// The longest to wait between two attempts.
+    pub max_delay: Duration,
+    /// Give up after this many failed attempts, or never give up if `None`.
+    pub max_attempts: Option<usize>,
+}
+
+impl Default for ExponentialBackoff {
+    fn default() -> Self {
+        ExponentialBackoff {
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+            max_attempts: None,
+        }
+    }
+}
+
+impl ExponentialBackoff {
+    /// How long to wait after the given number of failed attempts (starting from 1), or
+    /// `None` if we should give up.
+    pub fn delay(&self, failed_attempts: usize) -> Option<Duration> {
+        if self
+            .max_attempts
+            .map_or(false, |max| failed_attempts >= max)
+        {
+            return None
+        }
+        let doublings = failed_attempts.saturating_sub(1).min(31) as u32;
+        let delay = self.initial_delay.saturating_mul(1 << doublings);
+        Some(delay.min(self.max_delay))
+    }
+}
+
+/// A change in the state of the connection of a [`ReconnectingClient`] to the node, as
+/// handed to the handler given to [`ReconnectingClient::on_connection_event()`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    /// Connected to the endpoint with the URL given, either for the first time, after
+    /// reconnecting, or after failing over to (or moving back from) another endpoint.
+    Connected {
+        /// The URL of the endpoint.
+        endpoint: String,
+    },
+    /// The connection to the endpoint with the URL given was lost. This is reported as soon
+    /// as the connection is closed, or is found to be dead (see
+    /// [`WsClientConfig::max_inactivity`]), even if no calls are being made; reconnecting
+    /// waits for the next call though.
+    Disconnected {
+        /// The URL of the endpoint.
+        endpoint: String,
+        /// Why the connection is considered lost.
+        reason: String,
+    },
+    /// Trying to connect to each of the endpoints again, having lost the connection. The
+    /// first attempt is numbered 1.
+    Reconnecting {
+        /// The number of the attempt.
+        attempt: usize,
+    },
+    /// Gave up trying to reconnect, because every attempt allowed by the
+    /// [`ExponentialBackoff`] failed. Calls fail until the connection is re-established by
+    /// a later call.
+    GaveUp {
+        /// Why the last attempt failed.
+        reason: String,
+    },
+}
+
+// A handler for connection events.
+type ConnectionEventHandler = Arc<dyn Fn(&ConnectionEvent) + Send + Sync>;
+
+// The handler for connection events, if one has been registered, which is shared with each
+// connection so that it can report being lost.
+type SharedHandler = Arc<RwLock<Option<ConnectionEventHandler>>>;
+
+/// A WebSocket client which transparently reconnects to the node (waiting according to
+/// an [`ExponentialBackoff`] between attempts) if the connection is lost, for instance
+/// because the node was restarted.
+///
+/// Requests which fail because the connection was lost are retried once reconnected, as
+/// long as they're known to only read from the node; anything else (such as submitting an
+/// extrinsic) could have side effects if made twice, and so isn't retried.
+///
+/// Likewise, subscriptions to methods which only read from the node (such as new heads or
+/// storage changes) carry on after reconnecting, by subscribing again over the new
+/// connection; anything the node would have sent while the connection was down is missed
+/// though. Other subscriptions end when the connection is lost, and have to be made again
+/// by the caller: those which can't safely be made twice (such as watching an extrinsic
+/// that's submitted by subscribing), those whose ID is handed to later calls (following
+/// the chain with `chainHead_unstable_follow`), and those made with
+/// [`SubscriptionClientT::subscribe_to_method()`]. Event subscriptions do this themselves
+/// if they're resilient (see [`crate::events::EventSubscription::resilient()`]), as does
+/// the [`crate::indexer::EventIndexer`].
+///
+/// Several endpoints can be given (see [`ReconnectingClient::with_endpoints()`]), in
+/// which case we fail over to the next one when the current one is unreachable or keeps
+/// timing out, and move back to the first (primary) endpoint once it's healthy again.
+/// Moving back is like reconnecting, in that subscriptions are made again on the primary
+/// endpoint (or end, as above).
+///
+/// To pause work or flip health checks while the connection is down, register a handler
+/// for changes in its state with [`ReconnectingClient::on_connection_event()`].
+pub struct ReconnectingClient {
+    shared: Arc<Shared>,
+}
+
+// The state of a `ReconnectingClient`, which is shared with the subscriptions made through
+// it so that they can be made again once reconnected.
+struct Shared {
+    endpoints: Vec<String>,
+    connector: Connector,
+    // The maximum number of notifications buffered for each subscription.
+    buffer_size: usize,
+    backoff: ExponentialBackoff,
+    primary_check_interval: StdMutex<Duration>,
+    current: RwLock<Connection>,
+    // Held while reconnecting, so that only one request tries to reconnect at a time.
+    reconnecting: Mutex<()>,
+    reconnects: AtomicU64,
+    // The number of requests in a row which have timed out.
+    consecutive_timeouts: AtomicUsize,
+    last_primary_check: StdMutex<Instant>,
+    // The check of whether the primary endpoint is healthy again, while one is under way.
+    primary_probe: StdMutex<Option<PrimaryProbe>>,
+    on_connection_event: SharedHandler,
+}
+
+// Connects to the endpoint with the URL given, building a client which calls the function
+// given once its connection is closed (or found to be dead).
+type Connector = Arc<
+    dyn Fn(String, OnClose) -> BoxFuture<'static, Result<RpcClient, RpcError>>
+        + Send
+        + Sync,
+>;
+
+// Connect over WebSocket, building clients as configured.
+fn ws_connector(config: WsClientConfig) -> Connector {
+    Arc::new(move |url, on_close| {
+        let config = config.clone();
+        async move { ws_client_reporting_close(&url, &config, on_close).await }.boxed()
+    })
+}
+
+// Connects to the primary endpoint, handing back the connection once the node is healthy.
+type PrimaryProbe = BoxFuture<'static, Result<Connection, RpcError>>;
+
+// A client connected to one of the endpoints.
+#[derive(Clone)]
+struct Connection {
+    client: Arc<RpcClient>,
+    endpoint: usize,
+    loss: LossReporter,
+}
+
+// Reports the loss of a connection, at most once, and only while it's the current one.
+#[derive(Clone)]
+struct LossReporter {
+    endpoint: String,
+    current: Arc<AtomicBool>,
+    handler: SharedHandler,
+}
+
+impl LossReporter {
+    fn report(&self, reason: String) {
+        if self.current.swap(false, Ordering::SeqCst) {
+            connection_event(
+                &self.handler,
+                ConnectionEvent::Disconnected {
+                    endpoint: self.endpoint.clone(),
+                    reason,
+                },
+            );
+        }
+    }
+}
+
+// Connect to the endpoint with the URL given (the one at index `endpoint`). The connection
+// being lost is reported in the background once it becomes the current one.
+async fn connect(
+    url: String,
+    endpoint: usize,
+    connector: &Connector,
+    handler: SharedHandler,
+) -> Result<Connection, RpcError> {
+    let loss = LossReporter {
+        endpoint: url,
+        current: Arc::new(AtomicBool::new(false)),
+        handler,
+    };
+    let on_close = {
+        let loss = loss.clone();
+        Box::new(move |reason| loss.report(reason))
+    };
+    let client = connector(loss.endpoint.clone(), on_close).await?;
+    Ok(Connection {
+        client: Arc::new(client),
+        endpoint,
+        loss,
+    })
+}
+
+// Call the handler for connection events, if there is one.
+fn connection_event(handler: &SharedHandler, event: ConnectionEvent) {
+    let handler = handler.read().expect("lock is never poisoned; qed").clone();
+    if let Some(handler) = handler {
+        handler(&event);
+    }
+}
+
+/// How often to check whether the primary endpoint is healthy again, by default, while
+/// connected to some other endpoint.
+pub const DEFAULT_PRIMARY_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+// Fail over to the next endpoint once this many requests in a row have timed out.
+const MAX_CONSECUTIVE_TIMEOUTS: usize = 3;
+
+impl ReconnectingClient {
+    /// Connect to the node at the given URL, building clients as configured (see
+    /// [`super::ws_client_with_config()`]).
+    pub async fn new(
+        url: impl Into<String>,
+        config: WsClientConfig,
+        backoff: ExponentialBackoff,
+    ) -> Result<Self, RpcError> {
+        Self::with_endpoints(vec![url.into()], config, backoff).await
+    }
+
+    /// Connect to the node at the given URL, with the default configuration.
+    pub async fn connect(url: impl Into<String>) -> Result<Self, RpcError> {
+        Self::new(url, Default::default(), Default::default()).await
+    }
+
+    /// Connect to the first of the endpoints given that we can reach (in order), failing
+    /// over to the next one whenever the connection is lost. The first endpoint is the
+    /// primary one; while connected to any other, we periodically check whether it's
+    /// reachable again, and move back to it if so (see
+    /// [`ReconnectingClient::primary_check_interval()`]). Any subscriptions made on the
+    /// other endpoint are then made again on the primary one, just as they are when the
+    /// connection is lost.
+    pub async fn with_endpoints(
+        endpoints: Vec<String>,
+        config: WsClientConfig,
+        backoff: ExponentialBackoff,
+    ) -> Result<Self, RpcError> {
+        let buffer_size = config.buffer_size;
+        Self::with_connector(endpoints, ws_connector(config), buffer_size, backoff).await
+    }
+
+    // Connect to the first of the endpoints given that we can reach, with the connector
+    // given.
+    async fn with_connector(
+        endpoints: Vec<String>,
+        connector: Connector,
+        buffer_size: usize,
+        backoff: ExponentialBackoff,
+    ) -> Result<Self, RpcError> {
+        if endpoints.is_empty() {
+            return Err(RpcError::Custom("No endpoints given to connect to".into()))
+        }
+        let handler = SharedHandler::default();
+        let mut last_err = None;
+        for (endpoint, url) in endpoints.iter().enumerate() {
+            match connect(url.clone(), endpoint, &connector, handler.clone()).await {
+                Ok(current) => {
+                    current.loss.current.store(true, Ordering::SeqCst);
+                    let shared = Shared {
+                        endpoints,
+                        connector,
+                        buffer_size,
+                        backoff,
+                        primary_check_interval: StdMutex::new(
+                            DEFAULT_PRIMARY_CHECK_INTERVAL,
+                        ),
+                        current: RwLock::new(current),
+                        reconnecting: Mutex::new(()),
+                        reconnects: AtomicU64::new(0),
+                        consecutive_timeouts: AtomicUsize::new(0),
+                        last_primary_check: StdMutex::new(Instant::now()),
+                        primary_probe: StdMutex::new(None),
+                        on_connection_event: handler,
+                    };
+                    return Ok(ReconnectingClient {
+                        shared: Arc::new(shared),
+                    })
+                }
+                Err(e) => {
+                    log::warn!("Failed to connect to {}: {:?}", url, e);
+                    last_err = Some(e);
+                }
+            }
+        }
+        Err(last_err.expect("at least one endpoint was tried; qed"))
+    }
+
+    /// How often to check whether the primary endpoint is reachable again while connected
+    /// to another endpoint. Defaults to 30 seconds. Checks are made as requests are sent,
+    /// but don't hold them up: requests carry on being sent to the current endpoint until
+    /// the primary one has been found to be healthy.
+    pub fn primary_check_interval(self, interval: Duration) -> Self {
+        *self
+            .shared
+            .primary_check_interval
+            .lock()
+            .expect("lock is never poisoned; qed") = interval;
+        self
+    }
+
+    /// Call the handler given whenever the state of the connection changes: when it's
+    /// lost, while reconnecting, once reconnected, and if we give up on reconnecting.
+    /// The handler is called straight away with the current state.
+    ///
+    /// The handler shouldn't block, since it's called from whichever task noticed the
+    /// change: the client's background task when the connection is lost, or a call made
+    /// through this client otherwise. Reconnecting events are reported while reconnecting,
+    /// which every call made through this client waits for, so the handler mustn't wait on
+    /// any such call itself (for instance, to check on the node), or it will never finish.
+    ///
+    /// ```no_run
+    /// # #[async_std::main]
+    /// # async fn main() -> Result<(), subxt::BasicError> {
+    /// use std::sync::{
+    ///     atomic::{AtomicBool, Ordering},
+    ///     Arc,
+    /// };
+    /// use subxt::rpc::ConnectionEvent;
+    /// use subxt::{ClientBuilder, DefaultConfig, ReconnectingClient};
+    ///
+    /// let healthy = Arc::new(AtomicBool::new(false));
+    /// let client = ReconnectingClient::connect("ws://127.0.0.1:9944")
+    ///     .await?
+    ///     .on_connection_event({
+    ///         let healthy = healthy.clone();
+    ///         move |event| {
+    ///             let connected = matches!(event, ConnectionEvent::Connected { .. });
+    ///             healthy.store(connected, Ordering::Relaxed);
+    ///         }
+    ///     });
+    /// let client = ClientBuilder::new()
+    ///     .set_client(client)
+    ///     .build::<DefaultConfig>()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn on_connection_event(
+        mut self,
+        handler: impl Fn(&ConnectionEvent) + Send + Sync + 'static,
+    ) -> Self {
+        self.set_connection_event_handler(Arc::new(handler));
+        self
+    }
+
+    // Set the connection event handler, calling it with the current state.
+    pub(crate) fn set_connection_event_handler(
+        &mut self,
+        handler: ConnectionEventHandler,
+    ) {
+        *self
+            .shared
+            .on_connection_event
+            .write()
+            .expect("lock is never poisoned; qed") = Some(handler.clone());
+        let current = self.shared.current();
+        let endpoint = self.shared.endpoints[current.endpoint].clone();
+        if current.client.is_connected() {
+            handler(&ConnectionEvent::Connected { endpoint });
+        } else {
+            // The loss may have been noticed before there was a handler to report it to.
+            current.loss.current.store(false, Ordering::SeqCst);
+            handler(&ConnectionEvent::Disconnected {
+                endpoint,
+                reason: "The connection was closed".into(),
+            });
+        }
+    }
+
+    /// The number of times that we've reconnected to the node (or failed over to, or moved
+    /// back from, another endpoint).
+    pub fn reconnects(&self) -> u64 {
+        self.shared.reconnects.load(Ordering::Relaxed)
+    }
+
+    /// Is the client currently connected to the node?
+    pub fn is_connected(&self) -> bool {
+        self.shared.current().client.is_connected()
+    }
+
+    /// The URL of the endpoint that we're currently connected to.
+    pub fn endpoint(&self) -> &str {
+        &self.shared.endpoints[self.shared.current().endpoint]
+    }
+}
+
+impl Shared {
+    fn current(&self) -> Connection {
+        self.current
+            .read()
+            .expect("lock is never poisoned; qed")
+            .clone()
+    }
+
+    fn replace_current(&self, connection: Connection) {
+        let endpoint = self.endpoints[connection.endpoint].clone();
+        connection.loss.current.store(true, Ordering::SeqCst);
+        let previous = std::mem::replace(
+            &mut *self.current.write().expect("lock is never poisoned; qed"),
+            connection,
+        );
+        // We're done with the previous connection, so it being closed isn't worth reporting.
+        previous.loss.current.store(false, Ordering::SeqCst);
+        *self
+            .primary_probe
+            .lock()
+            .expect("lock is never poisoned; qed") = None;
+        self.reconnects.fetch_add(1, Ordering::Relaxed);
+        self.consecutive_timeouts.store(0, Ordering::Relaxed);
+        self.connection_event(ConnectionEvent::Connected { endpoint });
+    }
+
+    fn connection_event(&self, event: ConnectionEvent) {
+        connection_event(&self.on_connection_event, event)
+    }
+
+    // Hand back a connected client, reconnecting first if need be.
+    async fn connected(&self) -> Result<Arc<RpcClient>, RpcError> {
+        let current = self.current();
+        if !current.client.is_connected() {
+            let reason = "The connection was closed".to_string();
+            return self.reconnect(&current.client, reason).await
+        }
+        if current.endpoint != 0 {
+            return Ok(self.check_primary(current))
+        }
+        Ok(current.client)
+    }
+
+    // If it's time to, start checking whether the primary endpoint is healthy again. The
+    // check makes progress whenever this is called (as requests are sent) rather than being
+    // waited for, and once it succeeds we move back to the primary endpoint. Until then, the
+    // current connection is handed back.
+    fn check_primary(&self, current: Connection) -> Arc<RpcClient> {
+        let primary = &self.endpoints[0];
+        let probed = {
+            let mut probe = self
+                .primary_probe
+                .lock()
+                .expect("lock is never poisoned; qed");
+            let mut last_check = self
+                .last_primary_check
+                .lock()
+                .expect("lock is never poisoned; qed");
+            if probe.is_none() {
+                let interval = *self
+                    .primary_check_interval
+                    .lock()
+                    .expect("lock is never poisoned; qed");
+                if last_check.elapsed() < interval {
+                    return current.client
+                }
+                *probe = Some(probe_endpoint(
+                    primary.clone(),
+                    self.connector.clone(),
+                    self.on_connection_event.clone(),
+                ));
+            }
+            let in_progress = probe.as_mut().expect("a check is under way; qed");
+            match in_progress.poll_unpin(&mut Context::from_waker(noop_waker_ref())) {
+                Poll::Ready(probed) => {
+                    *probe = None;
+                    *last_check = Instant::now();
+                    probed
+                }
+                Poll::Pending => return current.client,
+            }
+        };
+        let connection = match probed {
+            Ok(connection) => connection,
+            Err(e) => {
+                log::debug!("Primary endpoint {} is still unhealthy: {:?}", primary, e);
+                return current.client
+            }
+        };
+        // Leave things be if we're busy reconnecting.
+        let _guard = match self.reconnecting.try_lock() {
+            Some(guard) => guard,
+            None => return current.client,
+        };
+        log::info!("Moving back to primary endpoint {}", primary);
+        let client = connection.client.clone();
+        self.replace_current(connection);
+        client
+    }
+
+    // Replace the client given, which has lost its connection (or keeps timing out) for
+    // the reason given, with a connection to the next endpoint that we can reach. If some
+    // other request has already done so, the client that it connected is handed back
+    // instead. Connection events are reported while holding the lock on reconnecting.
+    async fn reconnect(
+        &self,
+        lost: &Arc<RpcClient>,
+        reason: String,
+    ) -> Result<Arc<RpcClient>, RpcError> {
+        let _guard = self.reconnecting.lock().await;
+        let current = self.current();
+        if !Arc::ptr_eq(&current.client, lost) {
+            return Ok(current.client)
+        }
+        // This has already been reported if the connection was closed in the background.
+        current.loss.report(reason);
+
+        let num_endpoints = self.endpoints.len();
+        let mut failed_attempts = 0;
+        loop {
+            self.connection_event(ConnectionEvent::Reconnecting {
+                attempt: failed_attempts + 1,
+            });
+            // Try each endpoint once, starting with the next one (which is the same one
+            // again if there's only one).
+            let mut last_err = None;
+            for offset in 1..=num_endpoints {
+                let endpoint = (current.endpoint + offset) % num_endpoints;
+                let url = &self.endpoints[endpoint];
+                let handler = self.on_connection_event.clone();
+                match connect(url.clone(), endpoint, &self.connector, handler).await {
+                    Ok(connection) => {
+                        let client = connection.client.clone();
+                        self.replace_current(connection);
+                        log::info!("Reconnected to {}", url);
+                        return Ok(client)
+                    }
+                    Err(e) => {
+                        log::warn!("Failed to reconnect to {}: {:?}", url, e);
+                        last_err = Some(e);
+                    }
+                }
+            }
+
+            failed_attempts += 1;
+            let e = last_err.expect("at least one endpoint was tried; qed");
+            let delay = match self.backoff.delay(failed_attempts) {
+                Some(delay) => delay,
+                None => {
+                    self.connection_event(ConnectionEvent::GaveUp {
+                        reason: e.to_string(),
+                    });
+                    return Err(e)
+                }
+            };
+            log::warn!(
+                "Failed to reconnect (attempt {}); retrying in {:?}",
+                failed_attempts,
+                delay
+            );
+            Delay::new(delay).await;
+        }
+    }
+
+    // Work out whether a call that failed on the client given should be retried (on a new
+    // connection, which is made here) or not. Connections which keep timing out are
+    // treated as if they were lost, so that we fail over to another endpoint.
+    async fn should_retry(
+        &self,
+        client: &Arc<RpcClient>,
+        err: &RpcError,
+        idempotent: bool,
+    ) -> Result<bool, RpcError> {
+        let lost = match err {
+            RpcError::RequestTimeout => {
+                let timeouts = self.consecutive_timeouts.fetch_add(1, Ordering::Relaxed);
+                timeouts + 1 >= MAX_CONSECUTIVE_TIMEOUTS && self.endpoints.len() > 1
+            }
+            err => is_connection_lost(err),
+        };
+        if !lost {
+            return Ok(false)
+        }
+        let endpoint = &self.endpoints[self.current().endpoint];
+        log::warn!("Connection to {} lost: {:?}", endpoint, err);
+        self.reconnect(client, err.to_string()).await?;
+        Ok(idempotent)
+    }
+
+    // Note the result of a call which isn't being retried.
+    fn record_response<R>(&self, res: &Result<R, RpcError>) {
+        if !matches!(res, Err(RpcError::RequestTimeout)) {
+            self.consecutive_timeouts.store(0, Ordering::Relaxed);
+        }
+    }
+
+    // Subscribe through the current connection, handing back the client that the
+    // subscription was made on along with it.
+    async fn subscribe<'a, Notif>(
+        &self,
+        subscribe_method: &'a str,
+        params: Option<ParamsSer<'a>>,
+        unsubscribe_method: &'a str,
+    ) -> Result<(Arc<RpcClient>, Subscription<Notif>), RpcError>
+    where
+        Notif: DeserializeOwned,
+    {
+        let idempotent = is_idempotent(subscribe_method);
+        loop {
+            let client = self.connected().await?;
+            match client
+                .subscribe(subscribe_method, params.clone(), unsubscribe_method)
+                .await
+            {
+                Err(e) if self.should_retry(&client, &e, idempotent).await? => {
+                    log::warn!("Retrying {} after: {:?}", subscribe_method, e);
+                }
+                res => {
+                    self.record_response(&res);
+                    return res.map(|subscription| (client, subscription))
+                }
+            }
+        }
+    }
+}
+
+// Connect to the endpoint given, and check that the node there is healthy.
+fn probe_endpoint(
+    url: String,
+    connector: Connector,
+    handler: SharedHandler,
+) -> PrimaryProbe {
+    async move {
+        let connection = connect(url, 0, &connector, handler).await?;
+        connection
+            .client
+            .request::<JsonValue>("system_health", rpc_params![])
+            .await?;
+        Ok(connection)
+    }
+    .boxed()
+}
+
+// Did the request fail because the connection to the node was lost?
+pub(crate) fn is_connection_lost(err: &RpcError) -> bool {
+    matches!(err, RpcError::RestartNeeded(_) | RpcError::Transport(_))
+}
+
+// Subscriptions whose ID is handed to later calls, which would be left referring to a
+// subscription that's gone if it were made again.
+const SUBSCRIPTIONS_REFERRED_TO_BY_ID: &[&str] = &["chainHead_unstable_follow"];
+
+// Can a subscription to the given method be made again once reconnected, carrying on
+// as if nothing happened as far as the caller is concerned?
+fn resubscribes(method: &str) -> bool {
+    is_idempotent(method) && !SUBSCRIPTIONS_REFERRED_TO_BY_ID.contains(&method)
+}
+
+// The parameters of a call, given as they were serialized.
+fn params_ser(params: &JsonValue) -> Option<ParamsSer<'_>> {
+    match params {
+        JsonValue::Array(values) => Some(ParamsSer::Array(values.clone())),
+        JsonValue::Object(values) => {
+            Some(ParamsSer::Map(
+                values
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value.clone()))
+                    .collect(),
+            ))
+        }
+        _ => None,
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("The subscription has ended")]
+struct RelayEnded;
+
+// Hands a subscription made on whichever connection is current to the caller, through a
+// client of its own, so that the subscription can be made again over a new connection
+// without the caller noticing. The relay's client makes two requests: the subscription
+// (answered with the ID of the subscription being relayed) and, once the caller drops the
+// subscription, the unsubscription, which ends the relay.
+async fn relay<'a, Notif>(
+    shared: Arc<Shared>,
+    subscribe_method: &'a str,
+    params: Option<ParamsSer<'a>>,
+    unsubscribe_method: &'a str,
+) -> Result<Subscription<Notif>, RpcError>
+where
+    Notif: DeserializeOwned,
+{
+    let (connection, subscription) = shared
+        .subscribe::<JsonValue>(subscribe_method, params.clone(), unsubscribe_method)
+        .await?;
+    let id = match subscription.kind() {
+        SubscriptionKind::Subscription(id) => serde_json::to_value(id)?,
+        _ => "subxt_relayed_subscription".into(),
+    };
+    let (to_receiver, from_sender) = mpsc::unbounded();
+    let own_client = Arc::new(StdMutex::new(None));
+    let receiver = RelayReceiver {
+        shared: shared.clone(),
+        from_sender,
+        subscribe_method: subscribe_method.to_string(),
+        params: serde_json::to_value(&params)?,
+        unsubscribe_method: unsubscribe_method.to_string(),
+        id,
+        connection: Arc::downgrade(&connection),
+        subscription,
+        own_client: own_client.clone(),
+        ended: false,
+    };
+    let client = RpcClientBuilder::default()
+        .max_notifs_per_subscription(shared.buffer_size)
+        .build(RelaySender { to_receiver }, receiver);
+    let subscription = client
+        .subscribe(subscribe_method, params, unsubscribe_method)
+        .await?;
+    // Dropping the client would end the subscription, so it's kept by the relay until the
+    // relay ends.
+    *own_client.lock().expect("lock is never poisoned; qed") = Some(client);
+    Ok(subscription)
+}
+
+// Passes the requests made by the relay's client on to its receiver, which answers them.
+struct RelaySender {
+    to_receiver: mpsc::UnboundedSender<JsonValue>,
+}
+
+#[async_trait::async_trait]
+impl TransportSenderT for RelaySender {
+    type Error = RelayEnded;
+
+    async fn send(&mut self, msg: String) -> Result<(), Self::Error> {
+        let request = serde_json::from_str(&msg).map_err(|_| RelayEnded)?;
+        self.to_receiver
+            .unbounded_send(request)
+            .map_err(|_| RelayEnded)
+    }
+}
+
+struct RelayReceiver {
+    shared: Arc<Shared>,
+    from_sender: mpsc::UnboundedReceiver<JsonValue>,
+    subscribe_method: String,
+    params: JsonValue,
+    unsubscribe_method: String,
+    // The ID that the caller knows the subscription by.
+    id: JsonValue,
+    // The client that the subscription being relayed was made on.
+    connection: Weak<RpcClient>,
+    subscription: Subscription<JsonValue>,
+    own_client: Arc<StdMutex<Option<RpcClient>>>,
+    // Whether the caller has unsubscribed.
+    ended: bool,
+}
+
+impl RelayReceiver {
+    // Answer a request from the relay's client.
+    fn answer(&mut self, request: JsonValue) -> String {
+        let method = request.get("method").and_then(JsonValue::as_str);
+        let result = if method == Some(self.subscribe_method.as_str()) {
+            self.id.clone()
+        } else {
+            self.ended = true;
+            true.into()
+        };
+        json!({ "jsonrpc": "2.0", "result": result, "id": request.get("id") }).to_string()
+    }
+
+    // The subscription being relayed has ended. Make it again if that's because its
+    // connection was lost (or we moved to another endpoint), rather than because the node
+    // ended it.
+    async fn resubscribe(&mut self) -> Result<(), RelayEnded> {
+        let lost = match self.connection.upgrade() {
+            Some(client) => {
+                !client.is_connected()
+                    || !Arc::ptr_eq(&client, &self.shared.current().client)
+            }
+            None => true,
+        };
+        if !lost {
+            return Err(RelayEnded)
+        }
+        match self
+            .shared
+            .subscribe(
+                &self.subscribe_method,
+                params_ser(&self.params),
+                &self.unsubscribe_method,
+            )
+            .await
+        {
+            Ok((connection, subscription)) => {
+                log::info!("Subscribed to {} again", self.subscribe_method);
+                self.connection = Arc::downgrade(&connection);
+                self.subscription = subscription;
+                Ok(())
+            }
+            Err(e) => {
+                log::warn!(
+                    "Failed to subscribe to {} again: {:?}",
+                    self.subscribe_method,
+                    e
+                );
+                Err(RelayEnded)
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TransportReceiverT for RelayReceiver {
+    type Error = RelayEnded;
+
+    async fn receive(&mut self) -> Result<String, Self::Error> {
+        loop {
+            if self.ended {
+                // Let go of the relay's client, which is done with.
+                self.own_client
+                    .lock()
+                    .expect("lock is never poisoned; qed")
+                    .take();
+                return Err(RelayEnded)
+            }
+            let next = future::select(
+                self.from_sender.next(),
+                Box::pin(self.subscription.next()),
+            )
+            .await;
+            match next {
+                Either::Left((Some(request), _)) => return Ok(self.answer(request)),
+                Either::Left((None, _)) => return Err(RelayEnded),
+                Either::Right((Some(Ok(item)), _)) => {
+                    let notification = json!({
+                        "jsonrpc": "2.0",
+                        "method": self.subscribe_method,
+                        "params": { "subscription": self.id, "result": item },
+                    });
+                    return Ok(notification.to_string())
+                }
+                Either::Right((Some(Err(e)), _)) => {
+                    log::warn!(
+                        "Dropping a notification from {}: {:?}",
+                        self.subscribe_method,
+                        e
+                    );
+                }
+                Either::Right((None, _)) => self.resubscribe().await?,
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ClientT for ReconnectingClient {
+    async fn notification<'a>(
+        &self,
+        method: &'a str,
+        params: Option<ParamsSer<'a>>,
+    ) -> Result<(), RpcError> {
+        self.shared
+            .connected()
+            .await?
+            .notification(method, params)
+            .await
+    }
+
+    async fn request<'a, R>(
+        &self,
+        method: &'a str,
+        params: Option<ParamsSer<'a>>,
+    ) -> Result<R, RpcError>
+    where
+        R: DeserializeOwned,
+    {
+        let shared = &self.shared;
+        loop {
+            let client = shared.connected().await?;
+            match client.request(method, params.clone()).await {
+                Err(e)
+                    if shared
+                        .should_retry(&client, &e, is_idempotent(method))
+                        .await? =>
+                {
+                    log::warn!("Retrying {} after: {:?}", method, e);
+                }
+                res => {
+                    shared.record_response(&res);
+                    return res
+                }
+            }
+        }
+    }
+
+    async fn batch_request<'a, R>(
+        &self,
+        batch: Vec<(&'a str, Option<ParamsSer<'a>>)>,
+    ) -> Result<Vec<R>, RpcError>
+    where
+        R: DeserializeOwned + Default + Clone,
+    {
+        let shared = &self.shared;
+        let idempotent = batch.iter().all(|(method, _)| is_idempotent(method));
+        loop {
+            let client = shared.connected().await?;
+            match client.batch_request(batch.clone()).await {
+                Err(e) if shared.should_retry(&client, &e, idempotent).await? => {
+                    log::warn!("Retrying batch request after: {:?}", e);
+                }
+                res => {
+                    shared.record_response(&res);
+                    return res
+                }
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl SubscriptionClientT for ReconnectingClient {
+    async fn subscribe<'a, Notif>(
+        &self,
+        subscribe_method: &'a str,
+        params: Option<ParamsSer<'a>>,
+        unsubscribe_method: &'a str,
+    ) -> Result<Subscription<Notif>, RpcError>
+    where
+        Notif: DeserializeOwned,
+    {
+        if resubscribes(subscribe_method) {
+            let shared = self.shared.clone();
+            return relay(shared, subscribe_method, params, unsubscribe_method).await
+        }
+        let (_, subscription) = self
+            .shared
+            .subscribe(subscribe_method, params, unsubscribe_method)
+            .await?;
+        Ok(subscription)
+    }
+
+    async fn subscribe_to_method<'a, Notif>(
+        &self,
+        method: &'a str,
+    ) -> Result<Subscription<Notif>, RpcError>
+    where
+        Notif: DeserializeOwned,
+    {
+        let shared = &self.shared;
+        loop {
+            let client = shared.connected().await?;
+            match client.subscribe_to_method(method).await {
+                Err(e) if shared.should_retry(&client, &e, true).await? => {
+                    log::warn!("Retrying {} after: {:?}", method, e);
+                }
+                res => {
+                    shared.record_response(&res);
+                    return res
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::{
+        MockRpcClient,
+        DEFAULT_SUBSCRIPTION_BUFFER_SIZE,
+    };
+    use std::collections::VecDeque;
+
+    // A client whose first connection is answered by the first of the mocks given, and
+    // each connection made after that by the next one.
+    async fn mock_client(mocks: Vec<MockRpcClient>) -> ReconnectingClient {
+        let mocks = StdMutex::new(VecDeque::from(mocks));
+        let connector: Connector = Arc::new(move |_, _| {
+            let mock = mocks.lock().unwrap().pop_front();
+            async move {
+                mock.map(MockRpcClient::build)
+                    .ok_or_else(|| RpcError::Custom("No more mocks".into()))
+            }
+            .boxed()
+        });
+        let backoff = ExponentialBackoff {
+            max_attempts: Some(1),
+            ..Default::default()
+        };
+        ReconnectingClient::with_connector(
+            vec!["mock".into()],
+            connector,
+            DEFAULT_SUBSCRIPTION_BUFFER_SIZE,
+            backoff,
+        )
+        .await
+        .unwrap()
+    }
+
+    #[async_std::test]
+    async fn subscriptions_carry_on_after_reconnecting() {
+        // The first connection is closed once the subscription's items have been sent.
+        let client = mock_client(vec![
+            MockRpcClient::new()
+                .add_subscription_then_close("chain_subscribeNewHeads", vec![1, 2]),
+            MockRpcClient::new().add_subscription("chain_subscribeNewHeads", vec![3, 4]),
+        ])
+        .await;
+        let mut sub = client
+            .subscribe::<u32>(
+                "chain_subscribeNewHeads",
+                rpc_params![],
+                "chain_unsubscribeNewHeads",
+            )
+            .await
+            .unwrap();
+
+        let mut items = Vec::new();
+        for _ in 0..4 {
+            items.push(sub.next().await.unwrap().unwrap());
+        }
+        assert_eq!(items, vec![1, 2, 3, 4]);
+        assert_eq!(client.reconnects(), 1);
+        assert!(client.is_connected());
+    }
+
+    #[async_std::test]
+    async fn subscriptions_with_side_effects_end_when_the_connection_is_lost() {
+        let client = mock_client(vec![
+            MockRpcClient::new().add_subscription_then_close(
+                "author_submitAndWatchExtrinsic",
+                vec!["ready"],
+            ),
+            MockRpcClient::new()
+                .add_subscription("author_submitAndWatchExtrinsic", vec!["ready"]),
+        ])
+        .await;
+        let mut sub = client
+            .subscribe::<String>(
+                "author_submitAndWatchExtrinsic",
+                rpc_params!["0x00"],
+                "author_unwatchExtrinsic",
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(sub.next().await.unwrap().unwrap(), "ready");
+        // Submitting the extrinsic again could have it included twice.
+        assert!(sub.next().await.is_none());
+        assert_eq!(client.reconnects(), 0);
+    }
+
+    #[test]
+    fn backoff_delays() {
+        let backoff = ExponentialBackoff {
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(1000),
+            max_attempts: Some(6),
+        };
+        let delays: Vec<_> = (1..=6).map(|n| backoff.delay(n)).collect();
+        assert_eq!(
+            delays,
+            vec![
+                Some(Duration::from_millis(100)),
+                Some(Duration::from_millis(200)),
+                Some(Duration::from_millis(400)),
+                Some(Duration::from_millis(800)),
+                Some(Duration::from_millis(1000)),
+                None,
+            ]
+        );
+
+        let forever = ExponentialBackoff::default();
+        assert_eq!(forever.delay(1000), Some(forever.max_delay));
+    }
+}
@@ -0,0 +1,299 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+    ws_transport,
+    JsonValue,
+    MockRpcClient,
+    RpcClient,
+    RpcClientBuilder,
+    RpcError,
+    WsClientConfig,
+};
+use crate::error::BasicError;
+use jsonrpsee::core::client::{
+    TransportReceiverT,
+    TransportSenderT,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{
+        BufRead,
+        BufReader,
+        Write,
+    },
+    path::Path,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+// A line of a recording: a message sent to the node, or one received from it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum RecordedMessage {
+    Sent(JsonValue),
+    Received(JsonValue),
+}
+
+/// Connect to the node at the URL given, configured as given, recording every message
+/// sent and received to the file at `path` (which is overwritten). The recording can be
+/// served back later, without a node, by [`MockRpcClient::from_recording()`], which makes
+/// for fast and hermetic integration tests, and for reproducing bugs seen against a live
+/// node.
+///
+/// Each line of the recording is a JSON object, either `{"sent": <message>}` or
+/// `{"received": <message>}`, so recordings can be inspected or trimmed by hand.
+pub async fn recording_ws_client(
+    url: &str,
+    config: &WsClientConfig,
+    path: impl AsRef<Path>,
+) -> Result<RpcClient, RpcError> {
+    let file = File::create(path).map_err(|e| RpcError::Transport(e.into()))?;
+    let recording = Recording(Arc::new(Mutex::new(file)));
+    let (sender, receiver) = ws_transport(url, config).await?;
+    let sender = RecordingSender {
+        inner: sender,
+        recording: recording.clone(),
+    };
+    let receiver = RecordingReceiver {
+        inner: receiver,
+        recording,
+    };
+    Ok(RpcClientBuilder::default()
+        .max_notifs_per_subscription(config.buffer_size)
+        .request_timeout(config.request_timeout)
+        .build(sender, receiver))
+}
+
+// The file that messages are recorded to, shared by the sender and receiver.
+#[derive(Clone)]
+struct Recording(Arc<Mutex<File>>);
+
+impl Recording {
+    fn record(&self, message: RecordedMessage) {
+        let line = match serde_json::to_string(&message) {
+            Ok(line) => line,
+            Err(e) => {
+                log::warn!("Failed to serialize RPC message for recording: {}", e);
+                return
+            }
+        };
+        let mut file = self.0.lock().expect("lock is never poisoned; qed");
+        // A failure to record shouldn't interrupt the session being recorded.
+        if let Err(e) = writeln!(file, "{}", line) {
+            log::warn!("Failed to record RPC message: {}", e);
+        }
+    }
+}
+
+// Messages are recorded as JSON where possible, so that the recording is easier to read.
+fn to_json(msg: &str) -> JsonValue {
+    serde_json::from_str(msg).unwrap_or_else(|_| JsonValue::String(msg.to_string()))
+}
+
+struct RecordingSender<S> {
+    inner: S,
+    recording: Recording,
+}
+
+#[async_trait::async_trait]
+impl<S: TransportSenderT> TransportSenderT for RecordingSender<S> {
+    type Error = S::Error;
+
+    async fn send(&mut self, msg: String) -> Result<(), Self::Error> {
+        self.recording.record(RecordedMessage::Sent(to_json(&msg)));
+        self.inner.send(msg).await
+    }
+
+    async fn close(&mut self) -> Result<(), Self::Error> {
+        self.inner.close().await
+    }
+}
+
+struct RecordingReceiver<R> {
+    inner: R,
+    recording: Recording,
+}
+
+#[async_trait::async_trait]
+impl<R: TransportReceiverT> TransportReceiverT for RecordingReceiver<R> {
+    type Error = R::Error;
+
+    async fn receive(&mut self) -> Result<String, Self::Error> {
+        let msg = self.inner.receive().await?;
+        self.recording
+            .record(RecordedMessage::Received(to_json(&msg)));
+        Ok(msg)
+    }
+}
+
+impl MockRpcClient {
+    /// Build a client which serves back the responses (and subscription notifications)
+    /// recorded by [`recording_ws_client()`].
+    ///
+    /// Responses are matched to requests by method alone, and handed back in the order
+    /// they were recorded, so the recording replays deterministically provided that the
+    /// same requests are made in the same order as when it was recorded.
+    pub fn from_recording(path: impl AsRef<Path>) -> Result<Self, BasicError> {
+        let file = File::open(path)?;
+        replay(BufReader::new(file))
+    }
+}
+
+// What the node replied to a request in the recording.
+enum Reply {
+    Result(JsonValue),
+    Error { code: i32, message: String },
+}
+
+fn replay(recording: impl BufRead) -> Result<MockRpcClient, BasicError> {
+    // The methods of the requests which are waiting for a reply, by request ID.
+    let mut pending = HashMap::new();
+    let mut replies = Vec::new();
+    // The notifications received, by subscription ID.
+    let mut notifications: HashMap<String, Vec<JsonValue>> = HashMap::new();
+
+    for line in recording.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue
+        }
+        match serde_json::from_str(&line)? {
+            RecordedMessage::Sent(msg) => {
+                for request in batch_items(msg) {
+                    if let (Some(id), Some(method)) = (
+                        request.get("id"),
+                        request.get("method").and_then(JsonValue::as_str),
+                    ) {
+                        pending.insert(id.to_string(), method.to_string());
+                    }
+                }
+            }
+            RecordedMessage::Received(msg) => {
+                for response in batch_items(msg) {
+                    if let Some(params) = response.get("params") {
+                        if let Some(subscription) = params.get("subscription") {
+                            notifications
+                                .entry(subscription.to_string())
+                                .or_default()
+                                .push(params.get("result").cloned().unwrap_or_default());
+                        }
+                        continue
+                    }
+                    let method = match response
+                        .get("id")
+                        .and_then(|id| pending.remove(&id.to_string()))
+                    {
+                        Some(method) => method,
+                        None => continue,
+                    };
+                    let reply = match response.get("error") {
+                        Some(error) => {
+                            Reply::Error {
+                                code: error
+                                    .get("code")
+                                    .and_then(JsonValue::as_i64)
+                                    .unwrap_or_default()
+                                    as i32,
+                                message: error
+                                    .get("message")
+                                    .and_then(JsonValue::as_str)
+                                    .unwrap_or_default()
+                                    .to_string(),
+                            }
+                        }
+                        None => {
+                            Reply::Result(
+                                response.get("result").cloned().unwrap_or_default(),
+                            )
+                        }
+                    };
+                    replies.push((method, reply));
+                }
+            }
+        }
+    }
+
+    // Replies handing back the ID of a subscription which went on to receive
+    // notifications are replayed as subscriptions; everything else as a plain response.
+    let mut mock = MockRpcClient::new();
+    for (method, reply) in replies {
+        mock = match reply {
+            Reply::Result(result) => {
+                match notifications.remove(&result.to_string()) {
+                    Some(items) => mock.add_subscription(method, items),
+                    None => mock.add_response(method, result),
+                }
+            }
+            Reply::Error { code, message } => mock.add_error(method, code, message),
+        };
+    }
+    Ok(mock)
+}
+
+// The messages in a JSON-RPC batch, or the message itself if it isn't one.
+fn batch_items(msg: JsonValue) -> Vec<JsonValue> {
+    match msg {
+        JsonValue::Array(items) => items,
+        msg => vec![msg],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rpc::{
+            rpc_params,
+            Rpc,
+        },
+        DefaultConfig,
+    };
+
+    #[async_std::test]
+    async fn recordings_are_replayed() {
+        let recording = r#"
+{"sent":{"jsonrpc":"2.0","id":0,"method":"system_name","params":[]}}
+{"received":{"jsonrpc":"2.0","result":"node","id":0}}
+{"sent":{"jsonrpc":"2.0","id":1,"method":"test_subscribe","params":[]}}
+{"received":{"jsonrpc":"2.0","result":"abc","id":1}}
+{"received":{"jsonrpc":"2.0","method":"test_subscribe","params":{"subscription":"abc","result":7}}}
+{"sent":{"jsonrpc":"2.0","id":2,"method":"system_chain","params":[]}}
+{"received":{"jsonrpc":"2.0","error":{"code":-32000,"message":"oops"},"id":2}}
+"#;
+        let rpc = Rpc::<DefaultConfig>::new(replay(recording.as_bytes()).unwrap());
+
+        let name: String = rpc.request("system_name", rpc_params![]).await.unwrap();
+        assert_eq!(name, "node");
+        assert!(rpc
+            .request::<String>("system_chain", rpc_params![])
+            .await
+            .is_err());
+
+        let mut sub = rpc
+            .subscribe::<u32>("test_subscribe", rpc_params![], "test_unsubscribe")
+            .await
+            .unwrap();
+        assert_eq!(sub.next().await.unwrap().unwrap(), 7);
+    }
+}
@@ -0,0 +1,414 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+    is_idempotent,
+    reconnecting::is_connection_lost,
+    ClientT,
+    DeserializeOwned,
+    ExponentialBackoff,
+    JsonValue,
+    RpcConnection,
+    RpcError,
+    Subscription,
+    SubscriptionClientT,
+};
+use futures_timer::Delay;
+use jsonrpsee::types::ParamsSer;
+use rand::Rng;
+use std::time::Duration;
+
+/// How to retry calls which fail for reasons that are likely to go away by themselves, such
+/// as the connection being lost, the node being overloaded or a gateway in front of it
+/// timing out (see [`is_transient()`]).
+///
+/// The delay between attempts follows `backoff`, less a random amount of up to `jitter`
+/// (between 0 and 1) of it, so that many clients retrying at once don't all hit the node
+/// again at the same moment.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RetryPolicy {
+    /// How long to wait between attempts, and how many attempts to make.
+    pub backoff: ExponentialBackoff,
+    /// The fraction of each delay which may be randomly taken off of it.
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            backoff: ExponentialBackoff {
+                initial_delay: Duration::from_millis(250),
+                max_delay: Duration::from_secs(10),
+                max_attempts: Some(5),
+            },
+            jitter: 0.5,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// How long to wait after the given number of failed attempts (starting from 1) before
+    /// trying again, or `None` if we should give up.
+    pub fn delay(&self, failed_attempts: usize) -> Option<Duration> {
+        self.delay_with_rng(failed_attempts, &mut rand::thread_rng())
+    }
+
+    /// Much like [`RetryPolicy::delay()`], but the jitter is drawn from the random number
+    /// generator given (for instance, a seeded one to make the delays reproducible).
+    pub fn delay_with_rng<R: Rng + ?Sized>(
+        &self,
+        failed_attempts: usize,
+        rng: &mut R,
+    ) -> Option<Duration> {
+        self.backoff
+            .delay(failed_attempts)
+            .map(|delay| self.jittered(delay, rng.gen::<f64>()))
+    }
+
+    // Take the given fraction (between 0 and 1) of the jitter allowed off of the delay.
+    fn jittered(&self, delay: Duration, fraction: f64) -> Duration {
+        let jitter = self.jitter.clamp(0.0, 1.0) * fraction.clamp(0.0, 1.0);
+        delay.mul_f64(1.0 - jitter)
+    }
+}
+
+/// A client which retries calls made through the connection it wraps when they fail for
+/// transient reasons, waiting according to a [`RetryPolicy`] between attempts.
+///
+/// Calls which could have side effects if made twice (such as submitting an extrinsic) are
+/// never retried, since we can't tell whether a failed attempt got through to the node.
+/// Notifications aren't retried either. Notifications received on subscriptions aren't
+/// affected; a subscription which ends has to be re-established by whoever made it.
+///
+/// Calls which fail because the connection was lost are only retried if the connection
+/// wrapped is able to reconnect (see [`super::ReconnectingClient`]), since a plain
+/// jsonrpsee client never recovers from that.
+pub struct RetryingClient {
+    connection: RpcConnection,
+    policy: RetryPolicy,
+    // Whether the connection recovers from being lost.
+    reconnects: bool,
+}
+
+impl RetryingClient {
+    /// Retry calls made through the connection given according to the policy given.
+    pub fn new(connection: impl Into<RpcConnection>, policy: RetryPolicy) -> Self {
+        let connection = connection.into();
+        RetryingClient {
+            reconnects: reconnects(&connection),
+            connection,
+            policy,
+        }
+    }
+
+    /// The connection that calls are made through.
+    pub fn connection(&self) -> &RpcConnection {
+        &self.connection
+    }
+
+    // Work out whether a call which failed with the error given should be retried, waiting
+    // until it's time to do so if it should.
+    async fn should_retry(
+        &self,
+        what: &str,
+        err: &RpcError,
+        idempotent: bool,
+        failed_attempts: usize,
+    ) -> bool {
+        if !idempotent || !is_transient(err) {
+            return false
+        }
+        if is_connection_lost(err) && !self.reconnects {
+            return false
+        }
+        match self.policy.delay(failed_attempts) {
+            Some(delay) => {
+                log::warn!(
+                    "Retrying {} in {:?} (attempt {}) after: {:?}",
+                    what,
+                    delay,
+                    failed_attempts,
+                    err
+                );
+                Delay::new(delay).await;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// Does the connection given recover from being lost, by reconnecting itself or having other
+// connections to fall back on?
+fn reconnects(connection: &RpcConnection) -> bool {
+    match connection {
+        RpcConnection::Client(_) => false,
+        RpcConnection::Reconnecting(_) => true,
+        RpcConnection::LoadBalanced(client) => {
+            client.connections().iter().any(reconnects)
+        }
+        RpcConnection::RateLimited(client) => reconnects(client.connection()),
+        RpcConnection::Retrying(client) => reconnects(client.connection()),
+        #[cfg(feature = "metrics")]
+        RpcConnection::Metered(client) => reconnects(client.connection()),
+    }
+}
+
+/// Is the error one that is likely to go away by itself if the call is made again a little
+/// later? This is the case if the connection was lost, the request timed out, or the node
+/// (or a gateway in front of it) reported being overloaded or unavailable.
+pub fn is_transient(err: &RpcError) -> bool {
+    match err {
+        RpcError::RequestTimeout | RpcError::MaxSlotsExceeded => true,
+        // This includes a gateway rejecting the connection with a 429 or 5xx status.
+        err if is_connection_lost(err) => true,
+        RpcError::Request(response) => is_overloaded_response(response),
+        _ => false,
+    }
+}
+
+// Error codes handed back by nodes and gateways which are overloaded or unavailable:
+// "limit exceeded", as well as the HTTP status codes for "too many requests", "bad
+// gateway", "service unavailable" and "gateway timeout", which some gateways use.
+const TRANSIENT_ERROR_CODES: [i64; 5] = [-32005, 429, 502, 503, 504];
+
+// Does the error response given (as handed back by jsonrpsee) say that the node is
+// overloaded or unavailable, rather than that there was a problem with the call itself?
+fn is_overloaded_response(response: &str) -> bool {
+    if let Ok(response) = serde_json::from_str::<JsonValue>(response) {
+        let error = response.get("error").unwrap_or(&response);
+        if let Some(code) = error.get("code").and_then(JsonValue::as_i64) {
+            if TRANSIENT_ERROR_CODES.contains(&code) {
+                return true
+            }
+        }
+    }
+    let response = response.to_lowercase();
+    [
+        "too many requests",
+        "rate limit",
+        "gateway timeout",
+        "service unavailable",
+    ]
+    .iter()
+    .any(|reason| response.contains(reason))
+}
+
+#[async_trait::async_trait]
+impl ClientT for RetryingClient {
+    async fn notification<'a>(
+        &self,
+        method: &'a str,
+        params: Option<ParamsSer<'a>>,
+    ) -> Result<(), RpcError> {
+        self.connection.notification(method, params).await
+    }
+
+    async fn request<'a, R>(
+        &self,
+        method: &'a str,
+        params: Option<ParamsSer<'a>>,
+    ) -> Result<R, RpcError>
+    where
+        R: DeserializeOwned,
+    {
+        let idempotent = is_idempotent(method);
+        let mut failed_attempts = 0;
+        loop {
+            match self.connection.request(method, params.clone()).await {
+                Err(e) => {
+                    failed_attempts += 1;
+                    if !self
+                        .should_retry(method, &e, idempotent, failed_attempts)
+                        .await
+                    {
+                        return Err(e)
+                    }
+                }
+                res => return res,
+            }
+        }
+    }
+
+    async fn batch_request<'a, R>(
+        &self,
+        batch: Vec<(&'a str, Option<ParamsSer<'a>>)>,
+    ) -> Result<Vec<R>, RpcError>
+    where
+        R: DeserializeOwned + Default + Clone,
+    {
+        let idempotent = batch.iter().all(|(method, _)| is_idempotent(method));
+        let mut failed_attempts = 0;
+        loop {
+            match self.connection.batch_request(batch.clone()).await {
+                Err(e) => {
+                    failed_attempts += 1;
+                    if !self
+                        .should_retry("batch request", &e, idempotent, failed_attempts)
+                        .await
+                    {
+                        return Err(e)
+                    }
+                }
+                res => return res,
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl SubscriptionClientT for RetryingClient {
+    async fn subscribe<'a, Notif>(
+        &self,
+        subscribe_method: &'a str,
+        params: Option<ParamsSer<'a>>,
+        unsubscribe_method: &'a str,
+    ) -> Result<Subscription<Notif>, RpcError>
+    where
+        Notif: DeserializeOwned,
+    {
+        let idempotent = is_idempotent(subscribe_method);
+        let mut failed_attempts = 0;
+        loop {
+            match self
+                .connection
+                .subscribe(subscribe_method, params.clone(), unsubscribe_method)
+                .await
+            {
+                Err(e) => {
+                    failed_attempts += 1;
+                    if !self
+                        .should_retry(subscribe_method, &e, idempotent, failed_attempts)
+                        .await
+                    {
+                        return Err(e)
+                    }
+                }
+                res => return res,
+            }
+        }
+    }
+
+    async fn subscribe_to_method<'a, Notif>(
+        &self,
+        method: &'a str,
+    ) -> Result<Subscription<Notif>, RpcError>
+    where
+        Notif: DeserializeOwned,
+    {
+        let mut failed_attempts = 0;
+        loop {
+            match self.connection.subscribe_to_method(method).await {
+                Err(e) => {
+                    failed_attempts += 1;
+                    if !self.should_retry(method, &e, true, failed_attempts).await {
+                        return Err(e)
+                    }
+                }
+                res => return res,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::{
+        rpc_params,
+        MockRpcClient,
+    };
+    use rand::{
+        rngs::StdRng,
+        SeedableRng,
+    };
+
+    #[test]
+    fn transient_errors_are_classified() {
+        assert!(is_transient(&RpcError::RequestTimeout));
+        assert!(is_transient(&RpcError::RestartNeeded("gone".into())));
+        assert!(is_transient(&RpcError::Request(
+            r#"{"jsonrpc":"2.0","error":{"code":-32005,"message":"Limit exceeded"},"id":1}"#
+                .into()
+        )));
+        assert!(is_transient(&RpcError::Request(
+            r#"{"code":429,"message":"Too Many Requests"}"#.into()
+        )));
+        assert!(is_transient(&RpcError::Request(
+            "504 Gateway Timeout".into()
+        )));
+
+        assert!(!is_transient(&RpcError::Request(
+            r#"{"code":1010,"message":"Invalid Transaction"}"#.into()
+        )));
+        assert!(!is_transient(&RpcError::Custom("bad".into())));
+    }
+
+    #[test]
+    fn delays_are_jittered() {
+        let policy = RetryPolicy {
+            backoff: ExponentialBackoff {
+                initial_delay: Duration::from_millis(100),
+                max_delay: Duration::from_secs(1),
+                max_attempts: Some(3),
+            },
+            jitter: 0.5,
+        };
+        let delay = Duration::from_millis(400);
+        assert_eq!(policy.jittered(delay, 0.0), delay);
+        assert_eq!(policy.jittered(delay, 1.0), Duration::from_millis(200));
+
+        for _ in 0..100 {
+            let delay = policy.delay(2).unwrap();
+            assert!(delay >= Duration::from_millis(100));
+            assert!(delay <= Duration::from_millis(200));
+        }
+        assert_eq!(policy.delay(3), None);
+
+        // The same seed gives the same delays.
+        let delays = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (1..3)
+                .map(|n| policy.delay_with_rng(n, &mut rng).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(delays(7), delays(7));
+    }
+
+    #[async_std::test]
+    async fn lost_connections_are_only_retried_if_they_reconnect() {
+        // The mock's connection is closed once its subscription has been made.
+        let mock = MockRpcClient::new()
+            .add_response("system_name", "mock-node")
+            .add_subscription_then_close("chain_subscribeNewHeads", Vec::<u32>::new());
+        let client = RetryingClient::new(mock, RetryPolicy::default());
+        assert!(!client.reconnects);
+        let _sub = client
+            .subscribe::<JsonValue>(
+                "chain_subscribeNewHeads",
+                rpc_params![],
+                "chain_unsubscribeNewHeads",
+            )
+            .await
+            .unwrap();
+
+        // The call fails straight away, rather than being retried.
+        let started = std::time::Instant::now();
+        let res = client.request::<String>("system_name", rpc_params![]).await;
+        assert!(matches!(res, Err(ref e) if is_connection_lost(e)));
+        assert!(started.elapsed() < RetryPolicy::default().backoff.initial_delay);
+    }
+}
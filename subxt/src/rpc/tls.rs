@@ -0,0 +1,303 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! TLS for the connections made by the `transport` module, using rustls or (with the
+//! `native-tls` feature) the operating system's TLS library.
+
+#[cfg(not(feature = "native-tls"))]
+use super::CertificateStore;
+use super::{
+    transport::{
+        TransportError,
+        Tunnel,
+    },
+    WsClientConfig,
+};
+use std::io::{
+    self,
+    BufReader,
+};
+#[cfg(not(feature = "native-tls"))]
+use std::{
+    convert::TryFrom,
+    sync::Arc,
+    time::SystemTime,
+};
+use tokio::net::TcpStream;
+
+/// A root certificate to trust when connecting to the node over TLS, for instance that of
+/// a private certificate authority used for internal endpoints.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Certificate(Vec<u8>);
+
+impl Certificate {
+    /// Creates a certificate from its DER encoding.
+    pub fn from_der(der: impl Into<Vec<u8>>) -> Self {
+        Certificate(der.into())
+    }
+
+    /// Reads every certificate from the PEM encoded data given, for instance the contents
+    /// of a `.pem` or `.crt` file.
+    pub fn from_pem(pem: &[u8]) -> Result<Vec<Self>, io::Error> {
+        let certificates = rustls_pemfile::certs(&mut BufReader::new(pem))?;
+        if certificates.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "no certificates found",
+            ))
+        }
+        Ok(certificates.into_iter().map(Certificate).collect())
+    }
+}
+
+// Make a TLS connection to the host given over the stream given, as configured.
+#[cfg(feature = "native-tls")]
+pub(super) async fn connect(
+    stream: TcpStream,
+    host: &str,
+    config: &WsClientConfig,
+) -> Result<Box<dyn Tunnel>, TransportError> {
+    use tokio_native_tls::native_tls;
+
+    let tls_error = |e: native_tls::Error| TransportError::Tls(e.into());
+    let mut builder = native_tls::TlsConnector::builder();
+    for certificate in &config.root_certificates {
+        let certificate =
+            native_tls::Certificate::from_der(&certificate.0).map_err(tls_error)?;
+        builder.add_root_certificate(certificate);
+    }
+    builder
+        .danger_accept_invalid_certs(config.accept_invalid_certificates)
+        .danger_accept_invalid_hostnames(config.accept_invalid_certificates);
+    let connector =
+        tokio_native_tls::TlsConnector::from(builder.build().map_err(tls_error)?);
+    let stream = connector.connect(host, stream).await.map_err(tls_error)?;
+    Ok(Box::new(stream))
+}
+
+// Make a TLS connection to the host given over the stream given, as configured.
+#[cfg(not(feature = "native-tls"))]
+pub(super) async fn connect(
+    stream: TcpStream,
+    host: &str,
+    config: &WsClientConfig,
+) -> Result<Box<dyn Tunnel>, TransportError> {
+    use rustls::ServerName;
+    use tokio_rustls::TlsConnector;
+
+    let tls_config = rustls_config(config)?;
+    let name = ServerName::try_from(host)
+        .map_err(|_| TransportError::Url(format!("invalid host {}", host)))?;
+    let stream = TlsConnector::from(Arc::new(tls_config))
+        .connect(name, stream)
+        .await?;
+    Ok(Box::new(stream))
+}
+
+#[cfg(not(feature = "native-tls"))]
+fn rustls_config(
+    config: &WsClientConfig,
+) -> Result<rustls::ClientConfig, TransportError> {
+    use rustls::{
+        ClientConfig,
+        OwnedTrustAnchor,
+        RootCertStore,
+    };
+
+    let builder = ClientConfig::builder().with_safe_defaults();
+    if config.accept_invalid_certificates {
+        return Ok(builder
+            .with_custom_certificate_verifier(Arc::new(AcceptAnyCertificate))
+            .with_no_client_auth())
+    }
+
+    let mut roots = RootCertStore::empty();
+    match config.certificate_store {
+        CertificateStore::WebPki => {
+            roots.add_server_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.0.iter().map(
+                |anchor| {
+                    OwnedTrustAnchor::from_subject_spki_name_constraints(
+                        anchor.subject,
+                        anchor.spki,
+                        anchor.name_constraints,
+                    )
+                },
+            ));
+        }
+        _ => {
+            for certificate in rustls_native_certs::load_native_certs()? {
+                // Certificates which can't be parsed are skipped, as jsonrpsee does.
+                let _ = roots.add(&rustls::Certificate(certificate.0));
+            }
+        }
+    }
+    for certificate in &config.root_certificates {
+        roots
+            .add(&rustls::Certificate(certificate.0.clone()))
+            .map_err(|e| TransportError::Tls(e.into()))?;
+    }
+    Ok(builder.with_root_certificates(roots).with_no_client_auth())
+}
+
+// Accepts whatever certificate the node presents, for `accept_invalid_certificates`.
+#[cfg(not(feature = "native-tls"))]
+struct AcceptAnyCertificate;
+
+#[cfg(not(feature = "native-tls"))]
+impl rustls::client::ServerCertVerifier for AcceptAnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &rustls::Certificate,
+        _intermediates: &[rustls::Certificate],
+        _server_name: &rustls::ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
+        Ok(rustls::client::ServerCertVerified::assertion())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The PEM encoding of the DER encoded certificate given.
+    fn pem(der: &[u8]) -> String {
+        format!(
+            "-----BEGIN CERTIFICATE-----\n{}\n-----END CERTIFICATE-----\n",
+            base64::encode(der)
+        )
+    }
+
+    #[test]
+    fn certificates_are_read_from_pem() {
+        let first = rcgen::generate_simple_self_signed(vec!["localhost".into()])
+            .unwrap()
+            .serialize_der()
+            .unwrap();
+        let second = rcgen::generate_simple_self_signed(vec!["localhost".into()])
+            .unwrap()
+            .serialize_der()
+            .unwrap();
+        let bundle = format!("{}{}", pem(&first), pem(&second));
+
+        assert_eq!(
+            Certificate::from_pem(bundle.as_bytes()).unwrap(),
+            vec![Certificate::from_der(first), Certificate::from_der(second)]
+        );
+        assert!(Certificate::from_pem(b"not a certificate").is_err());
+    }
+
+    // A node with a self-signed certificate for `localhost`, which answers each request
+    // with its method. Returns its URL and certificate.
+    #[cfg(feature = "rustls")]
+    async fn node_with_self_signed_certificate() -> (String, Certificate) {
+        use futures::io::{
+            BufReader,
+            BufWriter,
+        };
+        use soketto::handshake::{
+            server::Response,
+            Server,
+        };
+        use tokio::net::TcpListener;
+        use tokio_rustls::TlsAcceptor;
+        use tokio_util::compat::TokioAsyncReadCompatExt;
+
+        let certificate =
+            rcgen::generate_simple_self_signed(vec!["localhost".into()]).unwrap();
+        let der = certificate.serialize_der().unwrap();
+        let tls_config = rustls::ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(
+                vec![rustls::Certificate(der.clone())],
+                rustls::PrivateKey(certificate.serialize_private_key_der()),
+            )
+            .unwrap();
+        let acceptor = TlsAcceptor::from(std::sync::Arc::new(tls_config));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("wss://localhost:{}", listener.local_addr().unwrap().port());
+
+        async_std::task::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                // Clients which don't trust the certificate give up on the handshake.
+                let stream = match acceptor.accept(stream).await {
+                    Ok(stream) => stream,
+                    Err(_) => continue,
+                };
+                let mut server =
+                    Server::new(BufReader::new(BufWriter::new(stream.compat())));
+                let key = server.receive_request().await.unwrap().key();
+                let accept = Response::Accept {
+                    key,
+                    protocol: None,
+                };
+                server.send_response(&accept).await.unwrap();
+                let (mut sender, mut receiver) = server.into_builder().finish();
+                let mut message = Vec::new();
+                receiver.receive_data(&mut message).await.unwrap();
+                let request: serde_json::Value =
+                    serde_json::from_slice(&message).unwrap();
+                let response = serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "result": request["method"],
+                    "id": request["id"],
+                });
+                sender.send_text(response.to_string()).await.unwrap();
+                sender.flush().await.unwrap();
+            }
+        });
+        (url, Certificate::from_der(der))
+    }
+
+    #[cfg(feature = "rustls")]
+    #[async_std::test]
+    async fn certificates_are_verified_as_configured() {
+        use crate::rpc::{
+            rpc_params,
+            ws_client_with_config,
+            ClientT,
+        };
+
+        let (url, certificate) = node_with_self_signed_certificate().await;
+
+        // The certificate isn't trusted by default...
+        assert!(ws_client_with_config(&url, &Default::default())
+            .await
+            .is_err());
+
+        // ...but is once it's added to the root certificates...
+        let config = WsClientConfig {
+            root_certificates: vec![certificate],
+            ..Default::default()
+        };
+        let client = ws_client_with_config(&url, &config).await.unwrap();
+        let result: String = client.request("system_name", rpc_params![]).await.unwrap();
+        assert_eq!(result, "system_name");
+
+        // ...or when certificates aren't verified at all.
+        let config = WsClientConfig {
+            accept_invalid_certificates: true,
+            ..Default::default()
+        };
+        let client = ws_client_with_config(&url, &config).await.unwrap();
+        let result: String = client.request("system_name", rpc_params![]).await.unwrap();
+        assert_eq!(result, "system_name");
+    }
+}
@@ -0,0 +1,256 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A WebSocket transport which makes the connection to the node itself, so that the TLS
+//! connection (see the `tls` module) and any proxy can be configured beyond what
+//! jsonrpsee's own transport allows.
+
+#[cfg(feature = "proxy")]
+use super::proxy::ProxyError;
+use super::{
+    tls,
+    RpcError,
+    Uri,
+    WsClientConfig,
+};
+use futures::{
+    future::{
+        self,
+        Either,
+    },
+    io::{
+        BufReader,
+        BufWriter,
+    },
+};
+use futures_timer::Delay;
+use jsonrpsee::core::client::{
+    TransportReceiverT,
+    TransportSenderT,
+};
+use soketto::{
+    connection,
+    handshake::client::{
+        Client as WsHandshakeClient,
+        Header,
+        ServerResponse,
+    },
+};
+use std::time::Duration;
+use tokio::{
+    io::{
+        AsyncRead,
+        AsyncWrite,
+    },
+    net::TcpStream,
+};
+use tokio_util::compat::{
+    Compat,
+    TokioAsyncReadCompatExt,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub(super) enum TransportError {
+    #[error("Invalid URL: {0}")]
+    Url(String),
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("TLS error: {0}")]
+    Tls(Box<dyn std::error::Error + Send + Sync>),
+    #[cfg(feature = "proxy")]
+    #[error("{0}")]
+    Proxy(#[from] ProxyError),
+    #[error("WebSocket handshake failed: {0}")]
+    Handshake(#[from] soketto::handshake::Error),
+    #[error("The node rejected the WebSocket handshake with status {0}")]
+    Rejected(u16),
+    #[error("Timed out connecting to the node after {0:?}")]
+    Timeout(Duration),
+}
+
+impl From<TransportError> for RpcError {
+    fn from(e: TransportError) -> Self {
+        RpcError::Transport(e.into())
+    }
+}
+
+// The node that a WebSocket URL points to.
+pub(super) struct Target {
+    pub(super) host: String,
+    pub(super) port: u16,
+    tls: bool,
+    path_and_query: String,
+}
+
+impl Target {
+    fn parse(url: &str) -> Result<Self, TransportError> {
+        let uri: Uri = url
+            .parse()
+            .map_err(|e| TransportError::Url(format!("{}", e)))?;
+        let tls = match uri.scheme_str() {
+            Some("ws") => false,
+            Some("wss") => true,
+            other => {
+                return Err(TransportError::Url(format!(
+                    "expected a ws:// or wss:// URL, not {:?}",
+                    other.unwrap_or("no scheme")
+                )))
+            }
+        };
+        let host = uri
+            .host()
+            .ok_or_else(|| TransportError::Url("no host given".into()))?
+            .to_string();
+        let port = uri.port_u16().unwrap_or(if tls { 443 } else { 80 });
+        let path_and_query = uri
+            .path_and_query()
+            .map_or_else(|| "/".to_string(), |path| path.to_string());
+        Ok(Target {
+            host,
+            port,
+            tls,
+            path_and_query,
+        })
+    }
+
+    pub(super) fn host_header(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+}
+
+// A connection to the node, with or without TLS on top.
+pub(super) trait Tunnel: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Tunnel for T {}
+
+type WsStream = BufReader<BufWriter<Compat<Box<dyn Tunnel>>>>;
+
+/// Sends messages to the node over a connection made by subxt itself.
+pub struct SocketSender {
+    inner: connection::Sender<WsStream>,
+}
+
+/// Receives messages from the node over a connection made by subxt itself.
+pub struct SocketReceiver {
+    inner: connection::Receiver<WsStream>,
+}
+
+#[async_trait::async_trait]
+impl TransportSenderT for SocketSender {
+    type Error = connection::Error;
+
+    async fn send(&mut self, msg: String) -> Result<(), Self::Error> {
+        self.inner.send_text(msg).await?;
+        self.inner.flush().await
+    }
+
+    async fn close(&mut self) -> Result<(), Self::Error> {
+        self.inner.close().await
+    }
+}
+
+#[async_trait::async_trait]
+impl TransportReceiverT for SocketReceiver {
+    type Error = connection::Error;
+
+    async fn receive(&mut self) -> Result<String, Self::Error> {
+        let mut message = Vec::new();
+        self.inner.receive_data(&mut message).await?;
+        String::from_utf8(message).map_err(|e| connection::Error::Utf8(e.utf8_error()))
+    }
+}
+
+// Connect to the node at the URL given, as configured.
+pub(super) async fn connect(
+    url: &str,
+    config: &WsClientConfig,
+) -> Result<(SocketSender, SocketReceiver), RpcError> {
+    let target = Target::parse(url)?;
+    let connecting = Box::pin(open(&target, config));
+    let res =
+        match future::select(connecting, Delay::new(config.connection_timeout)).await {
+            Either::Left((res, _)) => res,
+            Either::Right(_) => Err(TransportError::Timeout(config.connection_timeout)),
+        };
+    Ok(res?)
+}
+
+async fn open(
+    target: &Target,
+    config: &WsClientConfig,
+) -> Result<(SocketSender, SocketReceiver), TransportError> {
+    #[cfg(feature = "proxy")]
+    let stream = match &config.proxy {
+        Some(proxy) => super::proxy::tunnel(proxy, target).await?,
+        None => TcpStream::connect((target.host.as_str(), target.port)).await?,
+    };
+    #[cfg(not(feature = "proxy"))]
+    let stream = TcpStream::connect((target.host.as_str(), target.port)).await?;
+    stream.set_nodelay(true)?;
+    let stream: Box<dyn Tunnel> = if target.tls {
+        tls::connect(stream, &target.host, config).await?
+    } else {
+        Box::new(stream)
+    };
+
+    let host_header = target.host_header();
+    let headers: Vec<_> = config
+        .headers
+        .iter()
+        .map(|(name, value)| {
+            Header {
+                name,
+                value: value.as_bytes(),
+            }
+        })
+        .collect();
+    let socket = BufReader::new(BufWriter::new(stream.compat()));
+    let mut client = WsHandshakeClient::new(socket, &host_header, &target.path_and_query);
+    client.set_headers(&headers);
+    match client.handshake().await? {
+        ServerResponse::Accepted { .. } => {
+            let (sender, receiver) = client.into_builder().finish();
+            Ok((
+                SocketSender { inner: sender },
+                SocketReceiver { inner: receiver },
+            ))
+        }
+        ServerResponse::Rejected { status_code }
+        | ServerResponse::Redirect { status_code, .. } => {
+            Err(TransportError::Rejected(status_code))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets_are_parsed_from_urls() {
+        let target = Target::parse("wss://rpc.example.com/ws?key=1").unwrap();
+        assert_eq!(target.host_header(), "rpc.example.com:443");
+        assert!(target.tls);
+        assert_eq!(target.path_and_query, "/ws?key=1");
+
+        let target = Target::parse("ws://127.0.0.1:9944").unwrap();
+        assert_eq!(target.host_header(), "127.0.0.1:9944");
+        assert!(!target.tls);
+        assert_eq!(target.path_and_query, "/");
+
+        assert!(Target::parse("http://127.0.0.1:9933").is_err());
+    }
+}
@@ -0,0 +1,93 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+    error::BasicError,
+    rpc::Rpc,
+    Config,
+};
+use codec::{
+    Decode,
+    Encode,
+};
+
+/// Client for calling runtime API functions, which expose data (such as fee estimates,
+/// staking rewards or nomination pool details) that can't be read from storage directly.
+///
+/// The argument and return types are given by the caller. The V14 metadata that the
+/// codegen reads doesn't describe the runtime APIs, so no typed wrappers are generated for
+/// them.
+pub struct RuntimeApiClient<'a, T: Config> {
+    rpc: &'a Rpc<T>,
+}
+
+impl<'a, T: Config> Clone for RuntimeApiClient<'a, T> {
+    fn clone(&self) -> Self {
+        Self { rpc: self.rpc }
+    }
+}
+
+impl<'a, T: Config> RuntimeApiClient<'a, T> {
+    /// Create a new [`RuntimeApiClient`]
+    pub fn new(rpc: &'a Rpc<T>) -> Self {
+        Self { rpc }
+    }
+
+    /// Call a runtime API function at the given block (or the latest block if `None` is
+    /// given), SCALE encoding the arguments and decoding the return value. Functions are
+    /// named as `<api>_<method>`, for instance `TransactionPaymentApi_query_info`, and
+    /// functions taking several arguments are given them as a tuple.
+    ///
+    /// ```no_run
+    /// # #[async_std::main]
+    /// # async fn main() -> Result<(), subxt::BasicError> {
+    /// use sp_keyring::AccountKeyring;
+    /// use subxt::{ClientBuilder, DefaultConfig};
+    ///
+    /// let client = ClientBuilder::new().build::<DefaultConfig>().await?;
+    /// let nonce: u32 = client
+    ///     .runtime_api()
+    ///     .call(
+    ///         "AccountNonceApi_account_nonce",
+    ///         AccountKeyring::Alice.to_account_id(),
+    ///         None,
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn call<Args: Encode, Ret: Decode>(
+        &self,
+        function: &str,
+        args: Args,
+        at: Option<T::Hash>,
+    ) -> Result<Ret, BasicError> {
+        let bytes = self.call_raw(function, &args.encode(), at).await?;
+        Ok(Ret::decode(&mut &bytes[..])?)
+    }
+
+    /// Call a runtime API function at the given block (or the latest block if `None` is
+    /// given) with the SCALE encoded arguments given, handing back the SCALE encoded
+    /// return value.
+    pub async fn call_raw(
+        &self,
+        function: &str,
+        args: &[u8],
+        at: Option<T::Hash>,
+    ) -> Result<Vec<u8>, BasicError> {
+        Ok(self.rpc.call(function, args, at).await?.0)
+    }
+}
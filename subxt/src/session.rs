@@ -0,0 +1,101 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Helpers for rotating the session keys of a validator, as used by `pallet_session`.
+
+use crate::{
+    error::BasicError,
+    rpc::Rpc,
+    Call,
+    Config,
+};
+use codec::{
+    Decode,
+    Encode,
+};
+
+/// The `set_keys` call of `pallet_session`, which registers the session keys of the
+/// signer's validator account. `K` is the runtime's `SessionKeys` type, such as the one
+/// generated at `runtime_types::node_runtime::SessionKeys`.
+#[derive(Clone, Debug, Encode)]
+pub struct SetKeys<K> {
+    /// The session keys to register.
+    pub keys: K,
+    /// Proof of ownership of the keys. None of the key types used by substrate's runtimes
+    /// need one, so this is usually empty.
+    pub proof: Vec<u8>,
+}
+
+impl<K: Encode> Call for SetKeys<K> {
+    const PALLET: &'static str = "Session";
+    const FUNCTION: &'static str = "set_keys";
+}
+
+/// Generate new session keys in the node's keystore with `author_rotateKeys`, and build the
+/// `set_keys` call which registers them on chain once signed by the validator's controller
+/// account. The keys are decoded into the runtime's `SessionKeys` type `K`, which fails if
+/// they don't match it.
+pub async fn rotate_keys<T, K>(rpc: &Rpc<T>) -> Result<SetKeys<K>, BasicError>
+where
+    T: Config,
+    K: Decode,
+{
+    let bytes = rpc.rotate_keys().await?;
+    let keys = decode_keys(&bytes)?;
+    Ok(SetKeys {
+        keys,
+        proof: Vec::new(),
+    })
+}
+
+/// Check whether the node's keystore has the private keys for the session keys given, as
+/// handed back by [`rotate_keys()`].
+pub async fn has_keys<T, K>(rpc: &Rpc<T>, keys: &K) -> Result<bool, BasicError>
+where
+    T: Config,
+    K: Encode,
+{
+    rpc.has_session_keys(keys.encode().into()).await
+}
+
+// Decode the session keys handed back by the node, which must use all of the bytes given.
+fn decode_keys<K: Decode>(bytes: &[u8]) -> Result<K, BasicError> {
+    let input = &mut &bytes[..];
+    let keys = K::decode(input)?;
+    if !input.is_empty() {
+        return Err(format!(
+            "The session keys handed back by the node are {} bytes longer than expected",
+            input.len()
+        )
+        .into())
+    }
+    Ok(keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_must_use_all_of_the_bytes() {
+        let bytes = ([1u8; 32], [2u8; 32]).encode();
+        assert_eq!(
+            decode_keys::<([u8; 32], [u8; 32])>(&bytes).unwrap(),
+            ([1; 32], [2; 32])
+        );
+        assert!(decode_keys::<[u8; 32]>(&bytes).is_err());
+    }
+}
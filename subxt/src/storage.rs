@@ -27,7 +27,10 @@ use sp_core::storage::{
 };
 pub use sp_runtime::traits::SignedExtension;
 pub use sp_version::RuntimeVersion;
-use std::marker::PhantomData;
+use std::{
+    marker::PhantomData,
+    sync::Arc,
+};
 
 use crate::{
     error::BasicError,
@@ -35,7 +38,10 @@ use crate::{
         Metadata,
         MetadataError,
     },
-    rpc::Rpc,
+    rpc::{
+        FollowedChain,
+        Rpc,
+    },
     Config,
     StorageHasher,
 };
@@ -136,6 +142,7 @@ pub struct StorageClient<'a, T: Config> {
     rpc: &'a Rpc<T>,
     metadata: &'a Metadata,
     iter_page_size: u32,
+    chain_head: Option<&'a Arc<futures::lock::Mutex<FollowedChain<T>>>>,
 }
 
 impl<'a, T: Config> Clone for StorageClient<'a, T> {
@@ -144,6 +151,7 @@ impl<'a, T: Config> Clone for StorageClient<'a, T> {
             rpc: self.rpc,
             metadata: self.metadata,
             iter_page_size: self.iter_page_size,
+            chain_head: self.chain_head,
         }
     }
 }
@@ -155,16 +163,27 @@ impl<'a, T: Config> StorageClient<'a, T> {
             rpc,
             metadata,
             iter_page_size,
+            chain_head: None,
         }
     }
 
+    // Fetch values with `chainHead_unstable_storage` at the blocks being followed, if
+    // there are any.
+    pub(crate) fn with_chain_head(
+        mut self,
+        chain_head: Option<&'a Arc<futures::lock::Mutex<FollowedChain<T>>>>,
+    ) -> Self {
+        self.chain_head = chain_head;
+        self
+    }
+
     /// Fetch the value under an unhashed storage key
     pub async fn fetch_unhashed<V: Decode>(
         &self,
         key: StorageKey,
         hash: Option<T::Hash>,
     ) -> Result<Option<V>, BasicError> {
-        if let Some(data) = self.rpc.storage(&key, hash).await? {
+        if let Some(data) = self.fetch_raw(key, hash).await? {
             Ok(Some(Decode::decode(&mut &data.0[..])?))
         } else {
             Ok(None)
@@ -177,10 +196,22 @@ impl<'a, T: Config> StorageClient<'a, T> {
         key: StorageKey,
         hash: Option<T::Hash>,
     ) -> Result<Option<StorageData>, BasicError> {
+        if let Some(chain_head) = self.chain_head {
+            if let Some(data) = chain_head.lock().await.storage(&key, hash).await {
+                return Ok(data)
+            }
+        }
         self.rpc.storage(&key, hash).await
     }
 
     /// Fetch a StorageKey with an optional block hash.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(pallet = F::PALLET, storage = F::STORAGE, block_hash = ?hash)
+        )
+    )]
     pub async fn fetch<F: StorageEntry>(
         &self,
         store: &F,
@@ -0,0 +1,90 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Helpers for dispatching calls with root origin, as provided by `pallet_sudo`.
+
+use crate::{
+    error::{
+        BasicError,
+        Error,
+    },
+    Call,
+    Config,
+    Encoded,
+    Metadata,
+    TransactionEvents,
+};
+use codec::{
+    Decode,
+    Encode,
+};
+
+/// The `sudo` call of `pallet_sudo`, which dispatches the wrapped call with root origin.
+#[derive(Clone, Debug, Encode)]
+pub struct Sudo {
+    /// The call to dispatch, encoded as the runtime's outer `Call`.
+    pub call: Encoded,
+}
+
+impl Call for Sudo {
+    const PALLET: &'static str = "Sudo";
+    const FUNCTION: &'static str = "sudo";
+}
+
+/// The `sudo_unchecked_weight` call of `pallet_sudo`, which dispatches the wrapped call with
+/// root origin, using the given weight instead of the call's own.
+#[derive(Clone, Debug, Encode)]
+pub struct SudoUncheckedWeight {
+    /// The call to dispatch, encoded as the runtime's outer `Call`.
+    pub call: Encoded,
+    /// The weight to use for the call.
+    pub weight: u64,
+}
+
+impl Call for SudoUncheckedWeight {
+    const PALLET: &'static str = "Sudo";
+    const FUNCTION: &'static str = "sudo_unchecked_weight";
+}
+
+/// Wraps the given call in a `sudo` call.
+pub fn wrap_as_sudo<C: Call>(metadata: &Metadata, call: &C) -> Result<Sudo, BasicError> {
+    let call = metadata.pallet(C::PALLET)?.encode_call(call)?;
+    Ok(Sudo { call })
+}
+
+/// Wraps the given call in a `sudo_unchecked_weight` call with the given weight.
+pub fn wrap_as_sudo_unchecked_weight<C: Call>(
+    metadata: &Metadata,
+    call: &C,
+    weight: u64,
+) -> Result<SudoUncheckedWeight, BasicError> {
+    let call = metadata.pallet(C::PALLET)?.encode_call(call)?;
+    Ok(SudoUncheckedWeight { call, weight })
+}
+
+/// Returns the result of the call dispatched by a `sudo` or `sudo_unchecked_weight` call,
+/// which is reported by its `Sudid` event. The `sudo` extrinsic itself succeeds even when
+/// the wrapped call fails, so this should be checked in addition to the extrinsic's success.
+pub fn sudo_result<T, Evs, E>(
+    events: &TransactionEvents<'_, T, Evs>,
+) -> Result<(), Error<E>>
+where
+    T: Config,
+    Evs: Decode,
+    E: Decode,
+{
+    events.find_dispatch_result("Sudo", "Sudid")
+}
@@ -14,10 +14,17 @@
 // You should have received a copy of the GNU General Public License
 // along with subxt.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::task::Poll;
+use std::{
+    task::Poll,
+    time::Duration,
+};
 
 use crate::PhantomDataSendSync;
-use codec::Decode;
+use codec::{
+    Decode,
+    Encode,
+};
+use sp_core::H256;
 use sp_runtime::traits::Hash;
 pub use sp_runtime::traits::SignedExtension;
 pub use sp_version::RuntimeVersion;
@@ -36,17 +43,35 @@ use crate::{
         Events,
         RawEventDetails,
     },
-    rpc::SubstrateTransactionStatus,
+    rpc::{
+        DispatchClass,
+        InclusionFee,
+        SubstrateTransactionStatus,
+        TransactionWatchEvent,
+    },
     Config,
     Phase,
 };
 use derivative::Derivative;
 use futures::{
+    future::{
+        self,
+        AbortHandle,
+        Aborted,
+        BoxFuture,
+        Either,
+        RemoteHandle,
+    },
+    stream::BoxStream,
+    FutureExt,
     Stream,
     StreamExt,
+    TryStreamExt,
 };
+use futures_timer::Delay;
 use jsonrpsee::core::{
     client::Subscription as RpcSubscription,
+    DeserializeOwned,
     Error as RpcError,
 };
 
@@ -55,9 +80,12 @@ use jsonrpsee::core::{
 #[derive(Derivative)]
 #[derivative(Debug(bound = ""))]
 pub struct TransactionProgress<'client, T: Config, E: Decode, Evs: Decode> {
-    sub: Option<RpcSubscription<SubstrateTransactionStatus<T::Hash, T::Hash>>>,
+    #[derivative(Debug = "ignore")]
+    sub: Option<TransactionStatuses<T::Hash>>,
     ext_hash: T::Hash,
     client: &'client Client<T>,
+    timeout: Option<Duration>,
+    last_status: Option<String>,
     _error: PhantomDataSendSync<(E, Evs)>,
 }
 
@@ -75,15 +103,125 @@ impl<'client, T: Config, E: Decode, Evs: Decode> TransactionProgress<'client, T,
         sub: RpcSubscription<SubstrateTransactionStatus<T::Hash, T::Hash>>,
         client: &'client Client<T>,
         ext_hash: T::Hash,
+    ) -> Self {
+        Self::from_statuses(substrate_statuses(sub), client, ext_hash)
+    }
+
+    /// Instantiate a new [`TransactionProgress`] from a `transactionWatch_unstable_submitAndWatch`
+    /// subscription (see [`crate::rpc::Rpc::transaction_watch()`]). Its events are reported as
+    /// the equivalent [`TransactionStatus`]es; note that the new JSON-RPC spec only reports how
+    /// many peers a transaction was broadcast to, so [`TransactionStatus::Broadcast`] comes with
+    /// no peers. The node's reason for a transaction being dropped or invalid is handed back
+    /// with [`TransactionStatus::Dropped`] and [`TransactionStatus::Invalid`].
+    pub fn from_transaction_watch(
+        sub: RpcSubscription<TransactionWatchEvent<T::Hash>>,
+        client: &'client Client<T>,
+        ext_hash: T::Hash,
+    ) -> Self {
+        Self::from_statuses(transaction_watch_statuses(sub), client, ext_hash)
+    }
+
+    pub(crate) fn from_statuses(
+        sub: TransactionStatuses<T::Hash>,
+        client: &'client Client<T>,
+        ext_hash: T::Hash,
     ) -> Self {
         Self {
             sub: Some(sub),
             client,
             ext_hash,
+            timeout: None,
+            last_status: None,
             _error: PhantomDataSendSync::new(),
         }
     }
 
+    /// Give up waiting in [`TransactionProgress::wait_for_in_block()`],
+    /// [`TransactionProgress::wait_for_finalized()`] and
+    /// [`TransactionProgress::wait_for_finalized_success()`] if the awaited status isn't
+    /// reached within the given duration. The subscription is then cancelled, and a
+    /// [`TransactionError::Timeout`] containing the last status received is returned.
+    ///
+    /// Without a timeout, these wait for as long as the node keeps the subscription open,
+    /// which may be forever if the node silently drops the transaction.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Keep waiting for the transaction to be finalized successfully in the background,
+    /// without tying up the caller.
+    ///
+    /// This returns a [`TransactionMonitor`] handle, along with a future which does the
+    /// actual waiting, and which should be spawned on the executor of your choice (it
+    /// resolves when the transaction is finalized, fails, or monitoring is aborted). The
+    /// handle can be awaited later on to obtain the outcome, or used to abort monitoring.
+    /// Dropping the handle also aborts monitoring, unless [`TransactionMonitor::detach`]
+    /// is called.
+    ///
+    /// Any timeout set with [`TransactionProgress::with_timeout()`] still applies.
+    pub fn monitor(self) -> (TransactionMonitor<T, E>, BoxFuture<'static, ()>)
+    where
+        E: Send + 'static,
+        Evs: Send + 'static,
+    {
+        let client = self.client.clone();
+        let sub = self.sub;
+        let ext_hash = self.ext_hash;
+        let timeout = self.timeout;
+
+        let task = async move {
+            let progress = TransactionProgress::<T, E, Evs> {
+                sub,
+                ext_hash,
+                client: &client,
+                timeout,
+                last_status: None,
+                _error: PhantomDataSendSync::new(),
+            };
+            let events = progress.wait_for_finalized_success().await?;
+            Ok::<_, Error<E>>(FinalizedTransaction {
+                block_hash: events.block_hash(),
+                extrinsic_hash: ext_hash,
+            })
+        };
+        let (task, abort_handle) = future::abortable(task);
+        let (task, handle) = task.remote_handle();
+
+        let monitor = TransactionMonitor {
+            ext_hash,
+            handle,
+            abort_handle,
+        };
+        (monitor, task.boxed())
+    }
+
+    /// Returns the next status, or a [`TransactionError::Timeout`] error (after cancelling
+    /// the subscription) if the deadline passes first.
+    async fn next_before(
+        &mut self,
+        deadline: &mut Option<Delay>,
+    ) -> Option<Result<TransactionStatus<'client, T, E, Evs>, BasicError>> {
+        let deadline = match deadline {
+            Some(deadline) => deadline,
+            None => return self.next().await,
+        };
+        let next = match future::select(self.next(), deadline).await {
+            Either::Left((status, _)) => Some(status),
+            Either::Right(_) => None,
+        };
+        match next {
+            Some(status) => status,
+            None => {
+                self.sub = None;
+                Some(Err(TransactionError::Timeout {
+                    last_status: self.last_status.take(),
+                }
+                .into()))
+            }
+        }
+    }
+
     /// Return the next transaction status when it's emitted. This just delegates to the
     /// [`futures::Stream`] implementation for [`TransactionProgress`], but allows you to
     /// avoid importing that trait if you don't otherwise need it.
@@ -100,23 +238,41 @@ impl<'client, T: Config, E: Decode, Evs: Decode> TransactionProgress<'client, T,
     /// **Note:** consumes `self`. If you'd like to perform multiple actions as the state of the
     /// transaction progresses, use [`TransactionProgress::next_item()`] instead.
     ///
-    /// **Note:** transaction statuses like `Invalid` and `Usurped` are ignored, because while they
-    /// may well indicate with some probability that the transaction will not make it into a block,
-    /// there is no guarantee that this is true. Thus, we prefer to "play it safe" here. Use the lower
-    /// level [`TransactionProgress::next_item()`] API if you'd like to handle these statuses yourself.
+    /// **Note:** the node stops reporting on the transaction once it's `Usurped`, `Dropped` or
+    /// `Invalid`, and so these statuses are returned as the corresponding [`TransactionError`].
+    /// It's not guaranteed that such a transaction won't make it into a block (for instance, it may
+    /// still be valid in the pool of another node), so use the lower level
+    /// [`TransactionProgress::next_item()`] API if you'd like to handle these statuses yourself.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(tx_hash = ?self.ext_hash, block_hash = tracing::field::Empty)
+        )
+    )]
     pub async fn wait_for_in_block(
         mut self,
     ) -> Result<TransactionInBlock<'client, T, E, Evs>, BasicError> {
-        while let Some(status) = self.next_item().await {
+        let mut deadline = self.timeout.map(Delay::new);
+        while let Some(status) = self.next_before(&mut deadline).await {
             match status? {
                 // Finalized or otherwise in a block! Return.
                 TransactionStatus::InBlock(s) | TransactionStatus::Finalized(s) => {
+                    #[cfg(feature = "tracing")]
+                    record_block_hash(s.block_hash());
                     return Ok(s)
                 }
                 // Error scenarios; return the error.
                 TransactionStatus::FinalityTimeout(_) => {
                     return Err(TransactionError::FinalitySubscriptionTimeout.into())
                 }
+                TransactionStatus::Usurped(hash) => return Err(usurped(hash)),
+                TransactionStatus::Dropped(reason) => {
+                    return Err(TransactionError::Dropped(reason).into())
+                }
+                TransactionStatus::Invalid(reason) => {
+                    return Err(TransactionError::Invalid(reason).into())
+                }
                 // Ignore anything else and wait for next status event:
                 _ => continue,
             }
@@ -130,21 +286,41 @@ impl<'client, T: Config, E: Decode, Evs: Decode> TransactionProgress<'client, T,
     /// **Note:** consumes `self`. If you'd like to perform multiple actions as the state of the
     /// transaction progresses, use [`TransactionProgress::next_item()`] instead.
     ///
-    /// **Note:** transaction statuses like `Invalid` and `Usurped` are ignored, because while they
-    /// may well indicate with some probability that the transaction will not make it into a block,
-    /// there is no guarantee that this is true. Thus, we prefer to "play it safe" here. Use the lower
-    /// level [`TransactionProgress::next_item()`] API if you'd like to handle these statuses yourself.
+    /// **Note:** the node stops reporting on the transaction once it's `Usurped`, `Dropped` or
+    /// `Invalid`, and so these statuses are returned as the corresponding [`TransactionError`].
+    /// It's not guaranteed that such a transaction won't make it into a block (for instance, it may
+    /// still be valid in the pool of another node), so use the lower level
+    /// [`TransactionProgress::next_item()`] API if you'd like to handle these statuses yourself.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(tx_hash = ?self.ext_hash, block_hash = tracing::field::Empty)
+        )
+    )]
     pub async fn wait_for_finalized(
         mut self,
     ) -> Result<TransactionInBlock<'client, T, E, Evs>, BasicError> {
-        while let Some(status) = self.next_item().await {
+        let mut deadline = self.timeout.map(Delay::new);
+        while let Some(status) = self.next_before(&mut deadline).await {
             match status? {
                 // Finalized! Return.
-                TransactionStatus::Finalized(s) => return Ok(s),
+                TransactionStatus::Finalized(s) => {
+                    #[cfg(feature = "tracing")]
+                    record_block_hash(s.block_hash());
+                    return Ok(s)
+                }
                 // Error scenarios; return the error.
                 TransactionStatus::FinalityTimeout(_) => {
                     return Err(TransactionError::FinalitySubscriptionTimeout.into())
                 }
+                TransactionStatus::Usurped(hash) => return Err(usurped(hash)),
+                TransactionStatus::Dropped(reason) => {
+                    return Err(TransactionError::Dropped(reason).into())
+                }
+                TransactionStatus::Invalid(reason) => {
+                    return Err(TransactionError::Invalid(reason).into())
+                }
                 // Ignore and wait for next status event:
                 _ => continue,
             }
@@ -159,10 +335,11 @@ impl<'client, T: Config, E: Decode, Evs: Decode> TransactionProgress<'client, T,
     /// **Note:** consumes self. If you'd like to perform multiple actions as progress is made,
     /// use [`TransactionProgress::next_item()`] instead.
     ///
-    /// **Note:** transaction statuses like `Invalid` and `Usurped` are ignored, because while they
-    /// may well indicate with some probability that the transaction will not make it into a block,
-    /// there is no guarantee that this is true. Thus, we prefer to "play it safe" here. Use the lower
-    /// level [`TransactionProgress::next_item()`] API if you'd like to handle these statuses yourself.
+    /// **Note:** the node stops reporting on the transaction once it's `Usurped`, `Dropped` or
+    /// `Invalid`, and so these statuses are returned as the corresponding [`TransactionError`].
+    /// It's not guaranteed that such a transaction won't make it into a block (for instance, it may
+    /// still be valid in the pool of another node), so use the lower level
+    /// [`TransactionProgress::next_item()`] API if you'd like to handle these statuses yourself.
     pub async fn wait_for_finalized_success(
         self,
     ) -> Result<TransactionEvents<'client, T, Evs>, Error<E>> {
@@ -171,6 +348,99 @@ impl<'client, T: Config, E: Decode, Evs: Decode> TransactionProgress<'client, T,
     }
 }
 
+/// The statuses of a transaction, as reported by the node, each along with the reason the
+/// node gave for the transaction being dropped or invalid, if any.
+pub(crate) type TransactionStatuses<Hash> = BoxStream<
+    'static,
+    Result<(SubstrateTransactionStatus<Hash, Hash>, Option<String>), RpcError>,
+>;
+
+// Report the statuses of an `author_submitAndWatchExtrinsic` subscription, which doesn't give
+// reasons for failures.
+pub(crate) fn substrate_statuses<Hash>(
+    sub: RpcSubscription<SubstrateTransactionStatus<Hash, Hash>>,
+) -> TransactionStatuses<Hash>
+where
+    Hash: DeserializeOwned + Send + 'static,
+{
+    sub.map_ok(|status| (status, None)).boxed()
+}
+
+// Report the events of a `transactionWatch_unstable_submitAndWatch` subscription as the
+// equivalent statuses reported by `author_submitAndWatchExtrinsic`, along with the reasons
+// given for errors. The node giving up on watching the transaction is reported as it being
+// dropped, since either way the node won't report on it any further.
+pub(crate) fn transaction_watch_statuses<Hash>(
+    sub: RpcSubscription<TransactionWatchEvent<Hash>>,
+) -> TransactionStatuses<Hash>
+where
+    Hash: DeserializeOwned + Copy + Send + 'static,
+{
+    // The block that the transaction is in, which is needed to report it being retracted.
+    let in_block = None;
+    sub.scan(in_block, |in_block, event| {
+        let status = event.map(|event| {
+            match event {
+                TransactionWatchEvent::Validated => {
+                    (SubstrateTransactionStatus::Ready, None)
+                }
+                TransactionWatchEvent::Broadcasted { num_peers } => {
+                    log::debug!("Transaction broadcast to {} peers", num_peers);
+                    (SubstrateTransactionStatus::Broadcast(Vec::new()), None)
+                }
+                TransactionWatchEvent::BestChainBlockIncluded { block: Some(block) } => {
+                    *in_block = Some(block.hash);
+                    (SubstrateTransactionStatus::InBlock(block.hash), None)
+                }
+                TransactionWatchEvent::BestChainBlockIncluded { block: None } => {
+                    match in_block.take() {
+                        Some(hash) => (SubstrateTransactionStatus::Retracted(hash), None),
+                        None => (SubstrateTransactionStatus::Ready, None),
+                    }
+                }
+                TransactionWatchEvent::Finalized { block } => {
+                    (SubstrateTransactionStatus::Finalized(block.hash), None)
+                }
+                TransactionWatchEvent::Error { error } => {
+                    log::warn!("Node stopped watching transaction: {}", error);
+                    (SubstrateTransactionStatus::Dropped, Some(error))
+                }
+                TransactionWatchEvent::Invalid { error } => {
+                    (SubstrateTransactionStatus::Invalid, Some(error))
+                }
+                TransactionWatchEvent::Dropped { error, .. } => {
+                    (SubstrateTransactionStatus::Dropped, Some(error))
+                }
+            }
+        });
+        future::ready(Some(status))
+    })
+    .boxed()
+}
+
+// The error for a transaction replaced by the one with the given hash. The hash is handed
+// back as an `H256`, so an error saying so is handed back instead for hashes of any other
+// length.
+fn usurped(hash: impl AsRef<[u8]>) -> BasicError {
+    let bytes = hash.as_ref();
+    if bytes.len() != H256::len_bytes() {
+        return BasicError::Other(format!(
+            "The transaction was replaced by another whose hash is {} bytes long, rather \
+             than {}: 0x{}",
+            bytes.len(),
+            H256::len_bytes(),
+            hex::encode(bytes)
+        ))
+    }
+    TransactionError::Usurped(H256::from_slice(bytes)).into()
+}
+
+// Note the block that a transaction being waited for made it into on the current span.
+#[cfg(feature = "tracing")]
+fn record_block_hash(block_hash: impl std::fmt::Debug) {
+    tracing::Span::current().record("block_hash", &tracing::field::debug(block_hash));
+}
+
 impl<'client, T: Config, E: Decode, Evs: Decode> Stream
     for TransactionProgress<'client, T, E, Evs>
 {
@@ -187,7 +457,8 @@ impl<'client, T: Config, E: Decode, Evs: Decode> Stream
 
         sub.poll_next_unpin(cx)
             .map_err(|e| e.into())
-            .map_ok(|status| {
+            .map_ok(|(status, reason)| {
+                self.last_status = Some(format!("{:?}", status));
                 match status {
                     SubstrateTransactionStatus::Future => TransactionStatus::Future,
                     SubstrateTransactionStatus::Ready => TransactionStatus::Ready,
@@ -207,8 +478,12 @@ impl<'client, T: Config, E: Decode, Evs: Decode> Stream
                     SubstrateTransactionStatus::Usurped(hash) => {
                         TransactionStatus::Usurped(hash)
                     }
-                    SubstrateTransactionStatus::Dropped => TransactionStatus::Dropped,
-                    SubstrateTransactionStatus::Invalid => TransactionStatus::Invalid,
+                    SubstrateTransactionStatus::Dropped => {
+                        TransactionStatus::Dropped(reason)
+                    }
+                    SubstrateTransactionStatus::Invalid => {
+                        TransactionStatus::Invalid(reason)
+                    }
                     // Only the following statuses are actually considered "final" (see the substrate
                     // docs on `TransactionStatus`). Basically, either the transaction makes it into a
                     // block, or we eventually give up on waiting for it to make it into a block.
@@ -304,10 +579,14 @@ pub enum TransactionStatus<'client, T: Config, E: Decode, Evs: Decode> {
     /// The transaction has been replaced in the pool by another transaction
     /// that provides the same tags. (e.g. same (sender, nonce)).
     Usurped(T::Hash),
-    /// The transaction has been dropped from the pool because of the limit.
-    Dropped,
-    /// The transaction is no longer valid in the current state.
-    Invalid,
+    /// The transaction has been dropped from the pool because of the limit. This contains
+    /// the reason the node gave, if any (only `transactionWatch_unstable_submitAndWatch`
+    /// subscriptions give one).
+    Dropped(Option<String>),
+    /// The transaction is no longer valid in the current state. This contains the reason the
+    /// node gave, if any (only `transactionWatch_unstable_submitAndWatch` subscriptions give
+    /// one).
+    Invalid(Option<String>),
 }
 
 impl<'client, T: Config, E: Decode, Evs: Decode> TransactionStatus<'client, T, E, Evs> {
@@ -328,6 +607,94 @@ impl<'client, T: Config, E: Decode, Evs: Decode> TransactionStatus<'client, T, E
             _ => None,
         }
     }
+
+    /// A convenience method to return the hash of the transaction which replaced this
+    /// one in the pool. Returns [`None`] if the enum variant is not
+    /// [`TransactionStatus::Usurped`].
+    pub fn as_usurped(&self) -> Option<&T::Hash> {
+        match self {
+            Self::Usurped(hash) => Some(hash),
+            _ => None,
+        }
+    }
+}
+
+/// A handle to a transaction being monitored in the background, returned from
+/// [`TransactionProgress::monitor()`]. Await it to obtain the outcome.
+#[derive(Derivative)]
+#[derivative(Debug(bound = ""))]
+pub struct TransactionMonitor<T: Config, E> {
+    ext_hash: T::Hash,
+    #[derivative(Debug = "ignore")]
+    handle: RemoteHandle<Result<Result<FinalizedTransaction<T>, Error<E>>, Aborted>>,
+    abort_handle: AbortHandle,
+}
+
+// As with `TransactionProgress`, we don't care if this moves around in memory.
+impl<T: Config, E> Unpin for TransactionMonitor<T, E> {}
+
+impl<T: Config, E> TransactionMonitor<T, E> {
+    /// Return the hash of the extrinsic being monitored.
+    pub fn extrinsic_hash(&self) -> T::Hash {
+        self.ext_hash
+    }
+
+    /// Stop monitoring the transaction. Awaiting the handle then returns an error.
+    ///
+    /// **Note:** this doesn't (and can't) stop the transaction itself from making it into
+    /// a block.
+    pub fn abort(&self) {
+        self.abort_handle.abort()
+    }
+
+    /// Returns a handle which can be used to abort monitoring from elsewhere.
+    pub fn abort_handle(&self) -> AbortHandle {
+        self.abort_handle.clone()
+    }
+
+    /// Drop this handle without aborting monitoring. The outcome is then discarded.
+    pub fn detach(self) {
+        self.handle.forget()
+    }
+}
+
+impl<T: Config, E> std::future::Future for TransactionMonitor<T, E> {
+    type Output = Result<FinalizedTransaction<T>, Error<E>>;
+
+    fn poll(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Self::Output> {
+        self.handle.poll_unpin(cx).map(|res| {
+            res.unwrap_or_else(|Aborted| {
+                Err(Error::Other(
+                    "Monitoring the transaction was aborted".into(),
+                ))
+            })
+        })
+    }
+}
+
+/// The outcome of a transaction monitored with [`TransactionProgress::monitor()`], which
+/// was finalized successfully.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Copy(bound = ""), Debug(bound = ""))]
+pub struct FinalizedTransaction<T: Config> {
+    /// The hash of the finalized block containing the transaction.
+    pub block_hash: T::Hash,
+    /// The hash of the extrinsic.
+    pub extrinsic_hash: T::Hash,
+}
+
+impl<T: Config> FinalizedTransaction<T> {
+    /// Returns a [`TransactionInBlock`] for the transaction, which can be used to fetch
+    /// the events it produced.
+    pub fn in_block<'client, E: Decode, Evs: Decode>(
+        &self,
+        client: &'client Client<T>,
+    ) -> TransactionInBlock<'client, T, E, Evs> {
+        TransactionInBlock::new(self.block_hash, self.extrinsic_hash, client)
+    }
 }
 
 /// This struct represents a transaction that has made it into a block.
@@ -451,6 +818,11 @@ impl<'client, T: Config, Evs: Decode> TransactionEvents<'client, T, Evs> {
         self.ext_hash
     }
 
+    /// Return the index of the extrinsic in the block that it made it into.
+    pub fn extrinsic_index(&self) -> u32 {
+        self.ext_idx
+    }
+
     /// Return all of the events in the block that the transaction made it into.
     pub fn all_events_in_block(&self) -> &events::Events<'client, T, Evs> {
         &self.events
@@ -462,7 +834,7 @@ impl<'client, T: Config, Evs: Decode> TransactionEvents<'client, T, Evs> {
     /// exception that it filters out events not related to the submitted extrinsic.
     pub fn iter(
         &self,
-    ) -> impl Iterator<Item = Result<EventDetails<Evs>, BasicError>> + '_ {
+    ) -> impl Iterator<Item = Result<EventDetails<Evs, T::Hash>, BasicError>> + '_ {
         self.events.iter().filter(|ev| {
             ev.as_ref()
                 .map(|ev| ev.phase == Phase::ApplyExtrinsic(self.ext_idx))
@@ -476,7 +848,7 @@ impl<'client, T: Config, Evs: Decode> TransactionEvents<'client, T, Evs> {
     /// exception that it filters out events not related to the submitted extrinsic.
     pub fn iter_raw(
         &self,
-    ) -> impl Iterator<Item = Result<RawEventDetails, BasicError>> + '_ {
+    ) -> impl Iterator<Item = Result<RawEventDetails<T::Hash>, BasicError>> + '_ {
         self.events.iter_raw().filter(|ev| {
             ev.as_ref()
                 .map(|ev| ev.phase == Phase::ApplyExtrinsic(self.ext_idx))
@@ -497,6 +869,20 @@ impl<'client, T: Config, Evs: Decode> TransactionEvents<'client, T, Evs> {
         })
     }
 
+    /// Find all of the transaction events matching the event type provided as a generic parameter,
+    /// along with their index and the [`Phase`] in which they were produced.
+    ///
+    /// This works in the same way that [`events::Events::find_events()`] does, with the
+    /// exception that it filters out events not related to the submitted extrinsic.
+    pub fn find_events<Ev: crate::Event>(
+        &self,
+    ) -> impl Iterator<Item = Result<EventDetails<Ev, T::Hash>, BasicError>> + '_ {
+        self.iter_raw().filter_map(|ev| {
+            ev.and_then(|ev| ev.as_event_details::<Ev>().map_err(Into::into))
+                .transpose()
+        })
+    }
+
     /// Iterate through the transaction events using metadata to dynamically decode and skip
     /// them, and return the first event found which decodes to the provided `Ev` type.
     ///
@@ -506,6 +892,81 @@ impl<'client, T: Config, Evs: Decode> TransactionEvents<'client, T, Evs> {
         self.find::<Ev>().next().transpose()
     }
 
+    /// Iterate through the transaction events using metadata to dynamically decode and skip
+    /// them, and return the last event found which decodes to the provided `Ev` type.
+    ///
+    /// This works in the same way that [`events::Events::find_last_event()`] does, with the
+    /// exception that it ignores events not related to the submitted extrinsic.
+    pub fn find_last_event<Ev: crate::Event>(&self) -> Result<Option<Ev>, BasicError> {
+        self.find::<Ev>().last().transpose()
+    }
+
+    /// Like [`TransactionEvents::find_first_event()`], but also hands back the position of
+    /// the event found; that is, its index and the [`Phase`] in which it was produced. This
+    /// can be used to tell apart repeated events, for instance from the calls in a batch.
+    pub fn find_first_event_details<Ev: crate::Event>(
+        &self,
+    ) -> Result<Option<EventDetails<Ev, T::Hash>>, BasicError> {
+        self.find_events::<Ev>().next().transpose()
+    }
+
+    /// Like [`TransactionEvents::find_last_event()`], but also hands back the position of
+    /// the event found. See [`TransactionEvents::find_first_event_details()`].
+    pub fn find_last_event_details<Ev: crate::Event>(
+        &self,
+    ) -> Result<Option<EventDetails<Ev, T::Hash>>, BasicError> {
+        self.find_events::<Ev>().last().transpose()
+    }
+
+    /// Find the first event with the given pallet and variant names associated with this
+    /// transaction, and decode it as the result of a nested dispatch. Pallets which dispatch
+    /// calls on behalf of others (for instance `Proxy` or `Sudo`) emit such events, since the
+    /// outer extrinsic succeeds regardless of whether the nested call does.
+    pub(crate) fn find_dispatch_result<E: Decode>(
+        &self,
+        pallet: &str,
+        variant: &str,
+    ) -> Result<(), Error<E>> {
+        for ev in self.iter_raw() {
+            let ev = ev?;
+            if ev.pallet == pallet && ev.variant == variant {
+                let result = <Result<(), E>>::decode(&mut &*ev.data)?;
+                return result.map_err(|e| Error::Runtime(RuntimeError(e)))
+            }
+        }
+        Err(Error::Other(format!(
+            "No {}::{} event found",
+            pallet, variant
+        )))
+    }
+
+    /// Returns the [`DispatchInfo`] of the extrinsic, as reported by the
+    /// `System::ExtrinsicSuccess` or `System::ExtrinsicFailed` event. Unlike the estimate
+    /// returned from the `payment_queryInfo` RPC call, this contains the actual weight
+    /// used by the dispatch and whether it was charged for.
+    pub fn dispatch_info(&self) -> Result<DispatchInfo, BasicError> {
+        for ev in self.iter_raw() {
+            let ev = ev?;
+            if ev.pallet != "System" {
+                continue
+            }
+            let data = match ev.variant.as_str() {
+                "ExtrinsicSuccess" => &ev.data[..],
+                // The dispatch info follows the `DispatchError`, whose encoded length varies,
+                // so decode it from the fixed size tail of the event data instead.
+                "ExtrinsicFailed" => {
+                    let len = DispatchInfo::ENCODED_LEN;
+                    &ev.data[ev.data.len().saturating_sub(len)..]
+                }
+                _ => continue,
+            };
+            return Ok(DispatchInfo::decode(&mut &*data)?)
+        }
+        Err(BasicError::Other(
+            "No System::ExtrinsicSuccess or System::ExtrinsicFailed event found".into(),
+        ))
+    }
+
     /// Find an event in those associated with this transaction. Returns true if it was found.
     ///
     /// This works in the same way that [`events::Events::has()`] does, with the
@@ -514,3 +975,296 @@ impl<'client, T: Config, Evs: Decode> TransactionEvents<'client, T, Evs> {
         Ok(self.find::<Ev>().next().transpose()?.is_some())
     }
 }
+
+/// Displays the events related to the transaction in a human readable form, one per
+/// line, in the same way as [`events::Events::display()`].
+impl<'client, T: Config, Evs: Decode> std::fmt::Display
+    for TransactionEvents<'client, T, Evs>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "extrinsic {:?} (index {} in block {:?}):",
+            self.ext_hash,
+            self.ext_idx,
+            self.block_hash()
+        )?;
+        events::fmt_raw_events(self.iter_raw(), self.events.metadata(), f)
+    }
+}
+
+/// Whether a dispatch pays a fee.
+///
+/// # Note
+///
+/// This is copied from `frame-support` to avoid a dependency on that crate. Therefore it
+/// must be kept compatible with that type from the target substrate version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum Pays {
+    /// The transaction pays a fee.
+    Yes,
+    /// The transaction does not pay a fee.
+    No,
+}
+
+/// The weight and fee information of a dispatched extrinsic, as emitted in the
+/// `System::ExtrinsicSuccess` and `System::ExtrinsicFailed` events.
+///
+/// # Note
+///
+/// This is copied from `frame-support` to avoid a dependency on that crate. Therefore it
+/// must be kept compatible with that type from the target substrate version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub struct DispatchInfo {
+    /// The actual weight used by the dispatch.
+    pub weight: u64,
+    /// The class of the dispatch.
+    pub class: DispatchClass,
+    /// Whether the dispatch paid a fee.
+    pub pays_fee: Pays,
+}
+
+impl DispatchInfo {
+    // The weight, followed by the one byte class and pays_fee enums.
+    const ENCODED_LEN: usize = 8 + 1 + 1;
+
+    /// Computes the fee actually charged for the extrinsic, given the fee breakdown of
+    /// the pre-dispatch estimate (see
+    /// [`SubmittableExtrinsic::estimate_fee_details`](crate::SubmittableExtrinsic::estimate_fee_details)),
+    /// the weight that estimate was based on, and the tip paid.
+    ///
+    /// Dispatches which use less weight than estimated are refunded the difference, so
+    /// the weight fee is scaled down by the ratio of the actual to the estimated weight.
+    /// This assumes the runtime converts weight to fees linearly.
+    pub fn actual_fee(
+        &self,
+        estimated_fee: &InclusionFee,
+        estimated_weight: u64,
+        tip: u128,
+    ) -> u128 {
+        if self.pays_fee == Pays::No {
+            return tip
+        }
+        let weight_fee = if estimated_weight == 0 || self.weight >= estimated_weight {
+            estimated_fee.adjusted_weight_fee
+        } else {
+            estimated_fee
+                .adjusted_weight_fee
+                .saturating_mul(self.weight as u128)
+                / estimated_weight as u128
+        };
+        estimated_fee
+            .base_fee
+            .saturating_add(estimated_fee.len_fee)
+            .saturating_add(weight_fee)
+            .saturating_add(tip)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rpc::{
+            MockRpcClient,
+            Rpc,
+        },
+        ClientBuilder,
+        DefaultConfig,
+        Encoded,
+    };
+    use frame_metadata::v14::{
+        ExtrinsicMetadata,
+        RuntimeMetadataLastVersion,
+    };
+    use scale_info::meta_type;
+    use serde_json::json;
+
+    // The statuses reported for the given `transactionWatch_unstable_submitAndWatch` events.
+    async fn watch_statuses(
+        events: Vec<serde_json::Value>,
+    ) -> Vec<(SubstrateTransactionStatus<H256, H256>, Option<String>)> {
+        let mock = MockRpcClient::new()
+            .add_subscription("transactionWatch_unstable_submitAndWatch", events);
+        let rpc = Rpc::<DefaultConfig>::new(mock);
+        let sub = rpc.transaction_watch(Encoded(vec![])).await.unwrap();
+        transaction_watch_statuses(sub)
+            .map(|status| status.unwrap())
+            .collect()
+            .await
+    }
+
+    fn block(byte: u8) -> serde_json::Value {
+        json!({ "hash": H256::repeat_byte(byte), "index": 0 })
+    }
+
+    #[async_std::test]
+    async fn transaction_watch_events_are_reported_as_statuses() {
+        let statuses = watch_statuses(vec![
+            json!({ "event": "validated" }),
+            json!({ "event": "broadcasted", "numPeers": 2 }),
+            json!({ "event": "bestChainBlockIncluded", "block": block(1) }),
+            json!({ "event": "finalized", "block": block(1) }),
+        ])
+        .await;
+
+        assert_eq!(
+            statuses,
+            vec![
+                (SubstrateTransactionStatus::Ready, None),
+                (SubstrateTransactionStatus::Broadcast(vec![]), None),
+                (
+                    SubstrateTransactionStatus::InBlock(H256::repeat_byte(1)),
+                    None
+                ),
+                (
+                    SubstrateTransactionStatus::Finalized(H256::repeat_byte(1)),
+                    None
+                ),
+            ]
+        );
+    }
+
+    #[async_std::test]
+    async fn leaving_the_best_chain_is_reported_as_retracted() {
+        let statuses = watch_statuses(vec![
+            // Not yet in a block, so there's nothing to retract.
+            json!({ "event": "bestChainBlockIncluded", "block": null }),
+            json!({ "event": "bestChainBlockIncluded", "block": block(1) }),
+            json!({ "event": "bestChainBlockIncluded", "block": null }),
+            json!({ "event": "bestChainBlockIncluded", "block": block(2) }),
+        ])
+        .await;
+
+        assert_eq!(
+            statuses,
+            vec![
+                (SubstrateTransactionStatus::Ready, None),
+                (
+                    SubstrateTransactionStatus::InBlock(H256::repeat_byte(1)),
+                    None
+                ),
+                (
+                    SubstrateTransactionStatus::Retracted(H256::repeat_byte(1)),
+                    None
+                ),
+                (
+                    SubstrateTransactionStatus::InBlock(H256::repeat_byte(2)),
+                    None
+                ),
+            ]
+        );
+    }
+
+    #[async_std::test]
+    async fn failures_are_reported_with_their_reasons() {
+        let statuses = watch_statuses(vec![
+            json!({ "event": "invalid", "error": "bad nonce" }),
+            json!({ "event": "dropped", "broadcasted": true, "error": "pool full" }),
+            json!({ "event": "error", "error": "too many watchers" }),
+        ])
+        .await;
+
+        assert_eq!(
+            statuses,
+            vec![
+                (
+                    SubstrateTransactionStatus::Invalid,
+                    Some("bad nonce".into())
+                ),
+                (
+                    SubstrateTransactionStatus::Dropped,
+                    Some("pool full".into())
+                ),
+                (
+                    SubstrateTransactionStatus::Dropped,
+                    Some("too many watchers".into())
+                ),
+            ]
+        );
+    }
+
+    #[async_std::test]
+    async fn replaced_transactions_fail_with_the_replacement_hash() {
+        let extrinsic = ExtrinsicMetadata {
+            ty: meta_type::<()>(),
+            version: 4,
+            signed_extensions: vec![],
+        };
+        let metadata =
+            RuntimeMetadataLastVersion::new(vec![], extrinsic, meta_type::<()>());
+        let mock = MockRpcClient::new()
+            .with_chain(metadata.into())
+            .add_subscription(
+                "author_submitAndWatchExtrinsic",
+                vec![json!("ready"), json!({ "usurped": H256::repeat_byte(7) })],
+            );
+        let client: Client<DefaultConfig> =
+            ClientBuilder::new().set_client(mock).build().await.unwrap();
+        let sub = client.rpc().watch_extrinsic(Encoded(vec![])).await.unwrap();
+
+        let res = TransactionProgress::<_, (), ()>::new(sub, &client, H256::zero())
+            .wait_for_finalized()
+            .await;
+        assert!(matches!(
+            res,
+            Err(BasicError::Transaction(TransactionError::Usurped(hash)))
+                if hash == H256::repeat_byte(7)
+        ));
+    }
+
+    #[test]
+    fn replacement_hashes_of_the_wrong_length_are_an_error() {
+        assert!(matches!(
+            usurped(H256::repeat_byte(7)),
+            BasicError::Transaction(TransactionError::Usurped(hash))
+                if hash == H256::repeat_byte(7)
+        ));
+        assert!(matches!(
+            usurped([7u8; 20]),
+            BasicError::Other(msg) if msg.contains("20 bytes long")
+        ));
+    }
+
+    #[test]
+    fn failure_reasons_are_part_of_the_error() {
+        assert_eq!(
+            TransactionError::Invalid(Some("bad nonce".into())).to_string(),
+            "The transaction is no longer valid (reason: bad nonce)"
+        );
+        assert_eq!(
+            TransactionError::Dropped(None).to_string(),
+            "The transaction was dropped from the transaction pool (reason: none given)"
+        );
+    }
+
+    #[test]
+    fn actual_fee_refunds_unused_weight() {
+        let estimate = InclusionFee {
+            base_fee: 100,
+            len_fee: 10,
+            adjusted_weight_fee: 1000,
+        };
+        let info = |weight, pays_fee| {
+            DispatchInfo {
+                weight,
+                class: DispatchClass::Normal,
+                pays_fee,
+            }
+        };
+
+        assert_eq!(info(200, Pays::Yes).actual_fee(&estimate, 200, 5), 1115);
+        assert_eq!(info(50, Pays::Yes).actual_fee(&estimate, 200, 5), 365);
+        assert_eq!(info(50, Pays::No).actual_fee(&estimate, 200, 5), 5);
+    }
+
+    #[test]
+    fn dispatch_info_encoded_len() {
+        let info = DispatchInfo {
+            weight: u64::MAX,
+            class: DispatchClass::Mandatory,
+            pays_fee: Pays::No,
+        };
+        assert_eq!(info.encode().len(), DispatchInfo::ENCODED_LEN);
+    }
+}
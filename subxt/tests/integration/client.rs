@@ -18,13 +18,47 @@ use crate::{
     test_node_process,
     test_node_process_with,
     utils::node_runtime::system,
+    ConnectionProxy,
 };
 
+use futures::StreamExt;
 use sp_core::storage::{
     well_known_keys,
     StorageKey,
 };
 use sp_keyring::AccountKeyring;
+use std::{
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time::Duration,
+};
+use subxt::{
+    babe,
+    rpc::{
+        recording_ws_client,
+        rpc_params,
+        ClientT,
+        FollowEvent,
+        JsonValue,
+        RpcError,
+        SubscriptionClientT,
+        WsClientConfig,
+    },
+    BasicError,
+    ChainType,
+    ClientBuilder,
+    ConnectionEvent,
+    DefaultConfig,
+    ExponentialBackoff,
+    HeaderExt,
+    LoadBalancedClient,
+    MockRpcClient,
+    ReconnectingClient,
+    RetryPolicy,
+};
+use tempdir::TempDir;
 
 #[async_std::test]
 async fn insert_key() {
@@ -47,6 +81,122 @@ async fn insert_key() {
         .unwrap());
 }
 
+#[async_std::test]
+async fn fail_over_to_reachable_endpoint() {
+    let node_process = test_node_process().await;
+    let client = ClientBuilder::new()
+        .set_urls(["ws://127.0.0.1:1", node_process.ws_url()])
+        .build::<DefaultConfig>()
+        .await
+        .unwrap();
+    let block_hash = client.rpc().block_hash(None).await.unwrap();
+    assert!(block_hash.is_some());
+}
+
+#[async_std::test]
+async fn balance_reads_across_connections() {
+    let node_process = test_node_process().await;
+    let ws_url = node_process.ws_url();
+    let balanced = LoadBalancedClient::connect([ws_url, ws_url]).await.unwrap();
+    let client = ClientBuilder::new()
+        .set_client(balanced)
+        .build::<DefaultConfig>()
+        .await
+        .unwrap();
+    for _ in 0..4 {
+        let block_hash = client.rpc().block_hash(None).await.unwrap();
+        client.rpc().block(block_hash).await.unwrap();
+    }
+    let mut blocks = client.rpc().subscribe_blocks().await.unwrap();
+    blocks.next().await.unwrap().unwrap();
+}
+
+#[async_std::test]
+async fn requests_time_out() {
+    let node_process = test_node_process().await;
+    let result = ClientBuilder::new()
+        .set_url(node_process.ws_url())
+        .set_request_timeout(Duration::from_nanos(1))
+        .build::<DefaultConfig>()
+        .await;
+    assert!(matches!(
+        result,
+        Err(BasicError::Rpc(RpcError::RequestTimeout))
+    ));
+}
+
+#[async_std::test]
+async fn connect_with_custom_headers() {
+    let node_process = test_node_process().await;
+    let client = ClientBuilder::new()
+        .set_url(node_process.ws_url())
+        .set_header("X-Api-Key", "secret")
+        .set_bearer_token("token")
+        .build::<DefaultConfig>()
+        .await
+        .unwrap();
+    client.rpc().block_hash(None).await.unwrap();
+}
+
+#[async_std::test]
+async fn rate_limit_requests() {
+    let node_process = test_node_process().await;
+    let client = ClientBuilder::new()
+        .set_url(node_process.ws_url())
+        .set_rate_limit(20, 5)
+        .build::<DefaultConfig>()
+        .await
+        .unwrap();
+    for _ in 0..10 {
+        client.rpc().block_hash(None).await.unwrap();
+    }
+}
+
+#[async_std::test]
+async fn record_and_replay() {
+    let node_process = test_node_process().await;
+    let dir = TempDir::new("subxt-record-and-replay").unwrap();
+    let path = dir.path().join("recording.jsonl");
+    let recording =
+        recording_ws_client(node_process.ws_url(), &WsClientConfig::default(), &path)
+            .await
+            .unwrap();
+    let client = ClientBuilder::new()
+        .set_client(recording)
+        .build::<DefaultConfig>()
+        .await
+        .unwrap();
+    let hash = client.rpc().block_hash(Some(0u32.into())).await.unwrap();
+    drop(client);
+
+    // The same calls are answered from the recording, without the node.
+    let replay = MockRpcClient::from_recording(&path).unwrap();
+    let client = ClientBuilder::new()
+        .set_client(replay)
+        .build::<DefaultConfig>()
+        .await
+        .unwrap();
+    assert_eq!(
+        client.rpc().block_hash(Some(0u32.into())).await.unwrap(),
+        hash
+    );
+}
+
+#[async_std::test]
+async fn retry_transient_failures() {
+    let node_process = test_node_process().await;
+    let client = ClientBuilder::new()
+        .set_url(node_process.ws_url())
+        .set_retry_policy(RetryPolicy::default())
+        .set_rate_limit(20, 5)
+        .build::<DefaultConfig>()
+        .await
+        .unwrap();
+    for _ in 0..10 {
+        client.rpc().block_hash(None).await.unwrap();
+    }
+}
+
 #[async_std::test]
 async fn fetch_block_hash() {
     let node_process = test_node_process().await;
@@ -79,6 +229,304 @@ async fn fetch_read_proof() {
         .unwrap();
 }
 
+#[async_std::test]
+async fn fetch_in_batches() {
+    let node_process = test_node_process().await;
+    let client = node_process.client();
+
+    let hashes = client
+        .rpc()
+        .block_hashes(vec![0u32.into(), 1u32.into()])
+        .await
+        .unwrap();
+    assert_eq!(hashes.len(), 2);
+    assert_eq!(hashes[0], Some(*client.genesis()));
+    let hashes: Vec<_> = hashes.into_iter().flatten().collect();
+
+    let headers = client.rpc().headers(&hashes).await.unwrap();
+    assert_eq!(headers.len(), hashes.len());
+    assert!(headers.iter().all(Option::is_some));
+
+    let keys = vec![
+        StorageKey(well_known_keys::HEAP_PAGES.to_vec()),
+        StorageKey(well_known_keys::CODE.to_vec()),
+    ];
+    let values = client
+        .rpc()
+        .storage_values(&keys, Some(hashes[0]))
+        .await
+        .unwrap();
+    assert_eq!(values.len(), 2);
+    assert!(values[1].is_some());
+}
+
+#[async_std::test]
+async fn raw_rpc_request() {
+    let node_process = test_node_process().await;
+    let client = node_process.client();
+    let chain: String = client
+        .rpc()
+        .request("system_chain", rpc_params![])
+        .await
+        .unwrap();
+    assert_eq!(chain, client.rpc().system_chain().await.unwrap());
+}
+
+#[cfg(feature = "light-client")]
+#[async_std::test]
+async fn light_client_from_chain_spec() {
+    let node_process = test_node_process().await;
+    let node_client = node_process.client();
+
+    // The dev chain spec has no boot nodes, so point the light client at the test node.
+    let output = std::process::Command::new(crate::utils::test_node_path())
+        .args(["build-spec", "--dev", "--raw"])
+        .output()
+        .unwrap();
+    let mut chain_spec: JsonValue = serde_json::from_slice(&output.stdout).unwrap();
+    let listen_addresses: Vec<String> = node_client
+        .rpc()
+        .request("system_localListenAddresses", rpc_params![])
+        .await
+        .unwrap();
+    chain_spec["bootNodes"] = listen_addresses
+        .into_iter()
+        .filter(|address| address.starts_with("/ip4/127.0.0.1/tcp/"))
+        .collect();
+
+    let light_client = subxt::rpc::light_client(&chain_spec.to_string()).unwrap();
+    let client = ClientBuilder::new()
+        .set_client(light_client)
+        .build::<DefaultConfig>()
+        .await
+        .unwrap();
+    assert_eq!(client.genesis(), node_client.genesis());
+    // The metadata and runtime version were fetched through the light client.
+    assert_eq!(
+        client.rpc().runtime_version(None).await.unwrap(),
+        node_client.rpc().runtime_version(None).await.unwrap()
+    );
+    client.metadata().pallet("System").unwrap();
+}
+
+#[async_std::test]
+async fn raw_rpc_subscription() {
+    let node_process = test_node_process().await;
+    let client = node_process.client();
+    let mut sub = client
+        .rpc()
+        .subscribe::<<DefaultConfig as subxt::Config>::Header>(
+            "chain_subscribeAllHeads",
+            rpc_params![],
+            "chain_unsubscribeAllHeads",
+        )
+        .await
+        .unwrap();
+    sub.next().await.unwrap().unwrap();
+}
+
+#[async_std::test]
+async fn connection_events_are_reported() {
+    let mut node_process = test_node_process().await;
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let backoff = ExponentialBackoff {
+        initial_delay: Duration::from_millis(10),
+        max_delay: Duration::from_millis(10),
+        max_attempts: Some(2),
+    };
+    let client = ReconnectingClient::new(
+        node_process.ws_url(),
+        WsClientConfig::default(),
+        backoff,
+    )
+    .await
+    .unwrap()
+    .on_connection_event({
+        let events = events.clone();
+        move |event| events.lock().unwrap().push(event.clone())
+    });
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![ConnectionEvent::Connected {
+            endpoint: node_process.ws_url().to_string(),
+        }]
+    );
+
+    node_process.kill().unwrap();
+    let res = client
+        .request::<JsonValue>("system_health", rpc_params![])
+        .await;
+    assert!(res.is_err());
+    let events = events.lock().unwrap();
+    assert!(matches!(events[1], ConnectionEvent::Disconnected { .. }));
+    assert_eq!(events[2], ConnectionEvent::Reconnecting { attempt: 1 });
+    assert!(matches!(
+        events.last(),
+        Some(ConnectionEvent::GaveUp { .. })
+    ));
+}
+
+#[async_std::test]
+async fn disconnects_are_reported_without_making_calls() {
+    let node_process = test_node_process().await;
+    let proxy = ConnectionProxy::start(node_process.ws_url()).await;
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let client = ReconnectingClient::connect(proxy.ws_url())
+        .await
+        .unwrap()
+        .on_connection_event({
+            let events = events.clone();
+            move |event| events.lock().unwrap().push(event.clone())
+        });
+
+    proxy.drop_connections();
+
+    for _ in 0..100 {
+        if events.lock().unwrap().len() > 1 {
+            break
+        }
+        async_std::task::sleep(Duration::from_millis(50)).await;
+    }
+    assert!(matches!(
+        events.lock().unwrap()[..],
+        [
+            ConnectionEvent::Connected { .. },
+            ConnectionEvent::Disconnected { .. }
+        ]
+    ));
+    assert!(!client.is_connected());
+
+    // The loss isn't reported again when the next call reconnects.
+    let _: String = client.request("system_chain", rpc_params![]).await.unwrap();
+    let events = events.lock().unwrap();
+    assert!(matches!(
+        events[2],
+        ConnectionEvent::Reconnecting { attempt: 1 }
+    ));
+    assert!(matches!(events[3], ConnectionEvent::Connected { .. }));
+}
+
+#[async_std::test]
+async fn reconnecting_client_retries_read_only_calls() {
+    let node_process = test_node_process().await;
+    let proxy = ConnectionProxy::start(node_process.ws_url()).await;
+    let client = ReconnectingClient::connect(proxy.ws_url()).await.unwrap();
+    let chain: String = client.request("system_chain", rpc_params![]).await.unwrap();
+    let mut sub = client
+        .subscribe::<JsonValue>(
+            "chain_subscribeNewHeads",
+            rpc_params![],
+            "chain_unsubscribeNewHeads",
+        )
+        .await
+        .unwrap();
+
+    proxy.drop_connections();
+
+    // The subscription ends rather than being re-established...
+    while let Some(Ok(_)) = sub.next().await {}
+    // ...but requests reconnect, and are retried if they only read from the node.
+    let res: String = client.request("system_chain", rpc_params![]).await.unwrap();
+    assert_eq!(res, chain);
+    assert_eq!(client.reconnects(), 1);
+    assert!(client.is_connected());
+}
+
+#[async_std::test]
+async fn reconnecting_client_moves_back_to_the_primary_endpoint() {
+    let node_process = test_node_process().await;
+    let primary = ConnectionProxy::start(node_process.ws_url()).await;
+    let client = ReconnectingClient::with_endpoints(
+        vec![
+            primary.ws_url().to_string(),
+            node_process.ws_url().to_string(),
+        ],
+        WsClientConfig::default(),
+        ExponentialBackoff::default(),
+    )
+    .await
+    .unwrap()
+    .primary_check_interval(Duration::from_millis(100));
+
+    // Losing the primary endpoint fails over to the other one...
+    primary.refuse_connections(true);
+    primary.drop_connections();
+    let _: String = client.request("system_chain", rpc_params![]).await.unwrap();
+    assert_eq!(client.endpoint(), node_process.ws_url());
+
+    // ...and once it's reachable again, requests carry on while it's checked, after which
+    // we move back to it.
+    primary.refuse_connections(false);
+    for _ in 0..100 {
+        if client.endpoint() == primary.ws_url() {
+            break
+        }
+        async_std::task::sleep(Duration::from_millis(50)).await;
+        let _: String = client.request("system_chain", rpc_params![]).await.unwrap();
+    }
+    assert_eq!(client.endpoint(), primary.ws_url());
+    assert_eq!(client.reconnects(), 2);
+}
+
+#[async_std::test]
+async fn chain_head_backend_follows_afresh_after_reconnecting() {
+    let node_process = test_node_process().await;
+    let methods: JsonValue = node_process
+        .client()
+        .rpc()
+        .request("rpc_methods", rpc_params![])
+        .await
+        .unwrap();
+    let supported = methods["methods"].as_array().map_or(false, |methods| {
+        methods.contains(&"chainHead_unstable_follow".into())
+    });
+    if !supported {
+        // Older nodes don't serve the methods of the new JSON-RPC spec.
+        log::warn!("Skipping: the test node doesn't support chainHead_unstable_follow");
+        return
+    }
+
+    let proxy = ConnectionProxy::start(node_process.ws_url()).await;
+    let client = ClientBuilder::new()
+        .set_url(proxy.ws_url())
+        .set_reconnect(ExponentialBackoff::default())
+        .set_chain_head_backend(true)
+        .build::<DefaultConfig>()
+        .await
+        .unwrap();
+    assert_eq!(client.genesis(), node_process.client().genesis());
+    let pallets = |client: &subxt::Client<DefaultConfig>| {
+        let runtime_metadata = client.metadata().runtime_metadata();
+        runtime_metadata
+            .pallets
+            .iter()
+            .map(|pallet| pallet.name.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(pallets(&client), pallets(node_process.client()));
+
+    let mut backend = client.rpc().chain_head(false).await.unwrap();
+    backend.next_event().await.unwrap();
+    proxy.drop_connections();
+
+    // The subscription ends, which is reported as the node no longer following the chain
+    // for us, and then the chain is followed afresh over the new connection.
+    loop {
+        match backend.next_event().await.unwrap() {
+            FollowEvent::Stop => break,
+            _ => continue,
+        }
+    }
+    let finalized = match backend.next_event().await.unwrap() {
+        FollowEvent::Initialized {
+            finalized_block_hash,
+            ..
+        } => finalized_block_hash,
+        event => panic!("Expected an initialized event, got {:?}", event),
+    };
+    assert!(backend.header(finalized).await.unwrap().is_some());
+}
+
 #[async_std::test]
 async fn chain_subscribe_blocks() {
     let node_process = test_node_process().await;
@@ -95,6 +543,34 @@ async fn chain_subscribe_finalized_blocks() {
     blocks.next().await.unwrap().unwrap();
 }
 
+#[async_std::test]
+async fn chain_subscribe_headers() {
+    let node_process = test_node_process().await;
+    let client = node_process.client();
+    let mut best = client.blocks().subscribe_best_headers().await.unwrap();
+    let header = best.next().await.unwrap().unwrap();
+    assert!(header.pre_runtime_digest(*b"BABE").is_some());
+    let mut finalized = client.blocks().subscribe_finalized_headers().await.unwrap();
+    finalized.next().await.unwrap().unwrap();
+}
+
+#[async_std::test]
+async fn chain_subscribe_all_finalized_headers() {
+    let node_process = test_node_process().await;
+    let client = node_process.client();
+    let headers = client
+        .blocks()
+        .subscribe_all_finalized_headers()
+        .await
+        .unwrap();
+    let numbers: Vec<_> = headers
+        .take(3)
+        .map(|header| header.unwrap().block_number())
+        .collect()
+        .await;
+    assert_eq!(numbers, vec![numbers[0], numbers[0] + 1, numbers[0] + 2]);
+}
+
 #[async_std::test]
 async fn fetch_keys() {
     let node_process = test_node_process().await;
@@ -123,6 +599,29 @@ async fn test_iter() {
     assert_eq!(i, 13);
 }
 
+#[async_std::test]
+async fn call_runtime_api() {
+    let node_process = test_node_process().await;
+    let client = node_process.client();
+    let alice = AccountKeyring::Alice.to_account_id();
+    let nonce: u32 = client
+        .runtime_api()
+        .call("AccountNonceApi_account_nonce", &alice, None)
+        .await
+        .unwrap();
+    assert_eq!(nonce, 0);
+}
+
+#[async_std::test]
+async fn fetch_babe_epoch() {
+    let node_process = test_node_process().await;
+    let client = node_process.client();
+    let epoch = babe::current_epoch(client.rpc(), None).await.unwrap();
+    assert!(!epoch.authorities.is_empty());
+    assert!(epoch.end_slot() > epoch.start_slot);
+    client.rpc().epoch_authorship().await.unwrap();
+}
+
 #[async_std::test]
 async fn fetch_system_info() {
     let node_process = test_node_process().await;
@@ -130,4 +629,23 @@ async fn fetch_system_info() {
     assert_eq!(client.rpc().system_chain().await.unwrap(), "Development");
     assert_eq!(client.rpc().system_name().await.unwrap(), "Substrate Node");
     assert!(!client.rpc().system_version().await.unwrap().is_empty());
+    assert_eq!(
+        client.rpc().system_chain_type().await.unwrap(),
+        ChainType::Development
+    );
+    assert!(
+        !client
+            .rpc()
+            .system_health()
+            .await
+            .unwrap()
+            .should_have_peers
+    );
+    assert!(!client
+        .rpc()
+        .system_local_peer_id()
+        .await
+        .unwrap()
+        .is_empty());
+    client.rpc().system_sync_state().await.unwrap();
 }
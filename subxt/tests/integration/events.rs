@@ -16,15 +16,25 @@
 
 use crate::{
     node_runtime::{
+        self,
         balances,
         system,
     },
     pair_signer,
     test_context,
+    test_node_process,
+    ConnectionProxy,
+    NodeRuntimeSignedExtra,
 };
 use futures::StreamExt;
 use sp_keyring::AccountKeyring;
-use subxt::Signer;
+use std::time::Duration;
+use subxt::{
+    ClientBuilder,
+    DefaultConfig,
+    ReconnectingClient,
+    Signer,
+};
 
 // Check that we can subscribe to non-finalized block events.
 #[async_std::test]
@@ -137,3 +147,483 @@ async fn balance_transfer_subscription() -> Result<(), subxt::BasicError> {
 
     Ok(())
 }
+
+// Check that we can subscribe to individual, statically decoded events.
+#[async_std::test]
+async fn decoded_event_subscription() -> Result<(), subxt::BasicError> {
+    env_logger::try_init().ok();
+    let ctx = test_context().await;
+
+    let mut event_sub = ctx.api.events().subscribe().await?.decoded();
+
+    let alice = pair_signer(AccountKeyring::Alice.pair());
+    let bob = AccountKeyring::Bob.to_account_id();
+    ctx.api
+        .tx()
+        .balances()
+        .transfer(bob.clone().into(), 10_000)
+        .sign_and_submit_then_watch(&alice)
+        .await?;
+
+    // Wait for the transfer to show up in the stream of decoded events:
+    loop {
+        let (_block_hash, details) = event_sub.next().await.unwrap()?;
+        if let node_runtime::Event::Balances(balances::Event::Transfer {
+            from,
+            to,
+            amount,
+        }) = details.event
+        {
+            assert_eq!(&from, alice.account_id());
+            assert_eq!(to, bob);
+            assert_eq!(amount, 10_000);
+            break
+        }
+    }
+
+    Ok(())
+}
+
+// Check that only events from the pallets we filter on are handed back.
+#[async_std::test]
+async fn pallet_filtered_subscription() -> Result<(), subxt::BasicError> {
+    env_logger::try_init().ok();
+    let ctx = test_context().await;
+
+    let mut event_sub = ctx
+        .api
+        .events()
+        .subscribe()
+        .await?
+        .filter_pallet("Balances")
+        .decoded();
+
+    let alice = pair_signer(AccountKeyring::Alice.pair());
+    ctx.api
+        .tx()
+        .balances()
+        .transfer(AccountKeyring::Bob.to_account_id().into(), 10_000)
+        .sign_and_submit_then_watch(&alice)
+        .await?;
+
+    // We'd otherwise see System events here (e.g. ExtrinsicSuccess) first:
+    let (_block_hash, details) = event_sub.next().await.unwrap()?;
+    assert!(matches!(details.event, node_runtime::Event::Balances(_)));
+
+    Ok(())
+}
+
+// Check that we only see events which reference the account we're filtering on.
+#[async_std::test]
+async fn account_filtered_subscription() -> Result<(), subxt::BasicError> {
+    env_logger::try_init().ok();
+    let ctx = test_context().await;
+
+    let charlie = AccountKeyring::Charlie.to_account_id();
+    let mut event_sub = ctx
+        .api
+        .events()
+        .subscribe()
+        .await?
+        .filter_account(charlie.clone())
+        .decoded();
+
+    let alice = pair_signer(AccountKeyring::Alice.pair());
+    ctx.api
+        .tx()
+        .balances()
+        .transfer(charlie.clone().into(), 10_000)
+        .sign_and_submit_then_watch(&alice)
+        .await?;
+
+    // System events like `ExtrinsicSuccess` don't mention Charlie, so shouldn't show up:
+    loop {
+        let (_block_hash, details) = event_sub.next().await.unwrap()?;
+        match details.event {
+            node_runtime::Event::Balances(balances::Event::Transfer { to, .. }) => {
+                assert_eq!(to, charlie);
+                break
+            }
+            node_runtime::Event::System(system::Event::ExtrinsicSuccess { .. }) => {
+                panic!("unexpected event: {:?}", details.event)
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+// Check that we can look for several types of event at once in a subscription.
+#[async_std::test]
+async fn multiple_event_filter_subscription() -> Result<(), subxt::BasicError> {
+    env_logger::try_init().ok();
+    let ctx = test_context().await;
+
+    let mut event_sub = ctx
+        .api
+        .events()
+        .subscribe()
+        .await?
+        .filter_events::<(balances::events::Withdraw, balances::events::Transfer)>();
+
+    let alice = pair_signer(AccountKeyring::Alice.pair());
+    let bob = AccountKeyring::Bob.to_account_id();
+    ctx.api
+        .tx()
+        .balances()
+        .transfer(bob.clone().into(), 10_000)
+        .sign_and_submit_then_watch(&alice)
+        .await?;
+
+    // The fee is withdrawn first, and then the transfer happens:
+    let (_, withdraw) = event_sub.next().await.unwrap()?;
+    assert!(matches!(withdraw.event, (Some(_), None)));
+    let (_, transfer) = event_sub.next().await.unwrap()?;
+    assert_eq!(
+        transfer.event,
+        (
+            None,
+            Some(balances::events::Transfer {
+                from: alice.account_id().clone(),
+                to: bob,
+                amount: 10_000,
+            })
+        )
+    );
+
+    Ok(())
+}
+
+// Check that we can fetch and decode the events from some earlier block.
+#[async_std::test]
+async fn events_at_historical_block() -> Result<(), subxt::BasicError> {
+    env_logger::try_init().ok();
+    let ctx = test_context().await;
+
+    let alice = pair_signer(AccountKeyring::Alice.pair());
+    let bob = AccountKeyring::Bob.to_account_id();
+    let tx_events = ctx
+        .api
+        .tx()
+        .balances()
+        .transfer(bob.clone().into(), 10_000)
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized_success()
+        .await?;
+
+    let events = ctx
+        .client()
+        .events_at::<node_runtime::Event>(tx_events.block_hash())
+        .await?;
+    assert_eq!(events.block_hash(), tx_events.block_hash());
+
+    let transfer = events
+        .iter()
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .find(|ev| matches!(ev.event, node_runtime::Event::Balances(_)))
+        .expect("balances event expected");
+    assert!(matches!(transfer.phase, subxt::Phase::ApplyExtrinsic(_)));
+
+    Ok(())
+}
+
+// Check that we can wait for a specific event to show up.
+#[async_std::test]
+async fn wait_for_matching_event() -> Result<(), subxt::BasicError> {
+    env_logger::try_init().ok();
+    let ctx = test_context().await;
+
+    let alice = pair_signer(AccountKeyring::Alice.pair());
+    let bob = AccountKeyring::Bob.to_account_id();
+    let wait_for_transfer = ctx.client().wait_for_event::<balances::events::Transfer>(
+        |ev| ev.to == bob && ev.amount == 12_345,
+        Duration::from_secs(30),
+    );
+    let transfer = ctx
+        .api
+        .tx()
+        .balances()
+        .transfer(bob.clone().into(), 12_345)
+        .sign_and_submit(&alice);
+
+    let (found, submitted) = futures::join!(wait_for_transfer, transfer);
+    submitted?;
+    let (_block_hash, details) = found?;
+    assert_eq!(&details.event.from, alice.account_id());
+    assert!(details.extrinsic_index().is_some());
+
+    Ok(())
+}
+
+// Check that waiting for an event which never happens times out.
+#[async_std::test]
+async fn wait_for_event_times_out() {
+    env_logger::try_init().ok();
+    let ctx = test_context().await;
+
+    let res = ctx
+        .client()
+        .wait_for_event::<balances::events::Transfer>(|_| true, Duration::from_secs(1))
+        .await;
+    assert!(matches!(
+        res,
+        Err(subxt::GenericError::Transaction(
+            subxt::TransactionError::Timeout { last_status: None }
+        ))
+    ));
+}
+
+// Check that a resilient subscription hands back events for every block, in order, even
+// when the connection to the node drops partway through.
+#[async_std::test]
+async fn resilient_subscription_has_no_gaps() -> Result<(), subxt::BasicError> {
+    use sp_runtime::traits::Header;
+
+    env_logger::try_init().ok();
+    let node_process = test_node_process().await;
+    let proxy = ConnectionProxy::start(node_process.ws_url()).await;
+    let client = ClientBuilder::new()
+        .set_client(ReconnectingClient::connect(proxy.ws_url()).await?)
+        .build::<DefaultConfig>()
+        .await?;
+    let api = client
+        .clone()
+        .to_runtime_api::<node_runtime::RuntimeApi<DefaultConfig, NodeRuntimeSignedExtra>>();
+
+    let mut event_sub = api.events().subscribe_finalized().await?.resilient(3);
+
+    let mut numbers = Vec::new();
+    for i in 0..6 {
+        if i == 2 {
+            proxy.drop_connections();
+            // Give the node time to finalize blocks while we're disconnected.
+            async_std::task::sleep(Duration::from_secs(8)).await;
+        }
+        let events = event_sub.next().await.unwrap()?;
+        let header = client
+            .rpc()
+            .header(Some(events.block_hash()))
+            .await?
+            .expect("header should exist");
+        numbers.push(*header.number());
+    }
+
+    let expected: Vec<_> = (numbers[0]..numbers[0] + 6).collect();
+    assert_eq!(numbers, expected);
+    Ok(())
+}
+
+// Check that the metrics of a subscription keep track of the blocks handed back.
+#[async_std::test]
+async fn subscription_metrics_track_blocks() -> Result<(), subxt::BasicError> {
+    env_logger::try_init().ok();
+    let ctx = test_context().await;
+
+    let mut event_sub = ctx.api.events().subscribe_finalized().await?.resilient(3);
+    let metrics = event_sub.metrics();
+    assert_eq!(metrics.last_block_number(), None);
+
+    for _ in 0..2 {
+        event_sub.next().await.unwrap()?;
+    }
+
+    let head = metrics.head_block_number().expect("a block has been seen");
+    let last = metrics
+        .last_block_number()
+        .expect("a block has been handed back");
+    assert!(head >= last);
+    assert_eq!(metrics.lag(), head - last);
+    assert_eq!(metrics.resubscribes(), 0);
+    assert_eq!(metrics.dropped_blocks(), 0);
+
+    Ok(())
+}
+
+// Check that an event indexer hands events from historical blocks to the
+// handlers registered for them.
+#[async_std::test]
+async fn event_indexer_handles_historical_events() -> Result<(), subxt::BasicError> {
+    use sp_runtime::traits::Header;
+    use std::{
+        cell::RefCell,
+        rc::Rc,
+    };
+
+    env_logger::try_init().ok();
+    let ctx = test_context().await;
+    let alice = pair_signer(AccountKeyring::Alice.pair());
+    let bob = AccountKeyring::Bob.to_account_id();
+
+    let tx_events = ctx
+        .api
+        .tx()
+        .balances()
+        .transfer(bob.clone().into(), 10_000)
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized_success()
+        .await?;
+    let block_number = *ctx
+        .client()
+        .rpc()
+        .header(Some(tx_events.block_hash()))
+        .await?
+        .expect("header should exist")
+        .number() as u64;
+
+    let transfers = Rc::new(RefCell::new(Vec::new()));
+    let handled = transfers.clone();
+    ctx.client()
+        .event_indexer()
+        .from_block(block_number)
+        .to_block(block_number)
+        .on::<balances::events::Transfer, _, _>(move |transfer, ctx| {
+            let handled = handled.clone();
+            async move {
+                handled
+                    .borrow_mut()
+                    .push((ctx.block_number, ctx.block_hash, transfer));
+                Ok(())
+            }
+        })
+        .run()
+        .await?;
+
+    let transfers = transfers.borrow();
+    assert_eq!(transfers.len(), 1);
+    let (number, hash, transfer) = &transfers[0];
+    assert_eq!(*number, block_number);
+    assert_eq!(*hash, tx_events.block_hash());
+    assert_eq!(transfer.from, alice.account_id().clone());
+    assert_eq!(transfer.to, bob);
+    assert_eq!(transfer.amount, 10_000);
+
+    Ok(())
+}
+
+// Check that we can fetch the events of a range of blocks, in order.
+#[async_std::test]
+async fn events_between_blocks() -> Result<(), subxt::BasicError> {
+    use sp_runtime::traits::Header;
+
+    env_logger::try_init().ok();
+    let ctx = test_context().await;
+
+    let alice = pair_signer(AccountKeyring::Alice.pair());
+    let bob = AccountKeyring::Bob.to_account_id();
+    let tx_events = ctx
+        .api
+        .tx()
+        .balances()
+        .transfer(bob.into(), 10_000)
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized_success()
+        .await?;
+    let block_number = *ctx
+        .client()
+        .rpc()
+        .header(Some(tx_events.block_hash()))
+        .await?
+        .expect("header should exist")
+        .number() as u64;
+
+    let all_events = ctx
+        .api
+        .events()
+        .between(0, block_number)
+        .max_concurrent_requests(4)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(all_events.len() as u64, block_number + 1);
+
+    // The transfer is in the last block we asked for:
+    let last = all_events.last().unwrap();
+    assert_eq!(last.block_hash(), tx_events.block_hash());
+    assert!(last.has::<balances::events::Transfer>()?);
+
+    // Blocks are handed back in order:
+    for (n, events) in all_events.iter().enumerate() {
+        let hash = ctx
+            .client()
+            .rpc()
+            .block_hash(Some((n as u32).into()))
+            .await?;
+        assert_eq!(Some(events.block_hash()), hash);
+    }
+
+    Ok(())
+}
+
+// Check that we can follow the events in the best chain.
+#[async_std::test]
+async fn best_chain_subscription() -> Result<(), subxt::BasicError> {
+    use subxt::events::BestChainEvent;
+
+    env_logger::try_init().ok();
+    let ctx = test_context().await;
+
+    let mut event_sub = ctx.api.events().subscribe_best_chain().await?;
+
+    // With a single node there are no forks, so blocks are only ever imported:
+    let mut last_hash = None;
+    for _ in 0..2 {
+        match event_sub.next().await.unwrap()? {
+            BestChainEvent::Imported(events) => {
+                let block_hash = events.block_hash();
+                let header = ctx
+                    .client()
+                    .rpc()
+                    .header(Some(block_hash))
+                    .await?
+                    .expect("header should exist");
+                if let Some(last_hash) = last_hash {
+                    assert_eq!(header.parent_hash, last_hash);
+                }
+                last_hash = Some(block_hash);
+            }
+            BestChainEvent::Retracted { block_hash } => {
+                panic!("unexpected retraction of {:?}", block_hash)
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Check that we can count the events of each kind in a block.
+#[async_std::test]
+async fn event_summary_at_block() -> Result<(), subxt::BasicError> {
+    env_logger::try_init().ok();
+    let ctx = test_context().await;
+
+    let alice = pair_signer(AccountKeyring::Alice.pair());
+    let bob = AccountKeyring::Bob.to_account_id();
+    let tx_events = ctx
+        .api
+        .tx()
+        .balances()
+        .transfer(bob.into(), 10_000)
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized_success()
+        .await?;
+
+    let summary = ctx
+        .client()
+        .event_summary_at(tx_events.block_hash())
+        .await?;
+    assert_eq!(summary.count("Balances", "Transfer"), 1);
+    assert!(summary.count("System", "ExtrinsicSuccess") >= 1);
+    assert_eq!(
+        summary.total(),
+        ctx.api.events().at(tx_events.block_hash()).await?.len()
+    );
+
+    Ok(())
+}
@@ -16,6 +16,7 @@
 
 use crate::{
     node_runtime::{
+        self,
         balances,
         runtime_types,
         system,
@@ -24,15 +25,22 @@ use crate::{
     pair_signer,
     test_context,
 };
-use codec::Decode;
+use codec::{
+    Decode,
+    Encode,
+};
 use sp_core::{
     sr25519::Pair,
     Pair as _,
 };
 use sp_keyring::AccountKeyring;
 use subxt::{
+    BasicError,
+    DefaultExtraParams,
+    DryRunResult,
     Error,
     Signer,
+    TransactionError,
 };
 
 #[async_std::test]
@@ -94,6 +102,362 @@ async fn tx_basic_transfer() -> Result<(), subxt::Error<DispatchError>> {
     Ok(())
 }
 
+#[async_std::test]
+async fn tx_transfer_with_tip() -> Result<(), subxt::Error<DispatchError>> {
+    let alice = pair_signer(AccountKeyring::Alice.pair());
+    let bob_address = AccountKeyring::Bob.to_account_id().into();
+    let cxt = test_context().await;
+    let api = &cxt.api;
+
+    let tip = 1_000_000_000;
+    let alice_pre = api
+        .storage()
+        .system()
+        .account(alice.account_id().clone(), None)
+        .await?;
+
+    api.tx()
+        .balances()
+        .transfer(bob_address, 10_000)
+        .sign_and_submit_then_watch_with_params(
+            &alice,
+            DefaultExtraParams::new().tip(tip),
+        )
+        .await?
+        .wait_for_finalized_success()
+        .await?;
+
+    let alice_post = api
+        .storage()
+        .system()
+        .account(alice.account_id().clone(), None)
+        .await?;
+
+    assert!(alice_pre.data.free - 10_000 - tip >= alice_post.data.free);
+    Ok(())
+}
+
+#[async_std::test]
+async fn tx_replace_pending_transfer() -> Result<(), subxt::Error<DispatchError>> {
+    let alice = pair_signer(AccountKeyring::Alice.pair());
+    let bob = AccountKeyring::Bob.to_account_id();
+    let cxt = test_context().await;
+    let api = &cxt.api;
+
+    let nonce = api
+        .storage()
+        .system()
+        .account(alice.account_id().clone(), None)
+        .await?
+        .nonce;
+
+    let pending = api
+        .tx()
+        .balances()
+        .transfer(bob.clone().into(), 10_000)
+        .sign_and_submit_then_watch(&alice)
+        .await?;
+    // The higher tip gives the replacement a higher priority in the pool.
+    let replacement = api
+        .tx()
+        .balances()
+        .transfer(bob.into(), 20_000)
+        .replace_then_watch(&alice, nonce, DefaultExtraParams::new().tip(1_000_000_000))
+        .await?;
+
+    let res = pending.wait_for_finalized().await;
+    let events = replacement.wait_for_finalized_success().await?;
+    assert!(matches!(
+        res,
+        Err(BasicError::Transaction(TransactionError::Usurped(hash)))
+            if hash == events.extrinsic_hash()
+    ));
+    Ok(())
+}
+
+#[async_std::test]
+async fn tx_estimate_fee() -> Result<(), subxt::Error<DispatchError>> {
+    let alice = pair_signer(AccountKeyring::Alice.pair());
+    let bob_address = AccountKeyring::Bob.to_account_id().into();
+    let cxt = test_context().await;
+
+    let info = cxt
+        .api
+        .tx()
+        .balances()
+        .transfer(bob_address, 10_000)
+        .estimate_fee(&alice)
+        .await?;
+
+    assert!(info.weight > 0);
+    assert!(info.partial_fee > 0);
+    Ok(())
+}
+
+#[async_std::test]
+async fn tx_actual_fee_matches_balance_change() -> Result<(), subxt::Error<DispatchError>>
+{
+    let alice = pair_signer(AccountKeyring::Alice.pair());
+    let bob_address = AccountKeyring::Bob.to_account_id().into();
+    let cxt = test_context().await;
+    let api = &cxt.api;
+
+    let transfer = api.tx().balances().transfer(bob_address, 10_000);
+    let estimate = transfer.estimate_fee(&alice).await?;
+    let fee_details = transfer.estimate_fee_details(&alice).await?;
+
+    let alice_pre = api
+        .storage()
+        .system()
+        .account(alice.account_id().clone(), None)
+        .await?;
+    let events = transfer
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized_success()
+        .await?;
+    let alice_post = api
+        .storage()
+        .system()
+        .account(alice.account_id().clone(), None)
+        .await?;
+
+    let info = events.dispatch_info()?;
+    assert_eq!(info.pays_fee, subxt::Pays::Yes);
+    assert!(info.weight <= estimate.weight);
+
+    let actual_fee = info.actual_fee(
+        &fee_details
+            .inclusion_fee
+            .expect("signed extrinsics pay a fee"),
+        estimate.weight,
+        0,
+    );
+    assert_eq!(
+        alice_pre.data.free - alice_post.data.free,
+        10_000 + actual_fee
+    );
+    Ok(())
+}
+
+#[async_std::test]
+async fn tx_encode_call_data() -> Result<(), subxt::Error<DispatchError>> {
+    let bob_address: subxt::sp_runtime::MultiAddress<_, ()> =
+        AccountKeyring::Bob.to_account_id().into();
+    let cxt = test_context().await;
+
+    let call_data = cxt
+        .api
+        .tx()
+        .balances()
+        .transfer(bob_address.clone(), 10_000)
+        .encode_call_data()?;
+
+    let expected = runtime_types::node_runtime::Call::Balances(
+        runtime_types::pallet_balances::pallet::Call::transfer {
+            dest: bob_address,
+            value: 10_000,
+        },
+    );
+    assert_eq!(call_data, expected.encode());
+    Ok(())
+}
+
+#[async_std::test]
+async fn tx_watch_times_out() {
+    let alice = pair_signer(AccountKeyring::Alice.pair());
+    let bob_address = AccountKeyring::Bob.to_account_id().into();
+    let cxt = test_context().await;
+
+    // Blocks take far longer than this to be finalized:
+    let res = cxt
+        .api
+        .tx()
+        .balances()
+        .transfer(bob_address, 10_000)
+        .sign_and_submit_then_watch(&alice)
+        .await
+        .unwrap()
+        .with_timeout(std::time::Duration::from_millis(1))
+        .wait_for_finalized()
+        .await;
+
+    assert!(
+        matches!(
+            res,
+            Err(subxt::GenericError::Transaction(
+                subxt::TransactionError::Timeout { .. }
+            ))
+        ),
+        "expected a timeout, got {:?}",
+        res
+    );
+}
+
+#[async_std::test]
+async fn tx_monitor_in_background() -> Result<(), subxt::Error<DispatchError>> {
+    let alice = pair_signer(AccountKeyring::Alice.pair());
+    let bob_address = AccountKeyring::Bob.to_account_id().into();
+    let cxt = test_context().await;
+
+    let (monitor, task) = cxt
+        .api
+        .tx()
+        .balances()
+        .transfer(bob_address, 10_000)
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .monitor();
+    async_std::task::spawn(task);
+
+    let extrinsic_hash = monitor.extrinsic_hash();
+    let finalized = monitor.await?;
+    assert_eq!(finalized.extrinsic_hash, extrinsic_hash);
+
+    let events = finalized
+        .in_block::<DispatchError, node_runtime::Event>(cxt.client())
+        .fetch_events()
+        .await?;
+    assert!(events.has::<balances::events::Transfer>()?);
+    Ok(())
+}
+
+#[async_std::test]
+async fn tx_dry_run() -> Result<(), subxt::Error<DispatchError>> {
+    let alice = pair_signer(AccountKeyring::Alice.pair());
+    let hans = pair_signer(Pair::generate().0);
+    let bob_address: subxt::sp_runtime::MultiAddress<_, u32> =
+        AccountKeyring::Bob.to_account_id().into();
+    let cxt = test_context().await;
+
+    // Alice can afford this transfer:
+    let res = cxt
+        .api
+        .tx()
+        .balances()
+        .transfer(bob_address.clone(), 10_000)
+        .dry_run(&alice, None)
+        .await?;
+    assert!(matches!(res, DryRunResult::Success));
+
+    // Hans has no funds, and so cannot even pay the fees:
+    let res = cxt
+        .api
+        .tx()
+        .balances()
+        .transfer(bob_address, 10_000)
+        .dry_run(&hans, None)
+        .await?;
+    assert!(matches!(res, DryRunResult::TransactionValidityError(_)));
+    Ok(())
+}
+
+#[async_std::test]
+async fn tx_submit_and_watch_raw() -> Result<(), subxt::Error<DispatchError>> {
+    let alice = pair_signer(AccountKeyring::Alice.pair());
+    let bob_address = AccountKeyring::Bob.to_account_id().into();
+    let cxt = test_context().await;
+
+    let extrinsic = cxt
+        .api
+        .tx()
+        .balances()
+        .transfer(bob_address, 10_000)
+        .create_signed(&alice, Default::default())
+        .await?;
+
+    let events = cxt
+        .client()
+        .submit_and_watch_raw::<DispatchError, node_runtime::Event>(extrinsic.encode())
+        .await?
+        .wait_for_finalized_success()
+        .await?;
+
+    assert!(events.has::<balances::events::Transfer>()?);
+    Ok(())
+}
+
+#[async_std::test]
+async fn block_extrinsics_are_correlated_with_events(
+) -> Result<(), subxt::Error<DispatchError>> {
+    let alice = pair_signer(AccountKeyring::Alice.pair());
+    let bob_address = AccountKeyring::Bob.to_account_id().into();
+    let cxt = test_context().await;
+
+    let events = cxt
+        .api
+        .tx()
+        .balances()
+        .transfer(bob_address, 10_000)
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized_success()
+        .await?;
+
+    let block = cxt
+        .client()
+        .blocks()
+        .at::<node_runtime::Event>(events.block_hash())
+        .await?
+        .expect("the block is known");
+    assert_eq!(block.hash(), events.block_hash());
+
+    let extrinsics = block.extrinsics_with_events()?;
+    let extrinsic = &extrinsics[events.extrinsic_index() as usize];
+    let transfer = extrinsic
+        .details
+        .as_call::<balances::calls::Transfer>()?
+        .expect("the extrinsic is a transfer");
+    assert_eq!(transfer.value, 10_000);
+    let signature = extrinsic.details.signature.as_ref().expect("it's signed");
+    assert_eq!(signature.address, alice.account_id().clone().into());
+    assert!(extrinsic
+        .events
+        .iter()
+        .any(|event| event.pallet == "Balances" && event.variant == "Transfer"));
+    Ok(())
+}
+
+#[async_std::test]
+async fn tx_events_only_include_those_of_the_extrinsic(
+) -> Result<(), subxt::Error<DispatchError>> {
+    let alice = pair_signer(AccountKeyring::Alice.pair());
+    let bob = pair_signer(AccountKeyring::Bob.pair());
+    let charlie_address = AccountKeyring::Charlie.to_account_id().into();
+    let cxt = test_context().await;
+    let api = &cxt.api;
+
+    // Submit both transfers before waiting on either, so that they're likely
+    // to make it into the same block.
+    let alice_progress = api
+        .tx()
+        .balances()
+        .transfer(charlie_address.clone(), 10_000)
+        .sign_and_submit_then_watch(&alice)
+        .await?;
+    let bob_progress = api
+        .tx()
+        .balances()
+        .transfer(charlie_address, 20_000)
+        .sign_and_submit_then_watch(&bob)
+        .await?;
+
+    let (alice_events, bob_events) = futures::future::try_join(
+        alice_progress.wait_for_finalized_success(),
+        bob_progress.wait_for_finalized_success(),
+    )
+    .await?;
+
+    for (events, signer) in [(alice_events, &alice), (bob_events, &bob)] {
+        let transfers = events
+            .find::<balances::events::Transfer>()
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(&transfers[0].from, signer.account_id());
+    }
+    Ok(())
+}
+
 #[async_std::test]
 async fn storage_total_issuance() {
     let cxt = test_context().await;
@@ -179,9 +543,16 @@ async fn transfer_error() {
         .await;
 
     if let Err(Error::Runtime(err)) = res {
-        let details = err.inner().details().unwrap();
+        let err = err.inner();
+        let details = err.details().unwrap();
         assert_eq!(details.pallet, "Balances");
         assert_eq!(details.error, "InsufficientBalance");
+        assert!(matches!(
+            err.module_error(),
+            Some(node_runtime::ModuleError::Balances(
+                node_runtime::balances::Error::InsufficientBalance
+            ))
+        ));
     } else {
         panic!("expected a runtime module error");
     }
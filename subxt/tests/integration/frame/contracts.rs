@@ -32,6 +32,8 @@ use crate::{
     NodeRuntimeSignedExtra,
     TestContext,
 };
+use codec::Decode;
+use futures::StreamExt;
 use sp_core::sr25519::Pair;
 use sp_runtime::MultiAddress;
 use subxt::{
@@ -228,3 +230,77 @@ async fn tx_call() {
 
     assert!(executed.is_ok(), "Error calling contract: {:?}", executed);
 }
+
+#[async_std::test]
+async fn events_deposited_with_a_topic_are_subscribed_to() {
+    let cxt = ContractsTestContext::init().await;
+    let topic = Hash::repeat_byte(1);
+    // Deposits an event with the topic above (and no data) whenever it's called.
+    let contract = format!(
+        r#"
+            (module
+                (import "seal0" "seal_deposit_event"
+                    (func $seal_deposit_event (param i32 i32 i32 i32)))
+                (import "env" "memory" (memory 1 1))
+                ;; The SCALE encoded topics: a `Vec<Hash>` holding one topic.
+                (data (i32.const 0) "\04{}")
+                (func (export "deploy"))
+                (func (export "call")
+                    (call $seal_deposit_event
+                        (i32.const 0) (i32.const 33) (i32.const 0) (i32.const 0))
+                )
+            )
+        "#,
+        "\\01".repeat(32)
+    );
+    let code = wabt::wat2wasm(contract).expect("invalid wabt");
+
+    let instantiated = cxt
+        .contracts_tx()
+        .instantiate_with_code(
+            100_000_000_000_000_000, // endowment
+            500_000_000_000,         // gas_limit
+            None,                    // storage_deposit_limit
+            code,
+            vec![], // data
+            vec![], // salt
+        )
+        .sign_and_submit_then_watch(&cxt.signer)
+        .await
+        .unwrap()
+        .wait_for_finalized_success()
+        .await
+        .unwrap()
+        .find_first_event::<events::Instantiated>()
+        .unwrap()
+        .expect("Failed to find a Instantiated event");
+
+    let mut topic_events = cxt.client().subscribe_event_topic(topic).await.unwrap();
+    let called = cxt
+        .contracts_tx()
+        .call(
+            MultiAddress::Id(instantiated.contract.clone()),
+            0,               // value
+            500_000_000_000, // gas_limit
+            None,            // storage_deposit_limit
+            vec![],
+        )
+        .sign_and_submit_then_watch(&cxt.signer)
+        .await
+        .unwrap()
+        .wait_for_in_block()
+        .await
+        .unwrap();
+
+    let (block_hash, raw_events) = topic_events.next().await.unwrap().unwrap();
+    assert_eq!(block_hash, called.block_hash());
+    assert_eq!(raw_events.len(), 1);
+    let emitted = &raw_events[0];
+    assert_eq!(
+        (emitted.pallet.as_str(), emitted.variant.as_str()),
+        ("Contracts", "ContractEmitted")
+    );
+    assert_eq!(emitted.topics, vec![topic]);
+    let event: events::ContractEmitted = Decode::decode(&mut &emitted.data[..]).unwrap();
+    assert_eq!(event.contract, instantiated.contract);
+}
@@ -18,6 +18,8 @@
 
 mod balances;
 mod contracts;
+mod proxy;
+mod session;
 mod staking;
 mod sudo;
 mod system;
@@ -0,0 +1,121 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+    node_runtime::{
+        self,
+        balances,
+        runtime_types::node_runtime::ProxyType,
+        DispatchError,
+    },
+    pair_signer,
+    test_context,
+    NodeRuntimeSignedExtra,
+    TestContext,
+};
+use assert_matches::assert_matches;
+use sp_keyring::AccountKeyring;
+use subxt::{
+    proxy::{
+        proxy_result,
+        wrap_as_proxy,
+    },
+    DefaultConfig,
+    Error,
+    SubmittableExtrinsic,
+    TransactionEvents,
+};
+
+// Make Bob a proxy of Alice, and have him dispatch the given call on her behalf.
+async fn transfer_as_proxy(
+    cxt: &TestContext,
+    value: u128,
+) -> Result<TransactionEvents<'_, DefaultConfig, node_runtime::Event>, Error<DispatchError>>
+{
+    let alice = pair_signer(AccountKeyring::Alice.pair());
+    let bob = pair_signer(AccountKeyring::Bob.pair());
+
+    cxt.api
+        .tx()
+        .proxy()
+        .add_proxy(AccountKeyring::Bob.to_account_id(), ProxyType::Any, 0)
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized_success()
+        .await?;
+
+    let call = balances::calls::Transfer {
+        dest: AccountKeyring::Charlie.to_account_id().into(),
+        value,
+    };
+    let proxy_call = wrap_as_proxy(
+        cxt.client().metadata(),
+        AccountKeyring::Alice.to_account_id(),
+        None::<ProxyType>,
+        &call,
+    )?;
+
+    SubmittableExtrinsic::<
+        _,
+        NodeRuntimeSignedExtra,
+        node_runtime::DefaultAccountData,
+        _,
+        DispatchError,
+        node_runtime::Event,
+    >::new(cxt.client(), proxy_call)
+    .sign_and_submit_then_watch(&bob)
+    .await?
+    .wait_for_finalized_success()
+    .await
+}
+
+#[async_std::test]
+async fn test_wrap_as_proxy() -> Result<(), Error<DispatchError>> {
+    let cxt = test_context().await;
+    let charlie = AccountKeyring::Charlie.to_account_id();
+    let charlie_pre = cxt
+        .api
+        .storage()
+        .system()
+        .account(charlie.clone(), None)
+        .await?;
+
+    let events = transfer_as_proxy(&cxt, 10_000).await?;
+    proxy_result::<_, _, DispatchError>(&events)?;
+
+    let transfer = events
+        .find_first_event::<balances::events::Transfer>()?
+        .expect("Failed to find balances::events::Transfer");
+    assert_eq!(transfer.from, AccountKeyring::Alice.to_account_id());
+    let charlie_post = cxt.api.storage().system().account(charlie, None).await?;
+    assert_eq!(charlie_pre.data.free + 10_000, charlie_post.data.free);
+    Ok(())
+}
+
+#[async_std::test]
+async fn proxy_result_reports_failed_calls() -> Result<(), Error<DispatchError>> {
+    let cxt = test_context().await;
+
+    // The proxy extrinsic succeeds even though Alice can't afford the transfer.
+    let events = transfer_as_proxy(&cxt, u128::MAX).await?;
+    let res = proxy_result::<_, _, DispatchError>(&events);
+    assert_matches!(res, Err(Error::Runtime(err)) => {
+        let details = err.inner().details().unwrap();
+        assert_eq!(details.pallet, "Balances");
+        assert_eq!(details.error, "InsufficientBalance");
+    });
+    Ok(())
+}
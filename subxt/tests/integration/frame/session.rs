@@ -0,0 +1,89 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+    node_runtime::{
+        self,
+        runtime_types::node_runtime::SessionKeys,
+        session,
+        DispatchError,
+    },
+    pair_signer,
+    test_context,
+    NodeRuntimeSignedExtra,
+};
+use codec::{
+    Decode,
+    Encode,
+};
+use sp_core::{
+    sr25519,
+    Pair,
+};
+use sp_keyring::AccountKeyring;
+use subxt::{
+    session::{
+        has_keys,
+        rotate_keys,
+    },
+    Signer,
+    SubmittableExtrinsic,
+};
+
+#[async_std::test]
+async fn rotated_keys_are_registered() -> Result<(), subxt::Error<DispatchError>> {
+    let alice = pair_signer(AccountKeyring::Alice.pair());
+    let alice_stash = pair_signer(
+        sr25519::Pair::from_string("//Alice//stash", None)
+            .expect("static values are valid; qed"),
+    );
+    let cxt = test_context().await;
+
+    let set_keys = rotate_keys::<_, SessionKeys>(cxt.client().rpc()).await?;
+    assert!(has_keys(cxt.client().rpc(), &set_keys.keys).await?);
+
+    // The call is encoded just like the generated one.
+    let keys_bytes = set_keys.keys.encode();
+    let generated = session::calls::SetKeys {
+        keys: SessionKeys::decode(&mut &keys_bytes[..])?,
+        proof: Vec::new(),
+    };
+    assert_eq!(set_keys.encode(), generated.encode());
+
+    // Alice is the controller of the `//Alice//stash` validator.
+    SubmittableExtrinsic::<
+        _,
+        NodeRuntimeSignedExtra,
+        node_runtime::DefaultAccountData,
+        _,
+        DispatchError,
+        node_runtime::Event,
+    >::new(cxt.client(), set_keys)
+    .sign_and_submit_then_watch(&alice)
+    .await?
+    .wait_for_finalized_success()
+    .await?;
+
+    let next_keys = cxt
+        .api
+        .storage()
+        .session()
+        .next_keys(alice_stash.account_id().clone(), None)
+        .await?
+        .map(|keys| keys.encode());
+    assert_eq!(next_keys, Some(keys_bytes));
+    Ok(())
+}
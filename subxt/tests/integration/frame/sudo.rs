@@ -16,14 +16,18 @@
 
 use crate::{
     node_runtime::{
+        self,
+        balances,
         runtime_types,
         sudo,
         DispatchError,
     },
     pair_signer,
     test_context,
+    NodeRuntimeSignedExtra,
 };
 use sp_keyring::AccountKeyring;
+use subxt::SubmittableExtrinsic;
 
 type Call = runtime_types::node_runtime::Call;
 type BalancesCall = runtime_types::pallet_balances::pallet::Call;
@@ -79,3 +83,32 @@ async fn test_sudo_unchecked_weight() -> Result<(), subxt::Error<DispatchError>>
     assert!(found_event);
     Ok(())
 }
+
+#[async_std::test]
+async fn test_wrap_as_sudo() -> Result<(), subxt::Error<DispatchError>> {
+    let alice = pair_signer(AccountKeyring::Alice.pair());
+    let bob = AccountKeyring::Bob.to_account_id().into();
+    let cxt = test_context().await;
+
+    let call = balances::calls::Transfer {
+        dest: bob,
+        value: 10_000,
+    };
+    let sudo_call = subxt::sudo::wrap_as_sudo(cxt.client().metadata(), &call)?;
+
+    let events = SubmittableExtrinsic::<
+        _,
+        NodeRuntimeSignedExtra,
+        node_runtime::DefaultAccountData,
+        _,
+        DispatchError,
+        node_runtime::Event,
+    >::new(cxt.client(), sudo_call)
+    .sign_and_submit_then_watch(&alice)
+    .await?
+    .wait_for_finalized_success()
+    .await?;
+
+    subxt::sudo::sudo_result::<_, _, DispatchError>(&events)?;
+    Ok(())
+}
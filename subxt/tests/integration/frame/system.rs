@@ -24,7 +24,11 @@ use crate::{
 };
 use assert_matches::assert_matches;
 use sp_keyring::AccountKeyring;
-use subxt::Signer;
+use subxt::{
+    DispatchErrorKind,
+    Error,
+    Signer,
+};
 
 #[async_std::test]
 async fn storage_account() -> Result<(), subxt::Error<DispatchError>> {
@@ -61,3 +65,29 @@ async fn tx_remark_with_event() -> Result<(), subxt::Error<DispatchError>> {
     assert!(found_event);
     Ok(())
 }
+
+#[async_std::test]
+async fn non_module_dispatch_error_kind() {
+    let bob = pair_signer(AccountKeyring::Bob.pair());
+    let cxt = test_context().await;
+
+    // Bob isn't root, so this call should fail with `BadOrigin`:
+    let res = cxt
+        .api
+        .tx()
+        .system()
+        .set_heap_pages(64)
+        .sign_and_submit_then_watch(&bob)
+        .await
+        .unwrap()
+        .wait_for_finalized_success()
+        .await;
+
+    if let Err(Error::Runtime(err)) = res {
+        let err = err.inner();
+        assert!(err.details().is_none());
+        assert_eq!(err.kind(), DispatchErrorKind::BadOrigin);
+    } else {
+        panic!("expected a runtime error");
+    }
+}
@@ -35,16 +35,21 @@ const SUBSTRATE_NODE_PATH: &str = "substrate";
 pub type NodeRuntimeSignedExtra =
     DefaultExtraWithTxPayment<DefaultConfig, ChargeAssetTxPayment<DefaultConfig>>;
 
-pub async fn test_node_process_with(
-    key: AccountKeyring,
-) -> TestNodeProcess<DefaultConfig> {
-    let path = std::env::var("SUBSTRATE_NODE_PATH").unwrap_or_else(|_| {
+/// The path to the substrate binary used for integration tests.
+pub fn test_node_path() -> String {
+    std::env::var("SUBSTRATE_NODE_PATH").unwrap_or_else(|_| {
         if which::which(SUBSTRATE_NODE_PATH).is_err() {
             panic!("A substrate binary should be installed on your path for integration tests. \
             See https://github.com/paritytech/subxt/tree/master#integration-testing")
         }
         SUBSTRATE_NODE_PATH.to_string()
-    });
+    })
+}
+
+pub async fn test_node_process_with(
+    key: AccountKeyring,
+) -> TestNodeProcess<DefaultConfig> {
+    let path = test_node_path();
 
     let proc = TestNodeProcess::<DefaultConfig>::build(path.as_str())
         .with_authority(key)
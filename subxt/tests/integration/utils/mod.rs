@@ -16,6 +16,8 @@
 
 mod context;
 mod node_proc;
+mod proxy;
 
 pub use context::*;
 pub use node_proc::TestNodeProcess;
+pub use proxy::ConnectionProxy;
@@ -39,6 +39,7 @@ use subxt::{
 pub struct TestNodeProcess<R: Config> {
     proc: process::Child,
     client: Client<R>,
+    ws_url: String,
 }
 
 impl<R> Drop for TestNodeProcess<R>
@@ -77,6 +78,11 @@ where
     pub fn client(&self) -> &Client<R> {
         &self.client
     }
+
+    /// Returns the URL that the node's websocket RPC server is listening on.
+    pub fn ws_url(&self) -> &str {
+        &self.ws_url
+    }
 }
 
 /// Construct a test node process.
@@ -173,7 +179,13 @@ impl TestNodeProcessBuilder {
             }
         };
         match client {
-            Ok(client) => Ok(TestNodeProcess { proc, client }),
+            Ok(client) => {
+                Ok(TestNodeProcess {
+                    proc,
+                    client,
+                    ws_url,
+                })
+            }
             Err(err) => {
                 let err = format!(
                     "Failed to connect to node rpc at {} after {} attempts: {}",
@@ -0,0 +1,127 @@
+// Copyright 2019-2022 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+use async_std::{
+    io,
+    net::{
+        TcpListener,
+        TcpStream,
+    },
+    task,
+};
+use futures::future::{
+    self,
+    AbortHandle,
+    Abortable,
+};
+use std::sync::{
+    atomic::{
+        AtomicBool,
+        Ordering,
+    },
+    Arc,
+    Mutex,
+};
+
+/// Forwards connections to a test node, so that tests can cut them off to simulate the
+/// connection to the node being lost.
+pub struct ConnectionProxy {
+    ws_url: String,
+    listener: AbortHandle,
+    connections: Arc<Mutex<Vec<AbortHandle>>>,
+    refusing: Arc<AtomicBool>,
+}
+
+impl Drop for ConnectionProxy {
+    fn drop(&mut self) {
+        self.listener.abort();
+        self.drop_connections();
+    }
+}
+
+impl ConnectionProxy {
+    /// Start forwarding connections to the node with the websocket URL given.
+    pub async fn start(node_ws_url: &str) -> ConnectionProxy {
+        let node_addr = node_ws_url
+            .strip_prefix("ws://")
+            .expect("test node URLs are ws:// URLs")
+            .to_string();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let ws_url = format!("ws://{}", listener.local_addr().unwrap());
+        let connections = Arc::new(Mutex::new(Vec::new()));
+        let refusing = Arc::new(AtomicBool::new(false));
+
+        let accept = {
+            let connections = connections.clone();
+            let refusing = refusing.clone();
+            async move {
+                loop {
+                    let (inbound, _) = match listener.accept().await {
+                        Ok(accepted) => accepted,
+                        Err(_) => return,
+                    };
+                    if refusing.load(Ordering::SeqCst) {
+                        continue
+                    }
+                    let outbound = match TcpStream::connect(&node_addr).await {
+                        Ok(outbound) => outbound,
+                        Err(_) => continue,
+                    };
+                    let (handle, registration) = AbortHandle::new_pair();
+                    connections.lock().unwrap().push(handle);
+                    task::spawn(Abortable::new(forward(inbound, outbound), registration));
+                }
+            }
+        };
+        let (listener, registration) = AbortHandle::new_pair();
+        task::spawn(Abortable::new(accept, registration));
+
+        ConnectionProxy {
+            ws_url,
+            listener,
+            connections,
+            refusing,
+        }
+    }
+
+    /// The websocket URL to connect to the node through.
+    pub fn ws_url(&self) -> &str {
+        &self.ws_url
+    }
+
+    /// Close every connection made through the proxy so far. New connections can still be
+    /// made.
+    pub fn drop_connections(&self) {
+        for connection in self.connections.lock().unwrap().drain(..) {
+            connection.abort();
+        }
+    }
+
+    /// Close new connections straight away (if `true`), as if the node were unreachable,
+    /// or go back to forwarding them (if `false`).
+    pub fn refuse_connections(&self, refuse: bool) {
+        self.refusing.store(refuse, Ordering::SeqCst);
+    }
+}
+
+// Copy data both ways until either side closes the connection.
+async fn forward(inbound: TcpStream, outbound: TcpStream) {
+    let _ = future::try_join(
+        io::copy(&mut &inbound, &mut &outbound),
+        io::copy(&mut &outbound, &mut &inbound),
+    )
+    .await;
+}